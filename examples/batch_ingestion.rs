@@ -1,9 +1,6 @@
 //! Example demonstrating batch ingestion with automatic chunking and retries
 
-use chrono::Utc;
-use langfuse_client_base::models::{IngestionEvent, IngestionEventOneOf, TraceBody};
 use langfuse_ergonomic::{Batcher, ClientBuilder};
-use uuid::Uuid;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,41 +21,28 @@ async fn main() -> anyhow::Result<()> {
 
     // Create multiple traces
     for i in 0..20 {
-        let trace_id = Uuid::new_v4().to_string();
-        let timestamp = Utc::now().to_rfc3339();
-
-        // Add trace event
-        let trace_event = IngestionEvent::IngestionEventOneOf(Box::new(IngestionEventOneOf {
-            id: trace_id.clone(),
-            timestamp: timestamp.clone(),
-            r#type: langfuse_client_base::models::ingestion_event_one_of::Type::TraceCreate,
-            body: Box::new(TraceBody {
-                id: Some(Some(trace_id.clone())),
-                timestamp: Some(Some(timestamp.clone())),
-                name: Some(Some(format!("batch-trace-{}", i))),
-                user_id: Some(Some("test-user".to_string())),
-                metadata: Some(Some(serde_json::json!({
-                    "batch_number": i,
-                    "batch_test": true,
-                    "timestamp": timestamp
-                }))),
-                release: Some(Some("v1.0.0".to_string())),
-                version: Some(Some("1.0.0".to_string())),
-                session_id: Some(Some("batch-session-001".to_string())),
-                public: Some(Some(false)),
-                tags: Some(Some(vec!["batch".to_string(), "test".to_string()])),
-                input: Some(Some(serde_json::json!({
-                    "test_input": format!("Input for trace {}", i)
-                }))),
-                output: Some(Some(serde_json::json!({
-                    "test_output": format!("Output for trace {}", i)
-                }))),
-                environment: None,
-            }),
-            metadata: None,
-        }));
-
-        batcher.add(trace_event).await?;
+        // Queue a trace event
+        batcher
+            .trace()
+            .name(format!("batch-trace-{}", i))
+            .user_id("test-user")
+            .metadata(serde_json::json!({
+                "batch_number": i,
+                "batch_test": true
+            }))
+            .release("v1.0.0")
+            .version("1.0.0")
+            .session_id("batch-session-001")
+            .public(false)
+            .tags(vec!["batch".to_string(), "test".to_string()])
+            .input(serde_json::json!({
+                "test_input": format!("Input for trace {}", i)
+            }))
+            .output(serde_json::json!({
+                "test_output": format!("Output for trace {}", i)
+            }))
+            .call()
+            .await?;
 
         // Add a small delay to simulate real-world usage
         if i % 5 == 0 {
@@ -174,15 +174,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 success_count,
                 failure_count,
                 errors,
+                request_id,
                 ..
             } = &e
             {
                 println!("\n  Partial failure (207 Multi-Status):");
                 println!("   Successful: {}", success_count);
                 println!("   Failed: {}", failure_count);
-
-                // Note: request_id and retry_after metadata is available
-                // in other error types like RateLimit and Client errors
+                if let Some(id) = request_id {
+                    println!("   Request ID: {}", id);
+                }
 
                 println!("\n  Failed events will be retried:");
                 for error in errors.iter().take(3) {
@@ -0,0 +1,46 @@
+//! Generate synthetic load against a Langfuse instance for capacity testing.
+//!
+//! Run with: `cargo run --example loadgen --features loadgen -- [events_per_second] [seconds]`
+
+use langfuse_ergonomic::loadgen::{EventMix, LoadGenerator, LoadProfile};
+use langfuse_ergonomic::{Batcher, ClientBuilder};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+
+    let mut args = std::env::args().skip(1);
+    let events_per_second = args.next().and_then(|v| v.parse().ok()).unwrap_or(50);
+    let seconds = args.next().and_then(|v| v.parse().ok()).unwrap_or(10);
+
+    let profile = LoadProfile {
+        events_per_second,
+        duration: Duration::from_secs(seconds),
+        mix: EventMix {
+            traces: 1,
+            spans: 3,
+            generations: 2,
+        },
+        ..LoadProfile::default()
+    };
+    let generator = LoadGenerator::new(profile);
+
+    let client = ClientBuilder::from_env()?.build()?;
+
+    println!("Direct client calls:");
+    let report = generator.run_against_client(&client).await;
+    println!("  {report}");
+
+    println!("Batcher:");
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(1))
+        .build()
+        .await;
+    let report = generator.run_against_batcher(&batcher).await;
+    println!("  {report}");
+    batcher.shutdown().await?;
+
+    Ok(())
+}
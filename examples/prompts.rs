@@ -56,7 +56,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     match client
         .list_prompts()
         .page(1)
-        .limit("10".to_string())
+        .limit(10)
         .name("example-prompt")
         .call()
         .await
@@ -51,7 +51,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .score()
         .trace_id(&trace.id)
         .name("user_sentiment")
-        .string_value("positive")
+        .value("positive")
         .comment("User expressed satisfaction")
         .call()
         .await?;
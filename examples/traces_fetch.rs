@@ -1,6 +1,6 @@
 //! Example demonstrating trace fetching functionality
 
-use langfuse_ergonomic::ClientBuilder;
+use langfuse_ergonomic::{ClientBuilder, OrderBy, SortDirection, TraceFilter};
 use serde_json::json;
 
 #[tokio::main]
@@ -51,8 +51,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .list_traces()
         .limit(5)
         .page(1)
-        .user_id("example-user")
-        .name("fetch-example-trace")
+        .filter(
+            TraceFilter::new()
+                .user_id("example-user")
+                .name("fetch-example-trace"),
+        )
         .call()
         .await?;
 
@@ -63,7 +66,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let recent_traces = client
         .list_traces()
         .limit(3)
-        .order_by("timestamp")
+        .order_by(OrderBy::Timestamp(SortDirection::Desc))
         .call()
         .await?;
 
@@ -0,0 +1,89 @@
+//! Derive macros for [`langfuse-ergonomic`](https://docs.rs/langfuse-ergonomic)
+//!
+//! Use these through the `derive` feature of `langfuse-ergonomic` rather than
+//! depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+/// Derive `langfuse_ergonomic::prompts::PromptVars` for a struct with named fields
+///
+/// Every field becomes a prompt variable named after the field. Use
+/// `#[prompt_var(rename = "name")]` to map a field to a differently named
+/// variable, and `#[prompt_var(skip)]` to leave a field out.
+#[proc_macro_derive(PromptVars, attributes(prompt_var))]
+pub fn derive_prompt_vars(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "PromptVars can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PromptVars can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut names: Vec<String> = Vec::with_capacity(fields.len());
+    for field in fields {
+        let mut name = field
+            .ident
+            .as_ref()
+            .map(|ident| ident.to_string().trim_start_matches("r#").to_string())
+            .unwrap_or_default();
+        let mut skip = false;
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("prompt_var"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename = \"...\"` or `skip`"))
+                }
+            })?;
+        }
+
+        if skip {
+            continue;
+        }
+        if names.contains(&name) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!("duplicate prompt variable `{name}`"),
+            ));
+        }
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::langfuse_ergonomic::prompts::PromptVars for #ident #ty_generics #where_clause {
+            const NAMES: &'static [&'static str] = &[#(#names),*];
+        }
+    })
+}
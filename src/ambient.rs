@@ -0,0 +1,210 @@
+//! Ambient trace context carried by the current task
+//!
+//! Threading trace and observation IDs through every function between a
+//! request handler and the LLM call it makes gets tedious. [`with_context`]
+//! instead runs a future with a [`LangfuseContext`] in task-local storage, and
+//! the client's builders fall back to it for fields that are not set
+//! explicitly:
+//!
+//! - `span`, `generation`, and `event` use its trace ID and, as their parent,
+//!   its observation ID
+//! - `score` uses its trace ID
+//! - `trace` uses its user and session ID
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{with_context, ClientBuilder, LangfuseClient, LangfuseContext};
+//!
+//! async fn retrieve(client: &LangfuseClient) -> langfuse_ergonomic::Result<()> {
+//!     // No trace ID needed: it comes from the ambient context
+//!     client.span().name("retrieval").call().await?;
+//!     Ok(())
+//! }
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! let trace = client.trace().name("request").call().await?;
+//!
+//! let context = LangfuseContext::new().trace_id(trace.id).user_id("user-42");
+//! with_context(context, retrieve(&client)).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Contexts nest: fields left unset by an inner context are inherited from the
+//! outer one. Task-local storage does not follow `tokio::spawn`; wrap the
+//! spawned future in [`with_context`] with [`LangfuseContext::current`] to
+//! carry the context over.
+
+use std::future::Future;
+
+use crate::context::TraceContext;
+use crate::error::{Error, Result};
+
+tokio::task_local! {
+    static CURRENT: LangfuseContext;
+}
+
+/// Trace attribution that builders fall back to inside [`with_context`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LangfuseContext {
+    /// Trace that observations and scores are attached to
+    pub trace_id: Option<String>,
+    /// Observation that observations are nested under
+    pub parent_observation_id: Option<String>,
+    /// User that traces are attributed to
+    pub user_id: Option<String>,
+    /// Session that traces are grouped into
+    pub session_id: Option<String>,
+}
+
+impl LangfuseContext {
+    /// Create a context without any fields set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The context of the current task, if it runs inside [`with_context`]
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Attach observations and scores to the given trace
+    #[must_use]
+    pub fn trace_id(mut self, id: impl Into<String>) -> Self {
+        self.trace_id = Some(id.into());
+        self
+    }
+
+    /// Nest observations under the given observation
+    #[must_use]
+    pub fn parent_observation_id(mut self, id: impl Into<String>) -> Self {
+        self.parent_observation_id = Some(id.into());
+        self
+    }
+
+    /// Attribute traces to the given user
+    #[must_use]
+    pub fn user_id(mut self, id: impl Into<String>) -> Self {
+        self.user_id = Some(id.into());
+        self
+    }
+
+    /// Group traces into the given session
+    #[must_use]
+    pub fn session_id(mut self, id: impl Into<String>) -> Self {
+        self.session_id = Some(id.into());
+        self
+    }
+
+    /// Fill the fields unset in `self` from `outer`
+    fn inherit(self, outer: Self) -> Self {
+        Self {
+            trace_id: self.trace_id.or(outer.trace_id),
+            parent_observation_id: self.parent_observation_id.or(outer.parent_observation_id),
+            user_id: self.user_id.or(outer.user_id),
+            session_id: self.session_id.or(outer.session_id),
+        }
+    }
+}
+
+impl From<&TraceContext> for LangfuseContext {
+    fn from(context: &TraceContext) -> Self {
+        Self {
+            trace_id: Some(context.trace_id().to_string()),
+            parent_observation_id: context.observation_id().map(str::to_string),
+            ..Self::default()
+        }
+    }
+}
+
+/// Run `future` with `context` as the ambient context of the current task
+///
+/// Fields unset in `context` are inherited from the enclosing context, if any.
+/// See the [module documentation](self).
+pub async fn with_context<F: Future>(context: LangfuseContext, future: F) -> F::Output {
+    let context = match LangfuseContext::current() {
+        Some(outer) => context.inherit(outer),
+        None => context,
+    };
+    CURRENT.scope(context, future).await
+}
+
+/// Value of a field of the ambient context, if any
+pub(crate) fn ambient<T>(field: impl FnOnce(&LangfuseContext) -> Option<T>) -> Option<T> {
+    CURRENT.try_with(|context| field(context)).ok().flatten()
+}
+
+/// Resolve an observation's trace and parent observation IDs against the ambient context
+///
+/// The ambient parent is only used when the observation belongs to the ambient trace.
+pub(crate) fn observation_ids(
+    trace_id: Option<String>,
+    parent_observation_id: Option<String>,
+) -> Result<(String, Option<String>)> {
+    let ambient = LangfuseContext::current().unwrap_or_default();
+    let trace_id = trace_id.or(ambient.trace_id.clone()).ok_or_else(|| {
+        Error::Validation("trace_id is required outside of a context providing one".to_string())
+    })?;
+    let parent_observation_id = match parent_observation_id {
+        Some(id) => Some(id),
+        None if ambient.trace_id.as_deref() == Some(trace_id.as_str()) => {
+            ambient.parent_observation_id
+        }
+        None => None,
+    };
+    Ok((trace_id, parent_observation_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_nested_contexts_inherit() {
+        assert_eq!(LangfuseContext::current(), None);
+
+        let outer = LangfuseContext::new().trace_id("trace-1").user_id("user-1");
+        with_context(outer, async {
+            let inner = LangfuseContext::new().parent_observation_id("span-1");
+            with_context(inner, async {
+                let current = LangfuseContext::current().unwrap();
+                assert_eq!(current.trace_id.as_deref(), Some("trace-1"));
+                assert_eq!(current.parent_observation_id.as_deref(), Some("span-1"));
+                assert_eq!(current.user_id.as_deref(), Some("user-1"));
+                assert_eq!(current.session_id, None);
+            })
+            .await;
+
+            assert_eq!(
+                ambient(|context| context.parent_observation_id.clone()),
+                None
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_observation_ids_fall_back_to_context() {
+        assert!(observation_ids(None, None).is_err());
+
+        let context = LangfuseContext::new()
+            .trace_id("trace-1")
+            .parent_observation_id("span-1");
+        with_context(context, async {
+            assert_eq!(
+                observation_ids(None, None).unwrap(),
+                ("trace-1".to_string(), Some("span-1".to_string()))
+            );
+            assert_eq!(
+                observation_ids(None, Some("span-2".to_string())).unwrap(),
+                ("trace-1".to_string(), Some("span-2".to_string()))
+            );
+            // The ambient parent belongs to another trace
+            assert_eq!(
+                observation_ids(Some("trace-2".to_string()), None).unwrap(),
+                ("trace-2".to_string(), None)
+            );
+        })
+        .await;
+    }
+}
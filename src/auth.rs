@@ -0,0 +1,202 @@
+//! Authentication of Langfuse API requests
+//!
+//! Every request made by a [`LangfuseClient`](crate::LangfuseClient) is
+//! authenticated by an [`AuthProvider`]. By default this is [`BasicAuth`] with
+//! the public and secret key. Deployments behind a proxy that expects other
+//! credentials, such as an OIDC bearer token or a request signature, can
+//! configure a different provider with
+//! [`ClientBuilder::auth`](crate::ClientBuilder::auth):
+//!
+//! ```no_run
+//! use langfuse_ergonomic::auth::BearerToken;
+//! use langfuse_ergonomic::ClientBuilder;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::new()
+//!     .base_url("https://langfuse.internal.example.com")
+//!     .auth(BearerToken::new(std::env::var("OIDC_TOKEN")?)?)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Custom schemes implement the trait. The provider runs for every attempt of
+//! a request, so tokens can be refreshed and signatures computed over the
+//! final request:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::auth::AuthProvider;
+//!
+//! struct SignedRequests {
+//!     key_id: String,
+//! }
+//!
+//! #[async_trait::async_trait]
+//! impl AuthProvider for SignedRequests {
+//!     async fn apply(&self, request: &mut reqwest::Request) -> langfuse_ergonomic::Result<()> {
+//!         let signature = format!("keyId={},path={}", self.key_id, request.url().path());
+//!         request
+//!             .headers_mut()
+//!             .insert("x-signature", signature.parse().expect("valid header"));
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! Media content uploads go to presigned storage URLs and are sent without
+//! credentials.
+
+use base64::Engine as _;
+use http::Extensions;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::{Error, Result};
+
+/// Adds credentials to Langfuse API requests
+#[async_trait::async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Authenticate `request` before it is sent
+    async fn apply(&self, request: &mut Request) -> Result<()>;
+}
+
+/// HTTP basic authentication with a Langfuse public and secret key (default)
+#[derive(Clone)]
+pub struct BasicAuth {
+    header: HeaderValue,
+}
+
+impl BasicAuth {
+    /// Authenticate with a public and secret key
+    pub fn new(public_key: impl AsRef<str>, secret_key: impl AsRef<str>) -> Result<Self> {
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!(
+            "{}:{}",
+            public_key.as_ref(),
+            secret_key.as_ref()
+        ));
+        Ok(Self {
+            header: sensitive_header(format!("Basic {credentials}"))?,
+        })
+    }
+}
+
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuth").finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for BasicAuth {
+    async fn apply(&self, request: &mut Request) -> Result<()> {
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, self.header.clone());
+        Ok(())
+    }
+}
+
+/// Bearer token authentication, e.g. with an OIDC access token
+#[derive(Clone)]
+pub struct BearerToken {
+    header: HeaderValue,
+}
+
+impl BearerToken {
+    /// Authenticate with a fixed token
+    pub fn new(token: impl AsRef<str>) -> Result<Self> {
+        Ok(Self {
+            header: sensitive_header(format!("Bearer {}", token.as_ref()))?,
+        })
+    }
+}
+
+impl fmt::Debug for BearerToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BearerToken").finish_non_exhaustive()
+    }
+}
+
+#[async_trait::async_trait]
+impl AuthProvider for BearerToken {
+    async fn apply(&self, request: &mut Request) -> Result<()> {
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, self.header.clone());
+        Ok(())
+    }
+}
+
+/// Header value hidden from debug output
+fn sensitive_header(value: String) -> Result<HeaderValue> {
+    let mut header = HeaderValue::from_str(&value).map_err(|_| {
+        Error::Configuration("Credentials contain characters not allowed in a header".to_string())
+    })?;
+    header.set_sensitive(true);
+    Ok(header)
+}
+
+/// Request extension marking requests that must be sent without credentials
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Unauthenticated;
+
+/// Applies the client's [`AuthProvider`] to each request
+pub(crate) struct AuthMiddleware {
+    pub(crate) provider: Arc<dyn AuthProvider>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for AuthMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if extensions.get::<Unauthenticated>().is_none() {
+            self.provider
+                .apply(&mut req)
+                .await
+                .map_err(reqwest_middleware::Error::middleware)?;
+        }
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> Request {
+        Request::new(
+            reqwest::Method::GET,
+            "http://localhost/api/public/health".parse().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_basic_auth_header() {
+        let mut req = request();
+        BasicAuth::new("pk", "sk")
+            .unwrap()
+            .apply(&mut req)
+            .await
+            .unwrap();
+        assert_eq!(req.headers()[AUTHORIZATION], "Basic cGs6c2s=");
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token_header() {
+        let mut req = request();
+        BearerToken::new("abc")
+            .unwrap()
+            .apply(&mut req)
+            .await
+            .unwrap();
+        assert_eq!(req.headers()[AUTHORIZATION], "Bearer abc");
+        assert!(BearerToken::new("bad\ntoken").is_err());
+    }
+}
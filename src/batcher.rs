@@ -13,6 +13,51 @@
 //! | `retry_jitter` | Enabled (25%) | Random jitter to avoid thundering herd |
 //! | `initial_retry_delay` | 100ms | Starting delay for retries |
 //! | `max_retry_delay` | 30s | Maximum delay between retries |
+//! | `adaptive_flush` | Disabled | Adjust the flush interval to the queue depth |
+//! | `queue_backend` | None (memory only) | Persist queued events across restarts |
+//! | `circuit_breaker` | Disabled | Pause sends while Langfuse is down |
+//! | `dedup_capacity` | Disabled | Skip events re-added with a recently seen ID |
+//!
+//! ## Adaptive Flushing
+//!
+//! With [`AdaptiveFlush`], the effective flush interval follows the load: it is
+//! halved (down to [`AdaptiveFlush::min_interval`]) whenever a tick finds the
+//! queue filling faster than the interval drains it, and doubled (up to
+//! [`AdaptiveFlush::max_interval`]) whenever a tick finds nothing to send. The
+//! first event after an idle stretch resets it to `flush_interval`, so
+//! lengthening never delays events by more than the configured interval. The
+//! current value is reported as [`BatcherMetricsSnapshot::flush_interval_ms`].
+//!
+//! ## Persistent Queues
+//!
+//! With a [`QueueBackend`] such as [`FileQueue`](crate::queue::FileQueue),
+//! queued events are journaled and replayed by the next batcher using the same
+//! backend, so a crash does not lose them. See [`crate::queue`].
+//!
+//! ## Circuit Breaker
+//!
+//! With a [`CircuitBreaker`], [`CircuitBreaker::failure_threshold`] consecutive
+//! failed requests (5xx responses or network errors) open the circuit: the
+//! batcher stops retrying and pauses sends for [`CircuitBreaker::cooldown`]
+//! instead of hammering an instance that is down. Events stay queued, and
+//! flushes fail with [`Error::CircuitOpen`] until the cool-down is over. The
+//! next flush then probes with a single request: success closes the circuit,
+//! another failure opens it again right away.
+//!
+//! Openings are counted in [`BatcherMetricsSnapshot::circuit_opens`] and
+//! reported to the callback given to `on_circuit_change`. Combine the breaker
+//! with a queue backend so events still pending at shutdown are kept on disk.
+//!
+//! ## Deduplication
+//!
+//! With `dedup_capacity(n)`, the batcher remembers the IDs of the last `n`
+//! events added and skips an event whose ID is among them, so retry loops that
+//! re-submit events with deterministic IDs (see
+//! [`IdGenerator`](crate::IdGenerator)) do not send the same payload twice.
+//! Skipped events are counted in [`BatcherMetricsSnapshot::deduplicated`].
+//! Events rejected by [`Batcher::add`] or dropped by backpressure are
+//! forgotten, so adding them again is not skipped; events that failed after
+//! being sent are not, and stay skipped until their ID is evicted.
 //!
 //! ## Example
 //!
@@ -48,19 +93,31 @@
 
 use bon::bon;
 use rand::{rng, RngExt};
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{mpsc, Mutex};
-use tokio::time::interval;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tokio::time::sleep_until;
 
 use crate::client::LangfuseClient;
+use crate::debug_config::BatcherDebugConfig;
 use crate::error::{Error, EventError, IngestionResponse, Result};
+use crate::interceptor::BatchInterceptor;
+use crate::metrics_file::{MetricsFile, ShutdownMetrics};
+use crate::queue::QueueBackend;
+use crate::sampling::Sampler;
+use crate::scores::ScoreInput;
+use crate::traces::{
+    BatchedEventBuilder, BatchedGenerationBuilder, BatchedScoreBuilder, BatchedSpanBuilder,
+    BatchedTraceBuilder,
+};
+use crate::transport::IngestionTransport;
 use langfuse_client_base::models::{IngestionBatchRequest, IngestionEvent};
 
 /// Maximum batch size in bytes (3.5 MB as per Langfuse docs)
-const MAX_BATCH_SIZE_BYTES: usize = 3_500_000;
+pub(crate) const MAX_BATCH_SIZE_BYTES: usize = 3_500_000;
 
 /// Default maximum events per batch
 const DEFAULT_MAX_EVENTS: usize = 100;
@@ -82,6 +139,8 @@ pub struct BatchEvent {
     pub size: usize,
     /// Number of retry attempts
     pub retry_count: u32,
+    /// When the event was first queued
+    pub enqueued_at: Instant,
 }
 
 impl BatchEvent {
@@ -93,8 +152,273 @@ impl BatchEvent {
             id,
             size: serialized.len(),
             retry_count: 0,
+            enqueued_at: Instant::now(),
+        })
+    }
+}
+
+/// Message from producers to the background task
+enum Command {
+    /// Queue an event
+    Event(BatchEvent),
+    /// Reply once every command sent before this one has been handled
+    Sync(oneshot::Sender<()>),
+}
+
+/// A [`Batcher`] as held by the [registry](crate::registry)
+///
+/// Holds the channels weakly, so the batcher's task still stops, flushing
+/// what is pending, once the application drops its last [`Batcher`].
+pub(crate) struct WeakBatcher {
+    tx: mpsc::WeakSender<Command>,
+    shutdown_tx: mpsc::WeakSender<()>,
+    batcher: WeakParts,
+}
+
+/// Fields of a [`Batcher`] other than its channels
+struct WeakParts {
+    client: Arc<LangfuseClient>,
+    config: BatcherConfig,
+    buffer: Arc<Mutex<VecDeque<BatchEvent>>>,
+    buffer_size: Arc<AtomicUsize>,
+    rx: Arc<Mutex<mpsc::Receiver<Command>>>,
+    metrics: Arc<BatcherMetrics>,
+    flush_mutex: Arc<Mutex<()>>,
+    shutdown_flag: Arc<AtomicBool>,
+    task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    queue_backend: Option<Arc<dyn QueueBackend>>,
+    circuit: Arc<CircuitState>,
+    deliveries: Arc<Deliveries>,
+    recent_ids: Option<Arc<RecentIds>>,
+    registry_id: u64,
+}
+
+impl WeakBatcher {
+    /// The batcher, unless the application dropped it
+    pub(crate) fn upgrade(&self) -> Option<Batcher> {
+        let parts = &self.batcher;
+        Some(Batcher {
+            tx: self.tx.upgrade()?,
+            shutdown_tx: self.shutdown_tx.upgrade()?,
+            client: parts.client.clone(),
+            config: parts.config.clone(),
+            buffer: parts.buffer.clone(),
+            buffer_size: parts.buffer_size.clone(),
+            rx: parts.rx.clone(),
+            metrics: parts.metrics.clone(),
+            flush_mutex: parts.flush_mutex.clone(),
+            shutdown_flag: parts.shutdown_flag.clone(),
+            task_handle: parts.task_handle.clone(),
+            queue_backend: parts.queue_backend.clone(),
+            circuit: parts.circuit.clone(),
+            deliveries: parts.deliveries.clone(),
+            recent_ids: parts.recent_ids.clone(),
+            registry_id: parts.registry_id,
         })
     }
+
+    /// Drain the batcher within `deadline`, or wait for the final flush of a
+    /// dropped one
+    pub(crate) async fn drain(self, deadline: Duration) -> DrainReport {
+        if let Some(batcher) = self.upgrade() {
+            return batcher.drain(deadline).await;
+        }
+
+        let started = Instant::now();
+        let parts = self.batcher;
+        let finish = async {
+            let handle = parts.task_handle.lock().await.take();
+            if let Some(handle) = handle {
+                let _ = handle.await;
+            }
+        };
+        let timed_out = tokio::time::timeout(deadline, finish).await.is_err();
+        let metrics = parts.metrics.snapshot();
+        let remaining = parts.buffer.lock().await.len();
+        DrainReport {
+            flushed: metrics.flushed,
+            failed: metrics.failed,
+            dropped: metrics.dropped,
+            retries: metrics.retries,
+            remaining,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            timed_out,
+        }
+    }
+}
+
+/// Bounds of the adaptive flush interval
+///
+/// See [Adaptive Flushing](crate::batcher#adaptive-flushing).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct AdaptiveFlush {
+    /// Shortest effective flush interval under load
+    pub min_interval: Duration,
+    /// Longest effective flush interval when idle
+    pub max_interval: Duration,
+}
+
+impl Default for AdaptiveFlush {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_millis(100),
+            max_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl AdaptiveFlush {
+    /// Adapt the flush interval between `min_interval` and `max_interval`
+    pub fn new(min_interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            max_interval,
+        }
+    }
+
+    /// Interval until the next tick, given what the last tick found
+    ///
+    /// `busy` means the queue filled faster than the interval drained it;
+    /// `pending == 0` means the tick had nothing to send.
+    fn next_interval(&self, current: Duration, pending: usize, busy: bool) -> Duration {
+        if busy {
+            (current / 2).max(self.min_interval)
+        } else if pending == 0 {
+            (current * 2).min(self.max_interval)
+        } else {
+            current
+        }
+    }
+}
+
+/// Settings of the circuit breaker
+///
+/// See [Circuit Breaker](crate::batcher#circuit-breaker).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct CircuitBreaker {
+    /// Consecutive failed requests that open the circuit
+    pub failure_threshold: u32,
+    /// How long sends are paused once the circuit is open
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Open the circuit after `failure_threshold` consecutive failures, for `cooldown`
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+        }
+    }
+}
+
+/// Change in the state of a batcher's circuit breaker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitEvent {
+    /// Sends are paused for `cooldown` after `consecutive_failures` failed requests
+    Opened {
+        /// Failed requests in a row, including the one that opened the circuit
+        consecutive_failures: u32,
+        /// How long sends are paused
+        cooldown: Duration,
+    },
+    /// A request succeeded after the cool-down and sends resume
+    Closed,
+}
+
+/// Callback invoked when a batcher's circuit breaker opens or closes
+pub type CircuitCallback = Arc<dyn Fn(&CircuitEvent) + Send + Sync>;
+
+/// Runtime state of the circuit breaker; inert without settings
+struct CircuitState {
+    settings: Option<CircuitBreaker>,
+    consecutive_failures: AtomicU32,
+    callback: Option<CircuitCallback>,
+}
+
+impl CircuitState {
+    fn new(settings: Option<CircuitBreaker>, callback: Option<CircuitCallback>) -> Self {
+        Self {
+            settings,
+            consecutive_failures: AtomicU32::new(0),
+            callback,
+        }
+    }
+
+    /// Time left until the circuit closes, if it is open
+    fn open_for(&self, metrics: &BatcherMetrics) -> Option<Duration> {
+        let until = metrics.circuit_open_until_ms.load(Ordering::Relaxed);
+        let now = now_millis();
+        (until > now).then(|| Duration::from_millis(until - now))
+    }
+
+    /// Error returned while the circuit is open
+    fn check(&self, metrics: &BatcherMetrics) -> Result<()> {
+        match self.open_for(metrics) {
+            Some(retry_in) => Err(Error::CircuitOpen { retry_in }),
+            None => Ok(()),
+        }
+    }
+
+    /// Record a request that reached Langfuse, closing the circuit if it was open
+    fn record_success(&self, metrics: &BatcherMetrics) {
+        if self.settings.is_none() {
+            return;
+        }
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        if metrics.circuit_open_until_ms.swap(0, Ordering::Relaxed) != 0 {
+            tracing::info!("Langfuse circuit breaker closed, resuming sends");
+            if let Some(callback) = &self.callback {
+                callback(&CircuitEvent::Closed);
+            }
+        }
+    }
+
+    /// Record a failed request; returns whether it opened the circuit
+    fn record_failure(&self, metrics: &BatcherMetrics) -> bool {
+        let Some(settings) = self.settings else {
+            return false;
+        };
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures < settings.failure_threshold {
+            return false;
+        }
+
+        let cooldown_ms = u64::try_from(settings.cooldown.as_millis()).unwrap_or(u64::MAX);
+        metrics.circuit_open_until_ms.store(
+            now_millis().saturating_add(cooldown_ms).max(1),
+            Ordering::Relaxed,
+        );
+        metrics.circuit_opens.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            "Langfuse circuit breaker opened after {failures} consecutive failures, pausing sends for {:?}",
+            settings.cooldown
+        );
+        if let Some(callback) = &self.callback {
+            callback(&CircuitEvent::Opened {
+                consecutive_failures: failures,
+                cooldown: settings.cooldown,
+            });
+        }
+        true
+    }
 }
 
 /// Policy for handling events when the queue is full
@@ -116,6 +440,10 @@ impl BatchEvent {
 ///   - Cons: Older events may be lost
 ///   - Use when: Recent data is more important
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
 pub enum BackpressurePolicy {
     /// Block the producer until space is available
     Block,
@@ -126,7 +454,15 @@ pub enum BackpressurePolicy {
 }
 
 /// Configuration for the batcher
+///
+/// With the `json-schema` feature it can be read from config files; missing fields
+/// take their default.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+#[cfg_attr(feature = "json-schema", serde(default))]
 pub struct BatcherConfig {
     /// Maximum number of events per batch
     pub max_events: usize,
@@ -148,6 +484,12 @@ pub struct BatcherConfig {
     pub backpressure_policy: BackpressurePolicy,
     /// Add jitter to retry delays to avoid thundering herd
     pub retry_jitter: bool,
+    /// Adjust the flush interval to the queue depth, if set
+    pub adaptive_flush: Option<AdaptiveFlush>,
+    /// Pause sends after repeated failures, if set
+    pub circuit_breaker: Option<CircuitBreaker>,
+    /// Skip events whose ID is among this many most recently added, if set
+    pub dedup_capacity: Option<usize>,
 }
 
 impl Default for BatcherConfig {
@@ -163,6 +505,9 @@ impl Default for BatcherConfig {
             max_queue_size: 10000,
             backpressure_policy: BackpressurePolicy::Block,
             retry_jitter: true,
+            adaptive_flush: None,
+            circuit_breaker: None,
+            dedup_capacity: None,
         }
     }
 }
@@ -182,11 +527,45 @@ pub struct BatcherMetrics {
     pub retries: AtomicU64,
     /// Timestamp of last error (seconds since epoch)
     pub last_error_ts: AtomicU64,
+    /// Highest number of events waiting at once (channel plus buffer)
+    pub queue_high_water: AtomicU64,
+    /// Total milliseconds the queue spent full, excluding an ongoing saturation period
+    pub saturated_ms: AtomicU64,
+    /// Start of the ongoing saturation period (milliseconds since epoch), or 0 if not saturated
+    pub saturated_since_ms: AtomicU64,
+    /// Longest time an event waited between being added and being sent (milliseconds)
+    pub max_wait_ms: AtomicU64,
+    /// Current effective flush interval (milliseconds)
+    pub flush_interval_ms: AtomicU64,
+    /// Number of times the circuit breaker opened
+    pub circuit_opens: AtomicU64,
+    /// When the open circuit closes again (milliseconds since epoch), or 0 if closed
+    pub circuit_open_until_ms: AtomicU64,
+    /// Total events skipped because their ID was added recently
+    pub deduplicated: AtomicU64,
+    /// What the batcher last reported to the `metrics` recorder
+    #[cfg(feature = "metrics")]
+    pub(crate) export: crate::metrics_export::ExportState,
+}
+
+/// Milliseconds since the Unix epoch
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
 }
 
 impl BatcherMetrics {
     /// Get a snapshot of current metrics
     pub fn snapshot(&self) -> BatcherMetricsSnapshot {
+        let saturated_since = self.saturated_since_ms.load(Ordering::Relaxed);
+        let ongoing = if saturated_since == 0 {
+            0
+        } else {
+            now_millis().saturating_sub(saturated_since)
+        };
+
         BatcherMetricsSnapshot {
             queued: self.queued.load(Ordering::Relaxed),
             flushed: self.flushed.load(Ordering::Relaxed),
@@ -194,8 +573,45 @@ impl BatcherMetrics {
             dropped: self.dropped.load(Ordering::Relaxed),
             retries: self.retries.load(Ordering::Relaxed),
             last_error_ts: self.last_error_ts.load(Ordering::Relaxed),
+            queue_high_water: self.queue_high_water.load(Ordering::Relaxed),
+            saturated_ms: self.saturated_ms.load(Ordering::Relaxed) + ongoing,
+            max_wait_ms: self.max_wait_ms.load(Ordering::Relaxed),
+            flush_interval_ms: self.flush_interval_ms.load(Ordering::Relaxed),
+            circuit_opens: self.circuit_opens.load(Ordering::Relaxed),
+            circuit_open: self.circuit_open_until_ms.load(Ordering::Relaxed) > now_millis(),
+            deduplicated: self.deduplicated.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Record the current queue depth for high-water mark tracking
+    fn observe_depth(&self, depth: u64) {
+        self.queue_high_water.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Mark the queue as full, starting a saturation period if none is ongoing
+    fn mark_saturated(&self) {
+        let _ = self.saturated_since_ms.compare_exchange(
+            0,
+            now_millis().max(1),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// End the ongoing saturation period, if any, and accumulate its duration
+    fn clear_saturated(&self) {
+        let since = self.saturated_since_ms.swap(0, Ordering::Relaxed);
+        if since != 0 {
+            self.saturated_ms
+                .fetch_add(now_millis().saturating_sub(since), Ordering::Relaxed);
         }
     }
+
+    /// Record how long an event waited before being sent
+    fn observe_wait(&self, waited: Duration) {
+        self.max_wait_ms
+            .fetch_max(waited.as_millis() as u64, Ordering::Relaxed);
+    }
 }
 
 /// Snapshot of batcher metrics at a point in time
@@ -213,6 +629,245 @@ pub struct BatcherMetricsSnapshot {
     pub retries: u64,
     /// Unix timestamp of last error (seconds since epoch)
     pub last_error_ts: u64,
+    /// Highest number of events waiting at once (channel plus buffer)
+    pub queue_high_water: u64,
+    /// Total milliseconds the queue spent full, including an ongoing saturation period
+    pub saturated_ms: u64,
+    /// Longest time an event waited between being added and being sent (milliseconds)
+    pub max_wait_ms: u64,
+    /// Current effective flush interval (milliseconds)
+    pub flush_interval_ms: u64,
+    /// Number of times the circuit breaker opened
+    pub circuit_opens: u64,
+    /// Whether sends are currently paused by the circuit breaker
+    pub circuit_open: bool,
+    /// Total events skipped because their ID was added recently
+    pub deduplicated: u64,
+}
+
+/// Number of pending events by how long they have been waiting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingAgeBuckets {
+    /// Waiting less than 1 second
+    pub under_1s: usize,
+    /// Waiting between 1 and 10 seconds
+    pub under_10s: usize,
+    /// Waiting between 10 and 60 seconds
+    pub under_60s: usize,
+    /// Waiting 60 seconds or longer
+    pub over_60s: usize,
+}
+
+impl PendingAgeBuckets {
+    fn record(&mut self, age: Duration) {
+        match age.as_secs() {
+            0 => self.under_1s += 1,
+            1..=9 => self.under_10s += 1,
+            10..=59 => self.under_60s += 1,
+            _ => self.over_60s += 1,
+        }
+    }
+}
+
+/// Summary of the events waiting to be sent, for debugging stuck queues
+#[derive(Debug, Clone, Default)]
+pub struct PendingEventsSummary {
+    /// Events in the batch buffer
+    pub buffered: usize,
+    /// Events accepted by [`Batcher::add`] but not yet moved into the buffer
+    ///
+    /// These are not included in the type and age breakdowns.
+    pub in_channel: usize,
+    /// Buffered events grouped by ingestion event type (e.g. `span-create`)
+    pub by_type: BTreeMap<&'static str, usize>,
+    /// Buffered events grouped by time since they were added
+    pub by_age: PendingAgeBuckets,
+    /// Buffered events that have already been retried at least once
+    pub retrying: usize,
+    /// Serialized size of the buffered events in bytes
+    pub total_bytes: usize,
+    /// How long the oldest buffered event has been waiting
+    pub oldest_age: Option<Duration>,
+}
+
+/// Outcome of [`Batcher::drain`], suitable for logging as JSON
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DrainReport {
+    /// Total events successfully flushed over the batcher's lifetime
+    pub flushed: u64,
+    /// Total events that failed after all retries
+    pub failed: u64,
+    /// Total events dropped due to backpressure
+    pub dropped: u64,
+    /// Total retry attempts made
+    pub retries: u64,
+    /// Events still waiting when the drain finished
+    pub remaining: usize,
+    /// Time spent draining in milliseconds
+    pub elapsed_ms: u64,
+    /// Whether the deadline expired before all events were sent
+    pub timed_out: bool,
+}
+
+/// Outcome of an event added with [`Batcher::add_with_ack`]
+#[derive(Debug, Clone)]
+pub enum DeliveryResult {
+    /// Langfuse confirmed the event
+    Delivered,
+    /// Langfuse rejected the event, or it failed after all retries
+    Failed(EventError),
+    /// Dropped from the full queue under [`BackpressurePolicy::DropOldest`],
+    /// not sampled by the client's [`Sampler`](crate::Sampler), or skipped as
+    /// a duplicate of a recently added event (see
+    /// [`BatcherConfig::dedup_capacity`])
+    Dropped,
+}
+
+/// Senders of pending delivery notifications, by event ID
+#[derive(Default)]
+struct Deliveries {
+    pending: std::sync::Mutex<HashMap<String, oneshot::Sender<DeliveryResult>>>,
+}
+
+impl Deliveries {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<DeliveryResult>>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Rejects an ID that already has a pending notification, unless its
+    /// receiver was dropped
+    fn register(&self, id: String, sender: oneshot::Sender<DeliveryResult>) -> Result<()> {
+        match self.lock().entry(id) {
+            Entry::Occupied(entry) if !entry.get().is_closed() => Err(Error::Validation(format!(
+                "Event `{}` already has a pending delivery notification",
+                entry.key()
+            ))),
+            Entry::Occupied(mut entry) => {
+                entry.insert(sender);
+                Ok(())
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(sender);
+                Ok(())
+            }
+        }
+    }
+
+    fn cancel(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// Drop every pending sender, so receivers of events that can no longer
+    /// be sent resolve with an error
+    fn abandon(&self) {
+        self.lock().clear();
+    }
+
+    fn notify(&self, id: &str, result: DeliveryResult) {
+        if let Some(sender) = self.lock().remove(id) {
+            let _ = sender.send(result);
+        }
+    }
+
+    /// Notify every event of a flush that is not going back into the queue
+    ///
+    /// Events without a success or failure entry failed with `fallback`.
+    fn settle(
+        &self,
+        events: &[BatchEvent],
+        retry_queue: &[BatchEvent],
+        success_ids: &[String],
+        failures: &[EventError],
+        fallback: &str,
+    ) {
+        if self.lock().is_empty() {
+            return;
+        }
+        for event in events {
+            if retry_queue.iter().any(|r| r.id == event.id) {
+                continue;
+            }
+            let result = if success_ids.contains(&event.id) {
+                DeliveryResult::Delivered
+            } else {
+                DeliveryResult::Failed(
+                    failures
+                        .iter()
+                        .find(|f| f.event_id == event.id)
+                        .cloned()
+                        .unwrap_or_else(|| EventError {
+                            event_id: event.id.clone(),
+                            message: fallback.to_string(),
+                            code: None,
+                            retryable: false,
+                            validation_details: Vec::new(),
+                        }),
+                )
+            };
+            self.notify(&event.id, result);
+        }
+    }
+}
+
+/// IDs of the most recently added events, evicting the least recently seen
+struct RecentIds {
+    capacity: usize,
+    seen: std::sync::Mutex<SeenIds>,
+}
+
+#[derive(Default)]
+struct SeenIds {
+    tick: u64,
+    last_seen: HashMap<String, u64>,
+    by_tick: BTreeMap<u64, String>,
+}
+
+impl RecentIds {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seen: std::sync::Mutex::new(SeenIds::default()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SeenIds> {
+        self.seen
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Record `id` as the most recent, returning whether it was not seen before
+    fn insert(&self, id: &str) -> bool {
+        let mut guard = self.lock();
+        let seen = &mut *guard;
+        seen.tick += 1;
+        let tick = seen.tick;
+
+        if let Some(last) = seen.last_seen.get_mut(id) {
+            let previous = std::mem::replace(last, tick);
+            seen.by_tick.remove(&previous);
+            seen.by_tick.insert(tick, id.to_string());
+            return false;
+        }
+
+        if seen.last_seen.len() >= self.capacity {
+            if let Some((_, oldest)) = seen.by_tick.pop_first() {
+                seen.last_seen.remove(&oldest);
+            }
+        }
+        seen.last_seen.insert(id.to_string(), tick);
+        seen.by_tick.insert(tick, id.to_string());
+        true
+    }
+
+    fn remove(&self, id: &str) {
+        let mut seen = self.lock();
+        if let Some(tick) = seen.last_seen.remove(id) {
+            seen.by_tick.remove(&tick);
+        }
+    }
 }
 
 /// Batch ingestion handler with automatic chunking and retries
@@ -221,13 +876,19 @@ pub struct Batcher {
     config: BatcherConfig,
     buffer: Arc<Mutex<VecDeque<BatchEvent>>>, // VecDeque for O(1) DropOldest
     buffer_size: Arc<AtomicUsize>,            // Track running size for O(1) access
-    tx: mpsc::Sender<BatchEvent>,
-    rx: Arc<Mutex<mpsc::Receiver<BatchEvent>>>,
+    tx: mpsc::Sender<Command>,
+    rx: Arc<Mutex<mpsc::Receiver<Command>>>,
     shutdown_tx: mpsc::Sender<()>,
     metrics: Arc<BatcherMetrics>,
     flush_mutex: Arc<Mutex<()>>,
     shutdown_flag: Arc<AtomicBool>,
     task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    queue_backend: Option<Arc<dyn QueueBackend>>,
+    circuit: Arc<CircuitState>,
+    deliveries: Arc<Deliveries>,
+    recent_ids: Option<Arc<RecentIds>>,
+    /// Key of the batcher in the [registry](crate::registry)
+    registry_id: u64,
 }
 
 /// Builder type used once the required client has been provided via [`BatcherBuilder::client`].
@@ -237,7 +898,7 @@ pub type BatcherBuilderWithClient =
 #[bon]
 impl Batcher {
     /// Extract a stable identifier from any ingestion event variant Langfuse supports.
-    fn extract_event_id(event: &IngestionEvent) -> String {
+    pub(crate) fn extract_event_id(event: &IngestionEvent) -> String {
         match event {
             IngestionEvent::IngestionEventOneOf(e) => e.id.clone(),
             IngestionEvent::IngestionEventOneOf1(e) => e.id.clone(),
@@ -252,6 +913,39 @@ impl Batcher {
         }
     }
 
+    /// Trace an ingestion event belongs to, if any
+    pub(crate) fn event_trace_id(event: &IngestionEvent) -> Option<&str> {
+        let trace_id = match event {
+            IngestionEvent::IngestionEventOneOf(e) => &e.body.id,
+            IngestionEvent::IngestionEventOneOf1(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf2(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf3(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf4(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf5(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf6(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf7(_) => return None,
+            IngestionEvent::IngestionEventOneOf8(e) => &e.body.trace_id,
+            IngestionEvent::IngestionEventOneOf9(e) => &e.body.trace_id,
+        };
+        trace_id.as_ref().and_then(|id| id.as_deref())
+    }
+
+    /// Ingestion event type name as it appears in the API payload
+    fn event_type(event: &IngestionEvent) -> &'static str {
+        match event {
+            IngestionEvent::IngestionEventOneOf(_) => "trace-create",
+            IngestionEvent::IngestionEventOneOf1(_) => "score-create",
+            IngestionEvent::IngestionEventOneOf2(_) => "span-create",
+            IngestionEvent::IngestionEventOneOf3(_) => "span-update",
+            IngestionEvent::IngestionEventOneOf4(_) => "generation-create",
+            IngestionEvent::IngestionEventOneOf5(_) => "generation-update",
+            IngestionEvent::IngestionEventOneOf6(_) => "event-create",
+            IngestionEvent::IngestionEventOneOf7(_) => "sdk-log",
+            IngestionEvent::IngestionEventOneOf8(_) => "observation-create",
+            IngestionEvent::IngestionEventOneOf9(_) => "observation-update",
+        }
+    }
+
     /// Create a new batcher with custom configuration
     #[builder]
     pub async fn new(
@@ -266,6 +960,16 @@ impl Batcher {
         fail_fast: Option<bool>,
         max_queue_size: Option<usize>,
         backpressure_policy: Option<BackpressurePolicy>,
+        adaptive_flush: Option<AdaptiveFlush>,
+        /// Journal queued events here and replay the ones left from a previous run
+        #[builder(with = |backend: impl QueueBackend + 'static| Arc::new(backend) as Arc<dyn QueueBackend>)]
+        queue_backend: Option<Arc<dyn QueueBackend>>,
+        circuit_breaker: Option<CircuitBreaker>,
+        /// Called when the circuit breaker opens or closes
+        #[builder(with = |callback: impl Fn(&CircuitEvent) + Send + Sync + 'static| Arc::new(callback) as CircuitCallback)]
+        on_circuit_change: Option<CircuitCallback>,
+        /// Skip events whose ID is among this many most recently added
+        dedup_capacity: Option<usize>,
     ) -> Self {
         let config = BatcherConfig {
             max_events: max_events.unwrap_or(DEFAULT_MAX_EVENTS),
@@ -278,22 +982,58 @@ impl Batcher {
             fail_fast: fail_fast.unwrap_or(false),
             max_queue_size: max_queue_size.unwrap_or(10000),
             backpressure_policy: backpressure_policy.unwrap_or(BackpressurePolicy::Block),
+            adaptive_flush,
+            circuit_breaker,
+            dedup_capacity: dedup_capacity.filter(|&capacity| capacity > 0),
         };
 
         let (tx, rx) = mpsc::channel(config.max_queue_size);
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel(1);
 
+        let registry_id = crate::registry::next_id();
         let metrics = Arc::new(BatcherMetrics::default());
+        metrics
+            .flush_interval_ms
+            .store(config.flush_interval.as_millis() as u64, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        {
+            let tx = tx.downgrade();
+            metrics.export.init(registry_id, move || {
+                tx.upgrade()
+                    .map_or(0, |tx| tx.max_capacity() - tx.capacity())
+            });
+        }
         let flush_mutex = Arc::new(Mutex::new(()));
         let shutdown_flag = Arc::new(AtomicBool::new(false));
         let buffer_size = Arc::new(AtomicUsize::new(0));
 
         let task_handle = Arc::new(Mutex::new(None));
 
+        // Events a previous batcher journaled but never finished with
+        let mut replayed = VecDeque::new();
+        if let Some(backend) = &queue_backend {
+            match backend.replay().await {
+                Ok(events) => {
+                    for event in events {
+                        let id = Self::extract_event_id(&event);
+                        match BatchEvent::new(event, id) {
+                            Ok(event) => replayed.push_back(event),
+                            Err(e) => tracing::warn!("Skipping unreplayable queued event: {e}"),
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to replay queued events: {e}"),
+            }
+        }
+        buffer_size.store(replayed.iter().map(|e| e.size).sum(), Ordering::Relaxed);
+        metrics
+            .queued
+            .store(replayed.len() as u64, Ordering::Relaxed);
+
         let batcher = Self {
             client: Arc::new(client),
             config: config.clone(),
-            buffer: Arc::new(Mutex::new(VecDeque::new())),
+            buffer: Arc::new(Mutex::new(replayed)),
             buffer_size: buffer_size.clone(),
             tx,
             rx: Arc::new(Mutex::new(rx)),
@@ -302,6 +1042,13 @@ impl Batcher {
             flush_mutex: flush_mutex.clone(),
             shutdown_flag: shutdown_flag.clone(),
             task_handle: task_handle.clone(),
+            queue_backend: queue_backend.clone(),
+            circuit: Arc::new(CircuitState::new(circuit_breaker, on_circuit_change)),
+            deliveries: Arc::new(Deliveries::default()),
+            recent_ids: config
+                .dedup_capacity
+                .map(|capacity| Arc::new(RecentIds::new(capacity))),
+            registry_id,
         };
 
         // Start background flush task
@@ -312,28 +1059,61 @@ impl Batcher {
         let metrics_clone = metrics.clone();
         let flush_mutex_clone = flush_mutex.clone();
         let shutdown_flag_clone = shutdown_flag.clone();
+        let backend = queue_backend;
+        let circuit = batcher.circuit.clone();
+        let deliveries = batcher.deliveries.clone();
 
         let handle = tokio::spawn(async move {
-            let mut flush_interval = interval(config.flush_interval);
-            flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
-            // Consume the first immediate tick
-            flush_interval.tick().await;
+            let mut current_interval = config.flush_interval;
+            let mut next_tick = Instant::now() + current_interval;
+            // Whether a size-triggered flush happened since the last tick
+            let mut flushed_on_size = false;
 
             loop {
                 tokio::select! {
-                    _ = flush_interval.tick() => {
-                        let _ = Self::flush_buffer(&client, &buffer, &buffer_size_clone, &config, &metrics_clone, &flush_mutex_clone).await;
+                    _ = sleep_until(next_tick.into()) => {
+                        let pending = buffer.lock().await.len();
+                        let _ = Self::flush_buffer(&client, &buffer, &buffer_size_clone, &config, &metrics_clone, &flush_mutex_clone, backend.as_deref(), &circuit, &deliveries).await;
+
+                        if let Some(adaptive) = &config.adaptive_flush {
+                            let busy = flushed_on_size || pending >= config.max_events.div_ceil(2);
+                            current_interval = adaptive.next_interval(current_interval, pending, busy);
+                            metrics_clone
+                                .flush_interval_ms
+                                .store(current_interval.as_millis() as u64, Ordering::Relaxed);
+                        }
+                        flushed_on_size = false;
+                        next_tick = Instant::now() + current_interval;
                     }
-                    Some(event) = async {
+                    Some(command) = async {
                         let mut rx = rx.lock().await;
                         rx.recv().await
                     } => {
+                        let event = match command {
+                            Command::Event(event) => event,
+                            Command::Sync(reply) => {
+                                // Every event sent before this is in the buffer or flushed
+                                let _ = reply.send(());
+                                continue;
+                            }
+                        };
                         metrics_clone.queued.fetch_add(1, Ordering::Relaxed);
+                        // A slot just opened up in the channel
+                        metrics_clone.clear_saturated();
 
                         let event_size = event.size;
                         let should_flush = {
                             let mut buf = buffer.lock().await;
+
+                            // First event after an idle stretch: don't let it wait
+                            // longer than the configured interval
+                            if buf.is_empty() && current_interval > config.flush_interval {
+                                current_interval = config.flush_interval;
+                                next_tick = next_tick.min(Instant::now() + current_interval);
+                                metrics_clone
+                                    .flush_interval_ms
+                                    .store(current_interval.as_millis() as u64, Ordering::Relaxed);
+                            }
                             buf.push_back(event);
 
                             // Update running size atomically
@@ -345,30 +1125,47 @@ impl Batcher {
                         };
 
                         if should_flush {
-                            let _ = Self::flush_buffer(&client, &buffer, &buffer_size_clone, &config, &metrics_clone, &flush_mutex_clone).await;
+                            flushed_on_size = true;
+                            let _ = Self::flush_buffer(&client, &buffer, &buffer_size_clone, &config, &metrics_clone, &flush_mutex_clone, backend.as_deref(), &circuit, &deliveries).await;
                         }
                     }
-                    _ = shutdown_rx.recv() => {
+                    signal = shutdown_rx.recv() => {
                         shutdown_flag_clone.store(true, Ordering::Relaxed);
 
-                        // Drain any remaining events from the channel before shutting down
+                        // Drain any remaining events from the channel before shutting down.
+                        // Closing it first makes later sends fail instead of waiting
+                        // on a task that no longer runs.
                         {
                             let mut rx_guard = rx.lock().await;
-                            while let Ok(event) = rx_guard.try_recv() {
-                                let event_size = event.size;
-                                let mut buf = buffer.lock().await;
-                                buf.push_back(event);
-                                buffer_size_clone.fetch_add(event_size, Ordering::Relaxed);
-                                metrics_clone.queued.fetch_add(1, Ordering::Relaxed);
+                            rx_guard.close();
+                            while let Ok(command) = rx_guard.try_recv() {
+                                match command {
+                                    Command::Event(event) => {
+                                        let event_size = event.size;
+                                        let mut buf = buffer.lock().await;
+                                        buf.push_back(event);
+                                        buffer_size_clone.fetch_add(event_size, Ordering::Relaxed);
+                                        metrics_clone.queued.fetch_add(1, Ordering::Relaxed);
+                                    }
+                                    Command::Sync(reply) => {
+                                        let _ = reply.send(());
+                                    }
+                                }
                             }
                         }
 
                         // Final flush before shutdown
-                        let _ = Self::flush_buffer(&client, &buffer, &buffer_size_clone, &config, &metrics_clone, &flush_mutex_clone).await;
+                        let _ = Self::flush_buffer(&client, &buffer, &buffer_size_clone, &config, &metrics_clone, &flush_mutex_clone, backend.as_deref(), &circuit, &deliveries).await;
+                        // Every batcher was dropped, so nothing flushes what is left.
+                        // Handles and the registry keep `deliveries` alive.
+                        if signal.is_none() {
+                            deliveries.abandon();
+                        }
                         break;
                     }
                 }
             }
+            crate::registry::unregister(registry_id);
         });
 
         // Store the task handle
@@ -377,78 +1174,190 @@ impl Batcher {
             *handle_guard = Some(handle);
         }
 
+        crate::registry::register(batcher.registry_id, batcher.downgrade());
         batcher
     }
 
+    /// Reference to the batcher for the registry that does not keep it running
+    ///
+    /// The background task stops once every [`Batcher`] owning the shutdown
+    /// channel is dropped, so the registry holds only weak senders.
+    pub(crate) fn downgrade(&self) -> WeakBatcher {
+        WeakBatcher {
+            tx: self.tx.downgrade(),
+            shutdown_tx: self.shutdown_tx.downgrade(),
+            batcher: WeakParts {
+                client: self.client.clone(),
+                config: self.config.clone(),
+                buffer: self.buffer.clone(),
+                buffer_size: self.buffer_size.clone(),
+                rx: self.rx.clone(),
+                metrics: self.metrics.clone(),
+                flush_mutex: self.flush_mutex.clone(),
+                shutdown_flag: self.shutdown_flag.clone(),
+                task_handle: self.task_handle.clone(),
+                queue_backend: self.queue_backend.clone(),
+                circuit: self.circuit.clone(),
+                deliveries: self.deliveries.clone(),
+                recent_ids: self.recent_ids.clone(),
+                registry_id: self.registry_id,
+            },
+        }
+    }
+
     /// Add an event to the batch
+    ///
+    /// To add events from several tasks, give each a [`BatcherHandle`].
     pub async fn add(&self, event: IngestionEvent) -> Result<()> {
-        // Check if shutdown has been called
-        if self.shutdown_flag.load(Ordering::Relaxed) {
-            return Err(Error::Api("Batcher is shutting down".to_string()));
+        self.handle().add(event).await
+    }
+
+    /// Add an event and get notified once it is delivered, fails, or is dropped
+    ///
+    /// The receiver resolves when Langfuse confirms the event, when it is
+    /// rejected or runs out of retries, or when backpressure drops it. It
+    /// resolves with an error if the batcher is shut down or dropped with the
+    /// event still queued, for example when the final flush is blocked by the
+    /// circuit breaker.
+    ///
+    /// Event IDs identify the notification, so adding an event whose ID
+    /// already has a pending notification fails with [`Error::Validation`].
+    ///
+    /// ```no_run
+    /// use langfuse_ergonomic::{Batcher, ClientBuilder, DeliveryResult};
+    ///
+    /// # async fn example(event: langfuse_ergonomic::IngestionEvent) -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::from_env()?.build()?;
+    /// let batcher = Batcher::builder().client(client).build().await;
+    ///
+    /// let ack = batcher.add_with_ack(event).await?;
+    /// batcher.flush().await?;
+    /// match ack.await? {
+    ///     DeliveryResult::Delivered => println!("Ingested"),
+    ///     DeliveryResult::Failed(error) => eprintln!("Failed: {error}"),
+    ///     DeliveryResult::Dropped => eprintln!("Dropped by backpressure"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn add_with_ack(
+        &self,
+        event: IngestionEvent,
+    ) -> Result<oneshot::Receiver<DeliveryResult>> {
+        self.handle().add_with_ack(event).await
+    }
+
+    /// Clonable handle for adding events from other tasks
+    ///
+    /// Handles share this batcher's queue, backpressure policy, and metrics.
+    /// They don't keep it running: once the batcher is shut down or drained,
+    /// adding through a handle fails.
+    pub fn handle(&self) -> BatcherHandle {
+        BatcherHandle {
+            tx: self.tx.clone(),
+            buffer: self.buffer.clone(),
+            buffer_size: self.buffer_size.clone(),
+            metrics: self.metrics.clone(),
+            shutdown_flag: self.shutdown_flag.clone(),
+            max_bytes: self.config.max_bytes,
+            backpressure_policy: self.config.backpressure_policy,
+            queue_backend: self.queue_backend.clone(),
+            deliveries: self.deliveries.clone(),
+            recent_ids: self.recent_ids.clone(),
+            interceptors: self.client.interceptors.clone(),
+            sampler: self.client.sampler.clone(),
         }
+    }
 
-        let id = Self::extract_event_id(&event);
+    /// Queue a trace, built like [`LangfuseClient::trace`]
+    ///
+    /// `call()` adds the `trace-create` event to this batcher instead of
+    /// sending it, and returns once the event is queued. As with `build_event`,
+    /// large fields are not offloaded as media. Setting `dataset_run` fails
+    /// with [`Error::Validation`], since the dataset run item can only be
+    /// created once the trace is ingested; use [`LangfuseClient::trace`] for
+    /// traces of dataset runs.
+    ///
+    /// ```no_run
+    /// use langfuse_ergonomic::{Batcher, ClientBuilder};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::from_env()?.build()?;
+    /// let batcher = Batcher::builder().client(client).build().await;
+    ///
+    /// let trace = batcher.trace().name("checkout").call().await?;
+    /// let span_id = batcher
+    ///     .span()
+    ///     .trace_id(&trace.id)
+    ///     .name("fetch-cart")
+    ///     .call()
+    ///     .await?;
+    /// batcher
+    ///     .generation()
+    ///     .trace_id(&trace.id)
+    ///     .parent_observation_id(span_id)
+    ///     .name("summarize")
+    ///     .model("gpt-4o")
+    ///     .call()
+    ///     .await?;
+    /// batcher
+    ///     .score()
+    ///     .trace_id(&trace.id)
+    ///     .name("quality")
+    ///     .value(0.9)
+    ///     .call()
+    ///     .await?;
+    ///
+    /// batcher.shutdown().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn trace(&self) -> BatchedTraceBuilder<'_> {
+        self.client.batched_trace(self.handle())
+    }
 
-        let batch_event = BatchEvent::new(event, id.clone())?;
+    /// Queue a span, built like [`LangfuseClient::span`]
+    ///
+    /// See [`trace`](Self::trace).
+    pub fn span(&self) -> BatchedSpanBuilder<'_> {
+        self.client.batched_span(self.handle())
+    }
 
-        // Check size limit
-        if batch_event.size > self.config.max_bytes {
-            return Err(Error::BatchSizeExceeded {
-                size: batch_event.size,
-                max_size: self.config.max_bytes,
-            });
-        }
+    /// Queue a generation, built like [`LangfuseClient::generation`]
+    ///
+    /// See [`trace`](Self::trace).
+    pub fn generation(&self) -> BatchedGenerationBuilder<'_> {
+        self.client.batched_generation(self.handle())
+    }
 
-        // Handle backpressure based on policy
-        match self.config.backpressure_policy {
-            BackpressurePolicy::Block => {
-                // Block until space is available
-                self.tx
-                    .send(batch_event)
-                    .await
-                    .map_err(|e| Error::Api(format!("Failed to queue event: {e}")))?;
-            }
-            BackpressurePolicy::DropNew => {
-                // Try to send, drop if full
-                match self.tx.try_send(batch_event) {
-                    Ok(()) => {}
-                    Err(mpsc::error::TrySendError::Full(_)) => {
-                        self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
-                        return Err(Error::Backpressure {
-                            policy: BackpressurePolicy::DropNew,
-                            reason: "Queue full, new event dropped".to_string(),
-                        });
-                    }
-                    Err(e) => return Err(Error::Api(format!("Failed to queue event: {e}"))),
-                }
-            }
-            BackpressurePolicy::DropOldest => {
-                // Try to send, if full, remove oldest and retry
-                match self.tx.try_send(batch_event.clone()) {
-                    Ok(()) => {}
-                    Err(mpsc::error::TrySendError::Full(_)) => {
-                        // Remove oldest from buffer
-                        {
-                            let mut buf = self.buffer.lock().await;
-                            if let Some(dropped) = buf.pop_front() {
-                                // Update buffer size when dropping - O(1) with VecDeque
-                                self.buffer_size.fetch_sub(dropped.size, Ordering::Relaxed);
-                                self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
-                                self.metrics.queued.fetch_sub(1, Ordering::Relaxed);
-                            }
-                        }
-                        // Try again with blocking send
-                        self.tx
-                            .send(batch_event)
-                            .await
-                            .map_err(|e| Error::Api(format!("Failed to queue event: {e}")))?;
-                    }
-                    Err(e) => return Err(Error::Api(format!("Failed to queue event: {e}"))),
-                }
-            }
-        }
+    /// Queue an event, built like [`LangfuseClient::event`]
+    ///
+    /// See [`trace`](Self::trace).
+    pub fn event(&self) -> BatchedEventBuilder<'_> {
+        self.client.batched_event(self.handle())
+    }
 
-        Ok(())
+    /// Queue a score, built like [`LangfuseClient::score`]
+    ///
+    /// See [`trace`](Self::trace).
+    pub fn score(&self) -> BatchedScoreBuilder<'_> {
+        self.client.batched_score(self.handle())
+    }
+
+    /// Queue many scores, built like [`LangfuseClient::scores_batch`]
+    ///
+    /// Returns the ID of each score, or why it is invalid or could not be
+    /// queued, in the order of `scores`. Delivery is reported by flushes like
+    /// for any other event.
+    pub async fn scores_batch(&self, scores: Vec<ScoreInput>) -> Vec<Result<String>> {
+        self.client.batched_scores(self.handle(), scores).await
+    }
+
+    /// Remove events from the queue backend, logging failures
+    async fn acknowledge(backend: &dyn QueueBackend, event_ids: &[String]) {
+        if let Err(e) = backend.acknowledge(event_ids).await {
+            tracing::warn!("Failed to acknowledge queued events: {e}");
+        }
     }
 
     /// Manually flush the current batch
@@ -460,6 +1369,7 @@ impl Batcher {
     /// - Returns immediately after the flush attempt, even if events are re-queued
     ///
     /// ## Deterministic Behavior
+    /// - Every event added before the call is included; no timing is involved
     /// - Events are processed in FIFO order
     /// - Flush waits for all pending batches to complete
     /// - Metrics are updated atomically and accurately reflect state
@@ -470,8 +1380,7 @@ impl Batcher {
     /// The response may indicate partial failures even for events that will be retried.
     /// This allows callers to track which events succeeded immediately vs required retry.
     pub async fn flush(&self) -> Result<IngestionResponse> {
-        // Give background task time to add pending events to buffer
-        tokio::time::sleep(Duration::from_millis(50)).await;
+        self.sync().await;
 
         // Flush the buffer
         Self::flush_buffer(
@@ -481,31 +1390,126 @@ impl Batcher {
             &self.config,
             &self.metrics,
             &self.flush_mutex,
+            self.queue_backend.as_deref(),
+            &self.circuit,
+            &self.deliveries,
         )
         .await
     }
 
+    /// Flush the pending events of one trace ahead of the rest of the queue
+    ///
+    /// Sends only the buffered events belonging to `trace_id`, for example
+    /// before returning a response that links to the trace, without waiting
+    /// for or forcing a full flush. Other events stay queued and are sent on
+    /// the usual schedule. Retry and failure handling is the same as for
+    /// [`flush`](Self::flush).
+    pub async fn flush_trace(&self, trace_id: &str) -> Result<IngestionResponse> {
+        self.sync().await;
+
+        let result = Self::flush_events(
+            &self.client,
+            &self.buffer,
+            &self.buffer_size,
+            &self.config,
+            &self.metrics,
+            &self.flush_mutex,
+            self.queue_backend.as_deref(),
+            &self.circuit,
+            &self.deliveries,
+            Some(trace_id),
+        )
+        .await;
+        #[cfg(feature = "metrics")]
+        crate::metrics_export::publish(&self.metrics);
+        result
+    }
+
+    /// Wait until the background task has moved every event added so far into the buffer
+    ///
+    /// Returns at once if the task has stopped, since it moves the channel's
+    /// remaining events into the buffer before stopping.
+    async fn sync(&self) {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        if self.tx.send(Command::Sync(reply_tx)).await.is_ok() {
+            let _ = reply_rx.await;
+        }
+    }
+
+    /// Get a redacted snapshot of the effective configuration, including the client's
+    ///
+    /// See [`debug_config`](crate::debug_config) for the format.
+    pub fn debug_config(&self) -> BatcherDebugConfig {
+        let millis = |d: Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+        BatcherDebugConfig {
+            max_events: self.config.max_events,
+            max_bytes: self.config.max_bytes,
+            flush_interval_ms: millis(self.config.flush_interval),
+            adaptive_flush_min_ms: self.config.adaptive_flush.map(|a| millis(a.min_interval)),
+            adaptive_flush_max_ms: self.config.adaptive_flush.map(|a| millis(a.max_interval)),
+            max_retries: self.config.max_retries,
+            initial_retry_delay_ms: millis(self.config.initial_retry_delay),
+            max_retry_delay_ms: millis(self.config.max_retry_delay),
+            retry_jitter: self.config.retry_jitter,
+            fail_fast: self.config.fail_fast,
+            max_queue_size: self.config.max_queue_size,
+            backpressure_policy: format!("{:?}", self.config.backpressure_policy),
+            persistent_queue: self.queue_backend.is_some(),
+            circuit_failure_threshold: self.config.circuit_breaker.map(|c| c.failure_threshold),
+            circuit_cooldown_ms: self.config.circuit_breaker.map(|c| millis(c.cooldown)),
+            dedup_capacity: self.config.dedup_capacity,
+            client: self.client.debug_config(),
+        }
+    }
+
     /// Get current metrics
     pub fn metrics(&self) -> BatcherMetricsSnapshot {
         self.metrics.snapshot()
     }
 
+    /// Summarize the events waiting to be sent
+    ///
+    /// Counts buffered events by type and age without cloning their payloads,
+    /// which helps answer "what is stuck in the queue?" during incidents.
+    pub async fn pending_events_summary(&self) -> PendingEventsSummary {
+        let now = Instant::now();
+        let buffer = self.buffer.lock().await;
+
+        let mut summary = PendingEventsSummary {
+            buffered: buffer.len(),
+            in_channel: self.tx.max_capacity() - self.tx.capacity(),
+            ..PendingEventsSummary::default()
+        };
+        for event in buffer.iter() {
+            let age = now.saturating_duration_since(event.enqueued_at);
+            *summary
+                .by_type
+                .entry(Self::event_type(&event.event))
+                .or_default() += 1;
+            summary.by_age.record(age);
+            summary.total_bytes += event.size;
+            if event.retry_count > 0 {
+                summary.retrying += 1;
+            }
+            summary.oldest_age = summary.oldest_age.max(Some(age));
+        }
+        summary
+    }
+
     /// Wait for all pending events to be processed
     ///
     /// ## Behavior
-    /// - Blocks until the queue is empty
+    /// - Returns once the background task has taken in every event added
+    ///   before the call, or right away if the task has stopped
     /// - Does NOT guarantee events were successfully sent
     /// - Use `flush()` after this to ensure events are sent
     /// - Useful for ensuring all events are queued before shutdown
     pub async fn wait_for_pending(&self) {
-        // Wait until queue is empty
-        while self.metrics.queued.load(Ordering::Relaxed) > 0 {
-            tokio::time::sleep(Duration::from_millis(10)).await;
-        }
+        self.sync().await;
     }
 
     /// Internal flush implementation
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_arguments)]
     async fn flush_buffer(
         client: &LangfuseClient,
         buffer: &Mutex<VecDeque<BatchEvent>>,
@@ -513,18 +1517,69 @@ impl Batcher {
         config: &BatcherConfig,
         metrics: &BatcherMetrics,
         flush_mutex: &Mutex<()>,
+        queue_backend: Option<&dyn QueueBackend>,
+        circuit: &CircuitState,
+        deliveries: &Deliveries,
+    ) -> Result<IngestionResponse> {
+        let result = Self::flush_events(
+            client,
+            buffer,
+            buffer_size,
+            config,
+            metrics,
+            flush_mutex,
+            queue_backend,
+            circuit,
+            deliveries,
+            None,
+        )
+        .await;
+        #[cfg(feature = "metrics")]
+        crate::metrics_export::publish(metrics);
+        result
+    }
+
+    /// Send buffered events, only those of `trace_id` if given
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+    async fn flush_events(
+        client: &LangfuseClient,
+        buffer: &Mutex<VecDeque<BatchEvent>>,
+        buffer_size: &AtomicUsize,
+        config: &BatcherConfig,
+        metrics: &BatcherMetrics,
+        flush_mutex: &Mutex<()>,
+        queue_backend: Option<&dyn QueueBackend>,
+        circuit: &CircuitState,
+        deliveries: &Deliveries,
+        trace_id: Option<&str>,
     ) -> Result<IngestionResponse> {
         // Prevent concurrent flushes
         let _guard = flush_mutex.lock().await;
 
+        // Leave everything queued while the circuit is open
+        circuit.check(metrics)?;
+
         let mut events: Vec<BatchEvent> = {
             let mut buffer = buffer.lock().await;
-            let events = buffer.drain(..).collect();
-            // Reset size atomically when clearing buffer
-            buffer_size.store(0, Ordering::Relaxed);
-            // Update queued metric
-            metrics.queued.store(0, Ordering::Relaxed);
-            events
+            if let Some(trace_id) = trace_id {
+                let (selected, rest): (VecDeque<_>, VecDeque<_>) = buffer
+                    .drain(..)
+                    .partition(|e| Self::event_trace_id(&e.event) == Some(trace_id));
+                *buffer = rest;
+                let selected_size: usize = selected.iter().map(|e| e.size).sum();
+                buffer_size.fetch_sub(selected_size, Ordering::Relaxed);
+                metrics
+                    .queued
+                    .fetch_sub(selected.len() as u64, Ordering::Relaxed);
+                selected.into()
+            } else {
+                let events = buffer.drain(..).collect();
+                // Reset size atomically when clearing buffer
+                buffer_size.store(0, Ordering::Relaxed);
+                // Update queued metric
+                metrics.queued.store(0, Ordering::Relaxed);
+                events
+            }
         };
 
         if events.is_empty() {
@@ -533,20 +1588,42 @@ impl Batcher {
                 failures: vec![],
                 success_count: 0,
                 failure_count: 0,
+                request_id: None,
             });
         }
 
+        if let Some(oldest) = events.iter().map(|e| e.enqueued_at).min() {
+            metrics.observe_wait(oldest.elapsed());
+        }
+
         // Split into chunks that fit size limit
         let mut chunks = Self::chunk_events(&events, config.max_bytes, config.max_events);
 
         let mut all_success_ids = Vec::new();
         let mut all_failures = Vec::new();
         let mut retry_queue = Vec::new();
+        let mut last_request_id = None;
 
         let mut chunk_idx = 0;
         while chunk_idx < chunks.len() {
+            if circuit.open_for(metrics).is_some() {
+                // Keep the unsent chunks for after the cool-down
+                retry_queue.extend(chunks.drain(chunk_idx..).flatten());
+                break;
+            }
+
             let chunk = chunks[chunk_idx].clone();
-            match Self::send_batch_with_retry(client, &chunk, config, metrics).await {
+            #[cfg(feature = "metrics")]
+            let started = std::time::Instant::now();
+            let result =
+                Self::send_batch_with_retry(client, &chunk, config, metrics, circuit).await;
+            #[cfg(feature = "metrics")]
+            crate::metrics_export::record_batch(
+                started.elapsed(),
+                chunk.iter().map(|e| e.size).sum(),
+                result.is_ok(),
+            );
+            match result {
                 Ok(response) => {
                     // Update metrics
                     metrics
@@ -572,6 +1649,7 @@ impl Batcher {
                         }
                     }
                     all_failures.extend(response.failures);
+                    last_request_id = response.request_id.or(last_request_id);
                     chunk_idx += 1;
                 }
                 Err(Error::Client { status: 413, .. }) if chunk.len() > 1 => {
@@ -596,12 +1674,25 @@ impl Batcher {
 
                     // Queue all events for retry
                     for event in &chunk {
-                        if event.retry_count < config.max_retries {
+                        if circuit.open_for(metrics).is_some() {
+                            // The outage is not the event's fault
+                            retry_queue.push(event.clone());
+                        } else if event.retry_count < config.max_retries {
                             let mut event = event.clone();
                             event.retry_count += 1;
                             retry_queue.push(event);
                         } else {
                             metrics.failed.fetch_add(1, Ordering::Relaxed);
+                            deliveries.notify(
+                                &event.id,
+                                DeliveryResult::Failed(EventError {
+                                    event_id: event.id.clone(),
+                                    message: e.to_string(),
+                                    code: None,
+                                    retryable: true,
+                                    validation_details: Vec::new(),
+                                }),
+                            );
                         }
                     }
                     chunk_idx += 1;
@@ -621,6 +1712,13 @@ impl Batcher {
                         metrics
                             .failed
                             .fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                        deliveries.settle(
+                            &events,
+                            &retry_queue,
+                            &all_success_ids,
+                            &all_failures,
+                            &e.to_string(),
+                        );
                         return Err(e);
                     }
                     // Convert to failures
@@ -630,6 +1728,7 @@ impl Batcher {
                             message: e.to_string(),
                             code: None,
                             retryable: false,
+                            validation_details: Vec::new(),
                         });
                         metrics.failed.fetch_add(1, Ordering::Relaxed);
                     }
@@ -638,6 +1737,23 @@ impl Batcher {
             }
         }
 
+        // Everything not going back into the queue is done with
+        deliveries.settle(
+            &events,
+            &retry_queue,
+            &all_success_ids,
+            &all_failures,
+            "Event not confirmed by Langfuse",
+        );
+        if let Some(backend) = queue_backend {
+            let done: Vec<String> = events
+                .iter()
+                .filter(|e| !retry_queue.iter().any(|r| r.id == e.id))
+                .map(|e| e.id.clone())
+                .collect();
+            Self::acknowledge(backend, &done).await;
+        }
+
         // Re-queue retry events
         if !retry_queue.is_empty() {
             // Calculate total size of retry events
@@ -658,6 +1774,7 @@ impl Batcher {
             failures: all_failures.clone(),
             success_count: all_success_ids.len(),
             failure_count: all_failures.len(),
+            request_id: last_request_id,
         })
     }
 
@@ -697,6 +1814,7 @@ impl Batcher {
         events: &[BatchEvent],
         config: &BatcherConfig,
         metrics: &BatcherMetrics,
+        circuit: &CircuitState,
     ) -> Result<IngestionResponse> {
         let mut delay = config.initial_retry_delay;
         let mut last_error = None;
@@ -725,7 +1843,18 @@ impl Batcher {
                 metadata: None,
             };
 
-            match Self::send_batch_internal(client, batch_request, config, events).await {
+            let result = Self::send_batch_internal(client, batch_request, config, events).await;
+            match &result {
+                Err(Error::Server { .. } | Error::Network(_) | Error::Middleware(_)) => {
+                    if circuit.record_failure(metrics) {
+                        // Stop retrying against an instance that is down
+                        return result;
+                    }
+                }
+                _ => circuit.record_success(metrics),
+            }
+
+            match result {
                 Ok(response) => return Ok(response),
                 Err(Error::Client { status: 413, .. }) => {
                     // Payload too large - should be handled at the chunk level
@@ -749,120 +1878,19 @@ impl Batcher {
         Err(last_error.unwrap_or_else(|| Error::Api("Max retries exceeded".to_string())))
     }
 
-    /// Send a single batch and handle 207 responses
-    #[allow(clippy::too_many_lines)]
+    /// Send a single batch, splitting 413 responses into a size error or a re-chunking signal
     async fn send_batch_internal(
         client: &LangfuseClient,
         batch: IngestionBatchRequest,
         config: &BatcherConfig,
         events: &[BatchEvent],
     ) -> Result<IngestionResponse> {
-        // Get event IDs for tracking
-        let event_ids: Vec<String> = batch.batch.iter().map(Self::extract_event_id).collect();
-
-        // Use the raw response API to get status code
-        let response = client
-            .configuration
-            .client
-            .post(format!(
-                "{}/api/public/ingestion",
-                client.configuration.base_path
-            ))
-            .basic_auth(&client.public_key, Some(&client.secret_key))
-            .json(&batch)
-            .send()
-            .await
-            .map_err(Error::Middleware)?;
-
-        let status = response.status();
-        let request_id = response
-            .headers()
-            .get("x-request-id")
-            .and_then(|v| v.to_str().ok())
-            .map(ToString::to_string);
-
-        // Handle different status codes
-        match status.as_u16() {
-            200..=202 => {
-                // Full success
-                let count = event_ids.len();
-                Ok(IngestionResponse {
-                    success_ids: event_ids,
-                    failures: vec![],
-                    success_count: count,
-                    failure_count: 0,
-                })
-            }
-            207 => {
-                // Parse the 207 response body
-                #[derive(serde::Deserialize)]
-                struct MultiStatusResponse {
-                    successes: Vec<SuccessItem>,
-                    errors: Vec<ErrorItem>,
-                }
-
-                #[derive(serde::Deserialize)]
-                struct SuccessItem {
-                    id: String,
-                    #[allow(dead_code)]
-                    status: Option<u16>,
-                }
-
-                #[derive(serde::Deserialize)]
-                struct ErrorItem {
-                    id: String,
-                    status: Option<u16>,
-                    error: Option<String>,
-                    message: Option<String>,
-                }
-
-                // Multi-Status: Parse the response to identify partial failures
-                let body = response
-                    .text()
-                    .await
-                    .map_err(|e| Error::Api(format!("Failed to read 207 response: {e}")))?;
-
-                let multi_status: MultiStatusResponse = serde_json::from_str(&body)
-                    .map_err(|e| Error::Api(format!("Failed to parse 207 response: {e}")))?;
-
-                let success_ids: Vec<String> = multi_status
-                    .successes
-                    .iter()
-                    .map(|s| s.id.clone())
-                    .collect();
-
-                let failures: Vec<EventError> = multi_status
-                    .errors
-                    .iter()
-                    .map(|e| EventError {
-                        event_id: e.id.clone(),
-                        message: e
-                            .message
-                            .as_ref()
-                            .or(e.error.as_ref())
-                            .unwrap_or(&"Unknown error".to_string())
-                            .clone(),
-                        code: e.status.map(|s| s.to_string()),
-                        retryable: e.status.is_some_and(|s| s >= 500 || s == 429),
-                    })
-                    .collect();
-
-                Ok(IngestionResponse {
-                    success_count: success_ids.len(),
-                    failure_count: failures.len(),
-                    success_ids,
-                    failures,
-                })
-            }
-            401 | 403 => Err(Error::Auth {
-                message: response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Authentication failed".to_string()),
+        match IngestionTransport::new(client).send(&batch).await {
+            Err(Error::Client {
+                status: 413,
                 request_id,
-            }),
-            413 => {
-                // Payload too large - need to reduce chunk size
+                ..
+            }) => {
                 if events.len() == 1 {
                     // Single event exceeds limit - unrecoverable
                     Err(Error::BatchSizeExceeded {
@@ -878,52 +1906,71 @@ impl Batcher {
                     })
                 }
             }
-            429 => {
-                let retry_after = response
-                    .headers()
-                    .get("retry-after")
-                    .and_then(|v| v.to_str().ok())
-                    .and_then(|s| {
-                        // Try parsing as seconds (integer)
-                        s.parse::<u64>().ok().or_else(|| {
-                            // Try parsing as HTTP-date (RFC2616)
-                            chrono::DateTime::parse_from_rfc2822(s).ok().and_then(|dt| {
-                                let now = chrono::Utc::now();
-                                let retry_time = dt.with_timezone(&chrono::Utc);
-                                if retry_time > now {
-                                    Some((retry_time - now).num_seconds() as u64)
-                                } else {
-                                    None
-                                }
-                            })
-                        })
-                    })
-                    .map(Duration::from_secs);
+            result => result,
+        }
+    }
 
-                Err(Error::RateLimit {
-                    retry_after,
-                    request_id,
-                })
+    /// Stop accepting events and flush everything pending within `deadline`
+    ///
+    /// Unlike [`Batcher::shutdown`] this works through a shared reference, so it
+    /// can be called on a batcher held in an `Arc`, and it gives up once the
+    /// deadline expires. Events added afterwards are rejected.
+    pub async fn drain(&self, deadline: Duration) -> DrainReport {
+        let started = Instant::now();
+        crate::registry::unregister(self.registry_id);
+        self.shutdown_flag.store(true, Ordering::Relaxed);
+        let _ = self.shutdown_tx.try_send(());
+
+        let stop_and_flush = async {
+            // The background task moves channel events into the buffer and flushes them
+            if let Some(handle) = {
+                let mut handle_guard = self.task_handle.lock().await;
+                handle_guard.take()
+            } {
+                let _ = handle.await;
             }
-            500..=599 => Err(Error::Server {
-                status: status.as_u16(),
-                message: response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| "Server error".to_string()),
-                request_id,
-            }),
-            _ => Err(Error::Client {
-                status: status.as_u16(),
-                message: response
-                    .text()
-                    .await
-                    .unwrap_or_else(|_| format!("Unexpected status: {}", status)),
-                request_id,
-            }),
+            let _ = self.flush().await;
+        };
+        let timed_out = tokio::time::timeout(deadline, stop_and_flush)
+            .await
+            .is_err();
+
+        let metrics = self.metrics.snapshot();
+        let remaining =
+            self.tx.max_capacity() - self.tx.capacity() + self.buffer.lock().await.len();
+        DrainReport {
+            flushed: metrics.flushed,
+            failed: metrics.failed,
+            dropped: metrics.dropped,
+            retries: metrics.retries,
+            remaining,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            timed_out,
+        }
+    }
+
+    /// Collect the final metrics of the batcher after a drain
+    pub fn shutdown_metrics(&self, drain: DrainReport) -> ShutdownMetrics {
+        ShutdownMetrics {
+            drain,
+            batcher: self.metrics.snapshot(),
+            latency: self.client.latency_summary(),
         }
     }
 
+    /// Drain the batcher within `deadline` and write its final metrics to `file`
+    ///
+    /// See [`crate::metrics_file`] for the file formats.
+    pub async fn drain_to_file(
+        &self,
+        deadline: Duration,
+        file: &MetricsFile,
+    ) -> Result<DrainReport> {
+        let report = self.drain(deadline).await;
+        file.write(&self.shutdown_metrics(report.clone()))?;
+        Ok(report)
+    }
+
     /// Shutdown the batcher and flush remaining events
     ///
     /// This method:
@@ -932,10 +1979,14 @@ impl Batcher {
     /// - Waits for in-flight retries to complete
     /// - Is idempotent (can be called multiple times safely)
     pub async fn shutdown(self) -> Result<IngestionResponse> {
+        crate::registry::unregister(self.registry_id);
+
         // Check if already shutting down (idempotent)
         if self.shutdown_flag.swap(true, Ordering::Relaxed) {
             // Already shutting down, just return current state
-            return self.flush().await;
+            let flush_result = self.flush().await;
+            self.deliveries.abandon();
+            return flush_result;
         }
 
         // Signal shutdown to background task
@@ -951,6 +2002,7 @@ impl Batcher {
 
         // Final flush after task has stopped
         let flush_result = self.flush().await;
+        self.deliveries.abandon();
 
         // Log final metrics
         let final_metrics = self.metrics.snapshot();
@@ -967,10 +2019,226 @@ impl Batcher {
     }
 }
 
+/// Clonable handle for adding events to a [`Batcher`] from many tasks
+///
+/// Created with [`Batcher::handle`]. The batcher itself stays the single owner
+/// that flushes and shuts down.
+///
+/// ```no_run
+/// use langfuse_ergonomic::{Batcher, ClientBuilder};
+///
+/// # async fn example(events: Vec<langfuse_ergonomic::IngestionEvent>) -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::from_env()?.build()?;
+/// let batcher = Batcher::builder().client(client).build().await;
+///
+/// let mut tasks = Vec::new();
+/// for event in events {
+///     let handle = batcher.handle();
+///     tasks.push(tokio::spawn(async move { handle.add(event).await }));
+/// }
+/// for task in tasks {
+///     task.await??;
+/// }
+///
+/// batcher.shutdown().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct BatcherHandle {
+    tx: mpsc::Sender<Command>,
+    buffer: Arc<Mutex<VecDeque<BatchEvent>>>,
+    buffer_size: Arc<AtomicUsize>,
+    metrics: Arc<BatcherMetrics>,
+    shutdown_flag: Arc<AtomicBool>,
+    max_bytes: usize,
+    backpressure_policy: BackpressurePolicy,
+    queue_backend: Option<Arc<dyn QueueBackend>>,
+    deliveries: Arc<Deliveries>,
+    recent_ids: Option<Arc<RecentIds>>,
+    interceptors: Arc<[Arc<dyn BatchInterceptor>]>,
+    sampler: Option<Arc<Sampler>>,
+}
+
+impl BatcherHandle {
+    /// Add an event to the batch
+    ///
+    /// Same as [`Batcher::add`].
+    pub async fn add(&self, mut event: IngestionEvent) -> Result<()> {
+        // Check if shutdown has been called
+        if self.shutdown_flag.load(Ordering::Relaxed) {
+            return Err(Error::Api("Batcher is shutting down".to_string()));
+        }
+
+        if let Some(sampler) = &self.sampler {
+            if !sampler.keep(&event) {
+                let id = Batcher::extract_event_id(&event);
+                self.deliveries.notify(&id, DeliveryResult::Dropped);
+                return Ok(());
+            }
+        }
+
+        crate::interceptor::intercept(&self.interceptors, &mut event);
+        let id = Batcher::extract_event_id(&event);
+
+        if let Some(recent_ids) = &self.recent_ids {
+            if !recent_ids.insert(&id) {
+                self.metrics.deduplicated.fetch_add(1, Ordering::Relaxed);
+                self.deliveries.notify(&id, DeliveryResult::Dropped);
+                return Ok(());
+            }
+        }
+
+        let result = self.enqueue(event, id.clone()).await;
+        if result.is_err() {
+            // Not queued, so adding it again is not a duplicate
+            if let Some(recent_ids) = &self.recent_ids {
+                recent_ids.remove(&id);
+            }
+        }
+        result
+    }
+
+    /// Queue an event under the configured backpressure policy
+    async fn enqueue(&self, event: IngestionEvent, id: String) -> Result<()> {
+        let batch_event = BatchEvent::new(event, id.clone())?;
+
+        // Check size limit
+        if batch_event.size > self.max_bytes {
+            return Err(Error::BatchSizeExceeded {
+                size: batch_event.size,
+                max_size: self.max_bytes,
+            });
+        }
+
+        if let Some(backend) = &self.queue_backend {
+            backend.append(&batch_event).await?;
+        }
+
+        if self.tx.capacity() == 0 {
+            self.metrics.mark_saturated();
+        }
+
+        // Handle backpressure based on policy
+        match self.backpressure_policy {
+            BackpressurePolicy::Block => {
+                // Block until space is available
+                self.tx
+                    .send(Command::Event(batch_event))
+                    .await
+                    .map_err(|e| Error::Api(format!("Failed to queue event: {e}")))?;
+            }
+            BackpressurePolicy::DropNew => {
+                // Try to send, drop if full
+                match self.tx.try_send(Command::Event(batch_event)) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        self.metrics.mark_saturated();
+                        self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
+                        if let Some(backend) = &self.queue_backend {
+                            Batcher::acknowledge(backend.as_ref(), &[id]).await;
+                        }
+                        return Err(Error::Backpressure {
+                            policy: BackpressurePolicy::DropNew,
+                            reason: "Queue full, new event dropped".to_string(),
+                        });
+                    }
+                    Err(e) => return Err(Error::Api(format!("Failed to queue event: {e}"))),
+                }
+            }
+            BackpressurePolicy::DropOldest => {
+                // Try to send, if full, remove oldest and retry
+                match self.tx.try_send(Command::Event(batch_event.clone())) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        self.metrics.mark_saturated();
+                        // Remove oldest from buffer
+                        {
+                            let mut buf = self.buffer.lock().await;
+                            if let Some(dropped) = buf.pop_front() {
+                                // Update buffer size when dropping - O(1) with VecDeque
+                                self.buffer_size.fetch_sub(dropped.size, Ordering::Relaxed);
+                                self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
+                                self.metrics.queued.fetch_sub(1, Ordering::Relaxed);
+                                self.deliveries.notify(&dropped.id, DeliveryResult::Dropped);
+                                if let Some(recent_ids) = &self.recent_ids {
+                                    recent_ids.remove(&dropped.id);
+                                }
+                                if let Some(backend) = &self.queue_backend {
+                                    Batcher::acknowledge(backend.as_ref(), &[dropped.id]).await;
+                                }
+                            }
+                        }
+                        // Try again with blocking send
+                        self.tx
+                            .send(Command::Event(batch_event))
+                            .await
+                            .map_err(|e| Error::Api(format!("Failed to queue event: {e}")))?;
+                    }
+                    Err(e) => return Err(Error::Api(format!("Failed to queue event: {e}"))),
+                }
+            }
+        }
+
+        self.metrics.observe_depth(self.queue_depth());
+
+        Ok(())
+    }
+
+    /// Add an event and get notified once it is delivered, fails, or is dropped
+    ///
+    /// Same as [`Batcher::add_with_ack`].
+    pub async fn add_with_ack(
+        &self,
+        event: IngestionEvent,
+    ) -> Result<oneshot::Receiver<DeliveryResult>> {
+        let id = Batcher::extract_event_id(&event);
+        let (sender, receiver) = oneshot::channel();
+        // Registered first so a flush right after queueing can't miss it
+        self.deliveries.register(id.clone(), sender)?;
+        if let Err(e) = self.add(event).await {
+            self.deliveries.cancel(&id);
+            return Err(e);
+        }
+        Ok(receiver)
+    }
+
+    /// Number of events waiting in the channel and the buffer
+    fn queue_depth(&self) -> u64 {
+        let in_channel = self.tx.max_capacity() - self.tx.capacity();
+        in_channel as u64 + self.metrics.queued.load(Ordering::Relaxed)
+    }
+
+    /// Get current metrics of the batcher
+    pub fn metrics(&self) -> BatcherMetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Whether the batcher stopped accepting events
+    pub fn is_closed(&self) -> bool {
+        self.shutdown_flag.load(Ordering::Relaxed)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_recent_ids_evict_least_recently_seen() {
+        let recent = RecentIds::new(2);
+        assert!(recent.insert("a"));
+        assert!(recent.insert("b"));
+        // Seeing "a" again makes "b" the least recent
+        assert!(!recent.insert("a"));
+        assert!(recent.insert("c"));
+        assert!(recent.insert("b"));
+        assert!(!recent.insert("c"));
+
+        recent.remove("c");
+        assert!(recent.insert("c"));
+    }
+
     #[test]
     fn test_chunk_events() {
         let events = vec![
@@ -979,18 +2247,21 @@ mod tests {
                 id: "1".to_string(),
                 size: 1000,
                 retry_count: 0,
+                enqueued_at: Instant::now(),
             },
             BatchEvent {
                 event: IngestionEvent::IngestionEventOneOf(Box::default()),
                 id: "2".to_string(),
                 size: 2000,
                 retry_count: 0,
+                enqueued_at: Instant::now(),
             },
             BatchEvent {
                 event: IngestionEvent::IngestionEventOneOf(Box::default()),
                 id: "3".to_string(),
                 size: 1500,
                 retry_count: 0,
+                enqueued_at: Instant::now(),
             },
         ];
 
@@ -1006,4 +2277,28 @@ mod tests {
         assert_eq!(chunks[0].len(), 2); // Max 2 events per chunk
         assert_eq!(chunks[1].len(), 1);
     }
+
+    #[test]
+    fn test_adaptive_flush_interval() {
+        let adaptive = AdaptiveFlush::new(Duration::from_millis(500), Duration::from_secs(8));
+        let base = Duration::from_secs(2);
+
+        assert_eq!(
+            adaptive.next_interval(base, 80, true),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            adaptive.next_interval(Duration::from_millis(600), 80, true),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            adaptive.next_interval(base, 0, false),
+            Duration::from_secs(4)
+        );
+        assert_eq!(
+            adaptive.next_interval(Duration::from_secs(6), 0, false),
+            Duration::from_secs(8)
+        );
+        assert_eq!(adaptive.next_interval(base, 3, false), base);
+    }
 }
@@ -1,8 +1,31 @@
 //! Main client for interacting with the Langfuse API
 
+use crate::auth::{AuthMiddleware, AuthProvider, BasicAuth};
 use crate::batcher::{Batcher, BatcherConfig};
+use crate::debug_config::ClientDebugConfig;
+use crate::environment::Environment;
 use crate::error::{Error, Result};
+use crate::http_log::{
+    ApiRequest, ApiResponse, HttpLogMiddleware, RequestCallback, ResponseCallback,
+};
+use crate::interceptor::BatchInterceptor;
+use crate::latency::{
+    ApiCallCallback, ApiCallTiming, EndpointLatency, LatencyMiddleware, LatencyRecorder,
+};
+use crate::media::MediaField;
+use crate::payload::{
+    FieldSizeLimit, FieldSizePolicy, GuardedFields, TextLengthLimits, MIN_FIELD_BYTES,
+};
+use crate::payload_capture::PayloadCapture;
+use crate::policy::{NameKind, NamingPolicy, TraceRequirements};
+use crate::rate_limit::{RateLimit, RateLimitMiddleware, TokenBucket};
+use crate::sampling::Sampler;
+use crate::schema::SchemaVersion;
+use crate::scores::ScoreTransport;
+use crate::security::{Mask, Redactor};
 use langfuse_client_base::apis::configuration::Configuration;
+use langfuse_client_base::models::IngestionEvent;
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -19,10 +42,47 @@ const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 /// Main client for interacting with the Langfuse API
 #[derive(Clone)]
 pub struct LangfuseClient {
-    pub(crate) public_key: String,
-    pub(crate) secret_key: String,
     pub(crate) base_url: String,
     pub(crate) configuration: Configuration,
+    /// Whether the client only synthesizes responses, see [`ClientBuilder::disabled`]
+    pub(crate) disabled: bool,
+    pub(crate) field_size_limit: Option<FieldSizeLimit>,
+    pub(crate) text_length_limits: TextLengthLimits,
+    pub(crate) schema_version: Option<SchemaVersion>,
+    pub(crate) trace_requirements: Option<Arc<TraceRequirements>>,
+    pub(crate) naming_policy: Option<Arc<NamingPolicy>>,
+    pub(crate) media_offload_threshold: Option<usize>,
+    pub(crate) environment: Option<Environment>,
+    pub(crate) payload_capture: Option<Arc<PayloadCapture>>,
+    pub(crate) score_transport: ScoreTransport,
+    pub(crate) interceptors: Arc<[Arc<dyn BatchInterceptor>]>,
+    pub(crate) redactor: Option<Arc<Redactor>>,
+    pub(crate) mask: Option<Mask>,
+    pub(crate) sampler: Option<Arc<Sampler>>,
+    pub(crate) latency: Arc<LatencyRecorder>,
+    pub(crate) connection: ConnectionSettings,
+    /// Project ID, fetched on first use unless set on the builder
+    pub(crate) cached_project_id: Arc<tokio::sync::OnceCell<String>>,
+    pub(crate) project_scoped_urls: bool,
+    #[cfg(feature = "compression")]
+    pub(crate) request_compression: Option<Arc<crate::compression::CompressionState>>,
+    #[cfg(feature = "testing")]
+    pub(crate) recorder: Option<Arc<crate::testing::Recorder>>,
+}
+
+/// Connection settings kept for [`LangfuseClient::debug_config`]
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionSettings {
+    /// Request timeout, unknown with a custom HTTP client
+    timeout: Option<Duration>,
+    /// Connection timeout, unknown with a custom HTTP client
+    connect_timeout: Option<Duration>,
+    custom_http_client: bool,
+    /// Proxy URL without credentials, unless a custom HTTP client is used
+    proxy: Option<String>,
+    rate_limit: Option<RateLimit>,
+    /// Public key, unless a custom auth provider is used
+    public_key: Option<String>,
 }
 
 impl LangfuseClient {
@@ -41,7 +101,6 @@ impl LangfuseClient {
             .configuration
             .client
             .get(&url)
-            .basic_auth(&self.public_key, Some(&self.secret_key))
             .timeout(Duration::from_secs(5))
             .send()
             .await
@@ -90,21 +149,8 @@ impl LangfuseClient {
     /// # }
     /// ```
     pub async fn create_batcher(self: Arc<Self>, config: Option<BatcherConfig>) -> Batcher {
-        // Clone the Arc to avoid moving self
-        let client = LangfuseClient {
-            public_key: self.public_key.clone(),
-            secret_key: self.secret_key.clone(),
-            base_url: self.base_url.clone(),
-            configuration: Configuration {
-                base_path: self.configuration.base_path.clone(),
-                basic_auth: self.configuration.basic_auth.clone(),
-                api_key: self.configuration.api_key.clone(),
-                oauth_access_token: self.configuration.oauth_access_token.clone(),
-                bearer_access_token: self.configuration.bearer_access_token.clone(),
-                client: self.configuration.client.clone(),
-                user_agent: self.configuration.user_agent.clone(),
-            },
-        };
+        // Clone the client out of the Arc to avoid moving self
+        let client = LangfuseClient::clone(&self);
 
         let config = config.unwrap_or_default();
 
@@ -129,23 +175,444 @@ impl LangfuseClient {
         crate::batcher::Batcher::builder().client(self.clone())
     }
 
+    /// Create a variant of this client with different request settings
+    ///
+    /// The variant shares the connection pool, latency summaries, and all other
+    /// configuration with this client, so it is cheap to create. Useful when
+    /// one service needs both a low-latency path and a bulk-export path against
+    /// the same project:
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # use std::time::Duration;
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::from_env()?.build()?;
+    /// let export_client = client.with_overrides(|o| {
+    ///     o.timeout(Duration::from_secs(300))
+    ///         .header("x-export-job", "nightly")
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_overrides(
+        &self,
+        configure: impl FnOnce(ClientOverrides) -> ClientOverrides,
+    ) -> Result<LangfuseClient> {
+        let ClientOverrides {
+            base_url,
+            timeout,
+            user_agent,
+            headers,
+        } = configure(ClientOverrides::default());
+
+        let mut header_map = http::HeaderMap::new();
+        for (name, value) in headers {
+            let name = http::HeaderName::try_from(name.as_str())
+                .map_err(|e| Error::Configuration(format!("Invalid header name `{name}`: {e}")))?;
+            let value = http::HeaderValue::try_from(value.as_str()).map_err(|e| {
+                Error::Configuration(format!("Invalid value for header `{name}`: {e}"))
+            })?;
+            header_map.insert(name, value);
+        }
+
+        let mut client = self.clone();
+        if timeout.is_some() || !header_map.is_empty() {
+            client.configuration.client =
+                reqwest_middleware::ClientBuilder::from_client(self.configuration.client.clone())
+                    .with(OverridesMiddleware {
+                        timeout,
+                        headers: header_map,
+                    })
+                    .build();
+        }
+        if let Some(base_url) = base_url {
+            client.configuration.base_path = base_url.clone();
+            client.base_url = base_url;
+            // The parent's project belongs to the parent's instance
+            client.cached_project_id = Arc::new(tokio::sync::OnceCell::new());
+        }
+        if let Some(user_agent) = user_agent {
+            client.configuration.user_agent = Some(user_agent);
+        }
+        if timeout.is_some() {
+            client.connection.timeout = timeout;
+        }
+        Ok(client)
+    }
+
+    /// Whether events are sent to Langfuse
+    ///
+    /// A client built with [`ClientBuilder::disabled`] accepts every event
+    /// without sending it.
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
+
+    /// Get the size limit applied to input, output, and metadata fields, if any
+    pub fn field_size_limit(&self) -> Option<FieldSizeLimit> {
+        self.field_size_limit
+    }
+
+    /// Apply the configured field size limit to a set of event fields
+    ///
+    /// With `offload`, input and output values above the media offload
+    /// threshold are left as they are, to be uploaded by
+    /// [`offload_event_fields`](Self::offload_event_fields) once the event
+    /// is known to be sent. The configured schema version, if any, is stamped
+    /// on the metadata.
+    pub(crate) fn guard_fields(
+        &self,
+        input: Option<serde_json::Value>,
+        output: Option<serde_json::Value>,
+        metadata: Option<serde_json::Value>,
+        offload: bool,
+    ) -> Result<GuardedFields> {
+        let (held_input, input) = self.hold_for_offload(offload, MediaField::Input, input);
+        let (held_output, output) = self.hold_for_offload(offload, MediaField::Output, output);
+        let mut fields = match &self.field_size_limit {
+            Some(limit) => limit.apply(input, output, metadata)?,
+            None => GuardedFields {
+                input,
+                output,
+                metadata,
+            },
+        };
+        fields.input = fields.input.or(held_input);
+        fields.output = fields.output.or(held_output);
+        if let Some(version) = self.schema_version {
+            fields.metadata = Some(crate::schema::stamp(fields.metadata, version));
+        }
+        Ok(fields)
+    }
+
+    /// Get the size above which input and output values are uploaded as media, if any
+    pub fn media_offload_threshold(&self) -> Option<usize> {
+        self.media_offload_threshold
+    }
+
+    /// Get the environment events are assigned to, if any
+    pub fn environment(&self) -> Option<&Environment> {
+        self.environment.as_ref()
+    }
+
+    /// Split off a value that will be uploaded as media: `(held, value)`
+    fn hold_for_offload(
+        &self,
+        offload: bool,
+        field: MediaField,
+        value: Option<serde_json::Value>,
+    ) -> (Option<serde_json::Value>, Option<serde_json::Value>) {
+        let exceeds = |value: &serde_json::Value| {
+            self.offload_threshold(field).is_some_and(|threshold| {
+                serde_json::to_vec(value).is_ok_and(|serialized| serialized.len() > threshold)
+            })
+        };
+        match value {
+            Some(value) if offload && exceeds(&value) => (Some(value), None),
+            value => (None, value),
+        }
+    }
+
+    /// Upload input and output values of `event` above the media offload
+    /// threshold as media
+    ///
+    /// Called for events about to be sent, after validation and sampling, so
+    /// rejected and sampled-out events upload nothing. Offloaded values are
+    /// replaced with their media token. A value whose upload fails is kept
+    /// inline, where the field size limit then applies. The
+    /// [`FieldSizePolicy::Externalize`] policy lowers the threshold to the
+    /// field size limit.
+    pub(crate) async fn offload_event_fields(
+        &self,
+        event: &mut IngestionEvent,
+        trace_id: &str,
+        observation_id: Option<&str>,
+    ) -> Result<()> {
+        let Some([input, output, metadata]) = crate::interceptor::payload_fields(event) else {
+            return Ok(());
+        };
+
+        let mut inline = [None, None];
+        for ((slot, field), kept) in [&mut *input, &mut *output]
+            .into_iter()
+            .zip([MediaField::Input, MediaField::Output])
+            .zip(&mut inline)
+        {
+            let Some(value) = slot.as_mut().and_then(Option::take) else {
+                continue;
+            };
+            if self.offload_threshold(field).is_none() {
+                *slot = Some(Some(value));
+                continue;
+            }
+            match self
+                .offload_field(trace_id, observation_id, field, value)
+                .await
+            {
+                Ok(token) => *slot = Some(Some(token)),
+                Err(value) => *kept = Some(value),
+            }
+        }
+
+        let [kept_input, kept_output] = inline;
+        if kept_input.is_none() && kept_output.is_none() {
+            return Ok(());
+        }
+        let fields = match &self.field_size_limit {
+            Some(limit) => {
+                limit.apply_payload(kept_input, kept_output, metadata.clone().flatten())?
+            }
+            None => GuardedFields {
+                input: kept_input,
+                output: kept_output,
+                metadata: metadata.clone().flatten(),
+            },
+        };
+        if let Some(value) = fields.input {
+            *input = Some(Some(value));
+        }
+        if let Some(value) = fields.output {
+            *output = Some(Some(value));
+        }
+        if let Some(value) = fields.metadata {
+            *metadata = Some(Some(value));
+        }
+        Ok(())
+    }
+
+    /// Size above which values of `field` are uploaded as media, if any
+    fn offload_threshold(&self, field: MediaField) -> Option<usize> {
+        if self.disabled {
+            return None;
+        }
+        let field_limit = self
+            .field_size_limit
+            .filter(|limit| limit.policy == FieldSizePolicy::Externalize)
+            .map(|limit| limit.limit_for(field.as_str()));
+        [self.media_offload_threshold, field_limit]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Upload `value` as media, returning its token, or the value to keep
+    /// inline if it is below the threshold or its upload failed
+    async fn offload_field(
+        &self,
+        trace_id: &str,
+        observation_id: Option<&str>,
+        field: MediaField,
+        mut value: serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, serde_json::Value> {
+        let Some(threshold) = self.offload_threshold(field) else {
+            return Err(value);
+        };
+        if let Some(redactor) = &self.redactor {
+            value = redactor.redact_json(value);
+        }
+        if let Some(mask) = &self.mask {
+            value = mask.apply(&value);
+        }
+        let Ok(serialized) = serde_json::to_vec(&value) else {
+            return Err(value);
+        };
+        if serialized.len() <= threshold {
+            return Err(value);
+        }
+
+        let size = serialized.len();
+        match self
+            .upload_media()
+            .trace_id(trace_id)
+            .maybe_observation_id(observation_id)
+            .field(field)
+            .bytes(serialized)
+            .content_type("application/json")
+            .call()
+            .await
+        {
+            Ok(media) => Ok(serde_json::Value::String(media.token())),
+            Err(e) => {
+                tracing::warn!(
+                    field = field.as_str(),
+                    size,
+                    error = %e,
+                    "Failed to offload large payload as media, sending it inline"
+                );
+                Err(value)
+            }
+        }
+    }
+
+    /// Get the schema version stamped on event metadata, if any
+    pub fn schema_version(&self) -> Option<SchemaVersion> {
+        self.schema_version
+    }
+
+    /// Get the metadata keys and tags required on every trace, if any
+    pub fn trace_requirements(&self) -> Option<&TraceRequirements> {
+        self.trace_requirements.as_deref()
+    }
+
+    /// Get the naming conventions applied to trace, observation, and score names, if any
+    pub fn naming_policy(&self) -> Option<&NamingPolicy> {
+        self.naming_policy.as_deref()
+    }
+
+    /// Get the length limits applied to names and status messages
+    pub fn text_length_limits(&self) -> TextLengthLimits {
+        self.text_length_limits
+    }
+
+    /// Apply the configured naming policy and name length limit
+    pub(crate) fn guard_name(
+        &self,
+        kind: NameKind,
+        name: Option<String>,
+    ) -> Result<Option<String>> {
+        name.map(|n| self.check_name(kind, n)).transpose()
+    }
+
+    /// Apply the configured naming policy and name length limit to a required name
+    pub(crate) fn check_name(&self, kind: NameKind, name: String) -> Result<String> {
+        let name = match &self.naming_policy {
+            Some(policy) => policy.apply(kind, name)?,
+            None => name,
+        };
+        self.text_length_limits.name(name)
+    }
+
+    /// Apply the configured status message length limit
+    pub(crate) fn guard_status_message(&self, message: Option<String>) -> Result<Option<String>> {
+        message
+            .map(|m| self.text_length_limits.status_message(m))
+            .transpose()
+    }
+
+    /// Get a redacted snapshot of the effective configuration
+    ///
+    /// See [`debug_config`](crate::debug_config) for the format.
+    pub fn debug_config(&self) -> ClientDebugConfig {
+        let millis = |d: Duration| u64::try_from(d.as_millis()).unwrap_or(u64::MAX);
+        ClientDebugConfig {
+            sdk_version: SDK_VERSION.to_string(),
+            enabled: !self.disabled,
+            base_url: self.base_url.clone(),
+            public_key: self.connection.public_key.clone(),
+            auth: if self.connection.public_key.is_some() {
+                "basic"
+            } else {
+                "custom"
+            }
+            .to_string(),
+            user_agent: self.configuration.user_agent.clone(),
+            timeout_ms: self.connection.timeout.map(millis),
+            connect_timeout_ms: self.connection.connect_timeout.map(millis),
+            custom_http_client: self.connection.custom_http_client,
+            proxy: self.connection.proxy.clone(),
+            rate_limit: self.connection.rate_limit.map(|limit| {
+                format!(
+                    "{} requests/s, burst {}",
+                    limit.requests_per_second, limit.burst
+                )
+            }),
+            max_field_bytes: self
+                .field_size_limit
+                .map(|limit| limit.max_bytes)
+                .filter(|max| *max != usize::MAX),
+            max_input_bytes: self
+                .field_size_limit
+                .and_then(|limit| limit.max_input_bytes),
+            max_output_bytes: self
+                .field_size_limit
+                .and_then(|limit| limit.max_output_bytes),
+            field_size_policy: self
+                .field_size_limit
+                .map(|limit| format!("{:?}", limit.policy)),
+            max_name_chars: self.text_length_limits.max_name_chars,
+            max_status_message_chars: self.text_length_limits.max_status_message_chars,
+            text_length_policy: format!("{:?}", self.text_length_limits.policy),
+            media_offload_threshold: self.media_offload_threshold,
+            schema_version: self.schema_version.map(|v| v.to_string()),
+            trace_requirements: self.trace_requirements.is_some(),
+            naming_policy: self.naming_policy.is_some(),
+            environment: self.environment.as_ref().map(|e| e.to_string()),
+            payload_capture_path: self
+                .payload_capture
+                .as_ref()
+                .map(|capture| capture.path().display().to_string()),
+            score_transport: format!("{:?}", self.score_transport),
+            interceptors: self.interceptors.len()
+                - usize::from(self.redactor.is_some())
+                - usize::from(self.mask.is_some())
+                - usize::from(self.field_size_limit.is_some()),
+            redact_payloads: self.redactor.is_some(),
+            mask: self.mask.is_some(),
+            sampler: self.sampler.as_ref().map(|sampler| format!("{sampler:?}")),
+            project_scoped_urls: self.project_scoped_urls,
+            request_compression: self.request_compression_summary(),
+            features: crate::debug_config::enabled_features(),
+        }
+    }
+
+    /// Request compression for [`debug_config`](Self::debug_config)
+    fn request_compression_summary(&self) -> Option<String> {
+        #[cfg(feature = "compression")]
+        if let Some(state) = &self.request_compression {
+            let config = state.config();
+            return Some(format!(
+                "{} above {} bytes",
+                config.encoding().as_str(),
+                config.min_bytes_threshold()
+            ));
+        }
+        None
+    }
+
+    /// Get a rolling latency summary of recent API calls, grouped by endpoint
+    ///
+    /// Summaries are shared between clones of the client.
+    pub fn latency_summary(&self) -> Vec<EndpointLatency> {
+        self.latency.summary()
+    }
+
     fn build_internal(
-        public_key: String,
-        secret_key: String,
+        auth: Arc<dyn AuthProvider>,
         base_url: String,
-        timeout: Option<Duration>,
-        connect_timeout: Option<Duration>,
-        user_agent: Option<String>,
-        http_client: Option<reqwest_middleware::ClientWithMiddleware>,
+        field_size_limit: Option<FieldSizeLimit>,
+        proxy: Option<reqwest::Proxy>,
+        options: ClientBuilder,
     ) -> Self {
+        let custom_http_client = options.http_client.is_some();
+        let connection = ConnectionSettings {
+            timeout: (!custom_http_client).then(|| options.timeout.unwrap_or(DEFAULT_TIMEOUT)),
+            connect_timeout: (!custom_http_client)
+                .then(|| options.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT)),
+            custom_http_client,
+            proxy: options
+                .proxy
+                .as_deref()
+                .filter(|_| !custom_http_client)
+                .map(without_credentials),
+            rate_limit: options.rate_limit,
+            public_key: options
+                .auth
+                .is_none()
+                .then(|| options.public_key.clone())
+                .flatten(),
+        };
+
         // Use provided client or build a default one
-        let client = http_client.unwrap_or_else(|| {
+        let client = options.http_client.unwrap_or_else(|| {
             #[allow(unused_mut)]
             let mut client_builder = reqwest::Client::builder()
-                .timeout(timeout.unwrap_or(DEFAULT_TIMEOUT))
-                .connect_timeout(connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
+                .timeout(options.timeout.unwrap_or(DEFAULT_TIMEOUT))
+                .connect_timeout(options.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
                 .pool_max_idle_per_host(10)
                 .pool_idle_timeout(Duration::from_secs(90));
+            if let Some(proxy) = proxy {
+                client_builder = client_builder.proxy(proxy);
+            }
 
             #[cfg(not(feature = "compression"))]
             {
@@ -160,12 +627,41 @@ impl LangfuseClient {
             reqwest_middleware::ClientBuilder::new(reqwest_client).build()
         });
 
+        let redactor = options
+            .redact_payloads
+            .then(|| Arc::new(options.redactor.unwrap_or_default()));
+
+        // Time and authenticate every request, including those made through a
+        // user-provided client; authenticating last re-applies credentials on retries
+        let latency = Arc::new(LatencyRecorder::new(options.on_api_call));
+        let mut client = reqwest_middleware::ClientBuilder::from_client(client);
+        if options.disabled {
+            client = client.with(DisabledMiddleware);
+        }
+        if let Some(limit) = options.rate_limit {
+            // Waiting for a token is not part of a call's latency
+            client = client.with(RateLimitMiddleware {
+                bucket: Arc::new(TokenBucket::new(limit)),
+            });
+        }
+        client = client.with(LatencyMiddleware {
+            recorder: latency.clone(),
+        });
+        if options.on_request.is_some() || options.on_response.is_some() {
+            client = client.with(HttpLogMiddleware {
+                on_request: options.on_request,
+                on_response: options.on_response,
+                redactor: redactor.clone().unwrap_or_default(),
+            });
+        }
+        let client = client.with(AuthMiddleware { provider: auth }).build();
+
         let default_user_agent = format!("{}/{} (Rust)", SDK_NAME, SDK_VERSION);
-        let final_user_agent = user_agent.unwrap_or(default_user_agent);
+        let final_user_agent = options.user_agent.unwrap_or(default_user_agent);
 
         let configuration = Configuration {
             base_path: base_url.clone(),
-            basic_auth: Some((public_key.clone(), Some(secret_key.clone()))),
+            basic_auth: None,
             api_key: None,
             oauth_access_token: None,
             bearer_access_token: None,
@@ -173,17 +669,53 @@ impl LangfuseClient {
             user_agent: Some(final_user_agent),
         };
 
+        // Redaction and masking run before user interceptors
+        let mut interceptors: Vec<Arc<dyn BatchInterceptor>> = Vec::new();
+        if let Some(redactor) = &redactor {
+            interceptors.push(redactor.clone());
+        }
+        if let Some(mask) = &options.mask {
+            interceptors.push(Arc::new(mask.clone()));
+        }
+        interceptors.extend(options.interceptors);
+        // Size limits run last, on the final payload
+        if let Some(limit) = field_size_limit {
+            interceptors.push(Arc::new(limit));
+        }
+
         Self {
-            public_key,
-            secret_key,
             base_url,
             configuration,
+            disabled: options.disabled,
+            field_size_limit,
+            text_length_limits: options.text_length_limits,
+            schema_version: options.schema_version,
+            trace_requirements: options.trace_requirements.map(Arc::new),
+            naming_policy: options.naming_policy.map(Arc::new),
+            media_offload_threshold: options.media_offload_threshold,
+            environment: options.environment,
+            payload_capture: options.payload_capture,
+            score_transport: options.score_transport,
+            interceptors: interceptors.into(),
+            redactor,
+            mask: options.mask,
+            sampler: options.sampler,
+            latency,
+            connection,
+            project_scoped_urls: options.project_scoped_urls || options.project_id.is_some(),
+            cached_project_id: Arc::new(tokio::sync::OnceCell::new_with(options.project_id)),
+            #[cfg(feature = "compression")]
+            request_compression: options
+                .request_compression
+                .map(|config| Arc::new(crate::compression::CompressionState::new(config))),
+            #[cfg(feature = "testing")]
+            recorder: options.recorder,
         }
     }
 }
 
 /// Builder for [`LangfuseClient`], mirroring the style of `opentelemetry-langfuse`.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Clone)]
 pub struct ClientBuilder {
     public_key: Option<String>,
     secret_key: Option<String>,
@@ -192,6 +724,93 @@ pub struct ClientBuilder {
     connect_timeout: Option<Duration>,
     user_agent: Option<String>,
     http_client: Option<reqwest_middleware::ClientWithMiddleware>,
+    proxy: Option<String>,
+    no_proxy: Option<String>,
+    rate_limit: Option<RateLimit>,
+    max_field_bytes: Option<usize>,
+    max_input_bytes: Option<usize>,
+    max_output_bytes: Option<usize>,
+    field_size_policy: FieldSizePolicy,
+    on_api_call: Option<ApiCallCallback>,
+    on_request: Option<RequestCallback>,
+    on_response: Option<ResponseCallback>,
+    text_length_limits: TextLengthLimits,
+    schema_version: Option<SchemaVersion>,
+    trace_requirements: Option<TraceRequirements>,
+    naming_policy: Option<NamingPolicy>,
+    media_offload_threshold: Option<usize>,
+    environment: Option<Environment>,
+    payload_capture: Option<Arc<PayloadCapture>>,
+    score_transport: ScoreTransport,
+    interceptors: Vec<Arc<dyn BatchInterceptor>>,
+    redact_payloads: bool,
+    redactor: Option<Redactor>,
+    mask: Option<Mask>,
+    sampler: Option<Arc<Sampler>>,
+    auth: Option<Arc<dyn AuthProvider>>,
+    project_id: Option<String>,
+    project_scoped_urls: bool,
+    disabled: bool,
+    #[cfg(feature = "compression")]
+    request_compression: Option<crate::compression::RequestCompression>,
+    #[cfg(feature = "testing")]
+    recorder: Option<Arc<crate::testing::Recorder>>,
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ClientBuilder");
+        debug
+            .field("public_key", &self.public_key)
+            .field(
+                "secret_key",
+                &self.secret_key.as_ref().map(|_| "<redacted>"),
+            )
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("user_agent", &self.user_agent)
+            .field("http_client", &self.http_client)
+            .field("proxy", &self.proxy.as_deref().map(without_credentials))
+            .field("no_proxy", &self.no_proxy)
+            .field("rate_limit", &self.rate_limit)
+            .field("max_field_bytes", &self.max_field_bytes)
+            .field("max_input_bytes", &self.max_input_bytes)
+            .field("max_output_bytes", &self.max_output_bytes)
+            .field("field_size_policy", &self.field_size_policy)
+            .field(
+                "on_api_call",
+                &self.on_api_call.as_ref().map(|_| "<callback>"),
+            )
+            .field(
+                "on_request",
+                &self.on_request.as_ref().map(|_| "<callback>"),
+            )
+            .field(
+                "on_response",
+                &self.on_response.as_ref().map(|_| "<callback>"),
+            )
+            .field("text_length_limits", &self.text_length_limits)
+            .field("schema_version", &self.schema_version)
+            .field("trace_requirements", &self.trace_requirements)
+            .field("naming_policy", &self.naming_policy)
+            .field("media_offload_threshold", &self.media_offload_threshold)
+            .field("environment", &self.environment)
+            .field("payload_capture", &self.payload_capture)
+            .field("score_transport", &self.score_transport)
+            .field("interceptors", &self.interceptors.len())
+            .field("redact_payloads", &self.redact_payloads)
+            .field("redactor", &self.redactor)
+            .field("mask", &self.mask.as_ref().map(|_| "<callback>"))
+            .field("sampler", &self.sampler)
+            .field("auth", &self.auth.as_ref().map(|_| "<provider>"))
+            .field("project_id", &self.project_id)
+            .field("project_scoped_urls", &self.project_scoped_urls)
+            .field("disabled", &self.disabled);
+        #[cfg(feature = "compression")]
+        debug.field("request_compression", &self.request_compression);
+        debug.finish()
+    }
 }
 
 impl ClientBuilder {
@@ -202,27 +821,89 @@ impl ClientBuilder {
     }
 
     /// Create a builder pre-populated from environment variables.
+    ///
+    /// With `LANGFUSE_ENABLED=false` the client is [disabled](Self::disabled)
+    /// and the keys are optional.
     pub fn from_env() -> Result<Self> {
         use std::env;
 
-        let public_key = env::var("LANGFUSE_PUBLIC_KEY").map_err(|_| {
-            Error::Configuration("LANGFUSE_PUBLIC_KEY environment variable not set".to_string())
-        })?;
+        let disabled = match env::var("LANGFUSE_ENABLED") {
+            Ok(value) => !parse_enabled(&value)?,
+            Err(_) => false,
+        };
 
-        let secret_key = env::var("LANGFUSE_SECRET_KEY").map_err(|_| {
-            Error::Configuration("LANGFUSE_SECRET_KEY environment variable not set".to_string())
-        })?;
+        let public_key = env::var("LANGFUSE_PUBLIC_KEY").ok();
+        let secret_key = env::var("LANGFUSE_SECRET_KEY").ok();
+        if !disabled {
+            if public_key.is_none() {
+                return Err(Error::Configuration(
+                    "LANGFUSE_PUBLIC_KEY environment variable not set".to_string(),
+                ));
+            }
+            if secret_key.is_none() {
+                return Err(Error::Configuration(
+                    "LANGFUSE_SECRET_KEY environment variable not set".to_string(),
+                ));
+            }
+        }
 
         let base_url = env::var("LANGFUSE_BASE_URL").ok();
+        let proxy = env::var("LANGFUSE_HTTP_PROXY").ok();
+        let no_proxy = env::var("LANGFUSE_NO_PROXY").ok();
+        let environment = env::var("LANGFUSE_TRACING_ENVIRONMENT")
+            .ok()
+            .map(Environment::new)
+            .transpose()?;
 
         Ok(Self {
-            public_key: Some(public_key),
-            secret_key: Some(secret_key),
+            public_key,
+            secret_key,
             base_url,
+            proxy,
+            no_proxy,
+            environment,
+            disabled,
             ..Self::default()
         })
     }
 
+    /// Create a builder pre-populated from a named profile.
+    ///
+    /// Settings are read from `LANGFUSE_<PROFILE>_*` environment variables, falling
+    /// back to the profile's section in the config file. See [`crate::profiles`].
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::profile("staging")?.build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn profile(name: &str) -> Result<Self> {
+        Ok(Self::from_profile_settings(crate::profiles::resolve(
+            name, None,
+        )?))
+    }
+
+    /// Create a builder pre-populated from a named profile in the given config file.
+    ///
+    /// Environment variables still take precedence over the file.
+    pub fn profile_from_file(name: &str, path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Ok(Self::from_profile_settings(crate::profiles::resolve(
+            name,
+            Some(path.as_ref()),
+        )?))
+    }
+
+    fn from_profile_settings(settings: crate::profiles::ProfileSettings) -> Self {
+        Self {
+            public_key: Some(settings.public_key),
+            secret_key: Some(settings.secret_key),
+            base_url: settings.base_url,
+            ..Self::default()
+        }
+    }
+
     /// Set the public key used for authentication.
     #[must_use]
     pub fn public_key(mut self, value: impl Into<String>) -> Self {
@@ -244,6 +925,15 @@ impl ClientBuilder {
         self
     }
 
+    /// Like [`base_url`](Self::base_url), but keeps the current setting when `value` is `None`.
+    #[must_use]
+    pub fn maybe_base_url(mut self, value: Option<impl Into<String>>) -> Self {
+        if let Some(value) = value {
+            self.base_url = Some(value.into());
+        }
+        self
+    }
+
     /// Override the request timeout (defaults to 60 seconds).
     #[must_use]
     pub fn timeout(mut self, value: Duration) -> Self {
@@ -251,6 +941,15 @@ impl ClientBuilder {
         self
     }
 
+    /// Like [`timeout`](Self::timeout), but keeps the current setting when `value` is `None`.
+    #[must_use]
+    pub fn maybe_timeout(mut self, value: Option<Duration>) -> Self {
+        if let Some(value) = value {
+            self.timeout = Some(value);
+        }
+        self
+    }
+
     /// Override the connection timeout (defaults to 10 seconds).
     #[must_use]
     pub fn connect_timeout(mut self, value: Duration) -> Self {
@@ -258,6 +957,15 @@ impl ClientBuilder {
         self
     }
 
+    /// Like [`connect_timeout`](Self::connect_timeout), but keeps the current setting when `value` is `None`.
+    #[must_use]
+    pub fn maybe_connect_timeout(mut self, value: Option<Duration>) -> Self {
+        if let Some(value) = value {
+            self.connect_timeout = Some(value);
+        }
+        self
+    }
+
     /// Override the user agent string.
     #[must_use]
     pub fn user_agent(mut self, value: impl Into<String>) -> Self {
@@ -265,17 +973,32 @@ impl ClientBuilder {
         self
     }
 
-    /// Set a custom HTTP client with middleware.
+    /// Like [`user_agent`](Self::user_agent), but keeps the current setting when `value` is `None`.
+    #[must_use]
+    pub fn maybe_user_agent(mut self, value: Option<impl Into<String>>) -> Self {
+        if let Some(value) = value {
+            self.user_agent = Some(value.into());
+        }
+        self
+    }
+
+    /// Send every request through a preconfigured HTTP client.
     ///
-    /// This allows you to provide a pre-configured `ClientWithMiddleware` with
-    /// custom settings like retry policies, connection pooling, logging, etc.
+    /// Accepts a `reqwest::Client`, e.g. with a proxy or custom TLS roots, or a
+    /// `reqwest_middleware::ClientWithMiddleware` with retry, tracing, or other
+    /// middleware. The client is used for all API calls, including ingestion
+    /// and media uploads; authentication and latency tracking are layered on
+    /// top of it. [`ClientBuilder::timeout`] and
+    /// [`ClientBuilder::connect_timeout`] don't apply, since they are settings
+    /// of the client.
     ///
     /// # Example
     ///
-    /// ```rust,ignore
+    /// ```no_run
     /// use reqwest_middleware::ClientBuilder;
-    /// use reqwest_retry::{RetryTransientMiddleware, policies::ExponentialBackoff};
+    /// use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
     ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
     /// let client = ClientBuilder::new(reqwest::Client::new())
     ///     .with(RetryTransientMiddleware::new_with_policy(retry_policy))
@@ -284,33 +1007,546 @@ impl ClientBuilder {
     /// let langfuse_client = langfuse_ergonomic::ClientBuilder::from_env()?
     ///     .http_client(client)
     ///     .build()?;
+    /// # Ok(())
+    /// # }
     /// ```
     #[must_use]
-    pub fn http_client(mut self, client: reqwest_middleware::ClientWithMiddleware) -> Self {
-        self.http_client = Some(client);
+    pub fn http_client(
+        mut self,
+        client: impl Into<reqwest_middleware::ClientWithMiddleware>,
+    ) -> Self {
+        self.http_client = Some(client.into());
+        self
+    }
+
+    /// Send requests through the proxy at `url`, e.g. `http://proxy.internal:3128`.
+    ///
+    /// Credentials can be given in the URL. Without a proxy, the standard
+    /// `HTTPS_PROXY`, `HTTP_PROXY`, and `NO_PROXY` variables apply.
+    /// [`ClientBuilder::from_env`] reads the proxy from `LANGFUSE_HTTP_PROXY`.
+    /// Doesn't apply with [`ClientBuilder::http_client`].
+    #[must_use]
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Pace requests to the Langfuse API to stay within `limit`.
+    ///
+    /// The limit is shared by clones of the client. See [`crate::rate_limit`].
+    #[must_use]
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limit = Some(limit);
+        self
+    }
+
+    /// Bypass the proxy for these hosts, given as a comma-separated list like `NO_PROXY`.
+    ///
+    /// [`ClientBuilder::from_env`] reads the list from `LANGFUSE_NO_PROXY`.
+    #[must_use]
+    pub fn no_proxy(mut self, hosts: impl Into<String>) -> Self {
+        self.no_proxy = Some(hosts.into());
+        self
+    }
+
+    /// Limit the serialized size of individual input, output, and metadata values.
+    ///
+    /// Values above the limit are handled according to
+    /// [`ClientBuilder::field_size_policy`] (truncated by default) when the event is
+    /// built, and their original size is recorded in the event metadata. The
+    /// limit must be at least [`MIN_FIELD_BYTES`], the size of a truncated
+    /// value's marker.
+    #[must_use]
+    pub fn max_field_bytes(mut self, value: usize) -> Self {
+        self.max_field_bytes = Some(value);
+        self
+    }
+
+    /// Limit the serialized size of input values below [`ClientBuilder::max_field_bytes`].
+    ///
+    /// The input and output limits also apply to raw events queued in
+    /// batchers or passed to [`LangfuseClient::ingest`]. See [`crate::payload`].
+    #[must_use]
+    pub fn max_input_bytes(mut self, value: usize) -> Self {
+        self.max_input_bytes = Some(value);
+        self
+    }
+
+    /// Limit the serialized size of output values below [`ClientBuilder::max_field_bytes`].
+    ///
+    /// See [`ClientBuilder::max_input_bytes`].
+    #[must_use]
+    pub fn max_output_bytes(mut self, value: usize) -> Self {
+        self.max_output_bytes = Some(value);
+        self
+    }
+
+    /// Choose how fields exceeding [`ClientBuilder::max_field_bytes`] are handled.
+    #[must_use]
+    pub fn field_size_policy(mut self, value: FieldSizePolicy) -> Self {
+        self.field_size_policy = value;
+        self
+    }
+
+    /// Override the length limits applied to names and status messages.
+    ///
+    /// By default names are limited to [`DEFAULT_MAX_NAME_CHARS`](crate::payload::DEFAULT_MAX_NAME_CHARS)
+    /// and status messages to
+    /// [`DEFAULT_MAX_STATUS_MESSAGE_CHARS`](crate::payload::DEFAULT_MAX_STATUS_MESSAGE_CHARS)
+    /// characters, and longer values are truncated with a warning.
+    #[must_use]
+    pub fn text_length_limits(mut self, value: TextLengthLimits) -> Self {
+        self.text_length_limits = value;
+        self
+    }
+
+    /// Stamp traces and observations with a `schema_version` metadata entry.
+    ///
+    /// Explicit `schema_version` metadata values are kept. See [`crate::schema`]
+    /// for upgrading older events when the conventions change.
+    #[must_use]
+    pub fn schema_version(mut self, value: SchemaVersion) -> Self {
+        self.schema_version = Some(value);
+        self
+    }
+
+    /// Require metadata keys or tags on every trace, e.g. a `tenant_id`.
+    ///
+    /// See [`crate::policy`] for how violations are handled.
+    #[must_use]
+    pub fn trace_requirements(mut self, value: TraceRequirements) -> Self {
+        self.trace_requirements = Some(value);
+        self
+    }
+
+    /// Enforce naming conventions on trace, observation, and score names.
+    ///
+    /// See [`crate::policy`] for the available rules.
+    #[must_use]
+    pub fn naming_policy(mut self, value: NamingPolicy) -> Self {
+        self.naming_policy = Some(value);
+        self
+    }
+
+    /// Upload input and output values larger than `bytes` (serialized) as media.
+    ///
+    /// The value is replaced with a media reference that the Langfuse UI
+    /// resolves, keeping ingestion batches small without truncating payloads.
+    /// Applies to traces and observations created through the client. See
+    /// [`crate::media`].
+    #[must_use]
+    pub fn media_offload_threshold(mut self, bytes: usize) -> Self {
+        self.media_offload_threshold = Some(bytes);
+        self
+    }
+
+    /// Assign traces, observations, and scores created through the client to `environment`.
+    ///
+    /// Events that already name an environment keep it. See [`crate::environment`].
+    #[must_use]
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Write a sample of outgoing ingestion payloads to a local file.
+    ///
+    /// Covers events sent by the client and by batchers built from it. See
+    /// [`crate::payload_capture`].
+    #[must_use]
+    pub fn capture_payloads(mut self, capture: PayloadCapture) -> Self {
+        self.payload_capture = Some(Arc::new(capture));
+        self
+    }
+
+    /// Send scores created with [`LangfuseClient::score`] through `transport`.
+    ///
+    /// Defaults to [`ScoreTransport::Ingestion`]; individual scores can
+    /// override it.
+    #[must_use]
+    pub fn score_transport(mut self, transport: ScoreTransport) -> Self {
+        self.score_transport = transport;
+        self
+    }
+
+    /// Run `interceptor` on every ingestion event before it is sent.
+    ///
+    /// Can be called several times; interceptors run in the order they were
+    /// added. See [`crate::interceptor`].
+    #[must_use]
+    pub fn interceptor(mut self, interceptor: impl BatchInterceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Redact the input, output, and metadata of every event before it is sent.
+    ///
+    /// Uses [`Redactor::default`] unless [`ClientBuilder::redactor`] sets
+    /// other rules. See [`crate::security`].
+    #[must_use]
+    pub fn redact_payloads(mut self, enabled: bool) -> Self {
+        self.redact_payloads = enabled;
+        self
+    }
+
+    /// Redact outgoing events with `redactor`, enabling [`ClientBuilder::redact_payloads`].
+    #[must_use]
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self.redact_payloads = true;
+        self
+    }
+
+    /// Pass the input and output of every trace and observation through `mask` before it is sent.
+    ///
+    /// Mirrors the `mask` option of the Langfuse Python and JS SDKs. Runs
+    /// after redaction and before interceptors, and on values uploaded as
+    /// media. See [`crate::security`].
+    #[must_use]
+    pub fn mask(
+        mut self,
+        mask: impl Fn(&serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    ) -> Self {
+        self.mask = Some(Mask::new(mask));
+        self
+    }
+
+    /// Turn the client into a no-op that never contacts Langfuse.
+    ///
+    /// Builders still validate their input and return the IDs and URLs they
+    /// would have, so instrumented code runs unchanged in local development
+    /// and tests without credentials or a server. Events sent by the client
+    /// and its batchers are reported as delivered and dropped, and no media is
+    /// uploaded. Other API calls, such as fetching traces or prompts, fail
+    /// with [`Error::Configuration`]. [`ClientBuilder::from_env`] disables the
+    /// client when `LANGFUSE_ENABLED` is `false`.
+    #[must_use]
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    /// Keep the events a disabled client would have sent
+    #[cfg(feature = "testing")]
+    pub(crate) fn recorder(mut self, recorder: Arc<crate::testing::Recorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Send only this fraction of traces, with their observations and scores.
+    ///
+    /// Shorthand for [`ClientBuilder::sampler`] with [`Sampler::ratio`].
+    #[must_use]
+    pub fn sample_rate(self, rate: f64) -> Self {
+        self.sampler(Sampler::ratio(rate))
+    }
+
+    /// Decide with `sampler` which traces are sent. See [`crate::sampling`].
+    #[must_use]
+    pub fn sampler(mut self, sampler: Sampler) -> Self {
+        self.sampler = Some(Arc::new(sampler));
+        self
+    }
+
+    /// Build trace URLs in the project-scoped form `/project/{projectId}/traces/{id}`.
+    ///
+    /// The project ID is fetched with [`LangfuseClient::current_project`] the
+    /// first time a URL is built and cached for the client and its clones. If
+    /// it can't be fetched, URLs fall back to the unscoped `/trace/{id}` form.
+    #[must_use]
+    pub fn project_scoped_urls(mut self, enabled: bool) -> Self {
+        self.project_scoped_urls = enabled;
+        self
+    }
+
+    /// Compress ingestion request bodies. See [`crate::compression`].
+    #[cfg(feature = "compression")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+    #[must_use]
+    pub fn request_compression(
+        mut self,
+        compression: crate::compression::RequestCompression,
+    ) -> Self {
+        self.request_compression = Some(compression);
+        self
+    }
+
+    /// Set the ID of the client's project instead of fetching it.
+    ///
+    /// Enables [project-scoped URLs](ClientBuilder::project_scoped_urls), e.g.
+    /// for organization-scoped API keys whose project can't be looked up.
+    #[must_use]
+    pub fn project_id(mut self, project_id: impl Into<String>) -> Self {
+        self.project_id = Some(project_id.into());
+        self
+    }
+
+    /// Register a callback invoked with the timing of every API call.
+    ///
+    /// Calls are also reported as `tracing` debug events and summarized by
+    /// [`LangfuseClient::latency_summary`].
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = ClientBuilder::from_env()?
+    ///     .on_api_call(|call| {
+    ///         if call.duration.as_millis() > 500 {
+    ///             eprintln!("slow Langfuse call: {} {} {:?}", call.method, call.endpoint, call.duration);
+    ///         }
+    ///     })
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn on_api_call<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ApiCallTiming) + Send + Sync + 'static,
+    {
+        self.on_api_call = Some(Arc::new(callback));
+        self
+    }
+
+    /// Register a callback invoked with every API request before it is sent.
+    ///
+    /// Request bodies are redacted. See [`crate::http_log`].
+    #[must_use]
+    pub fn on_request<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ApiRequest) + Send + Sync + 'static,
+    {
+        self.on_request = Some(Arc::new(callback));
+        self
+    }
+
+    /// Register a callback invoked with the status and latency of every API request.
+    ///
+    /// See [`crate::http_log`].
+    #[must_use]
+    pub fn on_response<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ApiResponse) + Send + Sync + 'static,
+    {
+        self.on_response = Some(Arc::new(callback));
+        self
+    }
+
+    /// Authenticate requests with `provider` instead of the public and secret key.
+    ///
+    /// The keys are not required when a provider is set. See [`crate::auth`].
+    #[must_use]
+    pub fn auth(mut self, provider: impl AuthProvider + 'static) -> Self {
+        self.auth = Some(Arc::new(provider));
         self
     }
 
     /// Build a [`LangfuseClient`] using the configured options.
     pub fn build(self) -> Result<LangfuseClient> {
-        let public_key = self
-            .public_key
-            .ok_or_else(|| Error::Configuration("Langfuse public key is required".to_string()))?;
-        let secret_key = self
-            .secret_key
-            .ok_or_else(|| Error::Configuration("Langfuse secret key is required".to_string()))?;
+        let auth = match &self.auth {
+            Some(auth) => auth.clone(),
+            // Requests never leave a disabled client, so it needs no credentials
+            None if self.disabled && (self.public_key.is_none() || self.secret_key.is_none()) => {
+                Arc::new(BasicAuth::new("", "")?) as Arc<dyn AuthProvider>
+            }
+            None => {
+                let public_key = self.public_key.as_deref().ok_or_else(|| {
+                    Error::Configuration("Langfuse public key is required".to_string())
+                })?;
+                let secret_key = self.secret_key.as_deref().ok_or_else(|| {
+                    Error::Configuration("Langfuse secret key is required".to_string())
+                })?;
+                Arc::new(BasicAuth::new(public_key, secret_key)?) as Arc<dyn AuthProvider>
+            }
+        };
         let base_url = self
             .base_url
+            .clone()
             .unwrap_or_else(|| "https://cloud.langfuse.com".to_string());
 
+        if self.text_length_limits.max_name_chars == 0
+            || self.text_length_limits.max_status_message_chars == 0
+        {
+            return Err(Error::Configuration(
+                "text length limits must be greater than 0".to_string(),
+            ));
+        }
+        for (name, max) in [
+            ("max_field_bytes", self.max_field_bytes),
+            ("max_input_bytes", self.max_input_bytes),
+            ("max_output_bytes", self.max_output_bytes),
+        ] {
+            if max.is_some_and(|max| max < MIN_FIELD_BYTES) {
+                return Err(Error::Configuration(format!(
+                    "{name} must be at least {MIN_FIELD_BYTES}"
+                )));
+            }
+        }
+        let field_size_limit = (self.max_field_bytes.is_some()
+            || self.max_input_bytes.is_some()
+            || self.max_output_bytes.is_some())
+        .then(|| FieldSizeLimit {
+            max_bytes: self.max_field_bytes.unwrap_or(usize::MAX),
+            max_input_bytes: self.max_input_bytes,
+            max_output_bytes: self.max_output_bytes,
+            policy: self.field_size_policy,
+        });
+        if let Some(limit) = self.rate_limit {
+            if !(limit.requests_per_second.is_finite() && limit.requests_per_second > 0.0) {
+                return Err(Error::Configuration(
+                    "rate limit must allow more than 0 requests per second".to_string(),
+                ));
+            }
+            if limit.burst == 0 {
+                return Err(Error::Configuration(
+                    "rate limit burst must be greater than 0".to_string(),
+                ));
+            }
+        }
+        let proxy = match &self.proxy {
+            Some(url) => {
+                let proxy = reqwest::Proxy::all(url.as_str())
+                    .map_err(|e| Error::Configuration(format!("Invalid proxy URL: {e}")))?;
+                Some(
+                    proxy.no_proxy(
+                        self.no_proxy
+                            .as_deref()
+                            .and_then(reqwest::NoProxy::from_string),
+                    ),
+                )
+            }
+            None => None,
+        };
+
         Ok(LangfuseClient::build_internal(
-            public_key,
-            secret_key,
+            auth,
             base_url,
-            self.timeout,
-            self.connect_timeout,
-            self.user_agent,
-            self.http_client,
+            field_size_limit,
+            proxy,
+            self,
         ))
     }
 }
+
+/// Parse the value of `LANGFUSE_ENABLED`
+fn parse_enabled(value: &str) -> Result<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(Error::Configuration(format!(
+            "LANGFUSE_ENABLED must be true or false, got `{value}`"
+        ))),
+    }
+}
+
+/// Fails every request of a disabled client before it is sent
+struct DisabledMiddleware;
+
+#[async_trait::async_trait]
+impl reqwest_middleware::Middleware for DisabledMiddleware {
+    async fn handle(
+        &self,
+        _req: reqwest::Request,
+        _extensions: &mut http::Extensions,
+        _next: reqwest_middleware::Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        Err(reqwest_middleware::Error::middleware(Error::Configuration(
+            "Langfuse client is disabled".to_string(),
+        )))
+    }
+}
+
+/// `url` with any username and password removed
+fn without_credentials(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) if !parsed.username().is_empty() || parsed.password().is_some() => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// Request settings changed by [`LangfuseClient::with_overrides`]
+///
+/// The connection timeout belongs to the shared connection pool and cannot be
+/// overridden.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOverrides {
+    base_url: Option<String>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl ClientOverrides {
+    /// Send requests to a different Langfuse base URL
+    ///
+    /// The variant fetches the project ID of that instance instead of using
+    /// the parent's.
+    #[must_use]
+    pub fn base_url(mut self, value: impl Into<String>) -> Self {
+        self.base_url = Some(value.into());
+        self
+    }
+
+    /// Use a different request timeout
+    #[must_use]
+    pub fn timeout(mut self, value: Duration) -> Self {
+        self.timeout = Some(value);
+        self
+    }
+
+    /// Use a different user agent string
+    #[must_use]
+    pub fn user_agent(mut self, value: impl Into<String>) -> Self {
+        self.user_agent = Some(value.into());
+        self
+    }
+
+    /// Add a header to every request, replacing any existing value
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+}
+
+/// Applies the timeout and headers of [`ClientOverrides`] to each request
+struct OverridesMiddleware {
+    timeout: Option<Duration>,
+    headers: http::HeaderMap,
+}
+
+#[async_trait::async_trait]
+impl reqwest_middleware::Middleware for OverridesMiddleware {
+    async fn handle(
+        &self,
+        mut req: reqwest::Request,
+        extensions: &mut http::Extensions,
+        next: reqwest_middleware::Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        if let Some(timeout) = self.timeout {
+            *req.timeout_mut() = Some(timeout);
+        }
+        for (name, value) in &self.headers {
+            req.headers_mut().insert(name, value.clone());
+        }
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_enabled() {
+        assert!(parse_enabled("true").unwrap());
+        assert!(parse_enabled(" 1 ").unwrap());
+        assert!(!parse_enabled("FALSE").unwrap());
+        assert!(!parse_enabled("off").unwrap());
+        assert!(parse_enabled("maybe").is_err());
+    }
+}
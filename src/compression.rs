@@ -0,0 +1,182 @@
+//! Compression of ingestion request bodies
+//!
+//! Trace payloads with large inputs and outputs compress well. With
+//! [`ClientBuilder::request_compression`](crate::ClientBuilder::request_compression),
+//! ingestion requests above a size threshold are sent gzip-compressed, by
+//! single calls and batchers alike:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::compression::RequestCompression;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .request_compression(RequestCompression::gzip().min_bytes(4096))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! If the server answers a compressed request with `415 Unsupported Media
+//! Type`, as a proxy or older instance without request decompression may, the
+//! request is resent uncompressed and the client stops compressing. Request
+//! bodies of compressed requests are not passed to
+//! [`on_request`](crate::ClientBuilder::on_request) callbacks.
+//!
+//! Only gzip is supported for now; zstd is not implemented.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+/// Default size below which request bodies are sent uncompressed
+const DEFAULT_MIN_BYTES: usize = 1024;
+
+/// Content encoding used for request bodies
+///
+/// Only gzip for now. The enum is non-exhaustive so further encodings, such
+/// as zstd, can be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentEncoding {
+    /// `Content-Encoding: gzip`
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// Value of the `Content-Encoding` header
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+        }
+    }
+}
+
+/// Settings for compressing ingestion request bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestCompression {
+    encoding: ContentEncoding,
+    min_bytes: usize,
+    level: u32,
+}
+
+impl RequestCompression {
+    /// Compress with gzip at the default level, above 1 KiB
+    pub fn gzip() -> Self {
+        Self {
+            encoding: ContentEncoding::Gzip,
+            min_bytes: DEFAULT_MIN_BYTES,
+            level: Compression::default().level(),
+        }
+    }
+
+    /// Send bodies smaller than `bytes` uncompressed
+    #[must_use]
+    pub fn min_bytes(mut self, bytes: usize) -> Self {
+        self.min_bytes = bytes;
+        self
+    }
+
+    /// Compression level from 0 (none) to 9 (best); values above 9 are capped
+    #[must_use]
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = level.min(9);
+        self
+    }
+
+    /// Encoding of compressed bodies
+    pub fn encoding(&self) -> ContentEncoding {
+        self.encoding
+    }
+
+    /// Size below which bodies are sent uncompressed
+    pub fn min_bytes_threshold(&self) -> usize {
+        self.min_bytes
+    }
+}
+
+impl Default for RequestCompression {
+    fn default() -> Self {
+        Self::gzip()
+    }
+}
+
+/// Request compression of a client, shared by its clones and batchers
+#[derive(Debug)]
+pub(crate) struct CompressionState {
+    config: RequestCompression,
+    /// Set once the server rejected a compressed body
+    unsupported: AtomicBool,
+}
+
+impl CompressionState {
+    pub(crate) fn new(config: RequestCompression) -> Self {
+        Self {
+            config,
+            unsupported: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn config(&self) -> RequestCompression {
+        self.config
+    }
+
+    /// Compressed `body` and its encoding, unless it is too small to bother
+    /// or the server does not accept compressed bodies
+    pub(crate) fn compress(&self, body: &[u8]) -> Option<(Vec<u8>, ContentEncoding)> {
+        if body.len() < self.config.min_bytes || self.unsupported.load(Ordering::Relaxed) {
+            return None;
+        }
+        match self.config.encoding {
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(
+                    Vec::with_capacity(body.len() / 4),
+                    Compression::new(self.config.level),
+                );
+                encoder.write_all(body).ok()?;
+                encoder
+                    .finish()
+                    .ok()
+                    .map(|compressed| (compressed, ContentEncoding::Gzip))
+            }
+        }
+    }
+
+    /// Stop compressing after the server rejected a compressed body
+    pub(crate) fn mark_unsupported(&self) {
+        if !self.unsupported.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                encoding = self.config.encoding.as_str(),
+                "Langfuse rejected a compressed request body; sending uncompressed from now on"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_compresses_above_threshold() {
+        let state = CompressionState::new(RequestCompression::gzip().min_bytes(100));
+        assert!(state.compress(&[b'a'; 99]).is_none());
+
+        let body = br#"{"batch":[]}"#.repeat(100);
+        let (compressed, encoding) = state.compress(&body).unwrap();
+        assert_eq!(encoding, ContentEncoding::Gzip);
+        assert!(compressed.len() < body.len());
+        let mut decompressed = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+
+        state.mark_unsupported();
+        assert!(state.compress(&body).is_none());
+    }
+}
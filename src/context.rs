@@ -0,0 +1,377 @@
+//! Fluent construction of trace trees
+//!
+//! A [`TraceContext`] points at a position in a trace: the trace itself, or an
+//! observation within it. Observations created through the context get the
+//! trace ID and parent observation ID filled in. Spans and generations return
+//! an [`ObservationHandle`], which dereferences to a context for nesting their
+//! children and can update or end the observation:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let trace = client.trace().name("pipeline").call().await?.context(&client);
+//!
+//! let retrieval = trace.span().name("retrieval").call().await?;
+//! retrieval.event().name("cache-miss").call().await?;
+//! retrieval.end().await?;
+//!
+//! let answer = trace.generation().name("answer").model("gpt-4").call().await?;
+//! answer.update_output(serde_json::json!("42")).await?;
+//! answer.score().name("quality").value(0.9).call().await?;
+//! answer.end().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use bon::bon;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::client::LangfuseClient;
+use crate::environment::Environment;
+use crate::error::Result;
+use crate::scores::ScoreValue;
+use crate::usage::Usage;
+
+/// A trace, or an observation within a trace, that children can be attached to
+///
+/// Obtain one with [`TraceResponse::context`](crate::TraceResponse::context) or
+/// [`LangfuseClient::trace_context`].
+#[derive(Clone)]
+pub struct TraceContext {
+    client: LangfuseClient,
+    trace_id: String,
+    observation_id: Option<String>,
+}
+
+impl std::fmt::Debug for TraceContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceContext")
+            .field("trace_id", &self.trace_id)
+            .field("observation_id", &self.observation_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[bon]
+impl TraceContext {
+    pub(crate) fn new(client: LangfuseClient, trace_id: String) -> Self {
+        Self {
+            client,
+            trace_id,
+            observation_id: None,
+        }
+    }
+
+    /// ID of the trace
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// ID of the observation this context points at, or `None` for the trace itself
+    pub fn observation_id(&self) -> Option<&str> {
+        self.observation_id.as_deref()
+    }
+
+    /// Set the output of the trace, also when this context points at an observation
+    pub async fn set_output(&self, output: Value) -> Result<()> {
+        self.client
+            .update_trace()
+            .id(self.trace_id.clone())
+            .output(output)
+            .call()
+            .await
+            .map(|_| ())
+    }
+
+    /// Context for an existing observation of the same trace
+    pub fn child(&self, observation_id: impl Into<String>) -> Self {
+        Self {
+            client: self.client.clone(),
+            trace_id: self.trace_id.clone(),
+            observation_id: Some(observation_id.into()),
+        }
+    }
+
+    fn handle(&self, id: String, kind: ObservationKind) -> ObservationHandle {
+        ObservationHandle {
+            context: self.child(id),
+            parent_observation_id: self.observation_id.clone(),
+            kind,
+        }
+    }
+
+    /// Create a span under this context and return a handle to it
+    #[builder]
+    pub async fn span(
+        &self,
+        #[builder(into)] id: Option<String>,
+        #[builder(into)] name: Option<String>,
+        input: Option<Value>,
+        output: Option<Value>,
+        metadata: Option<Value>,
+        #[builder(into)] level: Option<String>,
+        #[builder(into)] status_message: Option<String>,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+    ) -> Result<ObservationHandle> {
+        let id = self
+            .client
+            .span()
+            .trace_id(self.trace_id.clone())
+            .maybe_parent_observation_id(self.observation_id.clone())
+            .maybe_id(id)
+            .maybe_name(name)
+            .maybe_input(input)
+            .maybe_output(output)
+            .maybe_metadata(metadata)
+            .maybe_level(level)
+            .maybe_status_message(status_message)
+            .maybe_start_time(start_time)
+            .maybe_end_time(end_time)
+            .maybe_environment(environment)
+            .maybe_event_id(event_id)
+            .call()
+            .await?;
+        Ok(self.handle(id.into(), ObservationKind::Span))
+    }
+
+    /// Create a generation under this context and return a handle to it
+    #[builder]
+    pub async fn generation(
+        &self,
+        #[builder(into)] id: Option<String>,
+        #[builder(into)] name: Option<String>,
+        input: Option<Value>,
+        output: Option<Value>,
+        metadata: Option<Value>,
+        #[builder(into)] level: Option<String>,
+        #[builder(into)] status_message: Option<String>,
+        start_time: Option<DateTime<Utc>>,
+        end_time: Option<DateTime<Utc>>,
+        #[builder(into)] model: Option<String>,
+        model_parameters: Option<Value>,
+        prompt_tokens: Option<i32>,
+        completion_tokens: Option<i32>,
+        total_tokens: Option<i32>,
+        usage_details: Option<HashMap<String, i32>>,
+        cost_details: Option<HashMap<String, f64>>,
+        usage: Option<Usage>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+    ) -> Result<ObservationHandle> {
+        let id = self
+            .client
+            .generation()
+            .trace_id(self.trace_id.clone())
+            .maybe_parent_observation_id(self.observation_id.clone())
+            .maybe_id(id)
+            .maybe_name(name)
+            .maybe_input(input)
+            .maybe_output(output)
+            .maybe_metadata(metadata)
+            .maybe_level(level)
+            .maybe_status_message(status_message)
+            .maybe_start_time(start_time)
+            .maybe_end_time(end_time)
+            .maybe_model(model)
+            .maybe_model_parameters(model_parameters)
+            .maybe_prompt_tokens(prompt_tokens)
+            .maybe_completion_tokens(completion_tokens)
+            .maybe_total_tokens(total_tokens)
+            .maybe_usage_details(usage_details)
+            .maybe_cost_details(cost_details)
+            .maybe_usage(usage)
+            .maybe_environment(environment)
+            .maybe_event_id(event_id)
+            .call()
+            .await?;
+        Ok(self.handle(id.into(), ObservationKind::Generation))
+    }
+
+    /// Create an event under this context and return its ID
+    #[builder]
+    pub async fn event(
+        &self,
+        #[builder(into)] id: Option<String>,
+        #[builder(into)] name: Option<String>,
+        input: Option<Value>,
+        output: Option<Value>,
+        metadata: Option<Value>,
+        #[builder(into)] level: Option<String>,
+        #[builder(into)] status_message: Option<String>,
+        start_time: Option<DateTime<Utc>>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+    ) -> Result<String> {
+        self.client
+            .event()
+            .trace_id(self.trace_id.clone())
+            .maybe_parent_observation_id(self.observation_id.clone())
+            .maybe_id(id)
+            .maybe_name(name)
+            .maybe_input(input)
+            .maybe_output(output)
+            .maybe_metadata(metadata)
+            .maybe_level(level)
+            .maybe_status_message(status_message)
+            .maybe_start_time(start_time)
+            .maybe_environment(environment)
+            .maybe_event_id(event_id)
+            .call()
+            .await
+            .map(String::from)
+    }
+
+    /// Score the observation this context points at, or the trace itself
+    #[builder]
+    pub async fn score(
+        &self,
+        #[builder(into)] name: String,
+        #[builder(into)] value: ScoreValue,
+        #[builder(into)] comment: Option<String>,
+        #[builder(into)] queue_id: Option<String>,
+        metadata: Option<Value>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+    ) -> Result<String> {
+        self.client
+            .score()
+            .trace_id(self.trace_id.clone())
+            .maybe_observation_id(self.observation_id.clone())
+            .name(name)
+            .value(value)
+            .maybe_comment(comment)
+            .maybe_queue_id(queue_id)
+            .maybe_metadata(metadata)
+            .maybe_environment(environment)
+            .maybe_event_id(event_id)
+            .call()
+            .await
+    }
+}
+
+/// Type of observation an [`ObservationHandle`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObservationKind {
+    /// A span
+    Span,
+    /// A generation
+    Generation,
+}
+
+/// A span or generation created through a [`TraceContext`]
+///
+/// The handle keeps the IDs needed to update the observation, and dereferences
+/// to the [`TraceContext`] of the observation for creating children.
+#[derive(Debug, Clone)]
+pub struct ObservationHandle {
+    context: TraceContext,
+    parent_observation_id: Option<String>,
+    kind: ObservationKind,
+}
+
+impl Deref for ObservationHandle {
+    type Target = TraceContext;
+
+    fn deref(&self) -> &TraceContext {
+        &self.context
+    }
+}
+
+impl ObservationHandle {
+    /// ID of the observation
+    pub fn id(&self) -> &str {
+        self.context.observation_id().unwrap_or_default()
+    }
+
+    /// Whether the observation is a span or a generation
+    pub fn kind(&self) -> ObservationKind {
+        self.kind
+    }
+
+    /// Context for creating children of the observation
+    pub fn context(&self) -> &TraceContext {
+        &self.context
+    }
+
+    /// Mark the observation as ended now
+    pub async fn end(&self) -> Result<()> {
+        self.update(None, None, None, Some(Utc::now())).await
+    }
+
+    /// Set the output of the observation
+    pub async fn update_output(&self, output: Value) -> Result<()> {
+        self.update(Some(output), None, None, None).await
+    }
+
+    /// Mark the observation as failed with `status_message` and end it now
+    pub async fn error(&self, status_message: impl Into<String>) -> Result<()> {
+        self.update(
+            None,
+            Some("ERROR".to_string()),
+            Some(status_message.into()),
+            Some(Utc::now()),
+        )
+        .await
+    }
+
+    pub(crate) async fn update(
+        &self,
+        output: Option<Value>,
+        level: Option<String>,
+        status_message: Option<String>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let client = &self.context.client;
+        let id = self.id().to_string();
+        let trace_id = self.context.trace_id.clone();
+        match self.kind {
+            ObservationKind::Span => {
+                client
+                    .update_span()
+                    .id(id)
+                    .trace_id(trace_id)
+                    .maybe_parent_observation_id(self.parent_observation_id.clone())
+                    .maybe_output(output)
+                    .maybe_level(level)
+                    .maybe_status_message(status_message)
+                    .maybe_end_time(end_time)
+                    .call()
+                    .await?;
+            }
+            ObservationKind::Generation => {
+                client
+                    .update_generation()
+                    .id(id)
+                    .trace_id(trace_id)
+                    .maybe_parent_observation_id(self.parent_observation_id.clone())
+                    .maybe_output(output)
+                    .maybe_level(level)
+                    .maybe_status_message(status_message)
+                    .maybe_end_time(end_time)
+                    .call()
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
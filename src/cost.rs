@@ -0,0 +1,424 @@
+//! Cost guardrails for LLM generations
+//!
+//! [`CostGuardrail`] prices generation token usage locally with a per-model
+//! [`ModelPricing`] table, keeps a rolling per-model cost window, and reports
+//! anomalies to Langfuse as soon as they happen: when a single trace or the
+//! rolling window exceeds its threshold, an `ERROR`-level `cost-anomaly` event
+//! is attached to the trace, optionally together with a numeric score.
+//!
+//! Requires the `cost-guardrails` feature.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use langfuse_ergonomic::cost::{CostGuardrail, ModelPricing};
+//! use langfuse_ergonomic::{ClientBuilder, Usage};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//!
+//! let guardrail = CostGuardrail::builder()
+//!     .client(client)
+//!     .pricing("gpt-4", ModelPricing::per_million_tokens(30.0, 60.0))
+//!     .max_trace_cost(0.50)
+//!     .max_window_cost(20.0)
+//!     .window(Duration::from_secs(3600))
+//!     .score_name("cost_anomaly")
+//!     .build();
+//!
+//! let check = guardrail
+//!     .record_usage("trace-id", "gpt-4", &Usage::new(1_200, 350))
+//!     .await?;
+//! if !check.violations.is_empty() {
+//!     eprintln!("cost anomaly: {:?}", check.violations);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use bon::bon;
+use serde_json::json;
+
+use crate::client::LangfuseClient;
+use crate::error::{Error, Result};
+use crate::usage::Usage;
+
+/// Name of the events emitted for cost anomalies
+pub const COST_ANOMALY_EVENT: &str = "cost-anomaly";
+
+/// Default length of the rolling cost window
+pub const DEFAULT_COST_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Maximum number of traces whose running cost is remembered
+const MAX_TRACKED_TRACES: usize = 10_000;
+
+/// Price of a model's input and output tokens, in USD per token
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    /// Price of one input (prompt) token
+    pub input: f64,
+    /// Price of one output (completion) token
+    pub output: f64,
+}
+
+impl ModelPricing {
+    /// Pricing from USD per million input and output tokens
+    pub fn per_million_tokens(input: f64, output: f64) -> Self {
+        Self {
+            input: input / 1_000_000.0,
+            output: output / 1_000_000.0,
+        }
+    }
+
+    /// Cost of the prompt and completion tokens of `usage`
+    ///
+    /// Missing counts are priced as zero; costs already set on `usage` are
+    /// not taken into account.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let tokens = |count: Option<i32>| f64::from(count.unwrap_or(0).max(0));
+        tokens(usage.prompt_tokens) * self.input + tokens(usage.completion_tokens) * self.output
+    }
+}
+
+/// A cost threshold that was crossed
+#[derive(Debug, Clone, PartialEq)]
+pub enum CostViolation {
+    /// The accumulated cost of one trace exceeded the per-trace limit
+    Trace {
+        /// Accumulated cost of the trace
+        total: f64,
+        /// Configured limit
+        limit: f64,
+    },
+    /// The cost across all models within the rolling window exceeded the limit
+    Window {
+        /// Cost within the window
+        total: f64,
+        /// Configured limit
+        limit: f64,
+        /// Length of the window
+        window: Duration,
+    },
+}
+
+/// Outcome of recording one generation's cost
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CostCheck {
+    /// Cost of the recorded generation
+    pub cost: f64,
+    /// Accumulated cost of the trace
+    pub trace_total: f64,
+    /// Cost across all models within the rolling window
+    pub window_total: f64,
+    /// Thresholds newly crossed by this generation
+    ///
+    /// Each threshold is reported once per trace, and once per window
+    /// crossing, so repeated generations do not flood Langfuse with events.
+    pub violations: Vec<CostViolation>,
+}
+
+/// Local cost bookkeeping, independent of the client
+#[derive(Debug, Default)]
+struct CostLedger {
+    window_entries: VecDeque<(Instant, String, f64)>,
+    trace_totals: HashMap<String, (f64, bool)>,
+    trace_order: VecDeque<String>,
+    window_alerted: bool,
+}
+
+impl CostLedger {
+    fn record(
+        &mut self,
+        now: Instant,
+        trace_id: &str,
+        model: &str,
+        cost: f64,
+        limits: &Limits,
+    ) -> CostCheck {
+        while let Some((at, _, _)) = self.window_entries.front() {
+            if now.saturating_duration_since(*at) < limits.window {
+                break;
+            }
+            self.window_entries.pop_front();
+        }
+        self.window_entries
+            .push_back((now, model.to_string(), cost));
+        let window_total: f64 = self.window_entries.iter().map(|(_, _, c)| c).sum();
+
+        if !self.trace_totals.contains_key(trace_id) {
+            if self.trace_order.len() == MAX_TRACKED_TRACES {
+                if let Some(oldest) = self.trace_order.pop_front() {
+                    self.trace_totals.remove(&oldest);
+                }
+            }
+            self.trace_order.push_back(trace_id.to_string());
+        }
+        let (trace_total, trace_alerted) = self
+            .trace_totals
+            .entry(trace_id.to_string())
+            .or_insert((0.0, false));
+        *trace_total += cost;
+
+        let mut violations = Vec::new();
+        if let Some(limit) = limits.max_trace_cost {
+            if *trace_total > limit && !*trace_alerted {
+                *trace_alerted = true;
+                violations.push(CostViolation::Trace {
+                    total: *trace_total,
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = limits.max_window_cost {
+            if window_total > limit {
+                if !self.window_alerted {
+                    self.window_alerted = true;
+                    violations.push(CostViolation::Window {
+                        total: window_total,
+                        limit,
+                        window: limits.window,
+                    });
+                }
+            } else {
+                self.window_alerted = false;
+            }
+        }
+
+        CostCheck {
+            cost,
+            trace_total: *trace_total,
+            window_total,
+            violations,
+        }
+    }
+
+    fn model_costs(&self, now: Instant, window: Duration) -> BTreeMap<String, f64> {
+        let mut costs = BTreeMap::new();
+        for (at, model, cost) in &self.window_entries {
+            if now.saturating_duration_since(*at) < window {
+                *costs.entry(model.clone()).or_insert(0.0) += cost;
+            }
+        }
+        costs
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    max_trace_cost: Option<f64>,
+    max_window_cost: Option<f64>,
+    window: Duration,
+}
+
+/// Tracks generation costs and reports threshold violations to Langfuse
+pub struct CostGuardrail {
+    client: LangfuseClient,
+    pricing: HashMap<String, ModelPricing>,
+    limits: Limits,
+    score_name: Option<String>,
+    ledger: Mutex<CostLedger>,
+}
+
+#[bon]
+impl CostGuardrail {
+    /// Create a guardrail
+    ///
+    /// Call `pricing` once per model. Without `max_trace_cost` or
+    /// `max_window_cost` costs are only tracked.
+    #[builder]
+    pub fn new(
+        /// Pricing of a model, keyed by the model name used when recording
+        #[builder(field)]
+        pricing: HashMap<String, ModelPricing>,
+        client: LangfuseClient,
+        /// Maximum accumulated cost of a single trace in USD
+        max_trace_cost: Option<f64>,
+        /// Maximum cost across all models within the rolling window in USD
+        max_window_cost: Option<f64>,
+        /// Length of the rolling window, one hour by default
+        window: Option<Duration>,
+        /// Also record a numeric score with this name, valued at the offending cost
+        #[builder(into)]
+        score_name: Option<String>,
+    ) -> Self {
+        Self {
+            client,
+            pricing,
+            limits: Limits {
+                max_trace_cost,
+                max_window_cost,
+                window: window.unwrap_or(DEFAULT_COST_WINDOW),
+            },
+            score_name,
+            ledger: Mutex::new(CostLedger::default()),
+        }
+    }
+
+    /// Price and record a generation's token usage
+    ///
+    /// Fails with [`Error::Validation`] if no pricing is configured for `model`.
+    pub async fn record_usage(
+        &self,
+        trace_id: &str,
+        model: &str,
+        usage: &Usage,
+    ) -> Result<CostCheck> {
+        let pricing = self.pricing.get(model).ok_or_else(|| {
+            Error::Validation(format!("No pricing configured for model `{model}`"))
+        })?;
+        self.record_cost(trace_id, model, pricing.cost(usage)).await
+    }
+
+    /// Record an already computed generation cost in USD
+    pub async fn record_cost(&self, trace_id: &str, model: &str, cost: f64) -> Result<CostCheck> {
+        let check = self
+            .ledger
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(Instant::now(), trace_id, model, cost, &self.limits);
+
+        for violation in &check.violations {
+            self.report(trace_id, model, &check, violation).await?;
+        }
+        Ok(check)
+    }
+
+    /// Cost per model within the rolling window
+    pub fn model_costs(&self) -> BTreeMap<String, f64> {
+        self.ledger
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .model_costs(Instant::now(), self.limits.window)
+    }
+
+    async fn report(
+        &self,
+        trace_id: &str,
+        model: &str,
+        check: &CostCheck,
+        violation: &CostViolation,
+    ) -> Result<()> {
+        let (scope, total, limit) = match violation {
+            CostViolation::Trace { total, limit } => ("trace", *total, *limit),
+            CostViolation::Window { total, limit, .. } => ("window", *total, *limit),
+        };
+        let message =
+            format!("{scope} cost ${total:.4} exceeded the ${limit:.4} threshold (model {model})");
+        tracing::warn!(
+            trace_id,
+            model,
+            scope,
+            total,
+            limit,
+            "Cost threshold exceeded"
+        );
+
+        self.client
+            .event()
+            .trace_id(trace_id)
+            .name(COST_ANOMALY_EVENT)
+            .level("ERROR")
+            .status_message(message.clone())
+            .metadata(json!({
+                "scope": scope,
+                "model": model,
+                "cost": check.cost,
+                "total": total,
+                "threshold": limit,
+                "window_seconds": self.limits.window.as_secs(),
+            }))
+            .call()
+            .await?;
+
+        if let Some(score_name) = &self.score_name {
+            self.client
+                .score()
+                .trace_id(trace_id)
+                .name(score_name.clone())
+                .value(total)
+                .comment(message)
+                .call()
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: cost_guardrail_builder::State> CostGuardrailBuilder<S> {
+    /// Set the pricing of a model
+    pub fn pricing(mut self, model: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.pricing.insert(model.into(), pricing);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> Limits {
+        Limits {
+            max_trace_cost: Some(1.0),
+            max_window_cost: Some(2.5),
+            window: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_pricing() {
+        let pricing = ModelPricing::per_million_tokens(30.0, 60.0);
+        let cost = pricing.cost(&Usage::new(1_000_000, 500_000));
+        assert!((cost - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trace_threshold_reported_once() {
+        let mut ledger = CostLedger::default();
+        let now = Instant::now();
+
+        assert!(ledger
+            .record(now, "t1", "gpt-4", 0.6, &limits())
+            .violations
+            .is_empty());
+        let check = ledger.record(now, "t1", "gpt-4", 0.6, &limits());
+        assert_eq!(
+            check.violations,
+            vec![CostViolation::Trace {
+                total: 1.2,
+                limit: 1.0
+            }]
+        );
+        assert!(ledger
+            .record(now, "t1", "gpt-4", 0.1, &limits())
+            .violations
+            .is_empty());
+    }
+
+    #[test]
+    fn test_window_threshold_rolls_over() {
+        let mut ledger = CostLedger::default();
+        let start = Instant::now();
+
+        ledger.record(start, "a", "gpt-4", 0.9, &limits());
+        ledger.record(start, "b", "claude", 0.9, &limits());
+        let check = ledger.record(start, "c", "gpt-4", 0.9, &limits());
+        assert!(matches!(
+            check.violations.as_slice(),
+            [CostViolation::Window { .. }]
+        ));
+
+        let costs = ledger.model_costs(start, Duration::from_secs(60));
+        assert!((costs["gpt-4"] - 1.8).abs() < 1e-9);
+
+        // Once the window has passed, old costs no longer count
+        let later = start + Duration::from_secs(61);
+        let check = ledger.record(later, "d", "gpt-4", 0.1, &limits());
+        assert!((check.window_total - 0.1).abs() < 1e-9);
+        assert!(check.violations.is_empty());
+    }
+}
@@ -3,6 +3,847 @@
 //! This module contains types and utilities for dataset management.
 //! The actual client methods are implemented in the traces module to
 //! consolidate all client methods under a single #[bon] impl block.
+//!
+//! ## Linking traces to dataset items
+//!
+//! Traces produced while running an evaluation can reference the dataset item
+//! they came from with `.dataset_item(dataset_name, item_id)` on the trace
+//! builder. The link is recorded as `dataset_name` and `dataset_item_id`
+//! metadata; adding `.dataset_run(run_name)` also creates the dataset run item
+//! right after the trace is ingested:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let trace = client
+//!     .trace()
+//!     .name("eval-case")
+//!     .dataset_item("qa-golden", "item-42")
+//!     .dataset_run("nightly-2024-06-01")
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Importing items
+//!
+//! [`LangfuseClient::import_dataset_items`] creates dataset items from a JSONL
+//! or CSV file, reading it a chunk of rows at a time on the blocking thread
+//! pool, so large test sets can be seeded without loading them into memory:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use std::fs::File;
+//! use std::io::BufReader;
+//!
+//! use langfuse_ergonomic::ExportFormat;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let file = BufReader::new(File::open("qa-golden.jsonl")?);
+//! let summary = client
+//!     .import_dataset_items("qa-golden", file, ExportFormat::Jsonl)
+//!     .chunk_size(20)
+//!     .on_progress(|progress| println!("{} rows read", progress.rows))
+//!     .call()
+//!     .await?;
+//! for failure in &summary.failures {
+//!     eprintln!("line {}: {}", failure.line, failure.error);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each JSONL line is an object, and each CSV row has a cell per column of
+//! the header row, with the fields `input` (required), `expected_output`,
+//! `metadata`, `id`, `source_trace_id`, and `source_observation_id`; the
+//! camelCase API names are accepted too. CSV cells of `input`,
+//! `expected_output`, and `metadata` are parsed as JSON when they are valid
+//! JSON and taken as text otherwise; empty cells are left unset.
+//!
+//! Rows with an `id` are upserted: Langfuse updates the existing item with
+//! that ID instead of creating a new one, so re-importing a file is safe.
+//! Rows that are not valid, and rows Langfuse rejects, are reported in
+//! [`ImportSummary::failures`] without stopping the import; only reading
+//! errors and an invalid CSV header fail it. Fields of exported items that
+//! cannot be set, such as `status` and `createdAt`, are ignored, so an
+//! [export](#exporting-items) can be imported again.
+//!
+//! ## Exporting items
+//!
+//! [`LangfuseClient::export_dataset`] writes every item of a dataset, with its
+//! input, expected output, and metadata, to a JSONL or CSV file, e.g. to run
+//! evaluations offline or to version a dataset in git:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::ExportFormat;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let items = client
+//!     .export_dataset("qa-golden")
+//!     .format(ExportFormat::Csv)
+//!     .to_file("datasets/qa-golden.csv")
+//!     .await?;
+//! println!("Exported {items} items");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! JSONL lines are the items as returned by the API. CSV rows hold the
+//! columns of [`DATASET_CSV_COLUMNS`]; the input, expected output, and
+//! metadata columns contain JSON. To process items without a file,
+//! [`DatasetExport::items`] streams them, fetching a page at a time:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use futures_util::StreamExt;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let mut items = client.export_dataset("qa-golden").items();
+//! while let Some(item) = items.next().await {
+//!     let item = item?;
+//!     println!("{}: {:?}", item.id, item.expected_output);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::BufRead;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_core::Stream;
+use langfuse_client_base::models::{DatasetItem, PaginatedDatasetItems};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::task::JoinSet;
+
+use crate::client::LangfuseClient;
+use crate::error::{run_blocking, Error, Result};
+use crate::export::{csv_escape, ExportFormat};
+use crate::interceptor::metadata_object;
 
 // Re-export common types that might be useful
 pub use langfuse_client_base::models::CreateDatasetRequest;
+
+/// Items created concurrently by default while importing
+const DEFAULT_CHUNK_SIZE: usize = 10;
+
+/// Items listed per page while exporting
+const PAGE_SIZE: i32 = 50;
+
+/// Columns of a CSV dataset export
+pub const DATASET_CSV_COLUMNS: [&str; 9] = [
+    "id",
+    "status",
+    "input",
+    "expected_output",
+    "metadata",
+    "source_trace_id",
+    "source_observation_id",
+    "created_at",
+    "updated_at",
+];
+
+/// Fields of an imported row, by their snake_case and camelCase names
+const IMPORT_FIELDS: [(&str, &str); 6] = [
+    ("id", "id"),
+    ("input", "input"),
+    ("expected_output", "expectedOutput"),
+    ("metadata", "metadata"),
+    ("source_trace_id", "sourceTraceId"),
+    ("source_observation_id", "sourceObservationId"),
+];
+
+/// Fields of exported items that are ignored when importing
+const IGNORED_FIELDS: [(&str, &str); 5] = [
+    ("status", "status"),
+    ("dataset_id", "datasetId"),
+    ("dataset_name", "datasetName"),
+    ("created_at", "createdAt"),
+    ("updated_at", "updatedAt"),
+];
+
+/// Dataset item a trace was produced from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetItemLink {
+    /// Name of the dataset
+    pub dataset_name: String,
+    /// ID of the dataset item
+    pub item_id: String,
+}
+
+impl DatasetItemLink {
+    /// Link to `item_id` of the dataset named `dataset_name`
+    pub fn new(dataset_name: impl Into<String>, item_id: impl Into<String>) -> Self {
+        Self {
+            dataset_name: dataset_name.into(),
+            item_id: item_id.into(),
+        }
+    }
+
+    /// Record the link, and the run name if any, in trace metadata
+    pub(crate) fn annotate(&self, metadata: Option<Value>, run_name: Option<&str>) -> Value {
+        let mut map = metadata_object(metadata);
+        map.insert(
+            "dataset_name".to_string(),
+            Value::from(self.dataset_name.as_str()),
+        );
+        map.insert(
+            "dataset_item_id".to_string(),
+            Value::from(self.item_id.as_str()),
+        );
+        if let Some(run_name) = run_name {
+            map.insert("dataset_run".to_string(), Value::from(run_name));
+        }
+        Value::Object(map)
+    }
+}
+
+/// Progress of a dataset import, reported after each chunk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// Rows read so far
+    pub rows: usize,
+    /// Items created or updated so far
+    pub imported: usize,
+    /// Rows that failed so far
+    pub failed: usize,
+}
+
+/// A row that was not imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowFailure {
+    /// Line the row starts on, counting from 1 (a CSV header is line 1)
+    pub line: usize,
+    /// ID given in the row, if any
+    pub id: Option<String>,
+    /// Why the row was not imported
+    pub error: String,
+}
+
+/// Outcome of a dataset import
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Rows read
+    pub rows: usize,
+    /// Items created or updated
+    pub imported: usize,
+    /// Rows that were not imported, in file order
+    pub failures: Vec<RowFailure>,
+}
+
+/// Callback of [`DatasetImport::on_progress`]
+type ProgressCallback<'a> = Box<dyn FnMut(&ImportProgress) + Send + 'a>;
+
+/// Import of dataset items from a JSONL or CSV reader
+///
+/// Created with [`LangfuseClient::import_dataset_items`]; see
+/// [Importing items](self#importing-items).
+pub struct DatasetImport<'a, R> {
+    client: &'a LangfuseClient,
+    dataset_name: String,
+    reader: R,
+    format: ExportFormat,
+    chunk_size: usize,
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl<'a, R: BufRead + Send + 'static> DatasetImport<'a, R> {
+    pub(crate) fn new(
+        client: &'a LangfuseClient,
+        dataset_name: String,
+        reader: R,
+        format: ExportFormat,
+    ) -> Self {
+        Self {
+            client,
+            dataset_name,
+            reader,
+            format,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            on_progress: None,
+        }
+    }
+
+    /// Rows read and items created concurrently per chunk (default 10)
+    #[must_use]
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Call `on_progress` after each chunk
+    #[must_use]
+    pub fn on_progress(mut self, on_progress: impl FnMut(&ImportProgress) + Send + 'a) -> Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+
+    /// Read every row and create its item
+    pub async fn call(mut self) -> Result<ImportSummary> {
+        let mut rows = RowReader::new(self.reader, self.format);
+        let chunk_size = self.chunk_size;
+        let mut summary = ImportSummary::default();
+
+        loop {
+            // The reader may block, so chunks are read on the blocking thread pool
+            let (reader, parsed) = run_blocking(move || {
+                let mut parsed = Vec::with_capacity(chunk_size);
+                while parsed.len() < chunk_size {
+                    match rows.next()? {
+                        Some(row) => parsed.push(row),
+                        None => break,
+                    }
+                }
+                Ok((rows, parsed))
+            })
+            .await?;
+            rows = reader;
+
+            let read = parsed.len();
+            let mut chunk = Vec::with_capacity(read);
+            for (line, row) in parsed {
+                match row.and_then(ItemRow::parse) {
+                    Ok(item) => chunk.push((line, item)),
+                    Err(error) => summary.failures.push(RowFailure {
+                        line,
+                        id: None,
+                        error,
+                    }),
+                }
+            }
+            summary.rows += read;
+            let done = read < chunk_size;
+
+            let mut tasks = JoinSet::new();
+            for (line, item) in chunk {
+                let client = self.client.clone();
+                let dataset_name = self.dataset_name.clone();
+                tasks.spawn(async move {
+                    let id = item.id.clone();
+                    let result = client
+                        .create_dataset_item()
+                        .dataset_name(dataset_name)
+                        .maybe_input(item.input)
+                        .maybe_expected_output(item.expected_output)
+                        .maybe_metadata(item.metadata)
+                        .maybe_source_trace_id(item.source_trace_id)
+                        .maybe_source_observation_id(item.source_observation_id)
+                        .maybe_id(item.id)
+                        .call()
+                        .await;
+                    (line, id, result)
+                });
+            }
+            let mut failures = Vec::new();
+            while let Some(joined) = tasks.join_next().await {
+                let (line, id, result) = joined?;
+                match result {
+                    Ok(_) => summary.imported += 1,
+                    Err(e) => failures.push(RowFailure {
+                        line,
+                        id,
+                        error: e.to_string(),
+                    }),
+                }
+            }
+            summary.failures.extend(failures);
+            summary.failures.sort_by_key(|failure| failure.line);
+
+            if let Some(on_progress) = &mut self.on_progress {
+                on_progress(&ImportProgress {
+                    rows: summary.rows,
+                    imported: summary.imported,
+                    failed: summary.failures.len(),
+                });
+            }
+            if done {
+                break;
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// Export of the items of a dataset
+///
+/// Created with [`LangfuseClient::export_dataset`]; see
+/// [Exporting items](self#exporting-items).
+pub struct DatasetExport<'a> {
+    client: &'a LangfuseClient,
+    dataset_name: String,
+    format: ExportFormat,
+}
+
+impl<'a> DatasetExport<'a> {
+    pub(crate) fn new(client: &'a LangfuseClient, dataset_name: String) -> Self {
+        Self {
+            client,
+            dataset_name,
+            format: ExportFormat::default(),
+        }
+    }
+
+    /// Write in this format
+    #[must_use]
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Stream the items instead of writing them
+    pub fn items(self) -> DatasetItemStream<'a> {
+        DatasetItemStream {
+            client: self.client,
+            dataset_name: self.dataset_name,
+            items: VecDeque::new(),
+            page: 1,
+            done: false,
+            pending: None,
+        }
+    }
+
+    /// Create or replace the file at `path` with the export, returning the
+    /// number of items written
+    ///
+    /// The export is written to `path` with a `.partial` suffix first and
+    /// renamed once complete, so a failed export leaves an existing file
+    /// untouched.
+    pub async fn to_file(self, path: impl AsRef<Path>) -> Result<usize> {
+        crate::export::write_file(path.as_ref(), |writer| self.to_writer(writer)).await
+    }
+
+    /// Write the export to `writer`, returning the number of items written
+    pub async fn to_writer<W: AsyncWrite + Unpin>(self, mut writer: W) -> Result<usize> {
+        let format = self.format;
+        if format == ExportFormat::Csv {
+            write_line(&mut writer, &DATASET_CSV_COLUMNS.join(",")).await?;
+        }
+
+        let mut items = self.items();
+        let mut written = 0;
+        while let Some(item) = std::future::poll_fn(|cx| Pin::new(&mut items).poll_next(cx)).await {
+            let item = item?;
+            let line = match format {
+                ExportFormat::Jsonl => serde_json::to_string(&item)?,
+                ExportFormat::Csv => csv_row(&item)?,
+            };
+            write_line(&mut writer, &line).await?;
+            written += 1;
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|e| Error::io("Failed to write dataset export", e))?;
+        Ok(written)
+    }
+}
+
+type PageFuture<'a> = Pin<Box<dyn Future<Output = Result<PaginatedDatasetItems>> + Send + 'a>>;
+
+/// Items of a dataset, listed a page at a time
+///
+/// Created with [`DatasetExport::items`]. The stream ends after the first
+/// error.
+pub struct DatasetItemStream<'a> {
+    client: &'a LangfuseClient,
+    dataset_name: String,
+    /// Items of the last page not yet yielded
+    items: VecDeque<DatasetItem>,
+    /// Next page to list
+    page: i32,
+    done: bool,
+    pending: Option<PageFuture<'a>>,
+}
+
+impl Stream for DatasetItemStream<'_> {
+    type Item = Result<DatasetItem>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.items.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            let client = this.client;
+            let dataset_name = &this.dataset_name;
+            let page = this.page;
+            let pending = this.pending.get_or_insert_with(|| {
+                let dataset_name = dataset_name.clone();
+                Box::pin(async move {
+                    client
+                        .list_dataset_items()
+                        .dataset_name(dataset_name)
+                        .page(page)
+                        .limit(PAGE_SIZE)
+                        .call()
+                        .await
+                })
+            });
+            let result = ready!(pending.as_mut().poll(cx));
+            this.pending = None;
+
+            match result {
+                Ok(response) => {
+                    let received = response.data.len();
+                    this.done =
+                        received < PAGE_SIZE as usize || this.page >= response.meta.total_pages;
+                    this.page += 1;
+                    this.items.extend(response.data);
+                }
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+        }
+    }
+}
+
+async fn write_line(writer: &mut (impl AsyncWrite + Unpin), line: &str) -> Result<()> {
+    let mut bytes = Vec::with_capacity(line.len() + 1);
+    bytes.extend_from_slice(line.as_bytes());
+    bytes.push(b'\n');
+    writer
+        .write_all(&bytes)
+        .await
+        .map_err(|e| Error::io("Failed to write dataset export", e))
+}
+
+/// CSV row of a dataset item, in the order of [`DATASET_CSV_COLUMNS`]
+fn csv_row(item: &DatasetItem) -> Result<String> {
+    let text = |value: &Option<Option<String>>| value.clone().flatten().unwrap_or_default();
+    let json = |value: &Option<Value>| match value.as_ref().filter(|v| !v.is_null()) {
+        Some(value) => serde_json::to_string(value),
+        None => Ok(String::new()),
+    };
+    let status = serde_json::to_value(item.status)?;
+
+    let cells = [
+        item.id.clone(),
+        status.as_str().unwrap_or_default().to_string(),
+        json(&item.input)?,
+        json(&item.expected_output)?,
+        json(&item.metadata)?,
+        text(&item.source_trace_id),
+        text(&item.source_observation_id),
+        item.created_at.clone(),
+        item.updated_at.clone(),
+    ];
+    Ok(cells
+        .iter()
+        .map(|cell| csv_escape(cell))
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// A validated row, ready to be created as a dataset item
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct ItemRow {
+    id: Option<String>,
+    input: Option<Value>,
+    #[serde(alias = "expectedOutput")]
+    expected_output: Option<Value>,
+    metadata: Option<Value>,
+    #[serde(alias = "sourceTraceId")]
+    source_trace_id: Option<String>,
+    #[serde(alias = "sourceObservationId")]
+    source_observation_id: Option<String>,
+}
+
+impl ItemRow {
+    fn parse(row: Value) -> std::result::Result<Self, String> {
+        let Value::Object(mut row) = row else {
+            return Err("row is not a JSON object".to_string());
+        };
+        for (snake, camel) in IGNORED_FIELDS {
+            row.remove(snake);
+            row.remove(camel);
+        }
+        let item: ItemRow =
+            serde_json::from_value(Value::Object(row)).map_err(|e| e.to_string())?;
+        if item.input.is_none() {
+            return Err("row has no input".to_string());
+        }
+        if item.id.as_deref() == Some("") {
+            return Err("row has an empty id".to_string());
+        }
+        Ok(item)
+    }
+}
+
+/// A row as a JSON object, or why it could not be parsed
+type ParsedRow = std::result::Result<Value, String>;
+
+/// Reads the rows of an import as JSON objects, tracking line numbers
+enum RowReader<R> {
+    Jsonl {
+        reader: R,
+        /// Lines read so far
+        lines: usize,
+    },
+    Csv {
+        reader: csv::Reader<std::io::Chain<R, &'static [u8]>>,
+        /// Columns of the import, once the header was read
+        columns: Option<Vec<&'static str>>,
+    },
+}
+
+impl<R: BufRead> RowReader<R> {
+    fn new(reader: R, format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Jsonl => Self::Jsonl { reader, lines: 0 },
+            // Records end at `\n` only, so line numbers of CRLF files are
+            // counted like those of LF files; a trailing `\r` is stripped from
+            // the last cell instead. The final newline makes every record end
+            // in one, which `next` relies on to count lines.
+            ExportFormat::Csv => Self::Csv {
+                reader: csv::ReaderBuilder::new()
+                    .flexible(true)
+                    .terminator(csv::Terminator::Any(b'\n'))
+                    .from_reader(reader.chain(&b"\n"[..])),
+                columns: None,
+            },
+        }
+    }
+
+    /// The next non-blank row and the line it starts on, or `None` at the end
+    ///
+    /// Rows that cannot be parsed are returned as errors; reading errors and
+    /// an invalid CSV header fail the import.
+    fn next(&mut self) -> Result<Option<(usize, ParsedRow)>> {
+        match self {
+            Self::Jsonl { reader, lines } => loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).map_err(read_error)? == 0 {
+                    return Ok(None);
+                }
+                *lines += 1;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let row = serde_json::from_str(&line).map_err(|e| format!("invalid JSON: {e}"));
+                return Ok(Some((*lines, row)));
+            },
+            Self::Csv { reader, columns } => {
+                if columns.is_none() {
+                    let header = reader.headers().map_err(csv_error)?;
+                    if header.is_empty() {
+                        return Err(Error::Validation(
+                            "CSV import has no header row".to_string(),
+                        ));
+                    }
+                    *columns = Some(csv_columns(header)?);
+                }
+                let columns = columns.as_deref().unwrap_or_default();
+                let mut record = csv::StringRecord::new();
+                loop {
+                    if !reader.read_record(&mut record).map_err(csv_error)? {
+                        return Ok(None);
+                    }
+                    // The position of a record includes the blank lines
+                    // skipped before it, so count back from where it ended
+                    let newlines: usize =
+                        record.iter().map(|cell| cell.matches('\n').count()).sum();
+                    let line = (reader.position().line() as usize).saturating_sub(newlines + 1);
+                    let last = record.len().saturating_sub(1);
+                    let cells: Vec<&str> = record
+                        .iter()
+                        .enumerate()
+                        .map(|(i, cell)| match i == last {
+                            true => cell.strip_suffix('\r').unwrap_or(cell),
+                            false => cell,
+                        })
+                        .collect();
+                    if cells.len() == 1 && cells[0].trim().is_empty() {
+                        continue;
+                    }
+                    if cells.len() != columns.len() {
+                        let error = format!(
+                            "row has {} cells, the header has {}",
+                            cells.len(),
+                            columns.len()
+                        );
+                        return Ok(Some((line, Err(error))));
+                    }
+                    let row = columns
+                        .iter()
+                        .zip(cells)
+                        .filter(|(_, cell)| !cell.is_empty())
+                        .map(|(&column, cell)| (column.to_string(), csv_cell(column, cell)))
+                        .collect::<Map<_, _>>();
+                    return Ok(Some((line, Ok(Value::Object(row)))));
+                }
+            }
+        }
+    }
+}
+
+/// Fields named by a CSV header, rejecting unknown and duplicate columns
+fn csv_columns(header: &csv::StringRecord) -> Result<Vec<&'static str>> {
+    let mut columns = Vec::with_capacity(header.len());
+    for name in header {
+        let name = name.trim();
+        let column = IMPORT_FIELDS
+            .iter()
+            .chain(&IGNORED_FIELDS)
+            .find(|(snake, camel)| name == *snake || name == *camel)
+            .map(|(snake, _)| *snake)
+            .ok_or_else(|| {
+                Error::Validation(format!("Unknown column '{}' in CSV import header", name))
+            })?;
+        if columns.contains(&column) {
+            return Err(Error::Validation(format!(
+                "Duplicate column '{}' in CSV import header",
+                name
+            )));
+        }
+        columns.push(column);
+    }
+    if !columns.contains(&"input") {
+        return Err(Error::Validation(
+            "CSV import header has no input column".to_string(),
+        ));
+    }
+    Ok(columns)
+}
+
+/// Value of a CSV cell: JSON for payload columns when it parses, text otherwise
+fn csv_cell(column: &str, cell: &str) -> Value {
+    match column {
+        "input" | "expected_output" | "metadata" => {
+            serde_json::from_str(cell).unwrap_or_else(|_| Value::from(cell))
+        }
+        _ => Value::from(cell),
+    }
+}
+
+fn read_error(error: std::io::Error) -> Error {
+    Error::io("Failed to read dataset import", error)
+}
+
+fn csv_error(error: csv::Error) -> Error {
+    let message = error.to_string();
+    match error.into_kind() {
+        csv::ErrorKind::Io(e) => read_error(e),
+        _ => Error::Validation(format!("Invalid CSV import: {message}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_annotate_metadata() {
+        let link = DatasetItemLink::new("qa", "item-1");
+        assert_eq!(
+            link.annotate(Some(json!({"env": "ci"})), Some("run-1")),
+            json!({
+                "env": "ci",
+                "dataset_name": "qa",
+                "dataset_item_id": "item-1",
+                "dataset_run": "run-1"
+            })
+        );
+        assert_eq!(
+            link.annotate(Some(json!("note")), None),
+            json!({"metadata": "note", "dataset_name": "qa", "dataset_item_id": "item-1"})
+        );
+    }
+
+    #[test]
+    fn test_csv_rows_span_lines() {
+        let csv = "input,id\r\n\"multi\r\nline, \"\"quoted\"\"\",item-1\n\nplain,item-2\n";
+        let mut rows = RowReader::new(csv.as_bytes(), ExportFormat::Csv);
+        let (line, row) = rows.next().unwrap().unwrap();
+        assert_eq!(line, 2);
+        assert_eq!(
+            row.unwrap(),
+            json!({"input": "multi\r\nline, \"quoted\"", "id": "item-1"})
+        );
+        let (line, row) = rows.next().unwrap().unwrap();
+        assert_eq!(line, 5);
+        assert_eq!(row.unwrap(), json!({"input": "plain", "id": "item-2"}));
+        assert!(rows.next().unwrap().is_none());
+
+        assert!(RowReader::new("".as_bytes(), ExportFormat::Csv)
+            .next()
+            .is_err());
+    }
+
+    #[test]
+    fn test_row_reader_validates_rows() {
+        let csv =
+            "input,expectedOutput,id\n\"{\"\"q\"\": 1}\",plain text,item-1\nonly,two\n".as_bytes();
+        let mut rows = RowReader::new(csv, ExportFormat::Csv);
+        let (line, row) = rows.next().unwrap().unwrap();
+        assert_eq!(line, 2);
+        let item = ItemRow::parse(row.unwrap()).unwrap();
+        assert_eq!(item.input, Some(json!({"q": 1})));
+        assert_eq!(item.expected_output, Some(json!("plain text")));
+        assert_eq!(item.id.as_deref(), Some("item-1"));
+        let (line, row) = rows.next().unwrap().unwrap();
+        assert_eq!(line, 3);
+        assert!(row.is_err());
+        assert!(rows.next().unwrap().is_none());
+
+        let header = "input,answer\n".as_bytes();
+        assert!(RowReader::new(header, ExportFormat::Csv).next().is_err());
+
+        assert_eq!(
+            ItemRow::parse(json!({"expected_output": "x"})).unwrap_err(),
+            "row has no input"
+        );
+        assert!(ItemRow::parse(json!({"input": "x", "inptu": "y"})).is_err());
+        assert!(ItemRow::parse(json!(["x"])).is_err());
+    }
+
+    #[test]
+    fn test_exported_rows_import_again() {
+        let item = DatasetItem {
+            id: "item-1".to_string(),
+            status: langfuse_client_base::models::DatasetStatus::Archived,
+            input: Some(json!({"q": "a, b"})),
+            expected_output: Some(json!("4")),
+            metadata: None,
+            source_trace_id: Some(Some("trace-1".to_string())),
+            dataset_id: "dataset-1".to_string(),
+            dataset_name: "qa".to_string(),
+            created_at: "2024-01-01T00:00:00.000Z".to_string(),
+            updated_at: "2024-01-02T00:00:00.000Z".to_string(),
+            ..Default::default()
+        };
+        let row = csv_row(&item).unwrap();
+        assert_eq!(
+            row,
+            r#"item-1,ARCHIVED,"{""q"":""a, b""}","""4""",,trace-1,,2024-01-01T00:00:00.000Z,2024-01-02T00:00:00.000Z"#
+        );
+
+        let csv = format!("{}\n{}\n", DATASET_CSV_COLUMNS.join(","), row);
+        let mut rows = RowReader::new(csv.as_bytes(), ExportFormat::Csv);
+        let (_, row) = rows.next().unwrap().unwrap();
+        let from_csv = ItemRow::parse(row.unwrap()).unwrap();
+        let from_jsonl = ItemRow::parse(serde_json::to_value(&item).unwrap()).unwrap();
+        assert_eq!(from_csv, from_jsonl);
+        assert_eq!(from_csv.input, Some(json!({"q": "a, b"})));
+        assert_eq!(from_csv.source_trace_id.as_deref(), Some("trace-1"));
+    }
+}
@@ -0,0 +1,220 @@
+//! Snapshots of the effective configuration for bug reports and startup logs
+//!
+//! [`LangfuseClient::debug_config`](crate::LangfuseClient::debug_config) and
+//! [`Batcher::debug_config`](crate::Batcher::debug_config) return the settings
+//! actually in use, defaults included. Credentials are never part of a
+//! snapshot: only the public key and which kind of authentication is used.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! tracing::info!(config = %serde_json::to_string(&client.debug_config())?, "Langfuse client ready");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Durations are in milliseconds and field names only ever get added, so the
+//! serialized form can be parsed by tooling.
+
+use serde::Serialize;
+
+/// Effective configuration of a [`LangfuseClient`](crate::LangfuseClient)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClientDebugConfig {
+    /// Version of this crate
+    pub sdk_version: String,
+    /// Whether events are sent, see [`ClientBuilder::disabled`](crate::ClientBuilder::disabled)
+    pub enabled: bool,
+    /// Langfuse base URL
+    pub base_url: String,
+    /// Public key, if the client authenticates with keys
+    pub public_key: Option<String>,
+    /// `basic` for key authentication, `custom` for an [`AuthProvider`](crate::AuthProvider)
+    pub auth: String,
+    /// User agent sent with requests
+    pub user_agent: Option<String>,
+    /// Request timeout; unknown with a custom HTTP client
+    pub timeout_ms: Option<u64>,
+    /// Connection timeout; unknown with a custom HTTP client
+    pub connect_timeout_ms: Option<u64>,
+    /// Whether requests go through a user-provided HTTP client
+    pub custom_http_client: bool,
+    /// Proxy URL without credentials; unknown with a custom HTTP client
+    pub proxy: Option<String>,
+    /// Client-side rate limit of API requests
+    pub rate_limit: Option<String>,
+    /// Size limit of input, output, and metadata fields in bytes
+    pub max_field_bytes: Option<usize>,
+    /// Size limit of input values in bytes, if lower than `max_field_bytes`
+    pub max_input_bytes: Option<usize>,
+    /// Size limit of output values in bytes, if lower than `max_field_bytes`
+    pub max_output_bytes: Option<usize>,
+    /// What happens to fields above the size limit
+    pub field_size_policy: Option<String>,
+    /// Maximum length of names in characters
+    pub max_name_chars: usize,
+    /// Maximum length of status messages in characters
+    pub max_status_message_chars: usize,
+    /// What happens to names and status messages above their limit
+    pub text_length_policy: String,
+    /// Size above which input and output values are uploaded as media
+    pub media_offload_threshold: Option<usize>,
+    /// Schema version stamped on event metadata
+    pub schema_version: Option<String>,
+    /// Whether required trace metadata keys or tags are enforced
+    pub trace_requirements: bool,
+    /// Whether a naming policy is applied
+    pub naming_policy: bool,
+    /// Environment events are assigned to
+    pub environment: Option<String>,
+    /// File a sample of ingestion payloads is written to
+    pub payload_capture_path: Option<String>,
+    /// How scores are sent (`Ingestion` or `Direct`)
+    pub score_transport: String,
+    /// Number of registered event interceptors
+    pub interceptors: usize,
+    /// Whether outgoing events are redacted
+    pub redact_payloads: bool,
+    /// Whether a mask function is applied to input and output
+    pub mask: bool,
+    /// Sampler deciding which traces are sent, if any
+    pub sampler: Option<String>,
+    /// Whether trace URLs include the project ID
+    pub project_scoped_urls: bool,
+    /// Encoding and size threshold of request body compression, if enabled
+    pub request_compression: Option<String>,
+    /// Cargo features this crate was built with
+    pub features: Vec<String>,
+}
+
+/// Effective configuration of a [`Batcher`](crate::Batcher)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatcherDebugConfig {
+    /// Maximum number of events per batch
+    pub max_events: usize,
+    /// Maximum batch size in bytes
+    pub max_bytes: usize,
+    /// Configured flush interval
+    pub flush_interval_ms: u64,
+    /// Shortest adaptive flush interval, if adaptive flushing is enabled
+    pub adaptive_flush_min_ms: Option<u64>,
+    /// Longest adaptive flush interval, if adaptive flushing is enabled
+    pub adaptive_flush_max_ms: Option<u64>,
+    /// Maximum retry attempts for failed events
+    pub max_retries: u32,
+    /// Initial retry delay
+    pub initial_retry_delay_ms: u64,
+    /// Maximum retry delay
+    pub max_retry_delay_ms: u64,
+    /// Whether retry delays get jitter
+    pub retry_jitter: bool,
+    /// Whether to fail fast on errors
+    pub fail_fast: bool,
+    /// Maximum number of queued events
+    pub max_queue_size: usize,
+    /// Policy for handling a full queue
+    pub backpressure_policy: String,
+    /// Whether queued events are persisted with a queue backend
+    pub persistent_queue: bool,
+    /// Consecutive failures that open the circuit breaker, if enabled
+    pub circuit_failure_threshold: Option<u32>,
+    /// How long the open circuit breaker pauses sends, if enabled
+    pub circuit_cooldown_ms: Option<u64>,
+    /// Number of recent event IDs remembered for deduplication, if enabled
+    pub dedup_capacity: Option<usize>,
+    /// Configuration of the batcher's client
+    pub client: ClientDebugConfig,
+}
+
+/// Every Cargo feature of this crate, and whether it is enabled
+const FEATURES: [(&str, bool); 14] = [
+    ("rustls", cfg!(feature = "rustls")),
+    ("native-tls", cfg!(feature = "native-tls")),
+    ("compression", cfg!(feature = "compression")),
+    ("loadgen", cfg!(feature = "loadgen")),
+    ("derive", cfg!(feature = "derive")),
+    ("otel", cfg!(feature = "otel")),
+    ("openai", cfg!(feature = "openai")),
+    ("signal", cfg!(feature = "signal")),
+    ("process", cfg!(feature = "process")),
+    ("cost-guardrails", cfg!(feature = "cost-guardrails")),
+    ("json-schema", cfg!(feature = "json-schema")),
+    ("metrics", cfg!(feature = "metrics")),
+    ("testing", cfg!(feature = "testing")),
+    ("e2e", cfg!(feature = "e2e")),
+];
+
+/// Cargo features this crate was built with
+pub(crate) fn enabled_features() -> Vec<String> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| (*name).to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BearerToken, ClientBuilder, ClientOverrides};
+    use std::time::Duration;
+
+    #[test]
+    fn test_features_match_manifest() {
+        let manifest = include_str!("../Cargo.toml");
+        let mut declared: Vec<&str> = manifest
+            .lines()
+            .skip_while(|line| line.trim() != "[features]")
+            .skip(1)
+            .take_while(|line| !line.starts_with('['))
+            .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim()))
+            .filter(|name| *name != "default")
+            .collect();
+        declared.sort_unstable();
+
+        let mut listed: Vec<&str> = super::FEATURES.iter().map(|(name, _)| *name).collect();
+        listed.sort_unstable();
+        assert_eq!(listed, declared);
+    }
+
+    #[test]
+    fn test_client_debug_config_redacts_secret() {
+        let client = ClientBuilder::new()
+            .public_key("pk-lf-test")
+            .secret_key("sk-lf-secret")
+            .base_url("http://localhost:3000")
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        let config = client.debug_config();
+        assert_eq!(config.base_url, "http://localhost:3000");
+        assert_eq!(config.public_key.as_deref(), Some("pk-lf-test"));
+        assert_eq!(config.auth, "basic");
+        assert_eq!(config.timeout_ms, Some(5_000));
+        assert_eq!(config.connect_timeout_ms, Some(10_000));
+        assert!(!config.custom_http_client);
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("sk-lf-secret"));
+    }
+
+    #[test]
+    fn test_client_debug_config_custom_auth_and_overrides() {
+        let client = ClientBuilder::new()
+            .auth(BearerToken::new("token-secret").unwrap())
+            .build()
+            .unwrap();
+        let variant = client
+            .with_overrides(|o: ClientOverrides| o.timeout(Duration::from_secs(1)))
+            .unwrap();
+
+        let config = variant.debug_config();
+        assert_eq!(config.public_key, None);
+        assert_eq!(config.auth, "custom");
+        assert_eq!(config.timeout_ms, Some(1_000));
+        assert!(!serde_json::to_string(&config)
+            .unwrap()
+            .contains("token-secret"));
+    }
+}
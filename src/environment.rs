@@ -0,0 +1,218 @@
+//! Validated Langfuse environment names
+//!
+//! Langfuse groups traces, observations, and scores by environment, such as
+//! `production` or `staging`. Environment names must be lowercase letters,
+//! digits, `-`, or `_`, at most [`MAX_ENVIRONMENT_CHARS`] characters long, and
+//! must not start with `langfuse`; the API rejects events that break these
+//! rules. An [`Environment`] can only be constructed from a valid name, so a
+//! typo surfaces when the client is configured instead of as a failed batch.
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{ClientBuilder, Environment};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .environment(Environment::new("staging")?)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`ClientBuilder::from_env`](crate::ClientBuilder::from_env) also reads the
+//! environment from `LANGFUSE_TRACING_ENVIRONMENT`.
+//!
+//! The client's environment is a default: trace, observation, and score
+//! builders take an `environment` of their own, which wins for that event.
+
+use std::fmt;
+use std::str::FromStr;
+
+use langfuse_client_base::models::IngestionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Maximum length of an environment name in characters
+pub const MAX_ENVIRONMENT_CHARS: usize = 40;
+
+/// Prefix reserved for environments created by Langfuse itself
+const RESERVED_PREFIX: &str = "langfuse";
+
+/// Name of the environment events are assigned to when none is set
+const DEFAULT_ENVIRONMENT: &str = "default";
+
+/// A Langfuse environment name that satisfies the API's constraints
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Environment(String);
+
+impl Environment {
+    /// Validate `name` as an environment name
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(Error::Validation(
+                "Environment name must not be empty".to_string(),
+            ));
+        }
+        if name.chars().count() > MAX_ENVIRONMENT_CHARS {
+            return Err(Error::Validation(format!(
+                "Environment name `{name}` is longer than {MAX_ENVIRONMENT_CHARS} characters"
+            )));
+        }
+        if let Some(c) = name
+            .chars()
+            .find(|c| !matches!(c, 'a'..='z' | '0'..='9' | '-' | '_'))
+        {
+            return Err(Error::Validation(format!(
+                "Environment name `{name}` contains `{c}`; only lowercase letters, digits, `-`, and `_` are allowed"
+            )));
+        }
+        if name.starts_with(RESERVED_PREFIX) {
+            return Err(Error::Validation(format!(
+                "Environment name `{name}` must not start with `{RESERVED_PREFIX}`"
+            )));
+        }
+        Ok(Self(name))
+    }
+
+    /// The environment name
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Set this environment on `event` unless it already names one
+    pub(crate) fn apply(&self, event: &mut IngestionEvent) {
+        let environment = match event {
+            IngestionEvent::IngestionEventOneOf(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf1(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf2(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf3(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf4(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf5(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf6(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf7(_) => return,
+            IngestionEvent::IngestionEventOneOf8(e) => &mut e.body.environment,
+            IngestionEvent::IngestionEventOneOf9(e) => &mut e.body.environment,
+        };
+        if environment.as_ref().is_none_or(Option::is_none) {
+            *environment = Some(Some(self.0.clone()));
+        }
+    }
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self(DEFAULT_ENVIRONMENT.to_string())
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Environment {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Environment {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<&str> for Environment {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<String> for Environment {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl From<Environment> for String {
+    fn from(environment: Environment) -> Self {
+        environment.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_names() {
+        for name in ["production", "staging-eu", "dev_2", "a"] {
+            assert_eq!(Environment::new(name).unwrap().as_str(), name);
+        }
+        let longest = "e".repeat(MAX_ENVIRONMENT_CHARS);
+        assert!(Environment::new(longest).is_ok());
+        assert_eq!(Environment::default().as_str(), "default");
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        for name in [
+            "",
+            "Production",
+            "prod env",
+            "prod.eu",
+            "langfuse-evals",
+            &"e".repeat(MAX_ENVIRONMENT_CHARS + 1),
+        ] {
+            assert!(
+                matches!(Environment::new(name), Err(Error::Validation(_))),
+                "{name:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_keeps_explicit_environment() {
+        use langfuse_client_base::models::{IngestionEventOneOf, TraceBody};
+
+        let event = |environment: Option<&str>| {
+            let body = TraceBody {
+                environment: environment.map(|e| Some(e.to_string())),
+                ..TraceBody::default()
+            };
+            IngestionEvent::IngestionEventOneOf(Box::new(IngestionEventOneOf {
+                body: Box::new(body),
+                ..IngestionEventOneOf::default()
+            }))
+        };
+        let environment_of = |event: &IngestionEvent| match event {
+            IngestionEvent::IngestionEventOneOf(e) => e.body.environment.clone().flatten(),
+            _ => unreachable!(),
+        };
+
+        let staging = Environment::new("staging").unwrap();
+        let mut unset = event(None);
+        staging.apply(&mut unset);
+        assert_eq!(environment_of(&unset).as_deref(), Some("staging"));
+
+        let mut explicit = event(Some("production"));
+        staging.apply(&mut explicit);
+        assert_eq!(environment_of(&explicit).as_deref(), Some("production"));
+    }
+
+    #[test]
+    fn test_serde_validates() {
+        let environment: Environment = serde_json::from_str("\"staging\"").unwrap();
+        assert_eq!(serde_json::to_string(&environment).unwrap(), "\"staging\"");
+        assert!(serde_json::from_str::<Environment>("\"Staging\"").is_err());
+    }
+}
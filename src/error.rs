@@ -27,6 +27,19 @@ pub enum Error {
     #[error("Middleware error: {0}")]
     Middleware(#[from] reqwest_middleware::Error),
 
+    /// A background task of the client panicked or was cancelled
+    #[error("Background task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+
+    /// Reading or writing a local file failed
+    #[error("IO error: {context}: {source}")]
+    Io {
+        /// What was being read or written
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+
     /// Authentication failure
     #[error("Authentication failed: {message}")]
     Auth {
@@ -71,6 +84,8 @@ pub enum Error {
         errors: Vec<EventError>,
         /// Successfully processed event IDs
         success_ids: Vec<String>,
+        /// Request ID for debugging
+        request_id: Option<String>,
     },
 
     /// Batch size exceeded
@@ -85,10 +100,21 @@ pub enum Error {
         /// Reason for the backpressure
         reason: String,
     },
+
+    /// Sends paused by the batcher's circuit breaker after repeated failures
+    #[error("Circuit breaker open (retry in {retry_in:?})")]
+    CircuitOpen {
+        /// Time left until sends resume
+        retry_in: Duration,
+    },
 }
 
 /// Error details for individual events in a batch
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
 pub struct EventError {
     /// The event ID that failed
     pub event_id: String,
@@ -98,6 +124,116 @@ pub struct EventError {
     pub code: Option<String>,
     /// Whether this error is retryable
     pub retryable: bool,
+    /// Per-field validation problems, if the API reported them
+    #[cfg_attr(feature = "json-schema", serde(default))]
+    pub validation_details: Vec<ValidationDetail>,
+}
+
+impl EventError {
+    /// Error for an item of the `errors` list of a 207 ingestion response
+    pub(crate) fn from_item(
+        event_id: String,
+        status: Option<u16>,
+        error: Option<&serde_json::Value>,
+        message: Option<String>,
+    ) -> Self {
+        let validation_details = error.map(ValidationDetail::parse_all).unwrap_or_default();
+        let message = message
+            .or_else(|| match error {
+                Some(serde_json::Value::String(error)) => Some(error.clone()),
+                _ if !validation_details.is_empty() => Some(
+                    validation_details
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                ),
+                Some(error) => Some(error.to_string()),
+                None => None,
+            })
+            .unwrap_or_else(|| "Unknown error".to_string());
+        Self {
+            event_id,
+            message,
+            code: status.map(|s| s.to_string()),
+            retryable: status.is_some_and(|s| s >= 500 || s == 429),
+            validation_details,
+        }
+    }
+}
+
+/// Validation problem reported for one field of a rejected event
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+pub struct ValidationDetail {
+    /// Path of the invalid field, e.g. `["body", "name"]`
+    pub path: Vec<String>,
+    /// Why the field is invalid
+    pub message: String,
+    /// Machine-readable reason, e.g. `invalid_type`
+    pub code: Option<String>,
+}
+
+impl ValidationDetail {
+    /// Field path joined with dots, e.g. `body.name`
+    pub fn field(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// Parse the validation details of an ingestion error item's `error` value
+    ///
+    /// Accepts a list of issues, an object with an `issues` or `errors` list,
+    /// a single issue, or any of these encoded as a JSON string. Anything else
+    /// yields no details.
+    pub(crate) fn parse_all(error: &serde_json::Value) -> Vec<Self> {
+        use serde_json::Value;
+
+        match error {
+            Value::String(text) => serde_json::from_str::<Value>(text)
+                .map(|value| match value {
+                    Value::String(_) => Vec::new(),
+                    value => Self::parse_all(&value),
+                })
+                .unwrap_or_default(),
+            Value::Array(items) => items.iter().filter_map(Self::parse_one).collect(),
+            Value::Object(map) => match map.get("issues").or_else(|| map.get("errors")) {
+                Some(issues @ Value::Array(_)) => Self::parse_all(issues),
+                _ => Self::parse_one(error).into_iter().collect(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse_one(issue: &serde_json::Value) -> Option<Self> {
+        use serde_json::Value;
+
+        let text = |key: &str| issue.get(key).and_then(Value::as_str).map(str::to_string);
+        let path = match issue.get("path")? {
+            Value::Array(segments) => segments
+                .iter()
+                .map(|segment| match segment {
+                    Value::String(name) => name.clone(),
+                    other => other.to_string(),
+                })
+                .collect(),
+            Value::String(path) => path.split('.').map(str::to_string).collect(),
+            _ => return None,
+        };
+        Some(Self {
+            path,
+            message: text("message").or_else(|| text("reason"))?,
+            code: text("code"),
+        })
+    }
+}
+
+impl fmt::Display for ValidationDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field(), self.message)
+    }
 }
 
 impl fmt::Display for EventError {
@@ -118,6 +254,10 @@ impl Error {
     pub fn is_retryable(&self) -> bool {
         match self {
             Error::Network(_) => true,
+            // Errors raised by the crate's own middleware, e.g. of a disabled client
+            Error::Middleware(reqwest_middleware::Error::Middleware(e)) => {
+                e.downcast_ref::<Error>().is_none_or(Error::is_retryable)
+            }
             Error::Middleware(_) => true,
             Error::RateLimit { .. } => true,
             Error::Server { .. } => true,
@@ -127,9 +267,113 @@ impl Error {
             Error::Validation(_) => false,
             Error::Serialization(_) => false,
             Error::Configuration(_) => false,
+            Error::Io { .. } => false,
+            Error::Task(_) => false,
             Error::Api(_) => false,
             Error::BatchSizeExceeded { .. } => false,
             Error::Backpressure { .. } => false,
+            Error::CircuitOpen { .. } => true,
+        }
+    }
+
+    /// Copy of the error for reporting one failure against several items
+    ///
+    /// Keeps the variant and its details. Network, HTTP client, and task
+    /// errors can't be rebuilt, so their copies are a middleware error or
+    /// [`Error::Api`] with the same message and retryability.
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            Error::Api(message) => Error::Api(message.clone()),
+            Error::Configuration(message) => Error::Configuration(message.clone()),
+            Error::Validation(message) => Error::Validation(message.clone()),
+            Error::Serialization(e) => {
+                Error::Serialization(<serde_json::Error as serde::de::Error>::custom(e))
+            }
+            Error::Network(e) => Error::Middleware(reqwest_middleware::Error::middleware(
+                std::io::Error::other(e.to_string()),
+            )),
+            Error::Middleware(reqwest_middleware::Error::Middleware(e)) => {
+                match e.downcast_ref::<Error>() {
+                    Some(inner) => {
+                        Error::Middleware(reqwest_middleware::Error::middleware(inner.duplicate()))
+                    }
+                    None => Error::Middleware(reqwest_middleware::Error::middleware(
+                        std::io::Error::other(format!("{e:#}")),
+                    )),
+                }
+            }
+            Error::Middleware(e) => Error::Middleware(reqwest_middleware::Error::middleware(
+                std::io::Error::other(e.to_string()),
+            )),
+            Error::Task(e) => Error::Api(format!("Background task failed: {e}")),
+            Error::Io { context, source } => Error::Io {
+                context: context.clone(),
+                source: std::io::Error::new(source.kind(), source.to_string()),
+            },
+            Error::Auth {
+                message,
+                request_id,
+            } => Error::Auth {
+                message: message.clone(),
+                request_id: request_id.clone(),
+            },
+            Error::RateLimit {
+                retry_after,
+                request_id,
+            } => Error::RateLimit {
+                retry_after: *retry_after,
+                request_id: request_id.clone(),
+            },
+            Error::Server {
+                status,
+                message,
+                request_id,
+            } => Error::Server {
+                status: *status,
+                message: message.clone(),
+                request_id: request_id.clone(),
+            },
+            Error::Client {
+                status,
+                message,
+                request_id,
+            } => Error::Client {
+                status: *status,
+                message: message.clone(),
+                request_id: request_id.clone(),
+            },
+            Error::PartialFailure {
+                success_count,
+                failure_count,
+                errors,
+                success_ids,
+                request_id,
+            } => Error::PartialFailure {
+                success_count: *success_count,
+                failure_count: *failure_count,
+                errors: errors.clone(),
+                success_ids: success_ids.clone(),
+                request_id: request_id.clone(),
+            },
+            Error::BatchSizeExceeded { size, max_size } => Error::BatchSizeExceeded {
+                size: *size,
+                max_size: *max_size,
+            },
+            Error::Backpressure { policy, reason } => Error::Backpressure {
+                policy: *policy,
+                reason: reason.clone(),
+            },
+            Error::CircuitOpen { retry_in } => Error::CircuitOpen {
+                retry_in: *retry_in,
+            },
+        }
+    }
+
+    /// IO error with a description of what was being read or written
+    pub(crate) fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
         }
     }
 
@@ -139,6 +383,7 @@ impl Error {
             Error::RateLimit { retry_after, .. } => *retry_after,
             Error::Server { .. } => Some(Duration::from_secs(5)), // Default retry for server errors
             Error::Network(_) => Some(Duration::from_secs(1)),    // Quick retry for network errors
+            Error::CircuitOpen { retry_in } => Some(*retry_in),
             _ => None,
         }
     }
@@ -149,7 +394,8 @@ impl Error {
             Error::Auth { request_id, .. }
             | Error::RateLimit { request_id, .. }
             | Error::Server { request_id, .. }
-            | Error::Client { request_id, .. } => request_id.as_deref(),
+            | Error::Client { request_id, .. }
+            | Error::PartialFailure { request_id, .. } => request_id.as_deref(),
             _ => None,
         }
     }
@@ -157,6 +403,10 @@ impl Error {
 
 /// Response from batch ingestion operations
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
 pub struct IngestionResponse {
     /// Successfully processed event IDs
     pub success_ids: Vec<String>,
@@ -165,6 +415,9 @@ pub struct IngestionResponse {
     /// Overall success/failure counts
     pub success_count: usize,
     pub failure_count: usize,
+    /// ID of the ingestion request, or of the last one when a batcher sent several
+    #[cfg_attr(feature = "json-schema", serde(default))]
+    pub request_id: Option<String>,
 }
 
 impl IngestionResponse {
@@ -186,6 +439,7 @@ impl IngestionResponse {
                 failure_count: self.failure_count,
                 errors: self.failures.clone(),
                 success_ids: self.success_ids.clone(),
+                request_id: self.request_id.clone(),
             })
         } else {
             None
@@ -235,6 +489,15 @@ pub fn map_api_error<T>(err: langfuse_client_base::apis::Error<T>) -> Error {
     }
 }
 
+/// Run blocking file IO on the blocking thread pool instead of the async executor
+pub(crate) async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,6 +575,7 @@ mod tests {
             failures: vec![],
             success_count: 2,
             failure_count: 0,
+            request_id: None,
         };
 
         assert!(response.is_success());
@@ -328,9 +592,11 @@ mod tests {
                 message: "Validation failed".to_string(),
                 code: Some("VALIDATION_ERROR".to_string()),
                 retryable: false,
+                validation_details: Vec::new(),
             }],
             success_count: 1,
             failure_count: 1,
+            request_id: None,
         };
 
         assert!(!response.is_success());
@@ -360,16 +626,19 @@ mod tests {
                     message: "Auth failed".to_string(),
                     code: Some("AUTH_ERROR".to_string()),
                     retryable: false,
+                    validation_details: Vec::new(),
                 },
                 EventError {
                     event_id: "id2".to_string(),
                     message: "Rate limited".to_string(),
                     code: Some("RATE_LIMIT".to_string()),
                     retryable: true,
+                    validation_details: Vec::new(),
                 },
             ],
             success_count: 0,
             failure_count: 2,
+            request_id: None,
         };
 
         assert!(!response.is_success());
@@ -384,6 +653,7 @@ mod tests {
             message: "Something went wrong".to_string(),
             code: Some("TEST_ERROR".to_string()),
             retryable: true,
+            validation_details: Vec::new(),
         };
 
         let display = format!("{}", error);
@@ -400,6 +670,7 @@ mod tests {
             message: "Minimal error".to_string(),
             code: None,
             retryable: false,
+            validation_details: Vec::new(),
         };
 
         let display = format!("{}", error);
@@ -407,4 +678,39 @@ mod tests {
         assert!(display.contains("Minimal error"));
         assert!(!display.contains("retryable"));
     }
+
+    #[test]
+    fn test_parse_validation_details() {
+        use serde_json::json;
+
+        let issues = json!([
+            {
+                "code": "invalid_type",
+                "expected": "string",
+                "received": "number",
+                "path": ["body", "name"],
+                "message": "Expected string, received number"
+            },
+            {"path": ["body", "tags", 0], "message": "Invalid tag"}
+        ]);
+        let details = ValidationDetail::parse_all(&issues);
+        assert_eq!(details.len(), 2);
+        assert_eq!(details[0].field(), "body.name");
+        assert_eq!(details[0].code.as_deref(), Some("invalid_type"));
+        assert_eq!(details[1].path, ["body", "tags", "0"]);
+        assert_eq!(details[1].to_string(), "body.tags.0: Invalid tag");
+
+        let wrapped = json!({"issues": issues});
+        assert_eq!(ValidationDetail::parse_all(&wrapped), details);
+        let encoded = json!(issues.to_string());
+        assert_eq!(ValidationDetail::parse_all(&encoded), details);
+
+        let single = json!({"path": "body.input", "reason": "too large"});
+        assert_eq!(
+            ValidationDetail::parse_all(&single)[0].field(),
+            "body.input"
+        );
+        assert!(ValidationDetail::parse_all(&json!("Invalid request")).is_empty());
+        assert!(ValidationDetail::parse_all(&json!({"message": "no path"})).is_empty());
+    }
 }
@@ -0,0 +1,132 @@
+//! Experiments over datasets
+//!
+//! [`LangfuseClient::run_experiment`](crate::LangfuseClient::run_experiment)
+//! runs a task against every active item of a dataset: for each item it calls
+//! the task with the item, records a trace with the item's input and the task's
+//! output, links the trace to the item in the dataset run, and records the
+//! scores the task returned. Runs show up side by side in the dataset view of
+//! the Langfuse UI.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::experiments::ExperimentOutput;
+//! use serde_json::json;
+//!
+//! # async fn answer(question: &serde_json::Value) -> Result<String, std::io::Error> { Ok(String::new()) }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let result = client
+//!     .run_experiment("qa-golden", "prompt-v2", |item| async move {
+//!         let question = item.input.unwrap_or_default();
+//!         let answer = answer(&question).await?;
+//!         let correct = item.expected_output == Some(json!(&answer));
+//!         Ok::<_, std::io::Error>(
+//!             ExperimentOutput::new(json!(answer)).score("exact_match", correct, None),
+//!         )
+//!     })
+//!     .await?;
+//! println!("{} of {} items failed", result.failed(), result.items.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Items run one after another. A task error is recorded on the item's trace
+//! (under the `error` metadata key) and in the [`ExperimentResult`], and the
+//! experiment continues; failing to reach Langfuse stops it.
+
+use serde_json::Value;
+
+use crate::scores::ScoreValue;
+
+/// What a task produced for one dataset item
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentOutput {
+    /// Output recorded on the item's trace
+    pub output: Value,
+    /// Scores recorded on the item's trace
+    pub scores: Vec<ExperimentScore>,
+}
+
+impl ExperimentOutput {
+    /// Output without scores
+    pub fn new(output: Value) -> Self {
+        Self {
+            output,
+            scores: Vec::new(),
+        }
+    }
+
+    /// Add a score for the item's trace
+    #[must_use]
+    pub fn score(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<ScoreValue>,
+        comment: Option<&str>,
+    ) -> Self {
+        self.scores.push(ExperimentScore {
+            name: name.into(),
+            value: value.into(),
+            comment: comment.map(str::to_string),
+        });
+        self
+    }
+}
+
+impl From<Value> for ExperimentOutput {
+    fn from(output: Value) -> Self {
+        Self::new(output)
+    }
+}
+
+/// Score produced by a task
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentScore {
+    /// Name of the score
+    pub name: String,
+    /// Value of the score
+    pub value: ScoreValue,
+    /// Why the score was given
+    pub comment: Option<String>,
+}
+
+/// Outcome of a task for one dataset item
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentItemResult {
+    /// ID of the dataset item
+    pub item_id: String,
+    /// ID of the trace recorded for the item
+    pub trace_id: String,
+    /// Output of the task, if it succeeded
+    pub output: Option<Value>,
+    /// Scores recorded for the item
+    pub scores: Vec<ExperimentScore>,
+    /// Error of the task, if it failed
+    pub error: Option<String>,
+}
+
+/// Outcome of a dataset run
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentResult {
+    /// Name of the dataset
+    pub dataset_name: String,
+    /// Name of the dataset run
+    pub run_name: String,
+    /// Per-item outcomes, in dataset order
+    pub items: Vec<ExperimentItemResult>,
+}
+
+impl ExperimentResult {
+    /// Number of items the task succeeded on
+    pub fn succeeded(&self) -> usize {
+        self.items
+            .iter()
+            .filter(|item| item.error.is_none())
+            .count()
+    }
+
+    /// Number of items the task failed on
+    pub fn failed(&self) -> usize {
+        self.items.len() - self.succeeded()
+    }
+}
@@ -0,0 +1,494 @@
+//! Bulk export of traces to JSONL or CSV
+//!
+//! [`LangfuseClient::export_traces`](crate::LangfuseClient::export_traces)
+//! streams every trace matching the filters, with its observations, to a
+//! writer or a local file, one trace at a time. Useful for offline analysis
+//! and for backing up self-hosted instances.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::export::ExportFormat;
+//! use langfuse_ergonomic::TraceFilter;
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let summary = client
+//!     .export_traces()
+//!     .format(ExportFormat::Jsonl)
+//!     .filter(TraceFilter::new().from_timestamp(Utc::now() - Duration::days(1)))
+//!     .to_file("traces.jsonl")
+//!     .await?;
+//! println!("Exported {} traces", summary.traces);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! JSONL lines are the traces as returned by the API. CSV rows hold one trace
+//! each; the tags, input, output, metadata, and observations columns contain
+//! JSON.
+//!
+//! Traces are listed page by page while the export runs. Unless the filter
+//! sets a `to_timestamp`, the listing stops at the time the export started,
+//! so traces ingested meanwhile do not shift later pages, and a trace listed
+//! twice is written once. Traces deleted during an export can still shift
+//! pages and cause others to be skipped.
+//!
+//! ## Anonymization
+//!
+//! With [`TraceExport::anonymize`], exports can be shared with vendors or
+//! researchers without leaking PII. The input, output, and metadata of traces
+//! and observations, observation status messages, and score comments, values,
+//! and metadata go through a [`Redactor`]. User IDs, including the authors of
+//! scores, are replaced by salted hashes, so the traces of one user stay
+//! linked without revealing who they are. The salt is random for each export
+//! unless set with [`TraceExport::anonymize_salt`].
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::Redactor;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! client
+//!     .export_traces()
+//!     .anonymize(Redactor::default())
+//!     .to_file("shareable.jsonl")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use langfuse_client_base::models::{ScoreV1, TraceWithFullDetails};
+use rand::{rng, RngExt};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::fs::File;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+
+use crate::client::LangfuseClient;
+use crate::error::{Error, Result};
+use crate::query::{TraceField, TraceFilter};
+use crate::security::Redactor;
+
+/// Traces listed per page while exporting
+const PAGE_SIZE: i32 = 50;
+
+/// Columns of a CSV export
+const CSV_COLUMNS: [&str; 14] = [
+    "id",
+    "timestamp",
+    "name",
+    "user_id",
+    "session_id",
+    "release",
+    "version",
+    "tags",
+    "latency",
+    "total_cost",
+    "input",
+    "output",
+    "metadata",
+    "observations",
+];
+
+/// File format of trace and dataset exports and dataset imports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line (default)
+    #[default]
+    Jsonl,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+/// Outcome of a trace export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    /// Number of traces written
+    pub traces: usize,
+    /// Number of observations written with them
+    pub observations: usize,
+}
+
+/// Export of the traces matching a set of filters
+///
+/// Created with [`LangfuseClient::export_traces`]; see the
+/// [module documentation](self).
+pub struct TraceExport<'a> {
+    client: &'a LangfuseClient,
+    format: ExportFormat,
+    filter: TraceFilter,
+    max_traces: Option<usize>,
+    observations: bool,
+    redactor: Option<Redactor>,
+    anonymize_salt: Option<String>,
+}
+
+impl<'a> TraceExport<'a> {
+    pub(crate) fn new(client: &'a LangfuseClient) -> Self {
+        Self {
+            client,
+            format: ExportFormat::default(),
+            filter: TraceFilter::default(),
+            max_traces: None,
+            observations: true,
+            redactor: None,
+            anonymize_salt: None,
+        }
+    }
+
+    /// Write in this format
+    #[must_use]
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Only the traces matching `filter`
+    #[must_use]
+    pub fn filter(mut self, filter: TraceFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Export at most this many traces
+    #[must_use]
+    pub fn max_traces(mut self, max_traces: usize) -> Self {
+        self.max_traces = Some(max_traces);
+        self
+    }
+
+    /// Include observations (default `true`)
+    #[must_use]
+    pub fn observations(mut self, observations: bool) -> Self {
+        self.observations = observations;
+        self
+    }
+
+    /// Redact payloads with `redactor` and hash user IDs
+    ///
+    /// See [Anonymization](self#anonymization).
+    #[must_use]
+    pub fn anonymize(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Salt of the user ID hashes when anonymizing (random by default)
+    ///
+    /// Exports with the same salt map a user to the same hash.
+    #[must_use]
+    pub fn anonymize_salt(mut self, salt: impl Into<String>) -> Self {
+        self.anonymize_salt = Some(salt.into());
+        self
+    }
+
+    /// Create or replace the file at `path` with the export
+    ///
+    /// The export is written to `path` with a `.partial` suffix first and
+    /// renamed once complete, so a failed export leaves an existing file
+    /// untouched.
+    pub async fn to_file(self, path: impl AsRef<Path>) -> Result<ExportSummary> {
+        write_file(path.as_ref(), |writer| self.to_writer(writer)).await
+    }
+
+    /// Write the export to `writer`, one trace at a time
+    pub async fn to_writer<W: AsyncWrite + Unpin>(self, mut writer: W) -> Result<ExportSummary> {
+        let mut fields = vec![TraceField::Core, TraceField::Io, TraceField::Metrics];
+        if self.observations {
+            fields.push(TraceField::Observations);
+        }
+        let max_traces = self.max_traces.unwrap_or(usize::MAX);
+        let salt = self
+            .anonymize_salt
+            .clone()
+            .unwrap_or_else(|| format!("{:016x}", rng().random::<u64>()));
+
+        if self.format == ExportFormat::Csv {
+            write_line(&mut writer, &CSV_COLUMNS.join(",")).await?;
+        }
+
+        // Pin the end of the listing so new traces don't shift its pages
+        let mut filter = self.filter.clone();
+        filter.to_timestamp.get_or_insert_with(Utc::now);
+        let mut seen = HashSet::new();
+
+        let mut summary = ExportSummary::default();
+        let mut page = 1;
+        'pages: loop {
+            let traces = self
+                .client
+                .list_traces()
+                .page(page)
+                .limit(PAGE_SIZE)
+                .filter(filter.clone())
+                .fields(vec![TraceField::Core])
+                .call()
+                .await?;
+
+            let received = traces.data.len();
+            for listed in traces.data {
+                if summary.traces == max_traces {
+                    break 'pages;
+                }
+                if !seen.insert(listed.id.clone()) {
+                    continue;
+                }
+                let trace = self
+                    .client
+                    .get_trace_with_fields(listed.id, &fields)
+                    .await?;
+                let trace = match &self.redactor {
+                    Some(redactor) => anonymize_trace(trace, redactor, &salt)?,
+                    None => trace,
+                };
+                let line = match self.format {
+                    ExportFormat::Jsonl => serde_json::to_string(&trace)?,
+                    ExportFormat::Csv => csv_row(&trace)?,
+                };
+                write_line(&mut writer, &line).await?;
+                summary.traces += 1;
+                summary.observations += trace.observations.len();
+            }
+            if received < PAGE_SIZE as usize || page >= traces.meta.total_pages {
+                break;
+            }
+            page += 1;
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|e| Error::io("Failed to write trace export", e))?;
+        Ok(summary)
+    }
+}
+
+/// Replace the file at `path` with what `write` writes, once it succeeds
+///
+/// Writes to a `.partial` sibling that is renamed over `path` on success and
+/// removed on failure.
+pub(crate) async fn write_file<T, F, Fut>(path: &Path, write: F) -> Result<T>
+where
+    F: FnOnce(BufWriter<File>) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let file = File::create(&partial).await.map_err(|e| {
+        Error::io(
+            format!("Failed to create export file {}", partial.display()),
+            e,
+        )
+    })?;
+
+    let written = match write(BufWriter::new(file)).await {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&partial).await;
+            return Err(e);
+        }
+    };
+    tokio::fs::rename(&partial, path).await.map_err(|e| {
+        Error::io(
+            format!("Failed to move export file to {}", path.display()),
+            e,
+        )
+    })?;
+    Ok(written)
+}
+
+async fn write_line(writer: &mut (impl AsyncWrite + Unpin), line: &str) -> Result<()> {
+    let mut bytes = Vec::with_capacity(line.len() + 1);
+    bytes.extend_from_slice(line.as_bytes());
+    bytes.push(b'\n');
+    writer
+        .write_all(&bytes)
+        .await
+        .map_err(|e| Error::io("Failed to write trace export", e))
+}
+
+/// Redact a trace with its observations and scores, and hash its user IDs
+fn anonymize_trace(
+    mut trace: TraceWithFullDetails,
+    redactor: &Redactor,
+    salt: &str,
+) -> Result<TraceWithFullDetails> {
+    let redact = |value: Option<Option<Value>>| value.map(|v| v.map(|v| redactor.redact_json(v)));
+    trace.input = redact(trace.input);
+    trace.output = redact(trace.output);
+    trace.metadata = redact(trace.metadata);
+    trace.user_id = trace.user_id.map(|id| id.map(|id| hash_user_id(&id, salt)));
+
+    for observation in &mut trace.observations {
+        observation.input = observation.input.take().map(|v| redactor.redact_json(v));
+        observation.output = observation.output.take().map(|v| redactor.redact_json(v));
+        observation.metadata = observation.metadata.take().map(|v| redactor.redact_json(v));
+        observation.status_message = observation
+            .status_message
+            .take()
+            .map(|message| message.map(|m| redactor.redact_str(&m)));
+    }
+
+    trace.scores = std::mem::take(&mut trace.scores)
+        .into_iter()
+        .map(|score| anonymize_score(score, redactor, salt))
+        .collect::<Result<_>>()?;
+    Ok(trace)
+}
+
+/// Redact a score through its JSON form, which every score type shares
+fn anonymize_score(score: ScoreV1, redactor: &Redactor, salt: &str) -> Result<ScoreV1> {
+    let mut value = serde_json::to_value(score)?;
+    if let Some(fields) = value.as_object_mut() {
+        for key in ["comment", "value", "stringValue"] {
+            if let Some(Value::String(text)) = fields.get_mut(key) {
+                *text = redactor.redact_str(text);
+            }
+        }
+        if let Some(metadata) = fields.get_mut("metadata") {
+            *metadata = redactor.redact_json(metadata.take());
+        }
+        if let Some(Value::String(author)) = fields.get_mut("authorUserId") {
+            *author = hash_user_id(author, salt);
+        }
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Pseudonym of a user ID: `user-` and 16 hex digits of its salted SHA-256
+fn hash_user_id(user_id: &str, salt: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update(b":")
+        .chain_update(user_id.as_bytes())
+        .finalize();
+    let hex: String = digest[..8]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    format!("user-{hex}")
+}
+
+/// CSV row of a trace, in the order of [`CSV_COLUMNS`]
+fn csv_row(trace: &TraceWithFullDetails) -> Result<String> {
+    let text = |value: &Option<Option<String>>| value.clone().flatten().unwrap_or_default();
+    let number = |value: Option<Option<f64>>| value.flatten().map(|n| n.to_string());
+    let json = |value: &Option<Option<Value>>| match value.as_ref().and_then(Option::as_ref) {
+        Some(value) => serde_json::to_string(value),
+        None => Ok(String::new()),
+    };
+
+    let cells = [
+        trace.id.clone(),
+        trace.timestamp.clone(),
+        text(&trace.name),
+        text(&trace.user_id),
+        text(&trace.session_id),
+        text(&trace.release),
+        text(&trace.version),
+        serde_json::to_string(&trace.tags)?,
+        number(trace.latency).unwrap_or_default(),
+        number(trace.total_cost).unwrap_or_default(),
+        json(&trace.input)?,
+        json(&trace.output)?,
+        json(&trace.metadata)?,
+        serde_json::to_string(&trace.observations)?,
+    ];
+    Ok(cells
+        .iter()
+        .map(|cell| csv_escape(cell))
+        .collect::<Vec<_>>()
+        .join(","))
+}
+
+/// Quote a CSV cell if it contains a separator, quote, or line break
+pub(crate) fn csv_escape(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape(r#"{"k":"v"}"#), r#""{""k"":""v""}""#);
+        assert_eq!(csv_escape("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_csv_row() {
+        let trace = TraceWithFullDetails {
+            id: "trace-1".to_string(),
+            timestamp: "2024-01-01T00:00:00.000Z".to_string(),
+            name: Some(Some("chat".to_string())),
+            tags: vec!["prod".to_string()],
+            latency: Some(Some(1.5)),
+            input: Some(Some(json!({"q": "hi"}))),
+            ..Default::default()
+        };
+        let row = csv_row(&trace).unwrap();
+        assert_eq!(
+            row,
+            r#"trace-1,2024-01-01T00:00:00.000Z,chat,,,,,"[""prod""]",1.5,,"{""q"":""hi""}",,,[]"#
+        );
+    }
+
+    #[test]
+    fn test_anonymize_trace() {
+        let trace = TraceWithFullDetails {
+            id: "trace-1".to_string(),
+            user_id: Some(Some("jane@example.com".to_string())),
+            input: Some(Some(json!({"question": "Mail me at jane@example.com"}))),
+            metadata: Some(Some(json!({"api_key": "abc"}))),
+            observations: vec![langfuse_client_base::models::ObservationsView {
+                output: Some(json!("Sent to jane@example.com")),
+                status_message: Some(Some("Failed for jane@example.com".to_string())),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let anonymized = anonymize_trace(trace.clone(), &Redactor::default(), "salt").unwrap();
+        let user_id = anonymized.user_id.clone().flatten().unwrap();
+        assert!(user_id.starts_with("user-"));
+        assert_eq!(user_id.len(), "user-".len() + 16);
+        assert_eq!(
+            anonymized.input,
+            Some(Some(json!({"question": "Mail me at [REDACTED]"})))
+        );
+        assert_eq!(
+            anonymized.metadata,
+            Some(Some(json!({"api_key": "[REDACTED]"})))
+        );
+        assert_eq!(
+            anonymized.observations[0].output,
+            Some(json!("Sent to [REDACTED]"))
+        );
+        assert_eq!(
+            anonymized.observations[0].status_message,
+            Some(Some("Failed for [REDACTED]".to_string()))
+        );
+
+        // The same salt gives the same pseudonym, another salt a different one
+        let again = anonymize_trace(trace.clone(), &Redactor::default(), "salt").unwrap();
+        assert_eq!(again.user_id, anonymized.user_id);
+        let other = anonymize_trace(trace, &Redactor::default(), "other").unwrap();
+        assert_ne!(other.user_id, anonymized.user_id);
+    }
+}
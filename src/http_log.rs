@@ -0,0 +1,150 @@
+//! Hooks observing every API request and response
+//!
+//! Callbacks registered with
+//! [`ClientBuilder::on_request`](crate::ClientBuilder::on_request) and
+//! [`ClientBuilder::on_response`](crate::ClientBuilder::on_response) see each
+//! HTTP call the client makes, including retries, with its method, path,
+//! status, and latency. This is enough to debug failing or slow API calls from
+//! application logs:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::ClientBuilder;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .on_request(|request| {
+//!         tracing::debug!(id = %request.request_id, body = ?request.body, "{} {}", request.method, request.path);
+//!     })
+//!     .on_response(|response| {
+//!         if !response.status.is_some_and(|status| status < 400) {
+//!             tracing::warn!(id = %response.request_id, status = ?response.status, "Langfuse call failed");
+//!         }
+//!     })
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each call gets a request ID, sent as the `x-request-id` header so it can be
+//! matched with proxy and server logs; uploads to media storage don't carry
+//! the header. JSON request bodies are passed through
+//! the client's [`Redactor`](crate::Redactor), or the default one when
+//! payload redaction is off, before a hook sees them. Credentials are never
+//! exposed.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use http::Extensions;
+use reqwest::header::HeaderValue;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::auth::Unauthenticated;
+use crate::latency::endpoint_template;
+use crate::security::Redactor;
+
+/// Header carrying the ID of a request
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Callback invoked before every API request is sent
+pub type RequestCallback = Arc<dyn Fn(&ApiRequest) + Send + Sync>;
+
+/// Callback invoked after every API request completes or fails
+pub type ResponseCallback = Arc<dyn Fn(&ApiResponse) + Send + Sync>;
+
+/// An API request about to be sent
+#[derive(Debug, Clone)]
+pub struct ApiRequest {
+    /// ID sent in the `x-request-id` header
+    pub request_id: String,
+    /// HTTP method
+    pub method: String,
+    /// Request path, e.g. `/api/public/traces/abc`
+    pub path: String,
+    /// Endpoint path template, e.g. `/api/public/traces/{id}`
+    pub endpoint: String,
+    /// Redacted JSON body, if the request has one
+    pub body: Option<Value>,
+}
+
+/// The outcome of an API request
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    /// ID sent in the `x-request-id` header
+    pub request_id: String,
+    /// HTTP method
+    pub method: String,
+    /// Request path
+    pub path: String,
+    /// Endpoint path template
+    pub endpoint: String,
+    /// HTTP status code, or `None` if the request failed before a response arrived
+    pub status: Option<u16>,
+    /// Time from sending the request to receiving the response headers
+    pub duration: Duration,
+    /// Error that prevented a response, if any
+    pub error: Option<String>,
+}
+
+/// Reports requests and responses to the registered hooks
+pub(crate) struct HttpLogMiddleware {
+    pub(crate) on_request: Option<RequestCallback>,
+    pub(crate) on_response: Option<ResponseCallback>,
+    pub(crate) redactor: Arc<Redactor>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for HttpLogMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let request_id = Uuid::new_v4().to_string();
+        // Presigned media uploads must be sent as signed
+        if extensions.get::<Unauthenticated>().is_none() {
+            if let Ok(value) = HeaderValue::from_str(&request_id) {
+                req.headers_mut().insert(REQUEST_ID_HEADER, value);
+            }
+        }
+        let method = req.method().to_string();
+        let path = req.url().path().to_string();
+        let endpoint = endpoint_template(&path);
+
+        if let Some(on_request) = &self.on_request {
+            let body = req
+                .body()
+                .and_then(|body| body.as_bytes())
+                .and_then(|bytes| serde_json::from_slice(bytes).ok())
+                .map(|value| self.redactor.redact_json(value));
+            on_request(&ApiRequest {
+                request_id: request_id.clone(),
+                method: method.clone(),
+                path: path.clone(),
+                endpoint: endpoint.clone(),
+                body,
+            });
+        }
+
+        let start = Instant::now();
+        let result = next.run(req, extensions).await;
+
+        if let Some(on_response) = &self.on_response {
+            on_response(&ApiResponse {
+                request_id,
+                method,
+                path,
+                endpoint,
+                status: result.as_ref().ok().map(|r| r.status().as_u16()),
+                duration: start.elapsed(),
+                error: result.as_ref().err().map(ToString::to_string),
+            });
+        }
+
+        result
+    }
+}
@@ -0,0 +1,104 @@
+//! Hooks that transform ingestion events before they are sent
+//!
+//! A [`BatchInterceptor`] registered with
+//! [`ClientBuilder::interceptor`](crate::ClientBuilder::interceptor) sees every
+//! ingestion event the client sends or builds, and every event added to a
+//! [`Batcher`](crate::Batcher) built from the client, before it is serialized.
+//! Use it to scrub fields, stamp a release or metadata on every event, or
+//! enrich events from application state:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{ClientBuilder, IngestionEvent};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .interceptor(|event: &mut IngestionEvent| {
+//!         if let IngestionEvent::IngestionEventOneOf(trace) = event {
+//!             trace.body.release.get_or_insert(Some("1.4.2".to_string()));
+//!         }
+//!     })
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Interceptors run in the order they were registered, after the client's
+//! [environment](crate::environment) is applied. Each event passes through
+//! them once: events queued by a batcher's builders are intercepted when the
+//! batcher accepts them, not before. Interceptors must not change event IDs,
+//! which batchers use to track deliveries.
+
+use std::sync::Arc;
+
+use langfuse_client_base::models::IngestionEvent;
+use serde_json::{Map, Value};
+
+/// Transforms ingestion events before they are serialized
+///
+/// Implemented for closures taking `&mut IngestionEvent`.
+pub trait BatchInterceptor: Send + Sync {
+    /// Inspect or modify `event`
+    fn on_event(&self, event: &mut IngestionEvent);
+}
+
+impl<F> BatchInterceptor for F
+where
+    F: Fn(&mut IngestionEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &mut IngestionEvent) {
+        self(event)
+    }
+}
+
+/// Run `event` through every interceptor, in order
+pub(crate) fn intercept(interceptors: &[Arc<dyn BatchInterceptor>], event: &mut IngestionEvent) {
+    for interceptor in interceptors {
+        interceptor.on_event(event);
+    }
+}
+
+/// Event metadata as a JSON object to add keys to
+///
+/// Non-object metadata is kept under a `metadata` key.
+pub(crate) fn metadata_object(metadata: Option<Value>) -> Map<String, Value> {
+    match metadata {
+        Some(Value::Object(map)) => map,
+        Some(other) => Map::from_iter([("metadata".to_string(), other)]),
+        None => Map::new(),
+    }
+}
+
+/// Input, output, and metadata of a trace or observation event's body
+///
+/// `None` for events without input and output, such as scores.
+pub(crate) fn payload_fields(
+    event: &mut IngestionEvent,
+) -> Option<[&mut Option<Option<Value>>; 3]> {
+    match event {
+        IngestionEvent::IngestionEventOneOf(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf2(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf3(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf4(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf5(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf6(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf8(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf9(e) => {
+            Some([&mut e.body.input, &mut e.body.output, &mut e.body.metadata])
+        }
+        IngestionEvent::IngestionEventOneOf1(_) | IngestionEvent::IngestionEventOneOf7(_) => None,
+    }
+}
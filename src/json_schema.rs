@@ -0,0 +1,116 @@
+//! JSON Schemas of the crate's serializable types (`json-schema` feature)
+//!
+//! The feature derives [`schemars::JsonSchema`] for [`BatcherConfig`],
+//! [`IngestionResponse`], [`EventError`], [`TraceResponse`], [`ScoreValue`],
+//! and the types they contain, next to their serde representation, so
+//! services can publish and validate batcher configs and dead-letter payloads
+//! with JSON Schema tooling. The tests here keep the schemas in step with what
+//! the types serialize to.
+//!
+//! [`BatcherConfig`]: crate::batcher::BatcherConfig
+//! [`IngestionResponse`]: crate::error::IngestionResponse
+//! [`EventError`]: crate::error::EventError
+//! [`TraceResponse`]: crate::traces::TraceResponse
+//! [`ScoreValue`]: crate::scores::ScoreValue
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use schemars::JsonSchema;
+    use serde_json::Value;
+
+    use crate::batcher::{BackpressurePolicy, BatcherConfig};
+    use crate::error::{EventError, IngestionResponse, ValidationDetail};
+    use crate::scores::ScoreValue;
+    use crate::traces::TraceResponse;
+
+    /// Property names of the root schema of `T`
+    fn schema_properties<T: JsonSchema>() -> Vec<String> {
+        let schema = schemars::schema_for!(T);
+        let mut names: Vec<String> = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|properties| properties.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// Field names of `value` serialized
+    fn serialized_fields(value: impl serde::Serialize) -> Vec<String> {
+        let mut names: Vec<String> = serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_schemas_match_serialized_fields() {
+        assert_eq!(
+            schema_properties::<BatcherConfig>(),
+            serialized_fields(BatcherConfig::default())
+        );
+
+        let failure = EventError {
+            event_id: "event-1".to_string(),
+            message: "Invalid".to_string(),
+            code: Some("400".to_string()),
+            retryable: false,
+            validation_details: vec![ValidationDetail {
+                path: vec!["body".to_string(), "name".to_string()],
+                message: "Required".to_string(),
+                code: None,
+            }],
+        };
+        assert_eq!(
+            schema_properties::<ValidationDetail>(),
+            serialized_fields(&failure.validation_details[0])
+        );
+        assert_eq!(
+            schema_properties::<EventError>(),
+            serialized_fields(&failure)
+        );
+        assert_eq!(
+            schema_properties::<IngestionResponse>(),
+            serialized_fields(IngestionResponse {
+                success_ids: vec![],
+                failures: vec![failure],
+                success_count: 0,
+                failure_count: 1,
+                request_id: Some("request-1".to_string()),
+            })
+        );
+        assert_eq!(
+            schema_properties::<TraceResponse>(),
+            serialized_fields(TraceResponse {
+                id: "trace-1".to_string(),
+                base_url: "https://cloud.langfuse.com".to_string(),
+                session_id: Some("session-1".to_string()),
+                project_id: Some("project-1".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_partial_batcher_config_deserializes() {
+        let config: BatcherConfig = serde_json::from_value(serde_json::json!({
+            "max_events": 50,
+            "backpressure_policy": "DropOldest",
+            "flush_interval": {"secs": 1, "nanos": 0}
+        }))
+        .unwrap();
+        assert_eq!(config.max_events, 50);
+        assert_eq!(config.backpressure_policy, BackpressurePolicy::DropOldest);
+        assert_eq!(config.flush_interval, Duration::from_secs(1));
+        assert_eq!(config.max_retries, BatcherConfig::default().max_retries);
+
+        let value: ScoreValue = serde_json::from_str("true").unwrap();
+        assert_eq!(value, ScoreValue::Boolean(true));
+    }
+}
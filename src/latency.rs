@@ -0,0 +1,298 @@
+//! Per-call latency instrumentation for Langfuse API requests
+//!
+//! Every request made by a [`LangfuseClient`](crate::LangfuseClient) is timed by
+//! a middleware layer. Each call is reported as a `tracing` debug event, passed
+//! to an optional callback registered with
+//! [`ClientBuilder::on_api_call`](crate::ClientBuilder::on_api_call), and folded
+//! into a rolling per-endpoint summary available via
+//! [`LangfuseClient::latency_summary`](crate::LangfuseClient::latency_summary).
+//!
+//! Endpoints are grouped by path template: identifiers in the path are replaced
+//! with `{id}`, so `/api/public/traces/abc` and `/api/public/traces/def` share a
+//! single `GET /api/public/traces/{id}` entry.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use http::Extensions;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+
+/// Number of recent samples kept per endpoint for percentile computation
+const WINDOW_SIZE: usize = 256;
+
+/// Callback invoked after every API call
+pub type ApiCallCallback = Arc<dyn Fn(&ApiCallTiming) + Send + Sync>;
+
+/// Timing information for a single API call
+#[derive(Debug, Clone)]
+pub struct ApiCallTiming {
+    /// HTTP method
+    pub method: String,
+    /// Endpoint path template, e.g. `/api/public/traces/{id}`
+    pub endpoint: String,
+    /// Wall-clock duration of the HTTP attempt
+    pub duration: Duration,
+    /// HTTP status code, or `None` if the request failed before a response arrived
+    pub status: Option<u16>,
+}
+
+/// Rolling latency summary for one endpoint
+#[derive(Debug, Clone)]
+pub struct EndpointLatency {
+    /// HTTP method
+    pub method: String,
+    /// Endpoint path template
+    pub endpoint: String,
+    /// Total number of calls observed
+    pub count: u64,
+    /// Number of calls that failed or returned a non-2xx status
+    pub errors: u64,
+    /// Median latency over the recent window
+    pub p50: Duration,
+    /// 95th percentile latency over the recent window
+    pub p95: Duration,
+    /// Maximum latency over the recent window
+    pub max: Duration,
+    /// Status code of the most recent call
+    pub last_status: Option<u16>,
+}
+
+impl fmt::Display for EndpointLatency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} calls, {} errors, p50 {:.2?}, p95 {:.2?}, max {:.2?}",
+            self.method, self.endpoint, self.count, self.errors, self.p50, self.p95, self.max
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndpointWindow {
+    samples: VecDeque<Duration>,
+    count: u64,
+    errors: u64,
+    last_status: Option<u16>,
+}
+
+/// Collects call timings and keeps a rolling per-endpoint summary
+#[derive(Default)]
+pub(crate) struct LatencyRecorder {
+    windows: Mutex<HashMap<(String, String), EndpointWindow>>,
+    callback: Option<ApiCallCallback>,
+}
+
+impl fmt::Debug for LatencyRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatencyRecorder")
+            .field("has_callback", &self.callback.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl LatencyRecorder {
+    pub(crate) fn new(callback: Option<ApiCallCallback>) -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+            callback,
+        }
+    }
+
+    pub(crate) fn record(&self, timing: ApiCallTiming) {
+        tracing::debug!(
+            method = %timing.method,
+            endpoint = %timing.endpoint,
+            duration_ms = timing.duration.as_millis() as u64,
+            status = timing.status,
+            "Langfuse API call"
+        );
+
+        if let Some(callback) = &self.callback {
+            callback(&timing);
+        }
+
+        let failed = !timing.status.is_some_and(|s| (200..300).contains(&s));
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let window = windows.entry((timing.method, timing.endpoint)).or_default();
+        if window.samples.len() == WINDOW_SIZE {
+            window.samples.pop_front();
+        }
+        window.samples.push_back(timing.duration);
+        window.count += 1;
+        if failed {
+            window.errors += 1;
+        }
+        window.last_status = timing.status;
+    }
+
+    pub(crate) fn summary(&self) -> Vec<EndpointLatency> {
+        let windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let mut summary: Vec<EndpointLatency> = windows
+            .iter()
+            .map(|((method, endpoint), window)| {
+                let mut sorted: Vec<Duration> = window.samples.iter().copied().collect();
+                sorted.sort_unstable();
+                EndpointLatency {
+                    method: method.clone(),
+                    endpoint: endpoint.clone(),
+                    count: window.count,
+                    errors: window.errors,
+                    p50: percentile(&sorted, 0.50),
+                    p95: percentile(&sorted, 0.95),
+                    max: sorted.last().copied().unwrap_or_default(),
+                    last_status: window.last_status,
+                }
+            })
+            .collect();
+        summary.sort_by(|a, b| (&a.endpoint, &a.method).cmp(&(&b.endpoint, &b.method)));
+        summary
+    }
+}
+
+/// Nearest-rank percentile of a sorted slice
+pub(crate) fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let rank = ((q * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    sorted[rank - 1]
+}
+
+/// Reduce a request path to a template by replacing identifiers with `{id}`
+///
+/// Paths under `/api/public` alternate between resource names and identifiers
+/// (`/datasets/{id}/runs/{id}`); an optional version segment (`v2`, `v3`, ...)
+/// directly after `public` is kept as is.
+pub(crate) fn endpoint_template(path: &str) -> String {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let Some(public) = segments
+        .windows(2)
+        .position(|w| w[0] == "api" && w[1] == "public")
+    else {
+        return path.to_string();
+    };
+
+    let mut template: Vec<&str> = segments[..public + 2].to_vec();
+    let mut rest = &segments[public + 2..];
+    if let Some(first) = rest.first() {
+        if first.len() > 1
+            && first.starts_with('v')
+            && first[1..].chars().all(|c| c.is_ascii_digit())
+        {
+            template.push(first);
+            rest = &rest[1..];
+        }
+    }
+    for (index, segment) in rest.iter().enumerate() {
+        template.push(if index % 2 == 1 { "{id}" } else { segment });
+    }
+
+    format!("/{}", template.join("/"))
+}
+
+/// Middleware that times every request and reports it to the recorder
+pub(crate) struct LatencyMiddleware {
+    pub(crate) recorder: Arc<LatencyRecorder>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for LatencyMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().to_string();
+        let endpoint = endpoint_template(req.url().path());
+        let start = Instant::now();
+
+        let result = next.run(req, extensions).await;
+
+        self.recorder.record(ApiCallTiming {
+            method,
+            endpoint,
+            duration: start.elapsed(),
+            status: result.as_ref().ok().map(|r| r.status().as_u16()),
+        });
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_template() {
+        assert_eq!(
+            endpoint_template("/api/public/ingestion"),
+            "/api/public/ingestion"
+        );
+        assert_eq!(
+            endpoint_template("/api/public/traces/abc-123"),
+            "/api/public/traces/{id}"
+        );
+        assert_eq!(
+            endpoint_template("/api/public/datasets/my-set/runs/run-1"),
+            "/api/public/datasets/{id}/runs/{id}"
+        );
+        assert_eq!(
+            endpoint_template("/api/public/v2/prompts/greeting"),
+            "/api/public/v2/prompts/{id}"
+        );
+        assert_eq!(
+            endpoint_template("/prefix/api/public/health"),
+            "/prefix/api/public/health"
+        );
+    }
+
+    #[test]
+    fn test_recorder_summary() {
+        let recorder = LatencyRecorder::new(None);
+        for (ms, status) in [
+            (10, Some(200)),
+            (20, Some(200)),
+            (30, Some(500)),
+            (40, None),
+        ] {
+            recorder.record(ApiCallTiming {
+                method: "POST".to_string(),
+                endpoint: "/api/public/ingestion".to_string(),
+                duration: Duration::from_millis(ms),
+                status,
+            });
+        }
+
+        let summary = recorder.summary();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].count, 4);
+        assert_eq!(summary[0].errors, 2);
+        assert_eq!(summary[0].p50, Duration::from_millis(20));
+        assert_eq!(summary[0].max, Duration::from_millis(40));
+        assert_eq!(summary[0].last_status, None);
+    }
+
+    #[test]
+    fn test_recorder_window_is_bounded() {
+        let recorder = LatencyRecorder::new(None);
+        for _ in 0..(WINDOW_SIZE + 10) {
+            recorder.record(ApiCallTiming {
+                method: "GET".to_string(),
+                endpoint: "/api/public/health".to_string(),
+                duration: Duration::from_millis(1),
+                status: Some(200),
+            });
+        }
+
+        let windows = recorder.windows.lock().unwrap();
+        let window = windows.values().next().unwrap();
+        assert_eq!(window.samples.len(), WINDOW_SIZE);
+        assert_eq!(window.count, (WINDOW_SIZE + 10) as u64);
+    }
+}
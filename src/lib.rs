@@ -47,6 +47,28 @@
 //! # }
 //! ```
 //!
+//! ## Optional Values
+//!
+//! Every optional field of the request builders, such as `trace()`, `span()`,
+//! and `score()`, also has a `maybe_*` setter taking an `Option`, so values
+//! that may be absent can be passed straight through. On [`ClientBuilder`],
+//! `base_url`, `timeout`, `connect_timeout`, and `user_agent` have one.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # use serde_json::Value;
+//! # async fn example(input: Option<Value>, user_id: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! client.trace()
+//!     .name("my-application")
+//!     .maybe_input(input)
+//!     .maybe_user_id(user_id)
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Type Safety
 //!
 //! All API methods return strongly-typed structs instead of JSON values:
@@ -88,6 +110,23 @@
 //! LANGFUSE_PUBLIC_KEY=pk-lf-...
 //! LANGFUSE_SECRET_KEY=sk-lf-...
 //! LANGFUSE_BASE_URL=https://cloud.langfuse.com  # Optional
+//! LANGFUSE_TRACING_ENVIRONMENT=production  # Optional
+//! LANGFUSE_HTTP_PROXY=http://proxy.internal:3128  # Optional
+//! LANGFUSE_NO_PROXY=localhost,.internal  # Optional
+//! LANGFUSE_ENABLED=false  # Optional, turns the client into a no-op
+//! ```
+//!
+//! To switch between projects or instances, use a named profile. It reads
+//! `LANGFUSE_STAGING_PUBLIC_KEY`, `LANGFUSE_STAGING_SECRET_KEY` and
+//! `LANGFUSE_STAGING_BASE_URL`, falling back to the `[staging]` section of
+//! `~/.langfuse/config`:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::profile("staging")?.build()?;
+//! # Ok(())
+//! # }
 //! ```
 //!
 //! Or configure explicitly:
@@ -134,7 +173,18 @@
 //!
 //! ## Feature Flags
 //!
-//! - `compression` - Enable gzip, brotli, and deflate compression for requests
+//! - `compression` - Enable gzip, brotli, and deflate response decompression, and gzip
+//!   compression of ingestion request bodies (see [`compression`])
+//! - `cost-guardrails` - Local cost tracking with anomaly events (see [`cost`])
+//! - `derive` - `#[derive(PromptVars)]` for checking prompt placeholders (see [`prompts`])
+//! - `json-schema` - Serde support and JSON Schemas (`schemars::JsonSchema`) for
+//!   `BatcherConfig`, `IngestionResponse`, `EventError`, `TraceResponse`, and `ScoreValue`
+//! - `loadgen` - Synthetic load generator for capacity testing (see [`loadgen`])
+//! - `openai` - Record calls of OpenAI-compatible clients as generations (see [`openai`])
+//! - `otel` - OpenTelemetry span exporter that forwards spans to Langfuse (see [`otel`])
+//! - `process` - Trace external commands as spans (see [`process`])
+//! - `signal` - Drain a batcher on SIGTERM or Ctrl-C (see [`shutdown`])
+//! - `testing` - In-memory recording client for asserting on telemetry in tests (see [`testing`])
 //!
 //! ## Examples
 //!
@@ -153,23 +203,138 @@
 #![warn(rustdoc::broken_intra_doc_links)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod ambient;
+pub mod auth;
 pub mod batcher;
 pub mod client;
+#[cfg(feature = "compression")]
+#[cfg_attr(docsrs, doc(cfg(feature = "compression")))]
+pub mod compression;
+pub mod context;
+#[cfg(feature = "cost-guardrails")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cost-guardrails")))]
+pub mod cost;
 pub mod datasets;
+pub mod debug_config;
+pub mod environment;
 pub mod error;
+pub mod experiments;
+pub mod export;
+pub mod http_log;
+pub mod interceptor;
+#[cfg(feature = "json-schema")]
+mod json_schema;
+pub mod latency;
+#[cfg(feature = "loadgen")]
+#[cfg_attr(docsrs, doc(cfg(feature = "loadgen")))]
+pub mod loadgen;
+pub mod media;
+pub mod messages;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+pub mod metrics_export;
+pub mod metrics_file;
 pub mod observations;
+#[cfg(feature = "openai")]
+#[cfg_attr(docsrs, doc(cfg(feature = "openai")))]
+pub mod openai;
+#[cfg(feature = "otel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "otel")))]
+pub mod otel;
+pub mod payload;
+pub mod payload_capture;
+pub mod policy;
+#[cfg(feature = "process")]
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+pub mod process;
+pub mod profiles;
+pub mod projects;
 pub mod prompts;
+pub mod query;
+pub mod queue;
+pub mod rate_limit;
+pub mod registry;
+pub mod rubric;
+pub mod sampling;
+pub mod schema;
 pub mod scores;
+pub mod security;
+#[cfg(feature = "signal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "signal")))]
+pub mod shutdown;
+pub mod stats;
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+pub mod traceparent;
+pub mod tracer;
 pub mod traces;
+mod transport;
+pub mod usage;
 
 // Re-export commonly used types at the crate root for convenience
+pub use ambient::{with_context, LangfuseContext};
+pub use auth::{AuthProvider, BasicAuth, BearerToken};
 pub use batcher::{
-    BackpressurePolicy, BatchEvent, Batcher, BatcherBuilderWithClient, BatcherConfig,
-    BatcherMetrics, BatcherMetricsSnapshot,
+    AdaptiveFlush, BackpressurePolicy, BatchEvent, Batcher, BatcherBuilderWithClient,
+    BatcherConfig, BatcherHandle, BatcherMetrics, BatcherMetricsSnapshot, CircuitBreaker,
+    CircuitCallback, CircuitEvent, DeliveryResult, DrainReport, PendingAgeBuckets,
+    PendingEventsSummary,
 };
-pub use client::{ClientBuilder, LangfuseClient};
-pub use error::{Error, EventError, IngestionResponse, Result};
-pub use traces::{IdGenerator, TraceResponse};
+pub use client::{ClientBuilder, ClientOverrides, LangfuseClient};
+pub use context::{ObservationHandle, ObservationKind, TraceContext};
+pub use datasets::{
+    DatasetExport, DatasetImport, DatasetItemLink, DatasetItemStream, ImportProgress,
+    ImportSummary, RowFailure,
+};
+pub use debug_config::{BatcherDebugConfig, ClientDebugConfig};
+pub use environment::Environment;
+pub use error::{Error, EventError, IngestionResponse, Result, ValidationDetail};
+pub use experiments::{ExperimentItemResult, ExperimentOutput, ExperimentResult, ExperimentScore};
+pub use export::{ExportFormat, ExportSummary, TraceExport};
+pub use http_log::{ApiRequest, ApiResponse};
+pub use interceptor::BatchInterceptor;
+pub use latency::{ApiCallTiming, EndpointLatency};
+pub use media::{MediaField, MediaReference, MediaSource};
+pub use metrics_file::{MetricsFile, MetricsFileFormat};
+pub use observations::{
+    GenerationRetries, GenerationTimer, RecordedStream, Stopwatch, StreamingGeneration,
+    TimedGeneration,
+};
+pub use payload::{FieldSizeLimit, FieldSizePolicy, TextLengthLimits, TextLengthPolicy};
+pub use payload_capture::PayloadCapture;
+pub use policy::{NamingMode, NamingPolicy, TraceRequirementPolicy, TraceRequirements};
+pub use prompts::{PromptPlaceholders, PromptVars};
+pub use query::{ObservationField, OrderBy, SortDirection, TraceField, TraceFilter};
+pub use queue::{FileQueue, QueueBackend};
+pub use rate_limit::RateLimit;
+pub use registry::{flush_all, shutdown_all};
+pub use rubric::{CriterionScore, Evaluator, RubricCriterion, RubricEvaluation};
+pub use sampling::Sampler;
+pub use schema::{SchemaUpgrader, SchemaVersion};
+pub use scores::{ScoreInput, ScoreTransport, ScoreValue};
+pub use security::Redactor;
+pub use stats::{TraceStats, TraceStatsFilter};
+pub use traceparent::Traceparent;
+pub use tracer::LangfuseTracer;
+pub use traces::{
+    BatchedEventBuilder, BatchedGenerationBuilder, BatchedScoreBuilder, BatchedSpanBuilder,
+    BatchedTraceBuilder, FinishedTrace, IdGenerator, IntoTags, ObservationResponse, TraceResponse,
+};
+pub use usage::Usage;
+
+#[cfg(feature = "process")]
+#[cfg_attr(docsrs, doc(cfg(feature = "process")))]
+pub use process::CommandRun;
+
+#[cfg(feature = "signal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "signal")))]
+pub use shutdown::install_shutdown_hook;
+
+/// Derive [`PromptVars`] for a struct with named fields
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use langfuse_ergonomic_derive::PromptVars;
 
 // Re-export types from langfuse-client-base for convenience
 //
@@ -184,17 +349,24 @@ pub use traces::{IdGenerator, TraceResponse};
 //
 // Type categories:
 // - Trace types: Trace, TraceBody, TraceWithDetails, TraceWithFullDetails, Traces
-// - Observation types: ObservationsView, ObservationsViews, ObservationLevel
+// - Observation types: ObservationsView, ObservationsViews, ObservationsV2Response,
+//                      ObservationLevel
 // - Dataset types: Dataset, DatasetItem, DatasetRunWithItems, PaginatedDatasets,
 //                  PaginatedDatasetItems, PaginatedDatasetRuns
 // - Prompt types: Prompt, PromptMetaListResponse
+// - Session types: PaginatedSessions, Session, SessionWithTraces
+// - Score types: GetScoresResponse, GetScoresResponseData, Score
+// - Model types: Model, ModelUsageUnit, PaginatedModels, PricingTierInput
 // - Event/Ingestion types: CreateEventBody, CreateGenerationBody, CreateSpanBody,
 //                          IngestionEvent, IngestionBatchRequest
+// - Project types: ApiKeyResponse, ApiKeySummary, Project
 // - Utility types: ScoreDataType
 pub use langfuse_client_base::models::{
-    CreateEventBody, CreateGenerationBody, CreateSpanBody, Dataset, DatasetItem,
-    DatasetRunWithItems, IngestionBatchRequest, IngestionEvent, LegacyObservationsViews,
-    ObservationLevel, ObservationsView, PaginatedDatasetItems, PaginatedDatasetRuns,
-    PaginatedDatasets, Prompt, PromptMetaListResponse, ScoreDataType, Trace, TraceBody,
-    TraceWithDetails, TraceWithFullDetails, Traces,
+    ApiKeyResponse, ApiKeySummary, CreateEventBody, CreateGenerationBody, CreateSpanBody, Dataset,
+    DatasetItem, DatasetRunWithItems, GetScoresResponse, GetScoresResponseData,
+    IngestionBatchRequest, IngestionEvent, LegacyObservationsViews, Model, ModelUsageUnit,
+    ObservationLevel, ObservationsV2Response, ObservationsView, PaginatedDatasetItems,
+    PaginatedDatasetRuns, PaginatedDatasets, PaginatedModels, PaginatedSessions, PricingTierInput,
+    Project, Prompt, PromptMetaListResponse, ScoreDataType, Session, SessionWithTraces, Trace,
+    TraceBody, TraceWithDetails, TraceWithFullDetails, Traces,
 };
@@ -0,0 +1,444 @@
+//! Synthetic load generation for capacity testing
+//!
+//! Generates a configurable mix of traces, spans, and generations at a target
+//! rate against a [`LangfuseClient`] or a [`Batcher`], and reports achieved
+//! throughput, latency percentiles, and error rates. Useful for sizing
+//! self-hosted Langfuse deployments.
+//!
+//! Requires the `loadgen` feature.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use langfuse_ergonomic::loadgen::{EventMix, LoadProfile, LoadGenerator};
+//! use langfuse_ergonomic::ClientBuilder;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//!
+//! let profile = LoadProfile {
+//!     events_per_second: 200,
+//!     duration: Duration::from_secs(30),
+//!     mix: EventMix { traces: 1, spans: 3, generations: 2 },
+//!     ..LoadProfile::default()
+//! };
+//!
+//! let report = LoadGenerator::new(profile).run_against_client(&client).await;
+//! println!("{report}");
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::Utc;
+use rand::{rng, RngExt};
+use serde_json::{json, Value};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::batcher::Batcher;
+use crate::client::LangfuseClient;
+use crate::latency::percentile;
+use langfuse_client_base::models::{
+    ingestion_event_one_of::Type as TraceEventType,
+    ingestion_event_one_of_2::Type as SpanEventType,
+    ingestion_event_one_of_4::Type as GenerationEventType, CreateGenerationBody, CreateSpanBody,
+    IngestionEvent, IngestionEventOneOf, IngestionEventOneOf2, IngestionEventOneOf4, TraceBody,
+};
+
+/// Relative weights of the event kinds to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventMix {
+    /// Weight of trace events
+    pub traces: u32,
+    /// Weight of span events
+    pub spans: u32,
+    /// Weight of generation events
+    pub generations: u32,
+}
+
+impl Default for EventMix {
+    fn default() -> Self {
+        Self {
+            traces: 1,
+            spans: 2,
+            generations: 1,
+        }
+    }
+}
+
+/// Kind of event produced by the load generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// A trace-create event
+    Trace,
+    /// A span-create event
+    Span,
+    /// A generation-create event
+    Generation,
+}
+
+impl EventMix {
+    fn pick(&self, roll: u32) -> EventKind {
+        if roll < self.traces {
+            EventKind::Trace
+        } else if roll < self.traces + self.spans {
+            EventKind::Span
+        } else {
+            EventKind::Generation
+        }
+    }
+
+    fn total(&self) -> u32 {
+        self.traces + self.spans + self.generations
+    }
+}
+
+/// Shape of the generated load
+#[derive(Debug, Clone)]
+pub struct LoadProfile {
+    /// Target number of events per second
+    pub events_per_second: u32,
+    /// How long to generate load for
+    pub duration: Duration,
+    /// Relative weights of traces, spans, and generations
+    pub mix: EventMix,
+    /// Minimum size of the generated input/output payloads in bytes
+    pub min_payload_bytes: usize,
+    /// Maximum size of the generated input/output payloads in bytes
+    pub max_payload_bytes: usize,
+    /// Maximum number of requests in flight at once
+    pub max_in_flight: usize,
+}
+
+impl Default for LoadProfile {
+    fn default() -> Self {
+        Self {
+            events_per_second: 50,
+            duration: Duration::from_secs(10),
+            mix: EventMix::default(),
+            min_payload_bytes: 128,
+            max_payload_bytes: 4_096,
+            max_in_flight: 64,
+        }
+    }
+}
+
+/// Results of a load generation run
+#[derive(Debug, Clone)]
+pub struct LoadReport {
+    /// Number of events attempted
+    pub attempted: u64,
+    /// Number of events that succeeded
+    pub succeeded: u64,
+    /// Number of events that failed
+    pub failed: u64,
+    /// Wall-clock time of the run
+    pub elapsed: Duration,
+    /// Median per-event latency
+    pub p50: Duration,
+    /// 95th percentile per-event latency
+    pub p95: Duration,
+    /// 99th percentile per-event latency
+    pub p99: Duration,
+    /// Maximum per-event latency
+    pub max: Duration,
+}
+
+impl LoadReport {
+    /// Achieved throughput in events per second
+    pub fn throughput(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            0.0
+        } else {
+            self.attempted as f64 / secs
+        }
+    }
+
+    /// Fraction of attempted events that failed
+    pub fn error_rate(&self) -> f64 {
+        if self.attempted == 0 {
+            0.0
+        } else {
+            self.failed as f64 / self.attempted as f64
+        }
+    }
+
+    fn from_samples(mut latencies: Vec<Duration>, failed: u64, elapsed: Duration) -> Self {
+        latencies.sort_unstable();
+        let attempted = latencies.len() as u64;
+        Self {
+            attempted,
+            succeeded: attempted.saturating_sub(failed),
+            failed,
+            elapsed,
+            p50: percentile(&latencies, 0.50),
+            p95: percentile(&latencies, 0.95),
+            p99: percentile(&latencies, 0.99),
+            max: latencies.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} events in {:.2?} ({:.1} ev/s), errors: {} ({:.2}%), latency p50: {:.2?}, p95: {:.2?}, p99: {:.2?}, max: {:.2?}",
+            self.attempted,
+            self.elapsed,
+            self.throughput(),
+            self.failed,
+            self.error_rate() * 100.0,
+            self.p50,
+            self.p95,
+            self.p99,
+            self.max
+        )
+    }
+}
+
+/// Drives synthetic load against a client or batcher
+#[derive(Debug, Clone)]
+pub struct LoadGenerator {
+    profile: LoadProfile,
+}
+
+impl LoadGenerator {
+    /// Create a load generator for the given profile
+    pub fn new(profile: LoadProfile) -> Self {
+        Self { profile }
+    }
+
+    /// Send events one request at a time through the client's builder APIs
+    ///
+    /// Latency is measured per request, including the network round trip.
+    pub async fn run_against_client(&self, client: &LangfuseClient) -> LoadReport {
+        let client = Arc::new(client.clone());
+        let semaphore = Arc::new(Semaphore::new(self.profile.max_in_flight.max(1)));
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = JoinSet::new();
+        let mut failed = 0;
+
+        let started = Instant::now();
+        let mut ticker = self.ticker();
+        let trace_id = Uuid::new_v4().to_string();
+
+        while started.elapsed() < self.profile.duration {
+            ticker.tick().await;
+            let kind = self.next_kind();
+            let payload = self.payload();
+
+            let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                break;
+            };
+            let client = client.clone();
+            let samples = samples.clone();
+            let trace_id = trace_id.clone();
+
+            tasks.spawn(async move {
+                let _permit = permit;
+                let start = Instant::now();
+                let result = match kind {
+                    EventKind::Trace => client
+                        .trace()
+                        .name("loadgen-trace")
+                        .input(payload)
+                        .call()
+                        .await
+                        .map(|_| ()),
+                    EventKind::Span => client
+                        .span()
+                        .trace_id(trace_id)
+                        .name("loadgen-span")
+                        .input(payload)
+                        .call()
+                        .await
+                        .map(|_| ()),
+                    EventKind::Generation => client
+                        .generation()
+                        .trace_id(trace_id)
+                        .name("loadgen-generation")
+                        .model("loadgen-model")
+                        .output(payload)
+                        .call()
+                        .await
+                        .map(|_| ()),
+                };
+                samples.lock().await.push(start.elapsed());
+                result.is_ok()
+            });
+        }
+
+        while let Some(result) = tasks.join_next().await {
+            if !matches!(result, Ok(true)) {
+                failed += 1;
+            }
+        }
+
+        let latencies = std::mem::take(&mut *samples.lock().await);
+        LoadReport::from_samples(latencies, failed, started.elapsed())
+    }
+
+    /// Enqueue events into a batcher and flush at the end of the run
+    ///
+    /// Latency is measured for each `add()` call, which reflects queueing and
+    /// backpressure rather than network time. Failures include events rejected
+    /// on enqueue and events the final flush reported as failed.
+    pub async fn run_against_batcher(&self, batcher: &Batcher) -> LoadReport {
+        let mut latencies = Vec::new();
+        let mut failed = 0;
+
+        let started = Instant::now();
+        let mut ticker = self.ticker();
+        let trace_id = Uuid::new_v4().to_string();
+
+        while started.elapsed() < self.profile.duration {
+            ticker.tick().await;
+            let event = build_event(self.next_kind(), &trace_id, self.payload());
+
+            let start = Instant::now();
+            if batcher.add(event).await.is_err() {
+                failed += 1;
+            }
+            latencies.push(start.elapsed());
+        }
+
+        match batcher.flush().await {
+            Ok(response) => failed += response.failure_count as u64,
+            Err(_) => failed = latencies.len() as u64,
+        }
+
+        let failed = failed.min(latencies.len() as u64);
+        LoadReport::from_samples(latencies, failed, started.elapsed())
+    }
+
+    fn ticker(&self) -> tokio::time::Interval {
+        let rate = self.profile.events_per_second.max(1);
+        let mut ticker = tokio::time::interval(Duration::from_secs(1) / rate);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Burst);
+        ticker
+    }
+
+    fn next_kind(&self) -> EventKind {
+        let total = self.profile.mix.total();
+        if total == 0 {
+            return EventKind::Trace;
+        }
+        self.profile.mix.pick(rng().random_range(0..total))
+    }
+
+    fn payload(&self) -> Value {
+        let min = self.profile.min_payload_bytes;
+        let max = self.profile.max_payload_bytes.max(min);
+        let size = rng().random_range(min..=max);
+        json!({ "data": "x".repeat(size) })
+    }
+}
+
+/// Construct an ingestion event of the given kind for batcher-based runs
+fn build_event(kind: EventKind, trace_id: &str, payload: Value) -> IngestionEvent {
+    let timestamp = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let id = Uuid::new_v4().to_string();
+
+    match kind {
+        EventKind::Trace => IngestionEvent::IngestionEventOneOf(Box::new(IngestionEventOneOf {
+            id: id.clone(),
+            timestamp: timestamp.clone(),
+            r#type: TraceEventType::TraceCreate,
+            body: Box::new(TraceBody {
+                id: Some(Some(id)),
+                timestamp: Some(Some(timestamp)),
+                name: Some(Some("loadgen-trace".to_string())),
+                input: Some(Some(payload)),
+                ..Default::default()
+            }),
+            metadata: None,
+        })),
+        EventKind::Span => IngestionEvent::IngestionEventOneOf2(Box::new(IngestionEventOneOf2 {
+            id: id.clone(),
+            timestamp: timestamp.clone(),
+            r#type: SpanEventType::SpanCreate,
+            body: Box::new(CreateSpanBody {
+                id: Some(Some(id)),
+                trace_id: Some(Some(trace_id.to_string())),
+                start_time: Some(Some(timestamp)),
+                name: Some(Some("loadgen-span".to_string())),
+                input: Some(Some(payload)),
+                ..Default::default()
+            }),
+            metadata: None,
+        })),
+        EventKind::Generation => {
+            IngestionEvent::IngestionEventOneOf4(Box::new(IngestionEventOneOf4 {
+                id: id.clone(),
+                timestamp: timestamp.clone(),
+                r#type: GenerationEventType::GenerationCreate,
+                body: Box::new(CreateGenerationBody {
+                    id: Some(Some(id)),
+                    trace_id: Some(Some(trace_id.to_string())),
+                    start_time: Some(Some(timestamp)),
+                    name: Some(Some("loadgen-generation".to_string())),
+                    model: Some(Some("loadgen-model".to_string())),
+                    output: Some(Some(payload)),
+                    ..Default::default()
+                }),
+                metadata: None,
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_mix_pick() {
+        let mix = EventMix {
+            traces: 1,
+            spans: 2,
+            generations: 1,
+        };
+        assert_eq!(mix.pick(0), EventKind::Trace);
+        assert_eq!(mix.pick(1), EventKind::Span);
+        assert_eq!(mix.pick(2), EventKind::Span);
+        assert_eq!(mix.pick(3), EventKind::Generation);
+    }
+
+    #[test]
+    fn test_report_percentiles() {
+        let latencies = (1..=100).map(Duration::from_millis).collect();
+        let report = LoadReport::from_samples(latencies, 5, Duration::from_secs(2));
+
+        assert_eq!(report.attempted, 100);
+        assert_eq!(report.succeeded, 95);
+        assert_eq!(report.p50, Duration::from_millis(50));
+        assert_eq!(report.p95, Duration::from_millis(95));
+        assert_eq!(report.p99, Duration::from_millis(99));
+        assert_eq!(report.max, Duration::from_millis(100));
+        assert!((report.throughput() - 50.0).abs() < f64::EPSILON);
+        assert!((report.error_rate() - 0.05).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = LoadReport::from_samples(Vec::new(), 0, Duration::ZERO);
+        assert_eq!(report.attempted, 0);
+        assert_eq!(report.p99, Duration::ZERO);
+        assert_eq!(report.throughput(), 0.0);
+        assert_eq!(report.error_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_failures_without_samples() {
+        let report = LoadReport::from_samples(Vec::new(), 3, Duration::from_secs(1));
+        assert_eq!(report.attempted, 0);
+        assert_eq!(report.succeeded, 0);
+    }
+}
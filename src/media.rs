@@ -0,0 +1,339 @@
+//! Media attachments for multimodal traces
+//!
+//! [`LangfuseClient::upload_media`](crate::LangfuseClient::upload_media)
+//! uploads an image, audio clip, or document to Langfuse's media storage and
+//! returns a [`MediaReference`]. Its [token](MediaReference::token) can be
+//! placed anywhere inside a trace or observation's input, output, or metadata,
+//! and the Langfuse UI renders the media in its place.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::media::MediaField;
+//! use serde_json::json;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let image = client
+//!     .upload_media()
+//!     .trace_id("trace-1")
+//!     .field(MediaField::Input)
+//!     .path("screenshots/cart.png")
+//!     .call()
+//!     .await?;
+//!
+//! client
+//!     .span()
+//!     .trace_id("trace-1")
+//!     .name("describe-image")
+//!     .input(json!({"prompt": "What is in this image?", "image": image.token()}))
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Content can be given as raw bytes, a file path, or a base64 data URI. The
+//! content type is taken from the data URI or the file extension unless set
+//! explicitly. Content that Langfuse already stores (same SHA-256 hash) is not
+//! uploaded again.
+//!
+//! With [`ClientBuilder::media_offload_threshold`](crate::ClientBuilder::media_offload_threshold),
+//! input and output values whose serialized JSON exceeds the threshold are
+//! uploaded this way automatically and replaced with their token, which keeps
+//! ingestion batches under the size limit without truncating payloads.
+//! [`FieldSizePolicy::Externalize`](crate::FieldSizePolicy::Externalize)
+//! does the same for values above
+//! [`ClientBuilder::max_field_bytes`](crate::ClientBuilder::max_field_bytes).
+//! Values are uploaded only once the event has passed validation and
+//! sampling, right before it is sent.
+
+use std::fmt;
+use std::path::Path;
+
+use base64::Engine as _;
+use langfuse_client_base::models::MediaContentType;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// Trace or observation field a media attachment belongs to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaField {
+    /// The input of the trace or observation (default)
+    #[default]
+    Input,
+    /// The output of the trace or observation
+    Output,
+    /// The metadata of the trace or observation
+    Metadata,
+}
+
+impl MediaField {
+    /// Field name as used by the API
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Output => "output",
+            Self::Metadata => "metadata",
+        }
+    }
+}
+
+/// Where uploaded media content came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaSource {
+    /// Raw bytes
+    Bytes,
+    /// A file on disk
+    File,
+    /// A base64 data URI
+    Base64DataUri,
+}
+
+impl MediaSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Bytes => "bytes",
+            Self::File => "file",
+            Self::Base64DataUri => "base64_data_uri",
+        }
+    }
+}
+
+/// Uploaded media, referenced from trace and observation payloads by its token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaReference {
+    /// Langfuse identifier of the media record
+    pub media_id: String,
+    /// MIME type of the content
+    pub content_type: String,
+    /// Where the content came from
+    pub source: MediaSource,
+    /// Size of the content in bytes
+    pub content_length: usize,
+    /// Whether the content was uploaded, as opposed to already stored by Langfuse
+    pub uploaded: bool,
+}
+
+impl MediaReference {
+    /// Token to place in input, output, or metadata where the media belongs
+    pub fn token(&self) -> String {
+        format!(
+            "@@@langfuseMedia:type={}|id={}|source={}@@@",
+            self.content_type,
+            self.media_id,
+            self.source.as_str()
+        )
+    }
+}
+
+/// Whether `value` is a media token, as produced by [`MediaReference::token`]
+pub(crate) fn is_media_token(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|text| text.starts_with("@@@langfuseMedia:") && text.ends_with("@@@"))
+}
+
+impl fmt::Display for MediaReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.token())
+    }
+}
+
+/// Media content ready for upload
+pub(crate) struct MediaPayload {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) mime: String,
+    pub(crate) content_type: MediaContentType,
+    pub(crate) source: MediaSource,
+}
+
+impl MediaPayload {
+    /// Load content from exactly one of `bytes`, `path`, or `data_uri`
+    pub(crate) async fn load(
+        bytes: Option<Vec<u8>>,
+        path: Option<&Path>,
+        data_uri: Option<&str>,
+        content_type: Option<String>,
+    ) -> Result<Self> {
+        let (bytes, detected, source) = match (bytes, path, data_uri) {
+            (Some(bytes), None, None) => (bytes, None, MediaSource::Bytes),
+            (None, Some(path), None) => {
+                let bytes = tokio::fs::read(path).await.map_err(|e| {
+                    Error::Validation(format!("Failed to read media file {}: {e}", path.display()))
+                })?;
+                let detected = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(mime_for_extension)
+                    .map(str::to_string);
+                (bytes, detected, MediaSource::File)
+            }
+            (None, None, Some(uri)) => {
+                let (mime, bytes) = parse_data_uri(uri)?;
+                (bytes, Some(mime), MediaSource::Base64DataUri)
+            }
+            _ => {
+                return Err(Error::Validation(
+                    "Media needs exactly one of bytes, path, or data_uri".to_string(),
+                ))
+            }
+        };
+
+        let mime = content_type.or(detected).ok_or_else(|| {
+            Error::Validation(
+                "Media content type could not be detected; set it with content_type".to_string(),
+            )
+        })?;
+        let content_type = parse_content_type(&mime)?;
+
+        Ok(Self {
+            bytes,
+            mime,
+            content_type,
+            source,
+        })
+    }
+
+    /// Base64-encoded SHA-256 hash of the content, as expected by the API
+    pub(crate) fn sha256(&self) -> String {
+        let digest = Sha256::digest(&self.bytes);
+        base64::engine::general_purpose::STANDARD.encode(digest)
+    }
+}
+
+/// Split a `data:<mime>;base64,<data>` URI into its MIME type and decoded bytes
+fn parse_data_uri(uri: &str) -> Result<(String, Vec<u8>)> {
+    let invalid =
+        || Error::Validation("Media data URI must look like data:<type>;base64,<data>".to_string());
+    let (header, data) = uri
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+        .ok_or_else(invalid)?;
+    let mime = header.strip_suffix(";base64").ok_or_else(invalid)?;
+    if mime.is_empty() {
+        return Err(invalid());
+    }
+
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| Error::Validation(format!("Invalid base64 in media data URI: {e}")))?;
+    Ok((mime.to_string(), bytes))
+}
+
+/// MIME type of common media file extensions
+fn mime_for_extension(extension: &str) -> Option<&'static str> {
+    let mime = match extension.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "tif" | "tiff" => "image/tiff",
+        "bmp" => "image/bmp",
+        "avif" => "image/avif",
+        "heic" => "image/heic",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" | "oga" => "audio/ogg",
+        "aac" => "audio/aac",
+        "m4a" => "audio/mp4",
+        "flac" => "audio/flac",
+        "opus" => "audio/opus",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Content type supported by Langfuse for a MIME type
+fn parse_content_type(mime: &str) -> Result<MediaContentType> {
+    serde_json::from_value(serde_json::Value::String(mime.to_string()))
+        .map_err(|_| Error::Validation(format!("Unsupported media content type `{mime}`")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_load_data_uri() {
+        let payload = MediaPayload::load(None, None, Some("data:image/png;base64,aGVsbG8="), None)
+            .await
+            .unwrap();
+        assert_eq!(payload.bytes, b"hello");
+        assert_eq!(payload.mime, "image/png");
+        assert_eq!(payload.content_type, MediaContentType::ImageSlashPng);
+        assert_eq!(payload.source, MediaSource::Base64DataUri);
+    }
+
+    #[tokio::test]
+    async fn test_load_requires_one_source_and_a_content_type() {
+        let both = MediaPayload::load(
+            Some(vec![1]),
+            None,
+            Some("data:image/png;base64,AA=="),
+            None,
+        );
+        assert!(matches!(both.await, Err(Error::Validation(_))));
+
+        let untyped = MediaPayload::load(Some(vec![1]), None, None, None);
+        assert!(matches!(untyped.await, Err(Error::Validation(_))));
+
+        let unsupported = MediaPayload::load(
+            Some(vec![1]),
+            None,
+            None,
+            Some("image/x-unknown".to_string()),
+        );
+        assert!(matches!(unsupported.await, Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn test_invalid_data_uri() {
+        assert!(parse_data_uri("image/png;base64,AA==").is_err());
+        assert!(parse_data_uri("data:image/png,AA==").is_err());
+        assert!(parse_data_uri("data:image/png;base64,not base64!").is_err());
+    }
+
+    #[test]
+    fn test_sha256_is_base64() {
+        let payload = MediaPayload {
+            bytes: Vec::new(),
+            mime: "text/plain".to_string(),
+            content_type: MediaContentType::TextSlashPlain,
+            source: MediaSource::Bytes,
+        };
+        assert_eq!(
+            payload.sha256(),
+            "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="
+        );
+    }
+
+    #[test]
+    fn test_token() {
+        let reference = MediaReference {
+            media_id: "media-1".to_string(),
+            content_type: "image/png".to_string(),
+            source: MediaSource::File,
+            content_length: 5,
+            uploaded: true,
+        };
+        assert_eq!(
+            reference.token(),
+            "@@@langfuseMedia:type=image/png|id=media-1|source=file@@@"
+        );
+        assert_eq!(reference.to_string(), reference.token());
+    }
+}
@@ -0,0 +1,348 @@
+//! Conversion of LLM chat messages into Langfuse formats
+//!
+//! Every call site that records a chat completion has to map the provider's
+//! messages to a generation input or a chat prompt. [`Message`] is a
+//! provider-neutral chat message that converts from the OpenAI and Anthropic
+//! message formats, as JSON or as serialized by the Rust crates for those
+//! APIs, and into both:
+//!
+//! ```
+//! use langfuse_ergonomic::messages::{from_anthropic, generation_input, Message};
+//! use serde_json::json;
+//!
+//! # fn main() -> langfuse_ergonomic::Result<()> {
+//! let messages = from_anthropic(
+//!     Some(&json!("You are terse.")),
+//!     &json!([
+//!         {"role": "user", "content": "Weather in Paris?"},
+//!         {"role": "assistant", "content": [
+//!             {"type": "tool_use", "id": "call-1", "name": "weather", "input": {"city": "Paris"}}
+//!         ]},
+//!         {"role": "user", "content": [
+//!             {"type": "tool_result", "tool_use_id": "call-1", "content": "18°C"}
+//!         ]},
+//!     ]),
+//! )?;
+//! assert_eq!(messages[0], Message::system("You are terse."));
+//! assert_eq!(messages[3], Message::tool("call-1", "18°C"));
+//!
+//! // Ready for `.input(...)` on a generation
+//! let input = generation_input(&messages);
+//! assert_eq!(input[2]["tool_calls"][0]["function"]["name"], "weather");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Generation inputs use the OpenAI chat format, which the Langfuse UI renders
+//! as a conversation, whatever the provider: `role` and `content`, plus
+//! `name`, `tool_call_id`, and `tool_calls` when set. Content is kept as text;
+//! non-text parts such as images are dropped. Messages also convert into
+//! [`ChatMessageWithPlaceholders`] for chat prompts, where tool calls have no
+//! representation and are left out. Any `(role, content)` pair converts into a
+//! [`Message`] too, covering crates with their own message structs.
+
+use langfuse_client_base::models::{ChatMessage, ChatMessageWithPlaceholders};
+use serde_json::{json, Map, Value};
+
+use crate::error::{Error, Result};
+
+/// Tool call requested by an assistant message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCall {
+    /// ID the tool's result refers to
+    pub id: String,
+    /// Name of the tool
+    pub name: String,
+    /// Arguments as a JSON string
+    pub arguments: String,
+}
+
+/// Chat message in a provider-neutral form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// `system`, `user`, `assistant`, or `tool`
+    pub role: String,
+    /// Text content
+    pub content: String,
+    /// Name of the participant, if any
+    pub name: Option<String>,
+    /// Tool call a `tool` message answers
+    pub tool_call_id: Option<String>,
+    /// Tool calls requested by an `assistant` message
+    pub tool_calls: Vec<ToolCall>,
+}
+
+impl Message {
+    /// Create a message with the given role and content
+    pub fn new(role: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            content: content.into(),
+            name: None,
+            tool_call_id: None,
+            tool_calls: Vec::new(),
+        }
+    }
+
+    /// Create a `system` message
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new("system", content)
+    }
+
+    /// Create a `user` message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new("user", content)
+    }
+
+    /// Create an `assistant` message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new("assistant", content)
+    }
+
+    /// Create a `tool` message with the result of a tool call
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new("tool", content)
+        }
+    }
+
+    /// Set the name of the participant
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Add a tool call with its arguments as a JSON string
+    #[must_use]
+    pub fn tool_call(
+        mut self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        arguments: impl Into<String>,
+    ) -> Self {
+        self.tool_calls.push(ToolCall {
+            id: id.into(),
+            name: name.into(),
+            arguments: arguments.into(),
+        });
+        self
+    }
+}
+
+impl<R: Into<String>, C: Into<String>> From<(R, C)> for Message {
+    fn from((role, content): (R, C)) -> Self {
+        Self::new(role, content)
+    }
+}
+
+impl From<&Message> for Value {
+    /// The message in the OpenAI chat format
+    fn from(message: &Message) -> Self {
+        let mut map = Map::new();
+        map.insert("role".to_string(), json!(message.role));
+        map.insert("content".to_string(), json!(message.content));
+        if let Some(name) = &message.name {
+            map.insert("name".to_string(), json!(name));
+        }
+        if let Some(id) = &message.tool_call_id {
+            map.insert("tool_call_id".to_string(), json!(id));
+        }
+        if !message.tool_calls.is_empty() {
+            let calls: Vec<Value> = message
+                .tool_calls
+                .iter()
+                .map(|call| {
+                    json!({
+                        "id": call.id,
+                        "type": "function",
+                        "function": {"name": call.name, "arguments": call.arguments},
+                    })
+                })
+                .collect();
+            map.insert("tool_calls".to_string(), Value::Array(calls));
+        }
+        Value::Object(map)
+    }
+}
+
+impl From<Message> for Value {
+    fn from(message: Message) -> Self {
+        Value::from(&message)
+    }
+}
+
+impl From<Message> for ChatMessageWithPlaceholders {
+    fn from(message: Message) -> Self {
+        ChatMessageWithPlaceholders::ChatMessage(Box::new(ChatMessage::new(
+            message.role,
+            message.content,
+        )))
+    }
+}
+
+/// Generation input for a conversation, in the OpenAI chat format
+pub fn generation_input(messages: &[Message]) -> Value {
+    Value::Array(messages.iter().map(Value::from).collect())
+}
+
+/// Read messages in the OpenAI chat completions format
+///
+/// `messages` is the `messages` array of a request. Text parts of array
+/// content are joined; tool calls and tool results are kept.
+pub fn from_openai(messages: &Value) -> Result<Vec<Message>> {
+    message_array(messages)?
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let mut converted = Message::new(role(index, message)?, text(&message["content"]));
+            converted.name = string_field(message, "name");
+            converted.tool_call_id = string_field(message, "tool_call_id");
+            if let Some(calls) = message["tool_calls"].as_array() {
+                for call in calls {
+                    let function = &call["function"];
+                    converted = converted.tool_call(
+                        string_field(call, "id").unwrap_or_default(),
+                        string_field(function, "name").unwrap_or_default(),
+                        arguments(&function["arguments"]),
+                    );
+                }
+            }
+            Ok(converted)
+        })
+        .collect()
+}
+
+/// Read messages in the Anthropic Messages API format
+///
+/// `system` is the request's system prompt, as a string or text blocks, and
+/// `messages` its `messages` array. `tool_use` blocks become tool calls of the
+/// assistant message, and `tool_result` blocks become `tool` messages ahead of
+/// the rest of the user message.
+pub fn from_anthropic(system: Option<&Value>, messages: &Value) -> Result<Vec<Message>> {
+    let mut converted = Vec::new();
+    if let Some(system) = system {
+        converted.push(Message::system(text(system)));
+    }
+
+    for (index, message) in message_array(messages)?.iter().enumerate() {
+        let role = role(index, message)?;
+        let Some(blocks) = message["content"].as_array() else {
+            converted.push(Message::new(role, text(&message["content"])));
+            continue;
+        };
+
+        let mut message = Message::new(role, text(&message["content"]));
+        let mut results = Vec::new();
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("tool_use") => {
+                    message = message.tool_call(
+                        string_field(block, "id").unwrap_or_default(),
+                        string_field(block, "name").unwrap_or_default(),
+                        arguments(&block["input"]),
+                    );
+                }
+                Some("tool_result") => results.push(Message::tool(
+                    string_field(block, "tool_use_id").unwrap_or_default(),
+                    text(&block["content"]),
+                )),
+                _ => {}
+            }
+        }
+
+        let has_results = !results.is_empty();
+        converted.extend(results);
+        if !has_results || !message.content.is_empty() || !message.tool_calls.is_empty() {
+            converted.push(message);
+        }
+    }
+    Ok(converted)
+}
+
+fn message_array(messages: &Value) -> Result<&Vec<Value>> {
+    messages
+        .as_array()
+        .ok_or_else(|| Error::Validation("Messages must be a JSON array".to_string()))
+}
+
+fn role(index: usize, message: &Value) -> Result<String> {
+    string_field(message, "role")
+        .ok_or_else(|| Error::Validation(format!("Message {index} has no role")))
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().map(str::to_string)
+}
+
+/// Text of string content or of the text parts of array content
+fn text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter(|part| part["type"] == "text")
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Tool call arguments as a JSON string
+fn arguments(value: &Value) -> String {
+    match value {
+        Value::String(arguments) => arguments.clone(),
+        Value::Null => "{}".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_openai() {
+        let messages = from_openai(&json!([
+            {"role": "system", "content": "Be brief."},
+            {"role": "user", "content": [
+                {"type": "text", "text": "What is this?"},
+                {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+            ]},
+            {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "call-1", "type": "function",
+                 "function": {"name": "classify", "arguments": "{\"kind\":\"image\"}"}},
+            ]},
+            {"role": "tool", "tool_call_id": "call-1", "content": "cat"},
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            messages,
+            vec![
+                Message::system("Be brief."),
+                Message::user("What is this?"),
+                Message::assistant("").tool_call("call-1", "classify", "{\"kind\":\"image\"}"),
+                Message::tool("call-1", "cat"),
+            ]
+        );
+        assert!(from_openai(&json!([{"content": "no role"}])).is_err());
+    }
+
+    #[test]
+    fn test_generation_input_and_prompt_messages() {
+        let messages = vec![Message::from(("user", "Hi")).name("jane")];
+        assert_eq!(
+            generation_input(&messages),
+            json!([{"role": "user", "content": "Hi", "name": "jane"}])
+        );
+
+        let prompt: ChatMessageWithPlaceholders = Message::assistant("Hello").into();
+        assert_eq!(
+            serde_json::to_value(prompt).unwrap(),
+            json!({"role": "assistant", "content": "Hello"})
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Batcher metrics through the [`metrics`] facade
+//!
+//! With the `metrics` feature, every [`Batcher`](crate::Batcher) reports its
+//! counters to whichever recorder the application installed, such as
+//! `metrics-exporter-prometheus`, so alerts on the health of the telemetry
+//! pipeline can live next to the application's own. Without a recorder,
+//! reporting costs next to nothing.
+//!
+//! | Metric | Type | Description |
+//! |---|---|---|
+//! | `langfuse_batcher_events_flushed_total` | counter | Events accepted by Langfuse |
+//! | `langfuse_batcher_events_failed_total` | counter | Events that failed for good |
+//! | `langfuse_batcher_events_dropped_total` | counter | Events dropped by backpressure |
+//! | `langfuse_batcher_events_deduplicated_total` | counter | Events skipped as duplicates |
+//! | `langfuse_batcher_retries_total` | counter | Retried batch requests |
+//! | `langfuse_batcher_circuit_opens_total` | counter | Times the circuit breaker opened |
+//! | `langfuse_batcher_payload_bytes_total` | counter | Bytes of events sent successfully |
+//! | `langfuse_batcher_queue_depth` | gauge | Events waiting to be sent, in the channel or the buffer |
+//! | `langfuse_batcher_circuit_open` | gauge | 1 while the circuit breaker is open |
+//! | `langfuse_batcher_flush_duration_seconds` | histogram | Time to send one batch, retries included |
+//! | `langfuse_batcher_batch_bytes` | histogram | Size of each batch sent |
+//!
+//! Counters and gauges mirror [`BatcherMetrics`] and are refreshed after every
+//! flush; the histograms are recorded for every batch request. Counters and
+//! gauges carry a `batcher` label with an ID unique within the process, so
+//! several batchers report separate series; sum over the label for totals.
+//! For a one-off snapshot without a recorder, see [`crate::metrics_file`].
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
+
+use metrics::{counter, gauge, histogram, Unit};
+
+use crate::batcher::BatcherMetrics;
+
+/// Events accepted by Langfuse
+pub const EVENTS_FLUSHED: &str = "langfuse_batcher_events_flushed_total";
+/// Events that failed for good
+pub const EVENTS_FAILED: &str = "langfuse_batcher_events_failed_total";
+/// Events dropped by backpressure
+pub const EVENTS_DROPPED: &str = "langfuse_batcher_events_dropped_total";
+/// Events skipped because their ID was added recently
+pub const EVENTS_DEDUPLICATED: &str = "langfuse_batcher_events_deduplicated_total";
+/// Retried batch requests
+pub const RETRIES: &str = "langfuse_batcher_retries_total";
+/// Times the circuit breaker opened
+pub const CIRCUIT_OPENS: &str = "langfuse_batcher_circuit_opens_total";
+/// Bytes of events sent successfully
+pub const PAYLOAD_BYTES: &str = "langfuse_batcher_payload_bytes_total";
+/// Events waiting to be sent
+pub const QUEUE_DEPTH: &str = "langfuse_batcher_queue_depth";
+/// 1 while the circuit breaker is open, 0 otherwise
+pub const CIRCUIT_OPEN: &str = "langfuse_batcher_circuit_open";
+/// Time to send one batch, retries included
+pub const FLUSH_DURATION: &str = "langfuse_batcher_flush_duration_seconds";
+/// Size of each batch sent
+pub const BATCH_BYTES: &str = "langfuse_batcher_batch_bytes";
+
+/// Describe the metrics to the installed recorder, once
+fn describe() {
+    static DESCRIBED: Once = Once::new();
+    DESCRIBED.call_once(|| {
+        metrics::describe_counter!(EVENTS_FLUSHED, "Events accepted by Langfuse");
+        metrics::describe_counter!(EVENTS_FAILED, "Events that failed for good");
+        metrics::describe_counter!(EVENTS_DROPPED, "Events dropped by backpressure");
+        metrics::describe_counter!(EVENTS_DEDUPLICATED, "Events skipped as duplicates");
+        metrics::describe_counter!(RETRIES, "Retried batch requests");
+        metrics::describe_counter!(CIRCUIT_OPENS, "Times the circuit breaker opened");
+        metrics::describe_counter!(
+            PAYLOAD_BYTES,
+            Unit::Bytes,
+            "Bytes of events sent successfully"
+        );
+        metrics::describe_gauge!(QUEUE_DEPTH, "Events waiting to be sent");
+        metrics::describe_gauge!(CIRCUIT_OPEN, "1 while the circuit breaker is open");
+        metrics::describe_histogram!(
+            FLUSH_DURATION,
+            Unit::Seconds,
+            "Time to send one batch, retries included"
+        );
+        metrics::describe_histogram!(BATCH_BYTES, Unit::Bytes, "Size of each batch sent");
+    });
+}
+
+/// Label identifying the batcher a series belongs to
+pub const BATCHER_LABEL: &str = "batcher";
+
+type ChannelDepth = Box<dyn Fn() -> usize + Send + Sync>;
+
+/// Per-batcher state of the export
+#[derive(Default)]
+pub(crate) struct ExportState {
+    /// Value of the `batcher` label
+    batcher: OnceLock<String>,
+    /// Events waiting in the batcher's channel
+    channel_depth: OnceLock<ChannelDepth>,
+    /// Counter values reported so far, in the order of [`COUNTERS`]
+    reported: [AtomicU64; COUNTERS.len()],
+}
+
+impl fmt::Debug for ExportState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExportState")
+            .field("batcher", &self.batcher.get())
+            .field("reported", &self.reported)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExportState {
+    /// Label the batcher's series with `id` and measure its channel with `channel_depth`
+    pub(crate) fn init(&self, id: u64, channel_depth: impl Fn() -> usize + Send + Sync + 'static) {
+        let _ = self.batcher.set(id.to_string());
+        let _ = self.channel_depth.set(Box::new(channel_depth));
+    }
+}
+
+/// Counters, in the order their reported values are kept
+const COUNTERS: [&str; 6] = [
+    EVENTS_FLUSHED,
+    EVENTS_FAILED,
+    EVENTS_DROPPED,
+    EVENTS_DEDUPLICATED,
+    RETRIES,
+    CIRCUIT_OPENS,
+];
+
+/// Report the batcher's counters and gauges
+///
+/// Counters are incremented by the change since the last report, so the
+/// series of several batchers add up instead of overwriting each other.
+pub(crate) fn publish(metrics: &BatcherMetrics) {
+    describe();
+    let snapshot = metrics.snapshot();
+    let export = &metrics.export;
+    let batcher = export.batcher.get().cloned().unwrap_or_default();
+
+    let totals = [
+        snapshot.flushed,
+        snapshot.failed,
+        snapshot.dropped,
+        snapshot.deduplicated,
+        snapshot.retries,
+        snapshot.circuit_opens,
+    ];
+    for ((name, reported), total) in COUNTERS.into_iter().zip(&export.reported).zip(totals) {
+        // Concurrent flushes may report out of order; only the newest total counts
+        let previous = reported.fetch_max(total, Ordering::Relaxed);
+        if total > previous {
+            counter!(name, BATCHER_LABEL => batcher.clone()).increment(total - previous);
+        }
+    }
+
+    let in_channel = export.channel_depth.get().map_or(0, |depth| depth());
+    gauge!(QUEUE_DEPTH, BATCHER_LABEL => batcher.clone())
+        .set((snapshot.queued + in_channel as u64) as f64);
+    gauge!(CIRCUIT_OPEN, BATCHER_LABEL => batcher).set(if snapshot.circuit_open {
+        1.0
+    } else {
+        0.0
+    });
+}
+
+/// Report one batch request
+pub(crate) fn record_batch(duration: Duration, bytes: usize, sent: bool) {
+    describe();
+    histogram!(FLUSH_DURATION).record(duration.as_secs_f64());
+    histogram!(BATCH_BYTES).record(bytes as f64);
+    if sent {
+        counter!(PAYLOAD_BYTES).increment(bytes as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use metrics::{
+        Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder,
+        SharedString,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Records the keys registered with it and the values reported
+    #[derive(Default)]
+    struct TestRecorder(Arc<Mutex<Vec<(String, String)>>>);
+
+    /// Appends every value it receives under its key
+    struct Sink {
+        key: String,
+        values: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    impl Sink {
+        fn push(&self, value: String) {
+            self.values.lock().unwrap().push((self.key.clone(), value));
+        }
+    }
+
+    impl CounterFn for Sink {
+        fn increment(&self, value: u64) {
+            self.push(format!("+{value}"));
+        }
+
+        fn absolute(&self, value: u64) {
+            self.push(format!("={value}"));
+        }
+    }
+
+    impl GaugeFn for Sink {
+        fn increment(&self, _: f64) {}
+        fn decrement(&self, _: f64) {}
+
+        fn set(&self, value: f64) {
+            self.push(format!("={value}"));
+        }
+    }
+
+    impl TestRecorder {
+        fn sink(&self, key: &Key) -> Arc<Sink> {
+            let labels: Vec<_> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let key = format!("{}{{{}}}", key.name(), labels.join(","));
+            self.0
+                .lock()
+                .unwrap()
+                .push((key.clone(), "registered".to_string()));
+            Arc::new(Sink {
+                key,
+                values: self.0.clone(),
+            })
+        }
+
+        fn take(&self) -> Vec<(String, String)> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.sink(key))
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.sink(key))
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            self.sink(key);
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_reports_all_metrics() {
+        let recorder = TestRecorder::default();
+        let metrics = BatcherMetrics::default();
+        for counter in [
+            &metrics.flushed,
+            &metrics.failed,
+            &metrics.dropped,
+            &metrics.deduplicated,
+            &metrics.retries,
+            &metrics.circuit_opens,
+        ] {
+            counter.store(1, Ordering::Relaxed);
+        }
+        metrics::with_local_recorder(&recorder, || {
+            publish(&metrics);
+            record_batch(Duration::from_millis(20), 512, true);
+        });
+
+        let mut names: Vec<_> = recorder
+            .take()
+            .into_iter()
+            .filter(|(_, value)| value == "registered")
+            .map(|(key, _)| key.split('{').next().unwrap().to_string())
+            .collect();
+        names.sort();
+        let mut expected = vec![
+            EVENTS_FLUSHED,
+            EVENTS_FAILED,
+            EVENTS_DROPPED,
+            EVENTS_DEDUPLICATED,
+            RETRIES,
+            CIRCUIT_OPENS,
+            PAYLOAD_BYTES,
+            QUEUE_DEPTH,
+            CIRCUIT_OPEN,
+            FLUSH_DURATION,
+            BATCH_BYTES,
+        ];
+        expected.sort_unstable();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_counters_report_increments_per_batcher() {
+        let recorder = TestRecorder::default();
+        let metrics = BatcherMetrics::default();
+        metrics.export.init(7, || 2);
+        metrics.queued.store(3, Ordering::Relaxed);
+
+        let reported = |recorder: &TestRecorder| -> Vec<(String, String)> {
+            recorder
+                .take()
+                .into_iter()
+                .filter(|(key, value)| {
+                    value != "registered"
+                        && (key.starts_with(EVENTS_FLUSHED) || key.starts_with(QUEUE_DEPTH))
+                })
+                .collect()
+        };
+
+        metrics::with_local_recorder(&recorder, || {
+            metrics.flushed.store(5, Ordering::Relaxed);
+            publish(&metrics);
+        });
+        assert_eq!(
+            reported(&recorder),
+            vec![
+                (format!("{EVENTS_FLUSHED}{{batcher=7}}"), "+5".to_string()),
+                // Buffered events plus those still in the channel
+                (format!("{QUEUE_DEPTH}{{batcher=7}}"), "=5".to_string()),
+            ]
+        );
+
+        metrics::with_local_recorder(&recorder, || {
+            metrics.flushed.store(8, Ordering::Relaxed);
+            publish(&metrics);
+            // Nothing new to report
+            publish(&metrics);
+        });
+        let flushed: Vec<_> = reported(&recorder)
+            .into_iter()
+            .filter(|(key, _)| key.starts_with(EVENTS_FLUSHED))
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(flushed, vec!["+3".to_string()]);
+    }
+}
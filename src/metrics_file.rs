@@ -0,0 +1,379 @@
+//! Final batcher metrics written to a file for post-run scraping
+//!
+//! Batch jobs exit before a metrics endpoint could be scraped, so
+//! [`Batcher::drain_to_file`](crate::Batcher::drain_to_file) drains the batcher
+//! and writes its final counters and API latency summaries to a
+//! [`MetricsFile`]: either a Prometheus textfile (for node_exporter's textfile
+//! collector) or a JSON summary.
+//!
+//! ```no_run
+//! use langfuse_ergonomic::metrics_file::MetricsFile;
+//! use langfuse_ergonomic::{Batcher, ClientBuilder};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! let batcher = Batcher::builder().client(client).build().await;
+//!
+//! // ... run the job ...
+//!
+//! let file = MetricsFile::new("/var/lib/node_exporter/textfile/langfuse.prom");
+//! batcher.drain_to_file(Duration::from_secs(10), &file).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The file is written to a temporary sibling first and renamed into place, so
+//! scrapers never see a partial file.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::batcher::{BatcherMetricsSnapshot, DrainReport};
+use crate::error::{Error, Result};
+use crate::latency::EndpointLatency;
+
+/// Environment variable naming the metrics file written by the shutdown hook
+pub const METRICS_FILE_ENV: &str = "LANGFUSE_METRICS_FILE";
+
+/// Format of a [`MetricsFile`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFileFormat {
+    /// Prometheus text exposition format
+    Prometheus,
+    /// JSON summary
+    Json,
+}
+
+/// Final metrics of a batcher, collected after draining it
+#[derive(Debug, Clone)]
+pub struct ShutdownMetrics {
+    /// Outcome of the drain
+    pub drain: DrainReport,
+    /// Batcher counters at the end of the drain
+    pub batcher: BatcherMetricsSnapshot,
+    /// API latency summaries of the batcher's client
+    pub latency: Vec<EndpointLatency>,
+}
+
+/// Destination for [`ShutdownMetrics`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsFile {
+    path: PathBuf,
+    format: MetricsFileFormat,
+}
+
+impl MetricsFile {
+    /// Write to `path`, as JSON if it ends in `.json` and as a Prometheus textfile otherwise
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let format = match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => MetricsFileFormat::Json,
+            _ => MetricsFileFormat::Prometheus,
+        };
+        Self { path, format }
+    }
+
+    /// Metrics file named by [`METRICS_FILE_ENV`], if set
+    pub fn from_env() -> Option<Self> {
+        std::env::var_os(METRICS_FILE_ENV)
+            .filter(|path| !path.is_empty())
+            .map(Self::new)
+    }
+
+    /// Override the format inferred from the file extension
+    #[must_use]
+    pub fn with_format(mut self, format: MetricsFileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Path the metrics are written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Format the metrics are written in
+    pub fn format(&self) -> MetricsFileFormat {
+        self.format
+    }
+
+    /// Render metrics in this file's format
+    pub fn render(&self, metrics: &ShutdownMetrics) -> String {
+        match self.format {
+            MetricsFileFormat::Prometheus => render_prometheus(metrics),
+            MetricsFileFormat::Json => render_json(metrics),
+        }
+    }
+
+    /// Atomically replace the file with the rendered metrics
+    pub fn write(&self, metrics: &ShutdownMetrics) -> Result<()> {
+        let contents = self.render(metrics);
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        let tmp = PathBuf::from(tmp);
+
+        std::fs::write(&tmp, contents)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| {
+                Error::Configuration(format!(
+                    "Failed to write metrics file {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+fn render_prometheus(metrics: &ShutdownMetrics) -> String {
+    let ShutdownMetrics {
+        drain,
+        batcher,
+        latency,
+    } = metrics;
+    let mut out = String::new();
+
+    let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {kind}");
+        let _ = writeln!(out, "{name} {value}");
+    };
+    metric(
+        "langfuse_batcher_events_flushed_total",
+        "counter",
+        "Events successfully flushed",
+        drain.flushed as f64,
+    );
+    metric(
+        "langfuse_batcher_events_failed_total",
+        "counter",
+        "Events that failed after all retries",
+        drain.failed as f64,
+    );
+    metric(
+        "langfuse_batcher_events_dropped_total",
+        "counter",
+        "Events dropped due to backpressure",
+        drain.dropped as f64,
+    );
+    metric(
+        "langfuse_batcher_retries_total",
+        "counter",
+        "Retry attempts made",
+        drain.retries as f64,
+    );
+    metric(
+        "langfuse_batcher_events_remaining",
+        "gauge",
+        "Events still pending when the drain finished",
+        drain.remaining as f64,
+    );
+    metric(
+        "langfuse_batcher_drain_duration_seconds",
+        "gauge",
+        "Time spent draining",
+        drain.elapsed_ms as f64 / 1000.0,
+    );
+    metric(
+        "langfuse_batcher_drain_timed_out",
+        "gauge",
+        "Whether the drain deadline expired",
+        if drain.timed_out { 1.0 } else { 0.0 },
+    );
+    metric(
+        "langfuse_batcher_queue_high_water",
+        "gauge",
+        "Highest number of events waiting at once",
+        batcher.queue_high_water as f64,
+    );
+    metric(
+        "langfuse_batcher_saturated_seconds_total",
+        "counter",
+        "Time the queue spent full",
+        batcher.saturated_ms as f64 / 1000.0,
+    );
+    metric(
+        "langfuse_batcher_max_wait_seconds",
+        "gauge",
+        "Longest time an event waited before being sent",
+        batcher.max_wait_ms as f64 / 1000.0,
+    );
+    metric(
+        "langfuse_batcher_circuit_opens_total",
+        "counter",
+        "Times the circuit breaker paused sends",
+        batcher.circuit_opens as f64,
+    );
+
+    if !latency.is_empty() {
+        out.push_str("# HELP langfuse_api_request_duration_seconds Latency of recent API calls\n");
+        out.push_str("# TYPE langfuse_api_request_duration_seconds summary\n");
+        for endpoint in latency {
+            let labels = endpoint_labels(endpoint);
+            for (quantile, value) in [("0.5", endpoint.p50), ("0.95", endpoint.p95)] {
+                let _ = writeln!(
+                    out,
+                    "langfuse_api_request_duration_seconds{{{labels},quantile=\"{quantile}\"}} {}",
+                    value.as_secs_f64()
+                );
+            }
+            let _ = writeln!(
+                out,
+                "langfuse_api_request_duration_seconds_count{{{labels}}} {}",
+                endpoint.count
+            );
+        }
+        out.push_str("# HELP langfuse_api_request_errors_total API calls that failed\n");
+        out.push_str("# TYPE langfuse_api_request_errors_total counter\n");
+        for endpoint in latency {
+            let _ = writeln!(
+                out,
+                "langfuse_api_request_errors_total{{{}}} {}",
+                endpoint_labels(endpoint),
+                endpoint.errors
+            );
+        }
+    }
+
+    out
+}
+
+fn endpoint_labels(endpoint: &EndpointLatency) -> String {
+    format!(
+        "method=\"{}\",endpoint=\"{}\"",
+        escape_label(&endpoint.method),
+        escape_label(&endpoint.endpoint)
+    )
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn render_json(metrics: &ShutdownMetrics) -> String {
+    let ShutdownMetrics {
+        drain,
+        batcher,
+        latency,
+    } = metrics;
+    let latency: Vec<_> = latency
+        .iter()
+        .map(|endpoint| {
+            json!({
+                "method": endpoint.method,
+                "endpoint": endpoint.endpoint,
+                "count": endpoint.count,
+                "errors": endpoint.errors,
+                "p50_ms": endpoint.p50.as_secs_f64() * 1000.0,
+                "p95_ms": endpoint.p95.as_secs_f64() * 1000.0,
+                "max_ms": endpoint.max.as_secs_f64() * 1000.0,
+            })
+        })
+        .collect();
+
+    let summary = json!({
+        "drain": drain,
+        "batcher": {
+            "queue_high_water": batcher.queue_high_water,
+            "saturated_ms": batcher.saturated_ms,
+            "max_wait_ms": batcher.max_wait_ms,
+            "circuit_opens": batcher.circuit_opens,
+        },
+        "latency": latency,
+    });
+    serde_json::to_string_pretty(&summary).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn metrics() -> ShutdownMetrics {
+        ShutdownMetrics {
+            drain: DrainReport {
+                flushed: 10,
+                failed: 1,
+                retries: 2,
+                elapsed_ms: 1500,
+                ..Default::default()
+            },
+            batcher: BatcherMetricsSnapshot {
+                queued: 0,
+                flushed: 10,
+                failed: 1,
+                dropped: 0,
+                retries: 2,
+                last_error_ts: 0,
+                queue_high_water: 7,
+                saturated_ms: 0,
+                max_wait_ms: 250,
+                flush_interval_ms: 5000,
+                circuit_opens: 0,
+                circuit_open: false,
+                deduplicated: 0,
+            },
+            latency: vec![EndpointLatency {
+                method: "POST".to_string(),
+                endpoint: "/api/public/ingestion".to_string(),
+                count: 3,
+                errors: 1,
+                p50: Duration::from_millis(20),
+                p95: Duration::from_millis(80),
+                max: Duration::from_millis(90),
+                last_status: Some(207),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            MetricsFile::new("out/langfuse.json").format(),
+            MetricsFileFormat::Json
+        );
+        assert_eq!(
+            MetricsFile::new("out/langfuse.prom").format(),
+            MetricsFileFormat::Prometheus
+        );
+    }
+
+    #[test]
+    fn test_render_prometheus() {
+        let text = MetricsFile::new("m.prom").render(&metrics());
+        assert!(text.contains("# TYPE langfuse_batcher_events_flushed_total counter\n"));
+        assert!(text.contains("langfuse_batcher_events_flushed_total 10\n"));
+        assert!(text.contains("langfuse_batcher_drain_duration_seconds 1.5\n"));
+        assert!(text.contains(
+            "langfuse_api_request_duration_seconds{method=\"POST\",endpoint=\"/api/public/ingestion\",quantile=\"0.95\"} 0.08\n"
+        ));
+        assert!(text.contains(
+            "langfuse_api_request_errors_total{method=\"POST\",endpoint=\"/api/public/ingestion\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let text = MetricsFile::new("m.json").render(&metrics());
+        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["drain"]["failed"], 1);
+        assert_eq!(value["batcher"]["max_wait_ms"], 250);
+        assert_eq!(value["latency"][0]["p50_ms"], 20.0);
+    }
+
+    #[test]
+    fn test_write_replaces_file() {
+        let path =
+            std::env::temp_dir().join(format!("langfuse-metrics-{}.prom", uuid::Uuid::new_v4()));
+        let file = MetricsFile::new(&path);
+        file.write(&metrics()).unwrap();
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert!(written.contains("langfuse_batcher_retries_total 2"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -3,8 +3,876 @@
 //! This module contains types and utilities for observations.
 //! The actual client methods are implemented in the traces module to
 //! consolidate all client methods under a single #[bon] impl block.
+//!
+//! ## Retried LLM calls
+//!
+//! [`GenerationRetries`] records every attempt of a retried LLM call as its own
+//! generation instead of only the final successful one. All attempts share the
+//! same trace and parent observation, and their metadata links them together:
+//!
+//! - `retry_group`: id of the first attempt, shared by every attempt
+//! - `attempt`: 1-based attempt number
+//! - `previous_error`: error of the preceding attempt, if any
+//!
+//! Failed attempts are recorded with level `ERROR` and the error as status message.
+//!
+//! ```rust,ignore
+//! let mut retries = client.generation_retries(&trace.id).name("chat").model("gpt-4");
+//! loop {
+//!     let start = chrono::Utc::now();
+//!     match call_llm(&request).await {
+//!         Ok(response) => {
+//!             retries.succeeded(start, Some(json!(request)), json!(response)).await?;
+//!             break;
+//!         }
+//!         Err(e) => retries.failed(start, Some(json!(request)), &e).await?,
+//!     };
+//! }
+//! ```
+//!
+//! ## Timing observations
+//!
+//! `.timed(|observation_id| async { ... })` on the span and generation builders
+//! runs an async block, sets the observation's start and end time around it,
+//! and returns the block's result together with the observation ID. The ID is
+//! passed to the block so nested observations can use it as their parent:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # async fn fetch_documents() -> Vec<String> { Vec::new() }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let (documents, span_id) = client
+//!     .span()
+//!     .trace_id("trace-1")
+//!     .name("retrieval")
+//!     .timed(|_span_id| fetch_documents())
+//!     .await;
+//! let span_id = span_id?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The block's result is returned even if recording the observation fails.
+//! For timing that does not fit in one block, [`Stopwatch`] measures with the
+//! monotonic clock and reports wall-clock start and end times for the builders.
+//!
+//! ## LLM call timeouts
+//!
+//! [`LangfuseClient::timed_generation`] races an LLM call against a timeout
+//! and records it as a generation either way. When the timeout expires, the
+//! call's future is dropped and the generation is recorded with level `ERROR`,
+//! a status message, and `"timeout": true` in its metadata, so timeouts look
+//! the same in every trace. The caller gets the timeout as an error:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::{ClientBuilder, TimedGeneration};
+//! # use std::time::Duration;
+//! # async fn call_llm() -> String { String::new() }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let generation = TimedGeneration::new("trace-1", Duration::from_secs(30))
+//!     .name("chat")
+//!     .model("gpt-4");
+//! let (response, generation_id) = client.timed_generation(generation, call_llm()).await;
+//! let generation_id = generation_id?;
+//! let response = response?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Generation timers
+//!
+//! [`LangfuseClient::start_generation`] records a generation with its start
+//! time as soon as it is called and returns a [`GenerationTimer`]. Finishing
+//! the timer records the end time, output, and usage, plus the completion
+//! start time if [`GenerationTimer::first_token`] was called, so latency and
+//! time to first token need no timestamp bookkeeping:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! # use serde_json::json;
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let mut timer = client
+//!     .start_generation()
+//!     .trace_id("trace-1")
+//!     .name("chat")
+//!     .model("gpt-4")
+//!     .call()
+//!     .await?;
+//! // ... on the first streamed chunk:
+//! timer.first_token();
+//! // ... once the response is complete:
+//! timer.finish(json!("Hello!"), None).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! For streamed responses, [`GenerationTimer::streaming`] returns a
+//! [`StreamingGeneration`] that also collects the text of each chunk.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures_core::Stream;
+use serde_json::{json, Map, Value};
+use tokio::time::error::Elapsed;
+use uuid::Uuid;
+
+use crate::client::LangfuseClient;
+use crate::environment::Environment;
+use crate::error::{Error, Result};
+use crate::usage::Usage;
+use langfuse_client_base::models::{
+    IngestionUsage, MapValue, Usage as IngestionUsageBody, UsageDetails,
+};
 
 // Re-export common types that might be useful
 pub use langfuse_client_base::models::{
     CreateEventBody, CreateGenerationBody, CreateSpanBody, ObservationLevel,
 };
+
+/// Records the attempts of a retried LLM call as sibling generations
+///
+/// Created with [`LangfuseClient::generation_retries`]. See the
+/// [module documentation](self) for the metadata linking the attempts.
+pub struct GenerationRetries<'a> {
+    client: &'a LangfuseClient,
+    trace_id: String,
+    parent_observation_id: Option<String>,
+    name: Option<String>,
+    model: Option<String>,
+    metadata: Map<String, Value>,
+    group_id: Option<String>,
+    attempts: u32,
+    previous_error: Option<String>,
+}
+
+impl<'a> GenerationRetries<'a> {
+    pub(crate) fn new(client: &'a LangfuseClient, trace_id: String) -> Self {
+        Self {
+            client,
+            trace_id,
+            parent_observation_id: None,
+            name: None,
+            model: None,
+            metadata: Map::new(),
+            group_id: None,
+            attempts: 0,
+            previous_error: None,
+        }
+    }
+
+    /// Nest all attempts under the given observation
+    pub fn parent_observation_id(mut self, id: impl Into<String>) -> Self {
+        self.parent_observation_id = Some(id.into());
+        self
+    }
+
+    /// Name of every attempt's generation
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Model used by every attempt
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Extra metadata added to every attempt
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Number of attempts recorded so far
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Id of the first attempt, shared by all attempts as `retry_group`
+    pub fn group_id(&self) -> Option<&str> {
+        self.group_id.as_deref()
+    }
+
+    /// Record a failed attempt and return its generation id
+    pub async fn failed(
+        &mut self,
+        start_time: DateTime<Utc>,
+        input: Option<Value>,
+        error: impl Display,
+    ) -> Result<String> {
+        let error = error.to_string();
+        let id = self.record(start_time, input, None, Some(&error)).await?;
+        self.previous_error = Some(error);
+        Ok(id)
+    }
+
+    /// Record the successful attempt and return its generation id
+    pub async fn succeeded(
+        &mut self,
+        start_time: DateTime<Utc>,
+        input: Option<Value>,
+        output: Value,
+    ) -> Result<String> {
+        self.record(start_time, input, Some(output), None).await
+    }
+
+    async fn record(
+        &mut self,
+        start_time: DateTime<Utc>,
+        input: Option<Value>,
+        output: Option<Value>,
+        error: Option<&str>,
+    ) -> Result<String> {
+        let id = Uuid::new_v4().to_string();
+        let group_id = self.group_id.clone().unwrap_or_else(|| id.clone());
+        let attempt = self.attempts + 1;
+
+        let mut metadata = self.metadata.clone();
+        metadata.insert("retry_group".to_string(), json!(group_id));
+        metadata.insert("attempt".to_string(), json!(attempt));
+        if let Some(previous_error) = &self.previous_error {
+            metadata.insert("previous_error".to_string(), json!(previous_error));
+        }
+
+        let id = self
+            .client
+            .generation()
+            .trace_id(self.trace_id.clone())
+            .id(id)
+            .maybe_parent_observation_id(self.parent_observation_id.clone())
+            .maybe_name(self.name.clone())
+            .maybe_model(self.model.clone())
+            .maybe_input(input)
+            .maybe_output(output)
+            .metadata(Value::Object(metadata))
+            .maybe_level(error.map(|_| "ERROR"))
+            .maybe_status_message(error)
+            .start_time(start_time)
+            .end_time(Utc::now())
+            .call()
+            .await?;
+
+        // Only an attempt that was recorded can anchor the group
+        self.group_id = Some(group_id);
+        self.attempts = attempt;
+        Ok(id.into())
+    }
+}
+
+/// Metadata key set to `true` on generations that timed out
+pub const TIMEOUT_METADATA_KEY: &str = "timeout";
+
+/// A generation recorded around an LLM call with a timeout
+///
+/// Passed to [`LangfuseClient::timed_generation`]. See
+/// [LLM call timeouts](self#llm-call-timeouts).
+#[derive(Debug, Clone)]
+pub struct TimedGeneration {
+    trace_id: String,
+    timeout: Duration,
+    parent_observation_id: Option<String>,
+    name: Option<String>,
+    model: Option<String>,
+    input: Option<Value>,
+    metadata: Map<String, Value>,
+}
+
+impl TimedGeneration {
+    /// Record a generation in the given trace, timing out after `timeout`
+    pub fn new(trace_id: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            trace_id: trace_id.into(),
+            timeout,
+            parent_observation_id: None,
+            name: None,
+            model: None,
+            input: None,
+            metadata: Map::new(),
+        }
+    }
+
+    /// Nest the generation under the given observation
+    pub fn parent_observation_id(mut self, id: impl Into<String>) -> Self {
+        self.parent_observation_id = Some(id.into());
+        self
+    }
+
+    /// Name of the generation
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Model used for the call
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Input sent to the model
+    pub fn input(mut self, input: impl Into<Value>) -> Self {
+        self.input = Some(input.into());
+        self
+    }
+
+    /// Extra metadata added to the generation
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Time the call may take before it is abandoned
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub(crate) async fn run<Fut, T>(
+        self,
+        client: &LangfuseClient,
+        call: Fut,
+    ) -> (std::result::Result<T, Elapsed>, Result<String>)
+    where
+        Fut: Future<Output = T>,
+    {
+        let stopwatch = Stopwatch::start();
+        let output = tokio::time::timeout(self.timeout, call).await;
+
+        let mut metadata = self.metadata;
+        let status_message = output.is_err().then(|| {
+            metadata.insert(TIMEOUT_METADATA_KEY.to_string(), json!(true));
+            metadata.insert(
+                "timeout_ms".to_string(),
+                json!(u64::try_from(self.timeout.as_millis()).unwrap_or(u64::MAX)),
+            );
+            format!("Timed out after {:?}", self.timeout)
+        });
+
+        let recorded = client
+            .generation()
+            .trace_id(self.trace_id)
+            .maybe_parent_observation_id(self.parent_observation_id)
+            .maybe_name(self.name)
+            .maybe_model(self.model)
+            .maybe_input(self.input)
+            .maybe_metadata((!metadata.is_empty()).then_some(Value::Object(metadata)))
+            .maybe_level(status_message.as_ref().map(|_| "ERROR"))
+            .maybe_status_message(status_message)
+            .start_time(stopwatch.started_at())
+            .end_time(stopwatch.now())
+            .call()
+            .await
+            .map(String::from);
+        (output, recorded)
+    }
+}
+
+/// Measures an operation for an observation's start and end time
+///
+/// The start is read from the wall clock once; the end is the start plus the
+/// time elapsed on the monotonic clock, so clock adjustments during the
+/// operation do not distort its duration.
+///
+/// ```no_run
+/// # use langfuse_ergonomic::{ClientBuilder, Stopwatch};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = ClientBuilder::from_env()?.build()?;
+/// let stopwatch = Stopwatch::start();
+/// // ... run the operation ...
+/// client
+///     .span()
+///     .trace_id("trace-1")
+///     .name("step")
+///     .start_time(stopwatch.started_at())
+///     .end_time(stopwatch.now())
+///     .call()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    started_at: DateTime<Utc>,
+    started: Instant,
+}
+
+impl Stopwatch {
+    /// Start measuring now
+    pub fn start() -> Self {
+        Self {
+            started_at: Utc::now(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Wall-clock time the stopwatch was started
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// Time elapsed since the stopwatch was started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Wall-clock time now, as the start time plus the elapsed time
+    pub fn now(&self) -> DateTime<Utc> {
+        self.started_at
+            + chrono::Duration::from_std(self.elapsed()).unwrap_or(chrono::Duration::MAX)
+    }
+}
+
+/// A generation in progress, timed from its start to [`finish`](Self::finish)
+///
+/// Started with [`LangfuseClient::start_generation`], which records the
+/// generation with its start time right away. Call [`first_token`](Self::first_token)
+/// when the first token of a streamed response arrives. See
+/// [Generation timers](self#generation-timers).
+pub struct GenerationTimer<'a> {
+    client: &'a LangfuseClient,
+    id: String,
+    trace_id: String,
+    stopwatch: Stopwatch,
+    first_token_at: Option<DateTime<Utc>>,
+    environment: Option<Environment>,
+}
+
+impl<'a> GenerationTimer<'a> {
+    pub(crate) fn new(
+        client: &'a LangfuseClient,
+        id: String,
+        trace_id: String,
+        stopwatch: Stopwatch,
+        environment: Option<Environment>,
+    ) -> Self {
+        Self {
+            client,
+            id,
+            trace_id,
+            stopwatch,
+            first_token_at: None,
+            environment,
+        }
+    }
+
+    /// ID of the generation
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// ID of the generation's trace
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    /// Time elapsed since the generation started
+    pub fn elapsed(&self) -> Duration {
+        self.stopwatch.elapsed()
+    }
+
+    /// Record that the first token arrived now, as the completion start time
+    ///
+    /// Only the first call counts, so it can be called for every chunk.
+    pub fn first_token(&mut self) {
+        if self.first_token_at.is_none() {
+            self.first_token_at = Some(self.stopwatch.now());
+        }
+    }
+
+    /// Time from the start to the first token, if [`first_token`](Self::first_token) was called
+    pub fn time_to_first_token(&self) -> Option<Duration> {
+        self.first_token_at
+            .and_then(|at| (at - self.stopwatch.started_at()).to_std().ok())
+    }
+
+    /// End the generation now with its output and token usage
+    pub async fn finish(self, output: impl Into<Value>, usage: Option<Usage>) -> Result<String> {
+        self.end(Some(output.into()), usage, None).await
+    }
+
+    /// End the generation now as failed, with level `ERROR` and the error as status message
+    pub async fn fail(self, error: impl Display) -> Result<String> {
+        self.end(None, None, Some(error.to_string())).await
+    }
+
+    /// Receive the model's response as a stream of chunks
+    ///
+    /// See [`StreamingGeneration`].
+    pub fn streaming(self) -> StreamingGeneration<'a> {
+        StreamingGeneration {
+            timer: self,
+            output: String::new(),
+            chunks: 0,
+        }
+    }
+
+    async fn end(
+        self,
+        output: Option<Value>,
+        usage: Option<Usage>,
+        error: Option<String>,
+    ) -> Result<String> {
+        self.client
+            .update_generation()
+            .id(self.id)
+            .trace_id(self.trace_id)
+            .maybe_output(output)
+            .maybe_usage(usage)
+            .maybe_level(error.as_ref().map(|_| "ERROR".to_string()))
+            .maybe_status_message(error)
+            .end_time(self.stopwatch.now())
+            .maybe_completion_start_time(self.first_token_at)
+            .maybe_environment(self.environment)
+            .call()
+            .await
+    }
+}
+
+/// A generation whose response arrives as a stream of chunks
+///
+/// Created with [`GenerationTimer::streaming`]. The first chunk sets the
+/// completion start time and the text of every chunk is appended to the
+/// output, which is recorded when the generation is finished. Chunks can be
+/// pushed one by one, passed through with [`wrap`](Self::wrap) while they are
+/// forwarded to a user, or read from a whole stream with
+/// [`consume`](Self::consume):
+///
+/// ```no_run
+/// # use langfuse_ergonomic::ClientBuilder;
+/// use futures_core::Stream;
+///
+/// # async fn example(chunks: impl Stream<Item = String> + Unpin) -> Result<(), Box<dyn std::error::Error>> {
+/// # let client = ClientBuilder::from_env()?.build()?;
+/// let mut generation = client
+///     .start_generation()
+///     .trace_id("trace-1")
+///     .name("chat")
+///     .model("gpt-4")
+///     .call()
+///     .await?
+///     .streaming();
+/// generation.consume(chunks, |chunk| Some(chunk.as_str())).await;
+/// println!("Response: {}", generation.output());
+/// generation.finish(None).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct StreamingGeneration<'a> {
+    timer: GenerationTimer<'a>,
+    output: String,
+    chunks: usize,
+}
+
+impl<'a> StreamingGeneration<'a> {
+    /// Record a chunk of the response
+    pub fn push(&mut self, text: &str) {
+        self.timer.first_token();
+        self.output.push_str(text);
+        self.chunks += 1;
+    }
+
+    /// Pass a stream of chunks through, recording the text `text` extracts from each
+    ///
+    /// Chunks without text, such as role or tool call deltas, still count as
+    /// the first token.
+    pub fn wrap<S, F>(&mut self, stream: S, text: F) -> RecordedStream<'_, 'a, S, F>
+    where
+        S: Stream + Unpin,
+        F: FnMut(&S::Item) -> Option<&str>,
+    {
+        RecordedStream {
+            generation: self,
+            stream,
+            text,
+        }
+    }
+
+    /// Read a stream of chunks to its end, recording the text `text` extracts from each
+    pub async fn consume<S, F>(&mut self, stream: S, text: F)
+    where
+        S: Stream + Unpin,
+        F: FnMut(&S::Item) -> Option<&str>,
+    {
+        let mut stream = self.wrap(stream, text);
+        while std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+            .await
+            .is_some()
+        {}
+    }
+
+    /// Response text received so far
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Number of chunks received so far
+    pub fn chunks(&self) -> usize {
+        self.chunks
+    }
+
+    /// The timer of the generation
+    pub fn timer(&self) -> &GenerationTimer<'a> {
+        &self.timer
+    }
+
+    /// End the generation with the received text as output and the token usage
+    pub async fn finish(self, usage: Option<Usage>) -> Result<String> {
+        self.timer
+            .end(Some(Value::String(self.output)), usage, None)
+            .await
+    }
+
+    /// End the generation as failed, keeping the text received before the error
+    pub async fn fail(self, error: impl Display) -> Result<String> {
+        let output = (!self.output.is_empty()).then_some(Value::String(self.output));
+        self.timer.end(output, None, Some(error.to_string())).await
+    }
+}
+
+/// Stream returned by [`StreamingGeneration::wrap`], yielding the wrapped stream's chunks
+pub struct RecordedStream<'g, 'a, S, F> {
+    generation: &'g mut StreamingGeneration<'a>,
+    stream: S,
+    text: F,
+}
+
+// The extractor is never pinned, so it need not be `Unpin` itself
+impl<S: Unpin, F> Unpin for RecordedStream<'_, '_, S, F> {}
+
+impl<S, F> Stream for RecordedStream<'_, '_, S, F>
+where
+    S: Stream + Unpin,
+    F: FnMut(&S::Item) -> Option<&str>,
+{
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        let this = &mut *self;
+        let chunk = ready!(Pin::new(&mut this.stream).poll_next(cx));
+        if let Some(chunk) = &chunk {
+            let text = (this.text)(chunk).unwrap_or_default();
+            this.generation.push(text);
+        }
+        Poll::Ready(chunk)
+    }
+}
+
+/// Convert a JSON object of model parameters to the API representation
+///
+/// Strings, booleans, numbers, and string arrays map directly; any other value
+/// is sent as its serialized JSON string. Integers outside the `i32` range are
+/// sent as numbers.
+pub(crate) fn model_parameters_map(parameters: Value) -> Result<HashMap<String, MapValue>> {
+    let Value::Object(parameters) = parameters else {
+        return Err(Error::Validation(
+            "model_parameters must be a JSON object".to_string(),
+        ));
+    };
+
+    Ok(parameters
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => MapValue::String(s),
+                Value::Bool(b) => MapValue::Boolean(b),
+                Value::Number(n) => match n.as_i64().and_then(|i| i32::try_from(i).ok()) {
+                    Some(i) => MapValue::Integer(i),
+                    None => MapValue::Number(n.as_f64().unwrap_or_default() as f32),
+                },
+                Value::Array(items) if items.iter().all(Value::is_string) => {
+                    MapValue::ArrayVecString(
+                        items
+                            .into_iter()
+                            .filter_map(|item| item.as_str().map(str::to_string))
+                            .collect(),
+                    )
+                }
+                other => MapValue::String(other.to_string()),
+            };
+            (key, value)
+        })
+        .collect())
+}
+
+/// Usage fields of a generation body
+#[derive(Debug, Default)]
+pub(crate) struct GenerationUsage {
+    pub usage: Option<Box<IngestionUsage>>,
+    pub usage_details: Option<Box<UsageDetails>>,
+    pub cost_details: Option<HashMap<String, f64>>,
+}
+
+impl GenerationUsage {
+    /// Build usage from a [`Usage`] and explicitly set counts and details
+    ///
+    /// Explicit token counts, usage details, and cost details take precedence
+    /// over those of `usage`.
+    pub(crate) fn resolve(
+        usage: Option<Usage>,
+        prompt_tokens: Option<i32>,
+        completion_tokens: Option<i32>,
+        total_tokens: Option<i32>,
+        details: Option<HashMap<String, i32>>,
+        cost_details: Option<HashMap<String, f64>>,
+    ) -> Self {
+        let Some(usage) = usage else {
+            return Self {
+                cost_details,
+                ..Self::new(prompt_tokens, completion_tokens, total_tokens, details)
+            };
+        };
+
+        let mut merged_details = usage.usage_details();
+        merged_details.extend(details.unwrap_or_default());
+        let mut merged_costs = usage.cost_details();
+        merged_costs.extend(cost_details.unwrap_or_default());
+
+        Self {
+            cost_details: (!merged_costs.is_empty()).then_some(merged_costs),
+            ..Self::new(
+                prompt_tokens.or(usage.prompt_tokens),
+                completion_tokens.or(usage.completion_tokens),
+                total_tokens.or(usage.total_tokens),
+                (!merged_details.is_empty()).then_some(merged_details),
+            )
+        }
+    }
+
+    /// Build usage from token counts and explicit usage details
+    ///
+    /// Token counts populate both the legacy `usage` object and the `input`,
+    /// `output`, and `total` usage details; explicit details take precedence.
+    /// A missing total is derived from the input and output counts.
+    pub(crate) fn new(
+        prompt_tokens: Option<i32>,
+        completion_tokens: Option<i32>,
+        total_tokens: Option<i32>,
+        details: Option<HashMap<String, i32>>,
+    ) -> Self {
+        let has_tokens =
+            prompt_tokens.is_some() || completion_tokens.is_some() || total_tokens.is_some();
+        if !has_tokens && details.is_none() {
+            return Self::default();
+        }
+
+        let mut usage_details = HashMap::new();
+        let usage = has_tokens.then(|| {
+            let input = prompt_tokens.unwrap_or(0);
+            let output = completion_tokens.unwrap_or(0);
+            let total = total_tokens.unwrap_or(input.saturating_add(output));
+            usage_details.insert("input".to_string(), input);
+            usage_details.insert("output".to_string(), output);
+            usage_details.insert("total".to_string(), total);
+            Box::new(IngestionUsage::Usage(Box::new(IngestionUsageBody {
+                input,
+                output,
+                total,
+                unit: Some(Some("TOKENS".to_string())),
+                ..Default::default()
+            })))
+        });
+        usage_details.extend(details.unwrap_or_default());
+
+        Self {
+            usage,
+            usage_details: Some(Box::new(UsageDetails::Object(usage_details))),
+            cost_details: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_parameters_map() {
+        let map = model_parameters_map(json!({
+            "temperature": 0.7,
+            "max_tokens": 150,
+            "stream": false,
+            "stop": ["\n"],
+            "response_format": {"type": "json_object"}
+        }))
+        .unwrap();
+
+        assert_eq!(map["temperature"], MapValue::Number(0.7));
+        assert_eq!(map["max_tokens"], MapValue::Integer(150));
+        assert_eq!(map["stream"], MapValue::Boolean(false));
+        assert_eq!(
+            map["stop"],
+            MapValue::ArrayVecString(vec!["\n".to_string()])
+        );
+        assert_eq!(
+            map["response_format"],
+            MapValue::String(r#"{"type":"json_object"}"#.to_string())
+        );
+        assert!(model_parameters_map(json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_generation_usage() {
+        let usage = GenerationUsage::new(Some(10), Some(5), None, None);
+        match usage.usage.as_deref() {
+            Some(IngestionUsage::Usage(usage)) => assert_eq!(usage.total, 15),
+            other => panic!("unexpected usage {other:?}"),
+        }
+        let details = HashMap::from([("cache_read".to_string(), 3), ("input".to_string(), 7)]);
+        let usage = GenerationUsage::new(Some(10), Some(5), None, Some(details));
+        match usage.usage_details.as_deref() {
+            Some(UsageDetails::Object(details)) => {
+                assert_eq!(details["input"], 7);
+                assert_eq!(details["cache_read"], 3);
+                assert_eq!(details["total"], 15);
+            }
+            other => panic!("unexpected usage details {other:?}"),
+        }
+        assert!(GenerationUsage::new(None, None, None, None).usage.is_none());
+    }
+
+    #[test]
+    fn test_generation_usage_resolve() {
+        let provider = Usage {
+            cache_read_tokens: Some(4),
+            ..Usage::new(10, 5).with_costs(0.25, 0.5)
+        };
+        let usage = GenerationUsage::resolve(
+            Some(provider),
+            None,
+            Some(6),
+            None,
+            None,
+            Some(HashMap::from([("output".to_string(), 0.75)])),
+        );
+        match usage.usage_details.as_deref() {
+            Some(UsageDetails::Object(details)) => {
+                assert_eq!(details["input"], 10);
+                assert_eq!(details["output"], 6);
+                assert_eq!(details["total"], 16);
+                assert_eq!(details["cache_read"], 4);
+            }
+            other => panic!("unexpected usage details {other:?}"),
+        }
+        let costs = usage.cost_details.unwrap();
+        assert_eq!(costs["input"], 0.25);
+        assert_eq!(costs["output"], 0.75);
+    }
+
+    #[test]
+    fn test_stopwatch_end_follows_start() {
+        let stopwatch = Stopwatch::start();
+        std::thread::sleep(Duration::from_millis(5));
+        let elapsed = (stopwatch.now() - stopwatch.started_at()).to_std().unwrap();
+        assert!(elapsed >= Duration::from_millis(5));
+        assert!(elapsed <= stopwatch.elapsed());
+    }
+}
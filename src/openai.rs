@@ -0,0 +1,293 @@
+//! Instrumentation of OpenAI-compatible clients
+//!
+//! [`LangfuseOpenAi`] wraps any client of an OpenAI-compatible API, such as
+//! `async-openai`, a hand-written `reqwest` client, or a client of a proxy
+//! like LiteLLM or vLLM, and records each call made through it as a
+//! generation. Requests and responses only need to serialize to the OpenAI
+//! JSON shapes; the model, model parameters, messages, output, token usage,
+//! and latency are read from them:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{with_context, ClientBuilder, LangfuseContext};
+//! use langfuse_ergonomic::openai::LangfuseOpenAi;
+//! use serde_json::{json, Value};
+//!
+//! # async fn send(http: &reqwest::Client, request: Value) -> Result<Value, reqwest::Error> {
+//! #     http.post("https://api.openai.com/v1/chat/completions").json(&request).send().await?.json().await
+//! # }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let langfuse = ClientBuilder::from_env()?.build()?;
+//! let openai = LangfuseOpenAi::new(reqwest::Client::new(), langfuse);
+//!
+//! let request = json!({
+//!     "model": "gpt-4o",
+//!     "temperature": 0.2,
+//!     "messages": [{"role": "user", "content": "Hello!"}],
+//! });
+//! let response = with_context(LangfuseContext::new().trace_id("trace-1"), async {
+//!     openai.chat(request, |http, request| send(http, request)).await
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Generations belong to the trace and parent observation of the
+//! [ambient context](crate::ambient), or to a new trace outside of one.
+//! Failed calls are recorded with level `ERROR` and the error as status
+//! message. Failing to record a generation is logged and never fails the call.
+//! Streaming calls are not recorded; use
+//! [`StreamingGeneration`](crate::StreamingGeneration) for them.
+
+use std::fmt::Display;
+use std::future::Future;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::client::LangfuseClient;
+use crate::observations::Stopwatch;
+use crate::usage::Usage;
+
+/// Request fields recorded as model parameters
+const MODEL_PARAMETERS: &[&str] = &[
+    "temperature",
+    "top_p",
+    "max_tokens",
+    "max_completion_tokens",
+    "frequency_penalty",
+    "presence_penalty",
+    "n",
+    "seed",
+    "stop",
+    "reasoning_effort",
+    "response_format",
+];
+
+/// An OpenAI-compatible client recording its calls as generations
+///
+/// See the [module documentation](self).
+#[derive(Clone)]
+pub struct LangfuseOpenAi<C> {
+    inner: C,
+    langfuse: LangfuseClient,
+    name: Option<String>,
+}
+
+impl<C> LangfuseOpenAi<C> {
+    /// Record the calls made through `inner` with `langfuse`
+    pub fn new(inner: C, langfuse: LangfuseClient) -> Self {
+        Self {
+            inner,
+            langfuse,
+            name: None,
+        }
+    }
+
+    /// Name the generations; defaults to `chat-completion` and `completion`
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Unwrap the client
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Make a chat completion call and record it as a generation
+    ///
+    /// `call` sends `request` with the wrapped client. The request's
+    /// `messages` become the generation input and the response's choice
+    /// messages its output.
+    pub async fn chat<'c, Req, Resp, E, Fut>(
+        &'c self,
+        request: Req,
+        call: impl FnOnce(&'c C, Req) -> Fut,
+    ) -> Result<Resp, E>
+    where
+        Req: Serialize,
+        Resp: Serialize,
+        E: Display,
+        Fut: Future<Output = Result<Resp, E>>,
+    {
+        self.record(Endpoint::Chat, request, call).await
+    }
+
+    /// Make a legacy completion call and record it as a generation
+    ///
+    /// The request's `prompt` becomes the generation input and the response's
+    /// choice texts its output.
+    pub async fn completion<'c, Req, Resp, E, Fut>(
+        &'c self,
+        request: Req,
+        call: impl FnOnce(&'c C, Req) -> Fut,
+    ) -> Result<Resp, E>
+    where
+        Req: Serialize,
+        Resp: Serialize,
+        E: Display,
+        Fut: Future<Output = Result<Resp, E>>,
+    {
+        self.record(Endpoint::Completion, request, call).await
+    }
+
+    async fn record<'c, Req, Resp, E, Fut>(
+        &'c self,
+        endpoint: Endpoint,
+        request: Req,
+        call: impl FnOnce(&'c C, Req) -> Fut,
+    ) -> Result<Resp, E>
+    where
+        Req: Serialize,
+        Resp: Serialize,
+        E: Display,
+        Fut: Future<Output = Result<Resp, E>>,
+    {
+        let request_json = serde_json::to_value(&request).unwrap_or(Value::Null);
+        let stopwatch = Stopwatch::start();
+        let result = call(&self.inner, request).await;
+        let end_time = stopwatch.now();
+
+        let (output, usage, model, status_message) = match &result {
+            Ok(response) => {
+                let response = serde_json::to_value(response).unwrap_or(Value::Null);
+                (
+                    endpoint.output(&response),
+                    response
+                        .get("usage")
+                        .and_then(|u| Usage::from_openai(u).ok()),
+                    response["model"].as_str().map(str::to_string),
+                    None,
+                )
+            }
+            Err(e) => (None, None, None, Some(e.to_string())),
+        };
+        let model = model.or_else(|| request_json["model"].as_str().map(str::to_string));
+        let trace_id = crate::ambient::ambient(|context| context.trace_id.clone())
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+        let recorded = self
+            .langfuse
+            .generation()
+            .trace_id(trace_id)
+            .name(
+                self.name
+                    .clone()
+                    .unwrap_or_else(|| endpoint.name().to_string()),
+            )
+            .maybe_model(model)
+            .maybe_model_parameters(model_parameters(&request_json))
+            .maybe_input(request_json.get(endpoint.input_field()).cloned())
+            .maybe_output(output)
+            .maybe_usage(usage)
+            .maybe_level(status_message.as_ref().map(|_| "ERROR"))
+            .maybe_status_message(status_message)
+            .start_time(stopwatch.started_at())
+            .end_time(end_time)
+            .call()
+            .await;
+        if let Err(e) = recorded {
+            tracing::warn!(error = %e, "Failed to record an OpenAI call as a generation");
+        }
+
+        result
+    }
+}
+
+/// OpenAI API endpoint of a recorded call
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    Chat,
+    Completion,
+}
+
+impl Endpoint {
+    fn name(self) -> &'static str {
+        match self {
+            Endpoint::Chat => "chat-completion",
+            Endpoint::Completion => "completion",
+        }
+    }
+
+    fn input_field(self) -> &'static str {
+        match self {
+            Endpoint::Chat => "messages",
+            Endpoint::Completion => "prompt",
+        }
+    }
+
+    /// Output of the response's choices, unwrapped if there is only one
+    fn output(self, response: &Value) -> Option<Value> {
+        let field = match self {
+            Endpoint::Chat => "message",
+            Endpoint::Completion => "text",
+        };
+        let mut outputs: Vec<Value> = response["choices"]
+            .as_array()?
+            .iter()
+            .filter_map(|choice| choice.get(field).cloned())
+            .collect();
+        match outputs.len() {
+            0 => None,
+            1 => outputs.pop(),
+            _ => Some(Value::Array(outputs)),
+        }
+    }
+}
+
+/// Model parameters set in a request, as a JSON object
+fn model_parameters(request: &Value) -> Option<Value> {
+    let parameters: Map<String, Value> = MODEL_PARAMETERS
+        .iter()
+        .filter_map(|&key| {
+            request
+                .get(key)
+                .filter(|value| !value.is_null())
+                .map(|value| (key.to_string(), value.clone()))
+        })
+        .collect();
+    (!parameters.is_empty()).then_some(Value::Object(parameters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reads_parameters_and_output() {
+        let request = json!({
+            "model": "gpt-4o",
+            "temperature": 0.2,
+            "max_tokens": 100,
+            "stop": null,
+            "messages": [],
+        });
+        assert_eq!(
+            model_parameters(&request),
+            Some(json!({"temperature": 0.2, "max_tokens": 100}))
+        );
+        assert_eq!(model_parameters(&json!({"model": "gpt-4o"})), None);
+
+        let response = json!({"choices": [
+            {"index": 0, "message": {"role": "assistant", "content": "Hi"}},
+        ]});
+        assert_eq!(
+            Endpoint::Chat.output(&response),
+            Some(json!({"role": "assistant", "content": "Hi"}))
+        );
+        let response = json!({"choices": [{"text": "a"}, {"text": "b"}]});
+        assert_eq!(
+            Endpoint::Completion.output(&response),
+            Some(json!(["a", "b"]))
+        );
+    }
+}
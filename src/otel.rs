@@ -0,0 +1,457 @@
+//! OpenTelemetry bridge for exporting spans to Langfuse
+//!
+//! [`OtelLangfuseExporter`] implements the OpenTelemetry SDK
+//! [`SpanExporter`] trait and converts finished spans into Langfuse ingestion
+//! events, which are queued on a [`Batcher`]. Instrument once with
+//! OpenTelemetry (or `tracing` through `tracing-opentelemetry`) and the spans
+//! show up in Langfuse without manual `client.trace()` calls.
+//!
+//! ## Mapping
+//!
+//! - The OpenTelemetry trace ID becomes the Langfuse trace ID and each span ID
+//!   becomes an observation ID, so parent/child relationships are preserved.
+//! - Root spans (no parent) additionally create the Langfuse trace, named after
+//!   the span unless `langfuse.trace.name` is set.
+//! - Spans with a `gen_ai.request.model` attribute become generations, with
+//!   token usage taken from `gen_ai.usage.input_tokens` and
+//!   `gen_ai.usage.output_tokens`. All other spans become spans.
+//! - Span events become Langfuse events under the span.
+//! - `langfuse.observation.input` and `langfuse.observation.output` set the
+//!   observation input and output (parsed as JSON when possible);
+//!   `langfuse.user.id` / `user.id` and `langfuse.session.id` / `session.id`
+//!   are set on the trace. Remaining attributes are kept as metadata.
+//! - An error status maps to the `ERROR` level with the status description as
+//!   the status message.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use langfuse_ergonomic::otel::OtelLangfuseExporter;
+//! use langfuse_ergonomic::{Batcher, ClientBuilder};
+//! use opentelemetry_sdk::trace::SdkTracerProvider;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! let batcher = Arc::new(Batcher::builder().client(client).build().await);
+//!
+//! let provider = SdkTracerProvider::builder()
+//!     .with_batch_exporter(OtelLangfuseExporter::new(batcher.clone()))
+//!     .build();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use langfuse_client_base::models::{
+    ingestion_event_one_of::Type as TraceEventType,
+    ingestion_event_one_of_2::Type as SpanEventType,
+    ingestion_event_one_of_4::Type as GenerationEventType,
+    ingestion_event_one_of_6::Type as EventEventType, CreateEventBody, CreateGenerationBody,
+    CreateSpanBody, IngestionEvent, IngestionEventOneOf, IngestionEventOneOf2,
+    IngestionEventOneOf4, IngestionEventOneOf6, ObservationLevel, TraceBody, UsageDetails,
+};
+use opentelemetry::trace::{SpanId, Status};
+use opentelemetry::{KeyValue, Value as OtelValue};
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::trace::{SpanData, SpanExporter};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::batcher::Batcher;
+
+/// Attribute overriding the trace name on root spans
+pub const TRACE_NAME_ATTRIBUTE: &str = "langfuse.trace.name";
+/// Attribute holding the observation input
+pub const INPUT_ATTRIBUTE: &str = "langfuse.observation.input";
+/// Attribute holding the observation output
+pub const OUTPUT_ATTRIBUTE: &str = "langfuse.observation.output";
+/// Attributes holding the trace user ID, in order of preference
+pub const USER_ID_ATTRIBUTES: [&str; 2] = ["langfuse.user.id", "user.id"];
+/// Attributes holding the trace session ID, in order of preference
+pub const SESSION_ID_ATTRIBUTES: [&str; 2] = ["langfuse.session.id", "session.id"];
+/// Attribute marking a span as an LLM generation
+pub const MODEL_ATTRIBUTE: &str = "gen_ai.request.model";
+/// Attribute holding the number of input tokens of a generation
+pub const INPUT_TOKENS_ATTRIBUTE: &str = "gen_ai.usage.input_tokens";
+/// Attribute holding the number of output tokens of a generation
+pub const OUTPUT_TOKENS_ATTRIBUTE: &str = "gen_ai.usage.output_tokens";
+
+/// OpenTelemetry span exporter that sends spans to Langfuse through a [`Batcher`]
+///
+/// The exporter only queues events; keep a handle to the batcher to flush or
+/// shut it down when the application exits.
+#[derive(Clone)]
+pub struct OtelLangfuseExporter {
+    batcher: Arc<Batcher>,
+}
+
+impl fmt::Debug for OtelLangfuseExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtelLangfuseExporter")
+            .finish_non_exhaustive()
+    }
+}
+
+impl OtelLangfuseExporter {
+    /// Create an exporter that queues converted spans on `batcher`
+    pub fn new(batcher: Arc<Batcher>) -> Self {
+        Self { batcher }
+    }
+}
+
+impl SpanExporter for OtelLangfuseExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let mut failures = 0usize;
+        for span in &batch {
+            for event in span_to_events(span) {
+                if let Err(e) = self.batcher.add(event).await {
+                    tracing::warn!(error = %e, "Failed to queue OpenTelemetry span for Langfuse");
+                    failures += 1;
+                }
+            }
+        }
+
+        if failures > 0 {
+            return Err(OTelSdkError::InternalFailure(format!(
+                "{failures} Langfuse events could not be queued"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Convert a finished OpenTelemetry span into Langfuse ingestion events
+///
+/// Returns the trace-create event for root spans, the span or generation
+/// itself, and one event per span event. Spans whose parent is local are
+/// nested under the parent's observation; spans continuing a remote context
+/// are attached to the trace without a parent observation.
+pub fn span_to_events(span: &SpanData) -> Vec<IngestionEvent> {
+    let trace_id = span.span_context.trace_id().to_string();
+    let observation_id = span.span_context.span_id().to_string();
+    let start_time = format_time(span.start_time);
+    let end_time = format_time(span.end_time);
+    let is_root = span.parent_span_id == SpanId::INVALID;
+    // A remote parent belongs to another service and is not an observation
+    // this exporter produced, so the span is attached to the trace directly
+    let parent_observation_id =
+        (!is_root && !span.parent_span_is_remote).then(|| span.parent_span_id.to_string());
+
+    let mut attributes = SpanAttributes::new(&span.attributes);
+    let trace_name = attributes.take_string(TRACE_NAME_ATTRIBUTE);
+    let user_id = attributes.take_first_string(&USER_ID_ATTRIBUTES);
+    let session_id = attributes.take_first_string(&SESSION_ID_ATTRIBUTES);
+    let input = attributes.take_json(INPUT_ATTRIBUTE);
+    let output = attributes.take_json(OUTPUT_ATTRIBUTE);
+    let model = attributes.take_string(MODEL_ATTRIBUTE);
+    let input_tokens = attributes.take_i64(INPUT_TOKENS_ATTRIBUTE);
+    let output_tokens = attributes.take_i64(OUTPUT_TOKENS_ATTRIBUTE);
+    let metadata = attributes.into_metadata();
+
+    let (level, status_message) = match &span.status {
+        Status::Error { description } => (
+            Some(ObservationLevel::Error),
+            Some(description.to_string()).filter(|d| !d.is_empty()),
+        ),
+        _ => (None, None),
+    };
+
+    let mut events = Vec::with_capacity(2 + span.events.len());
+
+    if is_root {
+        let trace_body = TraceBody::builder()
+            .id(Some(trace_id.clone()))
+            .timestamp(Some(start_time.clone()))
+            .name(Some(trace_name.unwrap_or_else(|| span.name.to_string())))
+            .maybe_user_id(user_id.map(Some))
+            .maybe_session_id(session_id.map(Some))
+            .maybe_input(input.clone().map(Some))
+            .maybe_output(output.clone().map(Some))
+            .build();
+        events.push(IngestionEvent::IngestionEventOneOf(Box::new(
+            IngestionEventOneOf::builder()
+                .body(Box::new(trace_body))
+                .id(Uuid::new_v4().to_string())
+                .timestamp(start_time.clone())
+                .r#type(TraceEventType::TraceCreate)
+                .build(),
+        )));
+    }
+
+    if let Some(model) = model {
+        let mut usage = HashMap::new();
+        if let Some(tokens) = input_tokens.and_then(|t| i32::try_from(t).ok()) {
+            usage.insert("input".to_string(), tokens);
+        }
+        if let Some(tokens) = output_tokens.and_then(|t| i32::try_from(t).ok()) {
+            usage.insert("output".to_string(), tokens);
+        }
+        let usage_details = (!usage.is_empty()).then(|| Box::new(UsageDetails::Object(usage)));
+
+        let body = CreateGenerationBody::builder()
+            .id(Some(observation_id.clone()))
+            .trace_id(Some(trace_id.clone()))
+            .name(Some(span.name.to_string()))
+            .start_time(Some(start_time.clone()))
+            .end_time(Some(end_time))
+            .model(Some(model))
+            .maybe_usage_details(usage_details)
+            .maybe_input(input.map(Some))
+            .maybe_output(output.map(Some))
+            .maybe_metadata(metadata.map(Some))
+            .maybe_level(level)
+            .maybe_status_message(status_message.map(Some))
+            .maybe_parent_observation_id(parent_observation_id.map(Some))
+            .build();
+        events.push(IngestionEvent::IngestionEventOneOf4(Box::new(
+            IngestionEventOneOf4::builder()
+                .body(Box::new(body))
+                .id(Uuid::new_v4().to_string())
+                .timestamp(start_time.clone())
+                .r#type(GenerationEventType::GenerationCreate)
+                .build(),
+        )));
+    } else {
+        let body = CreateSpanBody::builder()
+            .id(Some(observation_id.clone()))
+            .trace_id(Some(trace_id.clone()))
+            .name(Some(span.name.to_string()))
+            .start_time(Some(start_time.clone()))
+            .end_time(Some(end_time))
+            .maybe_input(input.map(Some))
+            .maybe_output(output.map(Some))
+            .maybe_metadata(metadata.map(Some))
+            .maybe_level(level)
+            .maybe_status_message(status_message.map(Some))
+            .maybe_parent_observation_id(parent_observation_id.map(Some))
+            .build();
+        events.push(IngestionEvent::IngestionEventOneOf2(Box::new(
+            IngestionEventOneOf2::builder()
+                .body(Box::new(body))
+                .id(Uuid::new_v4().to_string())
+                .timestamp(start_time.clone())
+                .r#type(SpanEventType::SpanCreate)
+                .build(),
+        )));
+    }
+
+    for span_event in span.events.iter() {
+        let timestamp = format_time(span_event.timestamp);
+        let metadata = SpanAttributes::new(&span_event.attributes).into_metadata();
+        let body = CreateEventBody::builder()
+            .id(Some(Uuid::new_v4().to_string()))
+            .trace_id(Some(trace_id.clone()))
+            .name(Some(span_event.name.to_string()))
+            .start_time(Some(timestamp.clone()))
+            .parent_observation_id(Some(observation_id.clone()))
+            .maybe_metadata(metadata.map(Some))
+            .build();
+        events.push(IngestionEvent::IngestionEventOneOf6(Box::new(
+            IngestionEventOneOf6::builder()
+                .body(Box::new(body))
+                .id(Uuid::new_v4().to_string())
+                .timestamp(timestamp)
+                .r#type(EventEventType::EventCreate)
+                .build(),
+        )));
+    }
+
+    events
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Span attributes with well-known keys removed as they are consumed
+struct SpanAttributes<'a> {
+    remaining: Vec<&'a KeyValue>,
+}
+
+impl<'a> SpanAttributes<'a> {
+    fn new(attributes: &'a [KeyValue]) -> Self {
+        Self {
+            remaining: attributes.iter().collect(),
+        }
+    }
+
+    fn take(&mut self, key: &str) -> Option<&'a OtelValue> {
+        let index = self
+            .remaining
+            .iter()
+            .position(|kv| kv.key.as_str() == key)?;
+        Some(&self.remaining.remove(index).value)
+    }
+
+    fn take_string(&mut self, key: &str) -> Option<String> {
+        self.take(key).map(|v| v.as_str().into_owned())
+    }
+
+    fn take_first_string(&mut self, keys: &[&str]) -> Option<String> {
+        let mut found = None;
+        for key in keys {
+            let value = self.take_string(key);
+            found = found.or(value);
+        }
+        found
+    }
+
+    fn take_i64(&mut self, key: &str) -> Option<i64> {
+        match self.take(key)? {
+            OtelValue::I64(n) => Some(*n),
+            OtelValue::F64(n) => Some(*n as i64),
+            OtelValue::String(s) => s.as_str().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn take_json(&mut self, key: &str) -> Option<Value> {
+        let value = self.take(key)?;
+        Some(match value {
+            OtelValue::String(s) => serde_json::from_str(s.as_str())
+                .unwrap_or_else(|_| Value::String(s.as_str().to_string())),
+            other => otel_to_json(other),
+        })
+    }
+
+    fn into_metadata(self) -> Option<Value> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let map: Map<String, Value> = self
+            .remaining
+            .into_iter()
+            .map(|kv| (kv.key.as_str().to_string(), otel_to_json(&kv.value)))
+            .collect();
+        Some(Value::Object(map))
+    }
+}
+
+fn otel_to_json(value: &OtelValue) -> Value {
+    match value {
+        OtelValue::Bool(b) => Value::from(*b),
+        OtelValue::I64(n) => Value::from(*n),
+        OtelValue::F64(n) => Value::from(*n),
+        OtelValue::String(s) => Value::from(s.as_str()),
+        other => Value::from(other.as_str().into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{SpanContext, SpanKind, TraceFlags, TraceId, TraceState};
+    use opentelemetry::InstrumentationScope;
+    use opentelemetry_sdk::trace::{SpanEvents, SpanLinks};
+    use std::time::Duration;
+
+    fn span_data(parent: SpanId, attributes: Vec<KeyValue>, status: Status) -> SpanData {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef_u128),
+                SpanId::from(0x1111_u64),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            parent_span_id: parent,
+            parent_span_is_remote: false,
+            span_kind: SpanKind::Internal,
+            name: "handle-request".into(),
+            start_time: start,
+            end_time: start + Duration::from_millis(250),
+            attributes,
+            dropped_attributes_count: 0,
+            events: SpanEvents::default(),
+            links: SpanLinks::default(),
+            status,
+            instrumentation_scope: InstrumentationScope::builder("test").build(),
+        }
+    }
+
+    fn to_json(event: &IngestionEvent) -> Value {
+        serde_json::to_value(event).unwrap()
+    }
+
+    #[test]
+    fn test_root_span_creates_trace_and_span() {
+        let span = span_data(
+            SpanId::INVALID,
+            vec![
+                KeyValue::new("user.id", "user-1"),
+                KeyValue::new(INPUT_ATTRIBUTE, r#"{"q":"hi"}"#),
+                KeyValue::new("http.method", "GET"),
+            ],
+            Status::Unset,
+        );
+
+        let events = span_to_events(&span);
+        assert_eq!(events.len(), 2);
+
+        let trace = to_json(&events[0]);
+        assert_eq!(trace["type"], "trace-create");
+        assert_eq!(trace["body"]["id"], "0123456789abcdef0123456789abcdef");
+        assert_eq!(trace["body"]["name"], "handle-request");
+        assert_eq!(trace["body"]["userId"], "user-1");
+
+        let observation = to_json(&events[1]);
+        assert_eq!(observation["type"], "span-create");
+        assert_eq!(observation["body"]["id"], "0000000000001111");
+        assert_eq!(observation["body"]["input"]["q"], "hi");
+        assert_eq!(observation["body"]["metadata"]["http.method"], "GET");
+        assert_eq!(observation["body"]["endTime"], "2023-11-14T22:13:20.250Z");
+        assert!(observation["body"].get("parentObservationId").is_none());
+    }
+
+    #[test]
+    fn test_model_span_becomes_generation() {
+        let span = span_data(
+            SpanId::from(0x2222_u64),
+            vec![
+                KeyValue::new(MODEL_ATTRIBUTE, "gpt-4"),
+                KeyValue::new(INPUT_TOKENS_ATTRIBUTE, 12_i64),
+                KeyValue::new(OUTPUT_TOKENS_ATTRIBUTE, 34_i64),
+            ],
+            Status::error("rate limited"),
+        );
+
+        let events = span_to_events(&span);
+        assert_eq!(events.len(), 1);
+
+        let generation = to_json(&events[0]);
+        assert_eq!(generation["type"], "generation-create");
+        assert_eq!(generation["body"]["model"], "gpt-4");
+        assert_eq!(generation["body"]["usageDetails"]["input"], 12);
+        assert_eq!(generation["body"]["usageDetails"]["output"], 34);
+        assert_eq!(
+            generation["body"]["parentObservationId"],
+            "0000000000002222"
+        );
+        assert_eq!(generation["body"]["level"], "ERROR");
+        assert_eq!(generation["body"]["statusMessage"], "rate limited");
+    }
+
+    #[test]
+    fn test_remote_parent_is_not_an_observation() {
+        let mut span = span_data(SpanId::from(0x3333_u64), Vec::new(), Status::Unset);
+        span.parent_span_is_remote = true;
+
+        let events = span_to_events(&span);
+        assert_eq!(events.len(), 1);
+
+        let observation = to_json(&events[0]);
+        assert_eq!(observation["type"], "span-create");
+        assert_eq!(
+            observation["body"]["traceId"],
+            "0123456789abcdef0123456789abcdef"
+        );
+        assert!(observation["body"].get("parentObservationId").is_none());
+    }
+}
@@ -0,0 +1,623 @@
+//! Size guards for JSON payload fields
+//!
+//! Large `input`, `output`, or `metadata` values are the most common cause of
+//! oversized ingestion batches. A [`FieldSizeLimit`] configured on the
+//! [`ClientBuilder`](crate::ClientBuilder) is applied when an event is built, so
+//! offending values are handled before they ever reach the network.
+//!
+//! Whenever a field is altered, its original serialized size is recorded in the
+//! event metadata under the `_oversized_fields` key, and the metadata gets
+//! `truncated: true`.
+//!
+//! Input and output can have their own limits, and oversized values can be
+//! dropped, hashed, or uploaded as media instead of truncated:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{ClientBuilder, FieldSizePolicy};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .max_field_bytes(64 * 1024)
+//!     .max_output_bytes(4 * 1024)
+//!     .field_size_policy(FieldSizePolicy::Hash)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The input and output limits also apply, after all interceptors, to events
+//! that do not go through the builders: raw events queued in a
+//! [`Batcher`](crate::Batcher) or passed to
+//! [`LangfuseClient::ingest`](crate::LangfuseClient::ingest). Those cannot be
+//! rejected or uploaded anymore, so their oversized values are truncated under
+//! the [`Reject`](FieldSizePolicy::Reject) and
+//! [`Externalize`](FieldSizePolicy::Externalize) policies.
+//!
+//! Names and status messages are checked against [`TextLengthLimits`], which
+//! are always active. Values over the limit are truncated with a warning by
+//! default instead of failing with an opaque 400 at ingestion time.
+
+use langfuse_client_base::models::IngestionEvent;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::interceptor::{metadata_object, BatchInterceptor};
+
+/// Metadata key under which original sizes of altered fields are recorded
+pub const OVERSIZED_FIELDS_KEY: &str = "_oversized_fields";
+
+/// Metadata key set to `true` on events with an altered field
+pub const TRUNCATED_KEY: &str = "truncated";
+
+/// Marker appended to truncated values
+const TRUNCATION_MARKER: &str = "...[truncated]";
+
+/// Smallest field size limit, which fits the truncation marker as a JSON string
+pub const MIN_FIELD_BYTES: usize = TRUNCATION_MARKER.len() + 2;
+
+/// Strategy for handling JSON fields that exceed the configured size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldSizePolicy {
+    /// Replace the value with its serialized form cut down to the limit (default)
+    #[default]
+    Truncate,
+    /// Replace the value with a short placeholder describing the original size
+    Omit,
+    /// Remove the value from the event
+    Drop,
+    /// Replace the value with the SHA-256 hash of its serialized form
+    Hash,
+    /// Fail event construction with [`Error::Validation`]
+    Reject,
+    /// Upload input and output values as media and send their media token
+    ///
+    /// Uses the same upload as
+    /// [`ClientBuilder::media_offload_threshold`](crate::ClientBuilder::media_offload_threshold).
+    /// Values that are not uploaded are truncated: metadata, values of events
+    /// built with `build_event` or queued in a batcher, and values whose
+    /// upload failed.
+    Externalize,
+}
+
+/// Maximum serialized size for individual JSON fields and the policy applied above it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSizeLimit {
+    /// Maximum serialized size of a single field in bytes
+    ///
+    /// `usize::MAX` when only input and output are limited.
+    pub max_bytes: usize,
+    /// Maximum serialized size of input values, if lower than `max_bytes`
+    pub max_input_bytes: Option<usize>,
+    /// Maximum serialized size of output values, if lower than `max_bytes`
+    pub max_output_bytes: Option<usize>,
+    /// What to do with values above their limit
+    pub policy: FieldSizePolicy,
+}
+
+impl BatchInterceptor for FieldSizeLimit {
+    /// Apply the input and output limits to events the builders did not check
+    fn on_event(&self, event: &mut IngestionEvent) {
+        let Some([input, output, metadata]) = crate::interceptor::payload_fields(event) else {
+            return;
+        };
+        let lenient = FieldSizeLimit {
+            policy: match self.policy {
+                FieldSizePolicy::Reject | FieldSizePolicy::Externalize => FieldSizePolicy::Truncate,
+                policy => policy,
+            },
+            ..*self
+        };
+
+        // Values already altered when the event was built are left alone
+        let recorded = match metadata {
+            Some(Some(Value::Object(map))) => map
+                .get(OVERSIZED_FIELDS_KEY)
+                .and_then(Value::as_object)
+                .map(|fields| fields.keys().cloned().collect())
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        let mut oversized = Map::new();
+        for (field, slot) in [("input", input), ("output", output)] {
+            if recorded.iter().any(|name| name == field) {
+                continue;
+            }
+            // Media tokens of offloaded values are never altered
+            if slot
+                .as_ref()
+                .is_some_and(|value| value.as_ref().is_some_and(crate::media::is_media_token))
+            {
+                continue;
+            }
+            let Some(value) = slot.as_mut().and_then(Option::take) else {
+                continue;
+            };
+            match lenient.guard(field, Some(value.clone()), &mut oversized) {
+                Ok(Some(value)) => *slot = Some(Some(value)),
+                Ok(None) => *slot = None,
+                Err(_) => *slot = Some(Some(value)),
+            }
+        }
+        if oversized.is_empty() {
+            return;
+        }
+
+        tracing::debug!(fields = ?oversized.keys().collect::<Vec<_>>(), policy = ?self.policy, "Field size limit altered oversized fields");
+        *metadata = Some(Some(record_oversized(metadata.take().flatten(), oversized)));
+    }
+}
+
+/// Default maximum length of observation, trace, and score names in characters
+pub const DEFAULT_MAX_NAME_CHARS: usize = 1_000;
+
+/// Default maximum length of observation status messages in characters
+pub const DEFAULT_MAX_STATUS_MESSAGE_CHARS: usize = 10_000;
+
+/// Strategy for handling names and status messages that exceed their limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextLengthPolicy {
+    /// Cut the value down to the limit and log a warning (default)
+    #[default]
+    Truncate,
+    /// Fail event construction with [`Error::Validation`]
+    Reject,
+}
+
+/// Maximum lengths for names and status messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLengthLimits {
+    /// Maximum length of names in characters
+    pub max_name_chars: usize,
+    /// Maximum length of status messages in characters
+    pub max_status_message_chars: usize,
+    /// What to do with values above the limit
+    pub policy: TextLengthPolicy,
+}
+
+impl Default for TextLengthLimits {
+    fn default() -> Self {
+        Self {
+            max_name_chars: DEFAULT_MAX_NAME_CHARS,
+            max_status_message_chars: DEFAULT_MAX_STATUS_MESSAGE_CHARS,
+            policy: TextLengthPolicy::default(),
+        }
+    }
+}
+
+impl TextLengthLimits {
+    /// Apply the name limit
+    pub(crate) fn name(&self, value: String) -> Result<String> {
+        self.limit("name", value, self.max_name_chars)
+    }
+
+    /// Apply the status message limit
+    pub(crate) fn status_message(&self, value: String) -> Result<String> {
+        self.limit("status_message", value, self.max_status_message_chars)
+    }
+
+    fn limit(&self, field: &str, value: String, max_chars: usize) -> Result<String> {
+        // Byte length is an upper bound on the character count
+        if value.len() <= max_chars {
+            return Ok(value);
+        }
+        let chars = value.chars().count();
+        if chars <= max_chars {
+            return Ok(value);
+        }
+
+        match self.policy {
+            TextLengthPolicy::Reject => Err(Error::Validation(format!(
+                "{field} is {chars} characters, exceeding the maximum of {max_chars}"
+            ))),
+            TextLengthPolicy::Truncate => {
+                tracing::warn!(
+                    field,
+                    length = chars,
+                    max_length = max_chars,
+                    "Truncating {field} to the maximum allowed length"
+                );
+                Ok(value.chars().take(max_chars).collect())
+            }
+        }
+    }
+}
+
+/// Input, output, and metadata after the size guard has been applied
+#[derive(Debug, Default)]
+pub(crate) struct GuardedFields {
+    pub input: Option<Value>,
+    pub output: Option<Value>,
+    pub metadata: Option<Value>,
+}
+
+impl FieldSizeLimit {
+    /// Create a limit with the default [`FieldSizePolicy::Truncate`] policy
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            max_input_bytes: None,
+            max_output_bytes: None,
+            policy: FieldSizePolicy::default(),
+        }
+    }
+
+    /// Limit of the field named `field`
+    pub(crate) fn limit_for(&self, field: &str) -> usize {
+        let specific = match field {
+            "input" => self.max_input_bytes,
+            "output" => self.max_output_bytes,
+            _ => None,
+        };
+        specific.map_or(self.max_bytes, |max| max.min(self.max_bytes))
+    }
+
+    /// Apply the limit to a set of event fields
+    pub(crate) fn apply(
+        &self,
+        input: Option<Value>,
+        output: Option<Value>,
+        metadata: Option<Value>,
+    ) -> Result<GuardedFields> {
+        let mut oversized = Map::new();
+
+        let input = self.guard("input", input, &mut oversized)?;
+        let output = self.guard("output", output, &mut oversized)?;
+        let mut metadata = self.guard("metadata", metadata, &mut oversized)?;
+
+        if !oversized.is_empty() {
+            metadata = Some(record_oversized(metadata, oversized));
+        }
+
+        Ok(GuardedFields {
+            input,
+            output,
+            metadata,
+        })
+    }
+
+    /// Apply the limit to input and output values only, recording the sizes
+    /// of altered values in `metadata`
+    pub(crate) fn apply_payload(
+        &self,
+        input: Option<Value>,
+        output: Option<Value>,
+        metadata: Option<Value>,
+    ) -> Result<GuardedFields> {
+        let mut oversized = Map::new();
+
+        let input = self.guard("input", input, &mut oversized)?;
+        let output = self.guard("output", output, &mut oversized)?;
+        let metadata = if oversized.is_empty() {
+            metadata
+        } else {
+            Some(record_oversized(metadata, oversized))
+        };
+
+        Ok(GuardedFields {
+            input,
+            output,
+            metadata,
+        })
+    }
+
+    fn guard(
+        &self,
+        field: &str,
+        value: Option<Value>,
+        oversized: &mut Map<String, Value>,
+    ) -> Result<Option<Value>> {
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        let max_bytes = self.limit_for(field);
+        let serialized = serde_json::to_string(&value)?;
+        let size = serialized.len();
+        if size <= max_bytes {
+            return Ok(Some(value));
+        }
+
+        let replacement = match self.policy {
+            FieldSizePolicy::Reject => {
+                return Err(Error::Validation(format!(
+                    "{field} is {size} bytes, exceeding the maximum field size of {max_bytes} bytes"
+                )));
+            }
+            FieldSizePolicy::Truncate | FieldSizePolicy::Externalize => {
+                Some(truncate_serialized(&serialized, max_bytes))
+            }
+            FieldSizePolicy::Omit => Some(Value::String(format!("[omitted: {size} bytes]"))),
+            FieldSizePolicy::Drop => None,
+            FieldSizePolicy::Hash => Some(Value::String(sha256_hex(&serialized))),
+        };
+
+        oversized.insert(field.to_string(), Value::from(size));
+        Ok(replacement)
+    }
+}
+
+/// String of the start of `serialized` and the marker, whose own serialized
+/// form is at most `max_bytes`
+///
+/// The result is sent as a JSON string, so its quotes and escapes count
+/// towards the limit. Below [`MIN_FIELD_BYTES`], the marker itself is cut.
+fn truncate_serialized(serialized: &str, max_bytes: usize) -> Value {
+    let Some(budget) = max_bytes.checked_sub(MIN_FIELD_BYTES) else {
+        let cut = max_bytes.saturating_sub(2);
+        return Value::String(TRUNCATION_MARKER[..cut].to_string());
+    };
+    let mut used = 0;
+    let mut cut = 0;
+    for (index, c) in serialized.char_indices() {
+        used += escaped_len(c);
+        if used > budget {
+            break;
+        }
+        cut = index + c.len_utf8();
+    }
+    Value::String(format!("{}{}", &serialized[..cut], TRUNCATION_MARKER))
+}
+
+/// Length of `c` inside a serialized JSON string
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{8}' | '\u{c}' => 2,
+        c if c < ' ' => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// `sha256:` followed by the hex-encoded SHA-256 hash of `text`
+fn sha256_hex(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("sha256:{hex}")
+}
+
+/// Attach the original sizes of altered fields to the metadata value and
+/// mark it as truncated
+///
+/// Sizes already recorded, e.g. when the event was built, are kept.
+fn record_oversized(metadata: Option<Value>, oversized: Map<String, Value>) -> Value {
+    let mut map = metadata_object(metadata);
+    match map.get_mut(OVERSIZED_FIELDS_KEY) {
+        Some(Value::Object(recorded)) => recorded.extend(oversized),
+        _ => {
+            map.insert(OVERSIZED_FIELDS_KEY.to_string(), Value::Object(oversized));
+        }
+    }
+    map.insert(TRUNCATED_KEY.to_string(), Value::Bool(true));
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_small_fields_are_untouched() {
+        let limit = FieldSizeLimit::new(1024);
+        let fields = limit
+            .apply(Some(json!({"q": "hi"})), None, Some(json!({"k": "v"})))
+            .unwrap();
+
+        assert_eq!(fields.input, Some(json!({"q": "hi"})));
+        assert_eq!(fields.output, None);
+        assert_eq!(fields.metadata, Some(json!({"k": "v"})));
+    }
+
+    #[test]
+    fn test_truncate_records_original_size() {
+        let limit = FieldSizeLimit::new(32);
+        let big = json!({"text": "x".repeat(100)});
+        let original = serde_json::to_string(&big).unwrap().len();
+
+        let fields = limit
+            .apply(Some(big), None, Some(json!({"k": "v"})))
+            .unwrap();
+
+        let input = fields.input.unwrap();
+        let text = input.as_str().unwrap();
+        assert!(text.len() <= 32);
+        assert!(text.ends_with(TRUNCATION_MARKER));
+
+        let metadata = fields.metadata.unwrap();
+        assert_eq!(metadata["k"], "v");
+        assert_eq!(metadata[OVERSIZED_FIELDS_KEY]["input"], original);
+    }
+
+    #[test]
+    fn test_truncated_values_fit_the_limit() {
+        let values = [
+            json!({"text": "x".repeat(100)}),
+            json!("quotes \" and \\ backslashes ".repeat(10)),
+            json!("new\nlines\tand\u{1}controls ".repeat(10)),
+            json!("ééé 日本語 🎉 ".repeat(10)),
+        ];
+        for value in values {
+            let serialized = serde_json::to_string(&value).unwrap();
+            for max_bytes in 2..=serialized.len() {
+                let truncated = truncate_serialized(&serialized, max_bytes);
+                let size = serde_json::to_string(&truncated).unwrap().len();
+                assert!(size <= max_bytes, "{size} > {max_bytes} for {value}");
+                if max_bytes >= MIN_FIELD_BYTES {
+                    assert!(truncated.as_str().unwrap().ends_with(TRUNCATION_MARKER));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_truncate_respects_char_boundaries() {
+        let limit = FieldSizeLimit::new(20);
+        let fields = limit
+            .apply(Some(json!("ééééééééééééééééééééé")), None, None)
+            .unwrap();
+        assert!(fields.input.unwrap().as_str().is_some());
+    }
+
+    #[test]
+    fn test_omit_replaces_value() {
+        let limit = FieldSizeLimit {
+            policy: FieldSizePolicy::Omit,
+            ..FieldSizeLimit::new(8)
+        };
+        let fields = limit
+            .apply(None, Some(json!("a long output value")), None)
+            .unwrap();
+
+        assert_eq!(fields.output, Some(json!("[omitted: 21 bytes]")));
+        assert_eq!(fields.metadata.unwrap()[OVERSIZED_FIELDS_KEY]["output"], 21);
+    }
+
+    #[test]
+    fn test_reject_returns_validation_error() {
+        let limit = FieldSizeLimit {
+            policy: FieldSizePolicy::Reject,
+            ..FieldSizeLimit::new(8)
+        };
+        let result = limit.apply(Some(json!("a long input value")), None, None);
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    #[test]
+    fn test_text_limits_truncate() {
+        let limits = TextLengthLimits {
+            max_name_chars: 4,
+            ..TextLengthLimits::default()
+        };
+        assert_eq!(limits.name("abc".to_string()).unwrap(), "abc");
+        assert_eq!(limits.name("ééééé".to_string()).unwrap(), "éééé");
+        assert_eq!(limits.status_message("ééééé".to_string()).unwrap(), "ééééé");
+    }
+
+    #[test]
+    fn test_text_limits_reject() {
+        let limits = TextLengthLimits {
+            max_status_message_chars: 3,
+            policy: TextLengthPolicy::Reject,
+            ..TextLengthLimits::default()
+        };
+        let result = limits.status_message("failure".to_string());
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+
+    fn span_event(input: Value, output: Value, metadata: Option<Value>) -> IngestionEvent {
+        use langfuse_client_base::models::{
+            ingestion_event_one_of_2::Type, CreateSpanBody, IngestionEventOneOf2,
+        };
+
+        let mut body = CreateSpanBody::new();
+        body.input = Some(Some(input));
+        body.output = Some(Some(output));
+        body.metadata = metadata.map(Some);
+        IngestionEvent::IngestionEventOneOf2(Box::new(IngestionEventOneOf2::new(
+            "event-1".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            body,
+            Type::SpanCreate,
+        )))
+    }
+
+    fn span_fields(event: &IngestionEvent) -> [Option<Value>; 3] {
+        let IngestionEvent::IngestionEventOneOf2(e) = event else {
+            panic!("expected a span-create event");
+        };
+        [
+            e.body.input.clone().flatten(),
+            e.body.output.clone().flatten(),
+            e.body.metadata.clone().flatten(),
+        ]
+    }
+
+    #[test]
+    fn test_interceptor_marks_altered_events() {
+        let policy = FieldSizeLimit {
+            max_input_bytes: Some(32),
+            ..FieldSizeLimit::new(usize::MAX)
+        };
+        let mut event = span_event(
+            json!({"document": "x".repeat(100)}),
+            json!("y".repeat(100)),
+            Some(json!({"k": "v"})),
+        );
+        policy.on_event(&mut event);
+
+        let [input, output, metadata] = span_fields(&event);
+        let input = input.unwrap();
+        assert!(input.as_str().unwrap().ends_with(TRUNCATION_MARKER));
+        assert!(input.as_str().unwrap().len() <= 32);
+        assert_eq!(output, Some(json!("y".repeat(100))));
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata["k"], "v");
+        assert_eq!(metadata[TRUNCATED_KEY], true);
+        assert_eq!(metadata[OVERSIZED_FIELDS_KEY]["input"], 115);
+
+        // Small payloads are left alone
+        let mut event = span_event(json!("hi"), json!("there"), None);
+        policy.on_event(&mut event);
+        assert_eq!(span_fields(&event)[2], None);
+    }
+
+    #[test]
+    fn test_interceptor_drop_and_hash() {
+        let mut event = span_event(json!("a long input"), json!("a long output"), None);
+        FieldSizeLimit {
+            policy: FieldSizePolicy::Drop,
+            ..FieldSizeLimit::new(8)
+        }
+        .on_event(&mut event);
+        let [input, output, metadata] = span_fields(&event);
+        assert_eq!((input, output), (None, None));
+        assert_eq!(metadata.unwrap()[TRUNCATED_KEY], true);
+
+        let mut event = span_event(json!("a long input"), json!("ok"), None);
+        let hash_limit = FieldSizeLimit {
+            policy: FieldSizePolicy::Hash,
+            ..FieldSizeLimit::new(8)
+        };
+        hash_limit.on_event(&mut event);
+        let [input, output, _] = span_fields(&event);
+        let hash = input.unwrap();
+        assert!(hash.as_str().unwrap().starts_with("sha256:"));
+        assert_eq!(hash.as_str().unwrap().len(), "sha256:".len() + 64);
+        assert_eq!(output, Some(json!("ok")));
+
+        // A second pass leaves the already hashed value alone
+        let before = span_fields(&event);
+        hash_limit.on_event(&mut event);
+        assert_eq!(span_fields(&event), before);
+    }
+
+    #[test]
+    fn test_interceptor_truncates_instead_of_rejecting() {
+        let mut event = span_event(json!("a long input value"), json!("ok"), None);
+        FieldSizeLimit {
+            policy: FieldSizePolicy::Reject,
+            ..FieldSizeLimit::new(16)
+        }
+        .on_event(&mut event);
+        let [input, _, metadata] = span_fields(&event);
+        assert!(input
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .ends_with(TRUNCATION_MARKER));
+        assert_eq!(metadata.unwrap()[OVERSIZED_FIELDS_KEY]["input"], 20);
+    }
+
+    #[test]
+    fn test_non_object_metadata_is_wrapped() {
+        let limit = FieldSizeLimit::new(8);
+        let fields = limit
+            .apply(Some(json!("a long input value")), None, Some(json!([1, 2])))
+            .unwrap();
+
+        let metadata = fields.metadata.unwrap();
+        assert_eq!(metadata["metadata"], json!([1, 2]));
+        assert!(metadata[OVERSIZED_FIELDS_KEY]["input"].is_number());
+    }
+}
@@ -0,0 +1,234 @@
+//! Sampled capture of outgoing ingestion payloads to a local file
+//!
+//! When data in Langfuse does not match what an application believes it
+//! sent, the payload actually sent is the first thing to look at. A
+//! [`PayloadCapture`] configured on the [`ClientBuilder`](crate::ClientBuilder)
+//! appends a sample of the ingestion requests, from the client and from
+//! batchers alike, to a JSON Lines file:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{ClientBuilder, PayloadCapture};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .capture_payloads(
+//!         PayloadCapture::new("/tmp/langfuse-payloads.jsonl")
+//!             .sample_rate(0.1)
+//!             .max_file_bytes(10 * 1024 * 1024),
+//!     )
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each line holds the capture time, the endpoint, and the request body:
+//!
+//! ```json
+//! {"captured_at":"2024-01-01T00:00:00.000Z","endpoint":"/api/public/ingestion","body":{"batch":[...]}}
+//! ```
+//!
+//! Payloads pass through a [`Redactor`] before they are written, the default
+//! one unless [`PayloadCapture::redactor`] sets another, so secrets and PII do
+//! not end up on disk. Once the file would grow past
+//! [`PayloadCapture::max_file_bytes`] it is rotated: `payloads.jsonl` becomes
+//! `payloads.jsonl.1`, `payloads.jsonl.1` becomes `payloads.jsonl.2`, and so
+//! on up to [`PayloadCapture::max_files`]. Capture is best effort: failing to
+//! write is logged and never fails the request.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use rand::{rng, RngExt};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::error::{run_blocking, Error, Result};
+use crate::security::Redactor;
+
+/// Default size at which the capture file is rotated
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Default number of rotated files kept besides the current one
+pub const DEFAULT_MAX_FILES: usize = 3;
+
+/// Writes a sample of outgoing payloads to a rotating JSON Lines file
+///
+/// See the [module documentation](self).
+#[derive(Debug)]
+pub struct PayloadCapture {
+    path: PathBuf,
+    sample_rate: f64,
+    max_file_bytes: u64,
+    max_files: usize,
+    redactor: Redactor,
+    file: Arc<Mutex<Option<File>>>,
+}
+
+impl PayloadCapture {
+    /// Capture every payload to `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            sample_rate: 1.0,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+            redactor: Redactor::default(),
+            file: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Capture only this fraction of payloads, between 0.0 and 1.0
+    #[must_use]
+    pub fn sample_rate(mut self, rate: f64) -> Self {
+        self.sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Rotate the file once it would grow past `bytes`
+    #[must_use]
+    pub fn max_file_bytes(mut self, bytes: u64) -> Self {
+        self.max_file_bytes = bytes;
+        self
+    }
+
+    /// Keep this many rotated files besides the current one
+    #[must_use]
+    pub fn max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+
+    /// Redact payloads with `redactor` before writing them
+    #[must_use]
+    pub fn redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Path of the current capture file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Capture the body of a request to `endpoint` if it is sampled
+    ///
+    /// The file is written and rotated on the blocking thread pool.
+    pub(crate) async fn capture(&self, endpoint: &str, body: &impl Serialize) {
+        if self.sample_rate < 1.0 && !rng().random_bool(self.sample_rate) {
+            return;
+        }
+        if let Err(e) = self.write(endpoint, body).await {
+            tracing::warn!("{e}");
+        }
+    }
+
+    async fn write(&self, endpoint: &str, body: &impl Serialize) -> Result<()> {
+        let body = self.redactor.redact_json(serde_json::to_value(body)?);
+        let record = json!({
+            "captured_at": Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "endpoint": endpoint,
+            "body": body,
+        });
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+
+        let file = Arc::clone(&self.file);
+        let path = self.path.clone();
+        let (max_file_bytes, max_files) = (self.max_file_bytes, self.max_files);
+        run_blocking(move || {
+            let mut file = file
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if let Some(current) = file.as_ref() {
+                let size = current.metadata().map_err(|e| io_error(&path, e))?.len();
+                if size > 0 && size + line.len() as u64 > max_file_bytes {
+                    *file = None;
+                    rotate(&path, max_files)?;
+                }
+            }
+            let current = match file.as_mut() {
+                Some(current) => current,
+                None => file.insert(
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&path)
+                        .map_err(|e| io_error(&path, e))?,
+                ),
+            };
+            current.write_all(&line).map_err(|e| io_error(&path, e))
+        })
+        .await
+    }
+}
+
+/// Shift `path.N` to `path.N+1`, dropping the oldest, and move the current file to `path.1`
+fn rotate(path: &Path, max_files: usize) -> Result<()> {
+    if max_files == 0 {
+        return std::fs::remove_file(path).map_err(|e| io_error(path, e));
+    }
+    let rotated = |n: usize| PathBuf::from(format!("{}.{n}", path.display()));
+    for n in (1..max_files).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            std::fs::rename(&from, rotated(n + 1)).map_err(|e| io_error(path, e))?;
+        }
+    }
+    std::fs::rename(path, rotated(1)).map_err(|e| io_error(path, e))
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> Error {
+    Error::io(
+        format!("Failed to write payload capture file {}", path.display()),
+        error,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture_redacts_and_rotates() {
+        let path =
+            std::env::temp_dir().join(format!("langfuse-capture-{}.jsonl", uuid::Uuid::new_v4()));
+        let capture = PayloadCapture::new(&path).max_file_bytes(200).max_files(1);
+
+        for i in 0..3 {
+            capture
+                .capture(
+                    "/api/public/ingestion",
+                    &json!({"batch": [{"id": i, "password": "hunter2"}]}),
+                )
+                .await;
+        }
+
+        let current = std::fs::read_to_string(&path).unwrap();
+        let rotated_path = PathBuf::from(format!("{}.1", path.display()));
+        let rotated = std::fs::read_to_string(&rotated_path).unwrap();
+        assert_eq!(current.lines().count() + rotated.lines().count(), 2);
+
+        let record: serde_json::Value =
+            serde_json::from_str(current.lines().last().unwrap()).unwrap();
+        assert_eq!(record["endpoint"], "/api/public/ingestion");
+        assert_eq!(record["body"]["batch"][0]["id"], 2);
+        assert_eq!(record["body"]["batch"][0]["password"], "[REDACTED]");
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_zero_sample_rate_captures_nothing() {
+        let path =
+            std::env::temp_dir().join(format!("langfuse-capture-{}.jsonl", uuid::Uuid::new_v4()));
+        let capture = PayloadCapture::new(&path).sample_rate(0.0);
+        capture
+            .capture("/api/public/ingestion", &json!({"batch": []}))
+            .await;
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,467 @@
+//! Organization-wide policies for trace attribution and naming
+//!
+//! [`TraceRequirements`] configured on the
+//! [`ClientBuilder`](crate::ClientBuilder) lists metadata keys and tags every
+//! trace must carry, such as a `tenant_id`. Traces created with
+//! [`LangfuseClient::trace`](crate::LangfuseClient::trace) are checked before
+//! they are sent; depending on the [`TraceRequirementPolicy`], a trace missing
+//! any of them is either rejected with [`Error::Validation`] or sent with the
+//! [`UNTAGGED_TAG`] tag so it can be found and attributed later.
+//!
+//! ```no_run
+//! use langfuse_ergonomic::policy::{TraceRequirementPolicy, TraceRequirements};
+//! use langfuse_ergonomic::ClientBuilder;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .trace_requirements(
+//!         TraceRequirements::new()
+//!             .metadata_key("tenant_id")
+//!             .policy(TraceRequirementPolicy::TagUntagged),
+//!     )
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A [`NamingPolicy`] keeps trace, observation, and score names consistent
+//! enough to build dashboards on: a per-service prefix, lowercase-kebab case,
+//! and a maximum length. Names that break it are rejected or, with
+//! [`NamingMode::Normalize`], rewritten to comply.
+//!
+//! ```no_run
+//! use langfuse_ergonomic::policy::{NamingMode, NamingPolicy};
+//! use langfuse_ergonomic::ClientBuilder;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .naming_policy(
+//!         NamingPolicy::new()
+//!             .prefix("checkout-")
+//!             .kebab_case(true)
+//!             .max_length(64)
+//!             .mode(NamingMode::Normalize),
+//!     )
+//!     .build()?;
+//! // A span named "Fetch Cart" is sent as "checkout-fetch-cart"
+//! # Ok(())
+//! # }
+//! ```
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// Tag added to traces missing required attributes under [`TraceRequirementPolicy::TagUntagged`]
+pub const UNTAGGED_TAG: &str = "untagged";
+
+/// What to do with traces that miss required attributes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraceRequirementPolicy {
+    /// Fail trace creation with [`Error::Validation`] (default)
+    #[default]
+    Reject,
+    /// Send the trace with the [`UNTAGGED_TAG`] tag and log a warning
+    TagUntagged,
+}
+
+/// Metadata keys and tags every trace must carry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceRequirements {
+    /// Metadata keys that must be present with a non-null, non-empty value
+    pub metadata_keys: Vec<String>,
+    /// Tags that must be present
+    pub tags: Vec<String>,
+    /// What to do with traces missing any of them
+    pub policy: TraceRequirementPolicy,
+}
+
+impl TraceRequirements {
+    /// Create requirements with nothing required yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a metadata key
+    #[must_use]
+    pub fn metadata_key(mut self, key: impl Into<String>) -> Self {
+        self.metadata_keys.push(key.into());
+        self
+    }
+
+    /// Require a tag
+    #[must_use]
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Set what happens to traces missing required attributes
+    #[must_use]
+    pub fn policy(mut self, policy: TraceRequirementPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Check a trace's metadata and tags, tagging it as untagged if allowed
+    pub(crate) fn enforce(&self, metadata: Option<&Value>, tags: &mut Vec<String>) -> Result<()> {
+        let mut missing: Vec<String> = self
+            .metadata_keys
+            .iter()
+            .filter(|key| {
+                !metadata
+                    .and_then(|m| m.get(key.as_str()))
+                    .is_some_and(|value| match value {
+                        Value::Null => false,
+                        Value::String(s) => !s.is_empty(),
+                        _ => true,
+                    })
+            })
+            .map(|key| format!("metadata `{key}`"))
+            .collect();
+        missing.extend(
+            self.tags
+                .iter()
+                .filter(|tag| !tags.contains(tag))
+                .map(|tag| format!("tag `{tag}`")),
+        );
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let missing = missing.join(", ");
+        match self.policy {
+            TraceRequirementPolicy::Reject => Err(Error::Validation(format!(
+                "Trace is missing required {missing}"
+            ))),
+            TraceRequirementPolicy::TagUntagged => {
+                tracing::warn!(missing = %missing, "Trace is missing required attributes, tagging as {UNTAGGED_TAG}");
+                if !tags.iter().any(|tag| tag == UNTAGGED_TAG) {
+                    tags.push(UNTAGGED_TAG.to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Kind of name checked by a [`NamingPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NameKind {
+    Trace,
+    Observation,
+    Score,
+}
+
+impl NameKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Trace => "Trace",
+            Self::Observation => "Observation",
+            Self::Score => "Score",
+        }
+    }
+}
+
+/// What to do with names that break a [`NamingPolicy`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NamingMode {
+    /// Fail with [`Error::Validation`] (default)
+    #[default]
+    Reject,
+    /// Rewrite the name to comply and log the change at debug level
+    ///
+    /// Fails with [`Error::Validation`] when no compliant name is left, for
+    /// example when the prefix alone exceeds the maximum length.
+    Normalize,
+}
+
+/// Naming conventions for trace, observation, and score names
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamingPolicy {
+    /// Prefix every name must start with, typically one per service
+    pub prefix: Option<String>,
+    /// Whether names must be lowercase-kebab (`a-z`, `0-9`, and single inner dashes)
+    pub kebab_case: bool,
+    /// Maximum name length in characters, including the prefix
+    pub max_length: Option<usize>,
+    /// Whether the prefix also applies to score names
+    ///
+    /// Scores are often compared across services, so they are exempt by default.
+    pub prefix_scores: bool,
+    /// What to do with names that break the policy
+    pub mode: NamingMode,
+}
+
+impl NamingPolicy {
+    /// Create a policy that accepts every name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require names to start with `prefix`
+    #[must_use]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Require lowercase-kebab names
+    #[must_use]
+    pub fn kebab_case(mut self, enabled: bool) -> Self {
+        self.kebab_case = enabled;
+        self
+    }
+
+    /// Limit names to `max_length` characters
+    #[must_use]
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Apply the prefix to score names too
+    #[must_use]
+    pub fn prefix_scores(mut self, enabled: bool) -> Self {
+        self.prefix_scores = enabled;
+        self
+    }
+
+    /// Set what happens to names that break the policy
+    #[must_use]
+    pub fn mode(mut self, mode: NamingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Check a name, normalizing it if allowed
+    pub(crate) fn apply(&self, kind: NameKind, name: String) -> Result<String> {
+        let prefix = self
+            .prefix
+            .as_deref()
+            .filter(|_| kind != NameKind::Score || self.prefix_scores);
+
+        let problems = self.problems(prefix, &name);
+        if problems.is_empty() {
+            return Ok(name);
+        }
+
+        match self.mode {
+            NamingMode::Reject => Err(Error::Validation(format!(
+                "{} name `{name}` {}",
+                kind.label(),
+                problems.join(", ")
+            ))),
+            NamingMode::Normalize => {
+                let normalized = self.normalize(prefix, &name);
+                // Normalizing can still fail, e.g. when nothing is left of the
+                // name or the prefix alone exceeds the maximum length
+                let mut problems = self.problems(prefix, &normalized);
+                if normalized.len() == prefix.map_or(0, str::len) {
+                    problems.insert(0, "is empty".to_string());
+                }
+                if !problems.is_empty() {
+                    return Err(Error::Validation(format!(
+                        "{} name `{name}` cannot be normalized: `{normalized}` {}",
+                        kind.label(),
+                        problems.join(", ")
+                    )));
+                }
+                tracing::debug!(original = %name, normalized = %normalized, "Normalized {} name", kind.label());
+                Ok(normalized)
+            }
+        }
+    }
+
+    fn problems(&self, prefix: Option<&str>, name: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(prefix) = prefix {
+            if !name.starts_with(prefix) {
+                problems.push(format!("does not start with `{prefix}`"));
+            }
+        }
+        if self.kebab_case && !is_kebab_case(name) {
+            problems.push("is not lowercase-kebab".to_string());
+        }
+        if let Some(max) = self.max_length {
+            if name.chars().count() > max {
+                problems.push(format!("is longer than {max} characters"));
+            }
+        }
+        problems
+    }
+
+    fn normalize(&self, prefix: Option<&str>, name: &str) -> String {
+        let mut rest = prefix
+            .and_then(|prefix| name.strip_prefix(prefix))
+            .unwrap_or(name)
+            .to_string();
+        if self.kebab_case {
+            rest = to_kebab_case(&rest);
+        }
+
+        let mut normalized = format!("{}{rest}", prefix.unwrap_or_default());
+        if let Some(max) = self.max_length {
+            if let Some((end, _)) = normalized.char_indices().nth(max) {
+                normalized.truncate(end);
+            }
+            if self.kebab_case {
+                normalized.truncate(normalized.trim_end_matches('-').len());
+            }
+        }
+        normalized
+    }
+}
+
+fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+}
+
+/// Lowercase `name`, splitting words at separators and lower-to-upper case changes
+fn to_kebab_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && previous_lower && !out.ends_with('-') {
+                out.push('-');
+            }
+            previous_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            out.push(c.to_ascii_lowercase());
+        } else {
+            previous_lower = false;
+            if !out.is_empty() && !out.ends_with('-') {
+                out.push('-');
+            }
+        }
+    }
+    out.truncate(out.trim_end_matches('-').len());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn requirements() -> TraceRequirements {
+        TraceRequirements::new()
+            .metadata_key("tenant_id")
+            .tag("prod")
+    }
+
+    #[test]
+    fn test_satisfied_requirements() {
+        let mut tags = vec!["prod".to_string()];
+        assert!(requirements()
+            .enforce(Some(&json!({"tenant_id": "acme"})), &mut tags)
+            .is_ok());
+        assert_eq!(tags, vec!["prod".to_string()]);
+    }
+
+    #[test]
+    fn test_reject_lists_missing_attributes() {
+        let err = requirements()
+            .enforce(Some(&json!({"tenant_id": ""})), &mut Vec::new())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: Trace is missing required metadata `tenant_id`, tag `prod`"
+        );
+    }
+
+    #[test]
+    fn test_tag_untagged() {
+        let requirements = requirements().policy(TraceRequirementPolicy::TagUntagged);
+        let mut tags = vec!["prod".to_string()];
+        requirements.enforce(None, &mut tags).unwrap();
+        requirements.enforce(None, &mut tags).unwrap();
+        assert_eq!(tags, vec!["prod".to_string(), UNTAGGED_TAG.to_string()]);
+    }
+
+    #[test]
+    fn test_naming_policy_accepts_compliant_names() {
+        let policy = NamingPolicy::new()
+            .prefix("checkout-")
+            .kebab_case(true)
+            .max_length(32);
+        assert_eq!(
+            policy
+                .apply(NameKind::Trace, "checkout-fetch-cart".to_string())
+                .unwrap(),
+            "checkout-fetch-cart"
+        );
+        // Scores are exempt from the prefix unless configured
+        assert!(policy
+            .apply(NameKind::Score, "accuracy".to_string())
+            .is_ok());
+        assert!(policy
+            .prefix_scores(true)
+            .apply(NameKind::Score, "accuracy".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn test_naming_policy_rejects() {
+        let err = NamingPolicy::new()
+            .prefix("checkout-")
+            .kebab_case(true)
+            .apply(NameKind::Observation, "Fetch Cart".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: Observation name `Fetch Cart` does not start with `checkout-`, is not lowercase-kebab"
+        );
+    }
+
+    #[test]
+    fn test_naming_policy_normalizes() {
+        let policy = NamingPolicy::new()
+            .prefix("checkout-")
+            .kebab_case(true)
+            .max_length(24)
+            .mode(NamingMode::Normalize);
+        let apply = |name: &str| policy.apply(NameKind::Trace, name.to_string()).unwrap();
+
+        assert_eq!(apply("Fetch Cart"), "checkout-fetch-cart");
+        assert_eq!(apply("fetchCart"), "checkout-fetch-cart");
+        assert_eq!(apply("checkout-Apply__Coupon!"), "checkout-apply-coupon");
+        assert_eq!(
+            apply("a very long name for a step"),
+            "checkout-a-very-long-nam"
+        );
+        assert_eq!(
+            apply("checkout-abcdefghijklmn x"),
+            "checkout-abcdefghijklmn"
+        );
+    }
+
+    #[test]
+    fn test_naming_policy_normalize_revalidates() {
+        let policy = NamingPolicy::new()
+            .prefix("checkout-")
+            .kebab_case(true)
+            .mode(NamingMode::Normalize);
+
+        let err = policy
+            .apply(NameKind::Trace, "!!!".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: Trace name `!!!` cannot be normalized: `checkout-` is empty, is not lowercase-kebab"
+        );
+
+        let err = policy
+            .max_length(8)
+            .apply(NameKind::Trace, "Fetch Cart".to_string())
+            .unwrap_err();
+        assert!(err.to_string().contains("cannot be normalized"));
+    }
+}
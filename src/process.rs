@@ -0,0 +1,157 @@
+//! Tracing of external commands
+//!
+//! LLM pipelines often shell out to tools such as ffmpeg, pandoc, or Python
+//! scripts. [`TraceContext::command`] runs a [`tokio::process::Command`] as a
+//! span: the program and arguments become the span input, and the exit code
+//! and duration its output. A command that fails to start or exits
+//! unsuccessfully marks the span as `ERROR`, with the tail of its stderr in the
+//! output.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use tokio::process::Command;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let trace = client.trace().name("transcribe").call().await?.context(&client);
+//!
+//! let run = trace
+//!     .command(Command::new("ffmpeg").args(["-i", "talk.mp4", "talk.wav"]))
+//!     .name("extract-audio")
+//!     .call()
+//!     .await?;
+//! let output = run.output?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+use std::process::Output;
+use std::time::{Duration, Instant};
+
+use bon::bon;
+use chrono::Utc;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+use crate::context::{ObservationHandle, TraceContext};
+use crate::error::Result;
+
+/// Default number of trailing stderr characters recorded for failed commands
+pub const DEFAULT_MAX_STDERR_CHARS: usize = 4_000;
+
+/// Result of a command run through [`TraceContext::command`]
+#[derive(Debug)]
+pub struct CommandRun {
+    /// Output of the command, or the error that prevented it from running
+    pub output: std::io::Result<Output>,
+    /// Time the command took
+    pub duration: Duration,
+    /// Span recording the command
+    pub span: ObservationHandle,
+}
+
+#[bon]
+impl TraceContext {
+    /// Run `command` to completion as a span under this context
+    ///
+    /// Stdout and stderr are captured. The span is named after the program
+    /// unless `name` is set. Errors creating the span are returned; the
+    /// command's own errors are in [`CommandRun::output`], and a failure to
+    /// record the end of the span is only logged so the output is not lost.
+    #[builder]
+    pub async fn command(
+        &self,
+        #[builder(start_fn)] command: &mut Command,
+        #[builder(into)] name: Option<String>,
+        metadata: Option<Value>,
+        /// Trailing stderr characters recorded for failed commands (default [`DEFAULT_MAX_STDERR_CHARS`])
+        max_stderr_chars: Option<usize>,
+    ) -> Result<CommandRun> {
+        let std_command = command.as_std();
+        let program = std_command.get_program().to_string_lossy().into_owned();
+        let args: Vec<String> = std_command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        let name = name.unwrap_or_else(|| {
+            Path::new(&program)
+                .file_name()
+                .map_or_else(|| program.clone(), |n| n.to_string_lossy().into_owned())
+        });
+
+        let span = self
+            .span()
+            .name(name)
+            .input(json!({"program": program, "args": args}))
+            .maybe_metadata(metadata)
+            .start_time(Utc::now())
+            .call()
+            .await?;
+
+        let started = Instant::now();
+        let output = command.output().await;
+        let duration = started.elapsed();
+        let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+
+        let max_stderr_chars = max_stderr_chars.unwrap_or(DEFAULT_MAX_STDERR_CHARS);
+        let (span_output, status_message) = match &output {
+            Ok(output) if output.status.success() => (
+                json!({"exit_code": output.status.code(), "duration_ms": duration_ms}),
+                None,
+            ),
+            Ok(output) => (
+                json!({
+                    "exit_code": output.status.code(),
+                    "duration_ms": duration_ms,
+                    "stderr": stderr_tail(&output.stderr, max_stderr_chars),
+                }),
+                Some(format!("{program} exited with {}", output.status)),
+            ),
+            Err(e) => (
+                json!({"duration_ms": duration_ms, "error": e.to_string()}),
+                Some(format!("{program} failed to start: {e}")),
+            ),
+        };
+        let level = status_message.as_ref().map(|_| "ERROR".to_string());
+
+        if let Err(e) = span
+            .update(Some(span_output), level, status_message, Some(Utc::now()))
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to record the end of a command span");
+        }
+
+        Ok(CommandRun {
+            output,
+            duration,
+            span,
+        })
+    }
+}
+
+/// Last `max_chars` characters of `stderr`, marked if cut
+fn stderr_tail(stderr: &[u8], max_chars: usize) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stderr = stderr.trim_end();
+    let chars = stderr.chars().count();
+    if chars <= max_chars {
+        return stderr.to_string();
+    }
+    let tail: String = stderr.chars().skip(chars - max_chars).collect();
+    format!("[truncated]...{tail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stderr_tail() {
+        assert_eq!(stderr_tail(b"error: boom\n", 100), "error: boom");
+        assert_eq!(
+            stderr_tail(b"warning\nerror: boom", 11),
+            "[truncated]...error: boom"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Named configuration profiles
+//!
+//! A profile bundles the credentials and base URL of one Langfuse project or
+//! instance, so tools and tests can switch between them by name with
+//! [`ClientBuilder::profile`](crate::ClientBuilder::profile).
+//!
+//! Each setting of profile `staging` is resolved from, in order of precedence:
+//!
+//! 1. The environment: `LANGFUSE_STAGING_PUBLIC_KEY`, `LANGFUSE_STAGING_SECRET_KEY`
+//!    and `LANGFUSE_STAGING_BASE_URL`. Dashes in the profile name become
+//!    underscores, so profile `eu-prod` reads `LANGFUSE_EU_PROD_*`.
+//! 2. The `[staging]` section of the config file, read from the path in
+//!    `LANGFUSE_CONFIG_FILE` or `~/.langfuse/config` by default:
+//!
+//! ```ini
+//! [staging]
+//! public_key = pk-lf-...
+//! secret_key = sk-lf-...
+//! base_url = https://staging.langfuse.example.com
+//!
+//! # Lines starting with `#` or `;` are comments
+//! [production]
+//! public_key = pk-lf-...
+//! secret_key = sk-lf-...
+//! ```
+//!
+//! The config file is optional when the environment provides the credentials.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Environment variable overriding the config file location
+pub const CONFIG_FILE_ENV: &str = "LANGFUSE_CONFIG_FILE";
+
+/// Default config file location, `~/.langfuse/config`
+pub fn default_config_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".langfuse").join("config"))
+}
+
+/// Settings resolved for one profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ProfileSettings {
+    pub(crate) public_key: String,
+    pub(crate) secret_key: String,
+    pub(crate) base_url: Option<String>,
+}
+
+/// Resolve a profile from the environment and the config file at `path`
+///
+/// Without a path, `LANGFUSE_CONFIG_FILE` or the default location is used and a
+/// missing file is not an error.
+pub(crate) fn resolve(name: &str, path: Option<&Path>) -> Result<ProfileSettings> {
+    let (path, required) = match path {
+        Some(path) => (Some(path.to_path_buf()), true),
+        None => match std::env::var_os(CONFIG_FILE_ENV) {
+            Some(path) => (Some(PathBuf::from(path)), true),
+            None => (default_config_path(), false),
+        },
+    };
+
+    let contents = match path {
+        Some(path) => match std::fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(Error::Configuration(format!(
+                    "Failed to read Langfuse config file {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        },
+        None => None,
+    };
+
+    resolve_with(name, contents.as_deref(), |key| std::env::var(key).ok())
+}
+
+fn resolve_with(
+    name: &str,
+    config: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<ProfileSettings> {
+    if name.trim().is_empty() {
+        return Err(Error::Configuration(
+            "Profile name cannot be empty".to_string(),
+        ));
+    }
+
+    let prefix = env_prefix(name);
+    let section = config
+        .map(|config| profile_section(config, name))
+        .unwrap_or_default();
+    let setting = |key: &str| {
+        env(&format!("{prefix}_{}", key.to_ascii_uppercase()))
+            .or_else(|| section.get(key).cloned())
+            .filter(|value| !value.is_empty())
+    };
+    let required = |key: &str| {
+        setting(key).ok_or_else(|| {
+            Error::Configuration(format!(
+                "Profile `{name}` has no {}: set {prefix}_{} or `{key}` in the [{name}] section of the config file",
+                key.replace('_', " "),
+                key.to_ascii_uppercase(),
+            ))
+        })
+    };
+
+    Ok(ProfileSettings {
+        public_key: required("public_key")?,
+        secret_key: required("secret_key")?,
+        base_url: setting("base_url"),
+    })
+}
+
+/// `LANGFUSE_<NAME>` with the profile name upper-cased and dashes replaced
+fn env_prefix(name: &str) -> String {
+    format!(
+        "LANGFUSE_{}",
+        name.trim()
+            .to_ascii_uppercase()
+            .replace(['-', '.', ' '], "_")
+    )
+}
+
+/// Key-value pairs of the `[name]` section of an INI-style config file
+fn profile_section(config: &str, name: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut in_section = false;
+    for line in config.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = section.trim() == name.trim();
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            values.insert(key.trim().to_string(), value.to_string());
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+# Langfuse profiles
+[staging]
+public_key = pk-lf-staging
+secret_key = "sk-lf-staging"
+base_url = https://staging.example.com
+
+[production]
+public_key = pk-lf-prod
+; no secret key here
+"#;
+
+    #[test]
+    fn test_env_prefix() {
+        assert_eq!(env_prefix("staging"), "LANGFUSE_STAGING");
+        assert_eq!(env_prefix("eu-prod"), "LANGFUSE_EU_PROD");
+    }
+
+    #[test]
+    fn test_resolve_from_config_file() {
+        let settings = resolve_with("staging", Some(CONFIG), |_| None).unwrap();
+        assert_eq!(
+            settings,
+            ProfileSettings {
+                public_key: "pk-lf-staging".to_string(),
+                secret_key: "sk-lf-staging".to_string(),
+                base_url: Some("https://staging.example.com".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_env_overrides_config_file() {
+        let env =
+            |key: &str| (key == "LANGFUSE_PRODUCTION_SECRET_KEY").then(|| "sk-lf-env".to_string());
+        let settings = resolve_with("production", Some(CONFIG), env).unwrap();
+        assert_eq!(settings.public_key, "pk-lf-prod");
+        assert_eq!(settings.secret_key, "sk-lf-env");
+        assert_eq!(settings.base_url, None);
+    }
+
+    #[test]
+    fn test_missing_profile_setting() {
+        let err = resolve_with("production", Some(CONFIG), |_| None).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Profile `production` has no secret key"));
+        assert!(resolve_with("dev", None, |_| None).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Projects and their API keys
+//!
+//! With a project's API key, [`LangfuseClient::current_project`] returns the
+//! project the client writes to. Provisioning tools that bootstrap Langfuse
+//! projects use an organization-scoped API key instead, which can create,
+//! update, and delete projects and manage their API keys:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::ClientBuilder;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Authenticated with an organization API key
+//! let admin = ClientBuilder::from_env()?.build()?;
+//!
+//! let project = admin.create_project().name("support-bot").call().await?;
+//! let key = admin
+//!     .create_project_api_key()
+//!     .project_id(&project.id)
+//!     .note("ci")
+//!     .call()
+//!     .await?;
+//!
+//! // The secret key is only returned once
+//! let client = ClientBuilder::new()
+//!     .public_key(key.public_key)
+//!     .secret_key(key.secret_key)
+//!     .build()?;
+//! assert_eq!(client.current_project().await?.id, project.id);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The builder methods creating and updating projects and API keys are
+//! implemented in the traces module to consolidate all client methods under
+//! a single #[bon] impl block.
+
+use langfuse_client_base::models::{ApiKeySummary, Project};
+
+use crate::client::LangfuseClient;
+use crate::error::{map_api_error, Error, Result};
+
+impl LangfuseClient {
+    /// Get the project of the client's API key
+    ///
+    /// Requires a project-scoped API key.
+    pub async fn current_project(&self) -> Result<Project> {
+        self.list_projects()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Api("No project found for the API key".to_string()))
+    }
+
+    /// Get the ID of the client's project
+    ///
+    /// Fetched with [`current_project`](Self::current_project) on first use and
+    /// cached for the client and its clones, unless set with
+    /// [`ClientBuilder::project_id`](crate::ClientBuilder::project_id).
+    pub async fn project_id(&self) -> Result<String> {
+        self.cached_project_id
+            .get_or_try_init(|| async { self.current_project().await.map(|project| project.id) })
+            .await
+            .cloned()
+    }
+
+    /// Project ID for UI URLs, if [project-scoped URLs](crate::ClientBuilder::project_scoped_urls) are enabled
+    ///
+    /// Fetched if not known yet and `fetch` is set.
+    pub(crate) async fn url_project_id(&self, fetch: bool) -> Option<String> {
+        if !self.project_scoped_urls {
+            return None;
+        }
+        if self.disabled || !fetch {
+            return self.cached_project_id.get().cloned();
+        }
+        self.project_id()
+            .await
+            .inspect_err(|e| {
+                tracing::warn!(error = %e, "Failed to fetch the project ID, using unscoped URLs");
+            })
+            .ok()
+    }
+
+    /// List the projects visible to the client's API key
+    ///
+    /// A project-scoped key sees its own project, an organization-scoped key
+    /// every project of the organization.
+    pub async fn list_projects(&self) -> Result<Vec<Project>> {
+        use langfuse_client_base::apis::projects_api;
+
+        projects_api::projects_get()
+            .configuration(self.configuration())
+            .call()
+            .await
+            .map(|projects| projects.data)
+            .map_err(map_api_error)
+    }
+
+    /// Delete a project
+    ///
+    /// Requires an organization-scoped API key. Langfuse deletes the project's
+    /// data in the background.
+    pub async fn delete_project(&self, project_id: impl Into<String>) -> Result<()> {
+        use langfuse_client_base::apis::projects_api;
+
+        let project_id = project_id.into();
+        projects_api::projects_delete()
+            .configuration(self.configuration())
+            .project_id(&project_id)
+            .call()
+            .await
+            .map(|_| ())
+            .map_err(map_api_error)
+    }
+
+    /// List the API keys of a project, without their secret keys
+    ///
+    /// Requires an organization-scoped API key.
+    pub async fn list_project_api_keys(
+        &self,
+        project_id: impl Into<String>,
+    ) -> Result<Vec<ApiKeySummary>> {
+        use langfuse_client_base::apis::projects_api;
+
+        let project_id = project_id.into();
+        projects_api::projects_get_api_keys()
+            .configuration(self.configuration())
+            .project_id(&project_id)
+            .call()
+            .await
+            .map(|list| list.api_keys)
+            .map_err(map_api_error)
+    }
+
+    /// Delete an API key of a project
+    ///
+    /// Requires an organization-scoped API key.
+    pub async fn delete_project_api_key(
+        &self,
+        project_id: impl Into<String>,
+        api_key_id: impl Into<String>,
+    ) -> Result<()> {
+        use langfuse_client_base::apis::projects_api;
+
+        let project_id = project_id.into();
+        let api_key_id = api_key_id.into();
+        projects_api::projects_delete_api_key()
+            .configuration(self.configuration())
+            .project_id(&project_id)
+            .api_key_id(&api_key_id)
+            .call()
+            .await
+            .map(|_| ())
+            .map_err(map_api_error)
+    }
+}
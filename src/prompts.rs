@@ -3,7 +3,258 @@
 //! This module contains types and utilities for prompt management.
 //! The actual client methods are implemented in the traces module to
 //! consolidate all client methods under a single #[bon] impl block.
+//!
+//! ## Placeholder checking
+//!
+//! [`PromptPlaceholders`] extracts the `{{variable}}` names and chat message
+//! placeholders from a fetched prompt, so the values supplied by the caller can
+//! be checked before compiling the prompt. Any type implementing [`PromptVars`]
+//! can be checked directly; with the `derive` feature the implementation can be
+//! generated with `#[derive(PromptVars)]`:
+//!
+//! ```rust,ignore
+//! use langfuse_ergonomic::prompts::check_prompt_vars;
+//! use langfuse_ergonomic::PromptVars;
+//!
+//! #[derive(PromptVars)]
+//! struct SupportVars {
+//!     customer: String,
+//!     #[prompt_var(rename = "chat_history")]
+//!     history: Vec<String>,
+//! }
+//!
+//! let prompt = client.get_prompt("support", None, None).await?;
+//! check_prompt_vars::<SupportVars>(&prompt)?;
+//! ```
+
+use std::collections::BTreeSet;
+
+use langfuse_client_base::models::{ChatMessageWithPlaceholders, Prompt};
+
+use crate::error::{Error, Result};
 
 // Re-export common types that might be useful
 // Note: CreatePromptRequest might be an enum or different structure
 // pub use langfuse_client_base::models::CreatePromptRequest;
+
+/// A set of values that can fill a prompt's placeholders
+///
+/// `NAMES` lists the variable names the type supplies. Implement it by hand or
+/// with `#[derive(PromptVars)]` (requires the `derive` feature).
+pub trait PromptVars {
+    /// Names of the variables supplied by this type
+    const NAMES: &'static [&'static str];
+}
+
+/// Placeholder names found in a prompt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PromptPlaceholders {
+    /// `{{variable}}` names used in the prompt text or chat message contents
+    pub variables: BTreeSet<String>,
+    /// Names of chat message placeholders (`{"type": "placeholder"}` messages)
+    pub messages: BTreeSet<String>,
+}
+
+impl PromptPlaceholders {
+    /// Extract the placeholders from a text or chat prompt
+    pub fn from_prompt(prompt: &Prompt) -> Self {
+        let mut placeholders = Self::default();
+        match prompt {
+            Prompt::PromptOneOf(chat) => {
+                for message in &chat.prompt {
+                    match message {
+                        ChatMessageWithPlaceholders::ChatMessage(message) => {
+                            placeholders
+                                .variables
+                                .extend(template_variables(&message.content));
+                        }
+                        ChatMessageWithPlaceholders::PlaceholderMessage(message) => {
+                            placeholders.messages.insert(message.name.clone());
+                        }
+                    }
+                }
+            }
+            Prompt::PromptOneOf1(text) => {
+                placeholders.variables = template_variables(&text.prompt);
+            }
+        }
+        placeholders
+    }
+
+    /// All placeholder names, variables and message placeholders combined
+    pub fn names(&self) -> BTreeSet<&str> {
+        self.variables
+            .iter()
+            .chain(&self.messages)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Check that `T` supplies exactly the placeholders of the prompt
+    pub fn check<T: PromptVars>(&self) -> Result<()> {
+        self.check_names(T::NAMES.iter().copied())
+    }
+
+    /// Check that the given names are exactly the placeholders of the prompt
+    ///
+    /// Useful for values held in a map, e.g. `placeholders.check_names(map.keys())`.
+    pub fn check_names<I, S>(&self, names: I) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let supplied: BTreeSet<String> = names
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        let expected = self.names();
+
+        let missing: Vec<&str> = expected
+            .iter()
+            .copied()
+            .filter(|name| !supplied.contains(*name))
+            .collect();
+        let unexpected: Vec<&str> = supplied
+            .iter()
+            .map(String::as_str)
+            .filter(|name| !expected.contains(name))
+            .collect();
+
+        if missing.is_empty() && unexpected.is_empty() {
+            return Ok(());
+        }
+
+        let mut problems = Vec::new();
+        if !missing.is_empty() {
+            problems.push(format!("missing {}", quote_names(&missing)));
+        }
+        if !unexpected.is_empty() {
+            problems.push(format!("unexpected {}", quote_names(&unexpected)));
+        }
+        Err(Error::Validation(format!(
+            "Prompt variables do not match placeholders: {}",
+            problems.join("; ")
+        )))
+    }
+}
+
+/// Check that `T` supplies exactly the placeholders of `prompt`
+pub fn check_prompt_vars<T: PromptVars>(prompt: &Prompt) -> Result<()> {
+    PromptPlaceholders::from_prompt(prompt).check::<T>()
+}
+
+/// Collect the `{{variable}}` names in a template
+fn template_variables(template: &str) -> BTreeSet<String> {
+    let mut variables = BTreeSet::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let name = rest[..end].trim();
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            variables.insert(name.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    variables
+}
+
+fn quote_names(names: &[&str]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chat_prompt() -> Prompt {
+        serde_json::from_value(json!({
+            "name": "support",
+            "version": 1,
+            "config": null,
+            "labels": [],
+            "tags": [],
+            "type": "chat",
+            "prompt": [
+                {"role": "system", "content": "You help {{ customer }} with {{product}}."},
+                {"type": "placeholder", "name": "history"},
+                {"role": "user", "content": "{{question}}"}
+            ]
+        }))
+        .unwrap()
+    }
+
+    struct SupportVars;
+
+    impl PromptVars for SupportVars {
+        const NAMES: &'static [&'static str] = &["customer", "product", "history", "question"];
+    }
+
+    struct PartialVars;
+
+    impl PromptVars for PartialVars {
+        const NAMES: &'static [&'static str] = &["customer", "extra"];
+    }
+
+    #[test]
+    fn test_template_variables() {
+        let vars = template_variables("Hi {{name}}, {{ name }} {{not a var}} {{unclosed");
+        assert_eq!(vars, BTreeSet::from(["name".to_string()]));
+    }
+
+    #[test]
+    fn test_extract_chat_placeholders() {
+        let placeholders = PromptPlaceholders::from_prompt(&chat_prompt());
+        assert_eq!(
+            placeholders.variables,
+            BTreeSet::from([
+                "customer".to_string(),
+                "product".to_string(),
+                "question".to_string()
+            ])
+        );
+        assert_eq!(
+            placeholders.messages,
+            BTreeSet::from(["history".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_check_prompt_vars() {
+        let prompt = chat_prompt();
+        assert!(check_prompt_vars::<SupportVars>(&prompt).is_ok());
+
+        let err = check_prompt_vars::<PartialVars>(&prompt).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: Prompt variables do not match placeholders: \
+             missing `history`, `product`, `question`; unexpected `extra`"
+        );
+    }
+
+    #[test]
+    fn test_check_names_for_text_prompt() {
+        let prompt: Prompt = serde_json::from_value(json!({
+            "name": "greeting",
+            "version": 1,
+            "config": null,
+            "labels": [],
+            "tags": [],
+            "type": "text",
+            "prompt": "Hello {{name}}"
+        }))
+        .unwrap();
+
+        let placeholders = PromptPlaceholders::from_prompt(&prompt);
+        let values = std::collections::HashMap::from([("name".to_string(), "Ada")]);
+        assert!(placeholders.check_names(values.keys()).is_ok());
+        assert!(placeholders.check_names(["name", "age"]).is_err());
+    }
+}
@@ -0,0 +1,388 @@
+//! Typed query parameters for list and get endpoints
+//!
+//! Langfuse lets callers select which groups of fields are returned by the trace
+//! and observation endpoints. Requesting only what is needed (for example, just
+//! ids and timestamps when scanning) greatly reduces payload size.
+//!
+//! Trace lists can also be sorted with an [`OrderBy`], which is sent in the
+//! `field.direction` form the API expects, and narrowed with a
+//! [`TraceFilter`]. The same filter selects the traces of
+//! [`trace_stats`](crate::LangfuseClient::trace_stats) and
+//! [`export_traces`](crate::LangfuseClient::export_traces):
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use chrono::{Duration, Utc};
+//! use langfuse_ergonomic::query::{OrderBy, SortDirection::Desc, TraceFilter};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let traces = client
+//!     .list_traces()
+//!     .order_by(OrderBy::Timestamp(Desc))
+//!     .filter(
+//!         TraceFilter::new()
+//!             .tags(["production", "chat"])
+//!             .environment(["prod"])
+//!             .from_timestamp(Utc::now() - Duration::days(1)),
+//!     )
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::traces::IntoTags;
+
+/// Field groups that can be selected on trace queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceField {
+    /// Identifiers, timestamps, name, and other top-level attributes (always returned)
+    Core,
+    /// Input, output, and metadata
+    Io,
+    /// Scores attached to the trace
+    Scores,
+    /// Observations belonging to the trace
+    Observations,
+    /// Aggregated latency and cost
+    Metrics,
+}
+
+impl TraceField {
+    /// Every trace field group
+    pub const ALL: [TraceField; 5] = [
+        TraceField::Core,
+        TraceField::Io,
+        TraceField::Scores,
+        TraceField::Observations,
+        TraceField::Metrics,
+    ];
+
+    /// Name of the field group as understood by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TraceField::Core => "core",
+            TraceField::Io => "io",
+            TraceField::Scores => "scores",
+            TraceField::Observations => "observations",
+            TraceField::Metrics => "metrics",
+        }
+    }
+}
+
+impl fmt::Display for TraceField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Field groups that can be selected on observation queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObservationField {
+    /// Identifiers, trace id, start/end time, parent id, and type (always returned)
+    Core,
+    /// Name, level, status message, version, environment, user, and session
+    Basic,
+    /// Completion start, creation, and update times
+    Time,
+    /// Input and output
+    Io,
+    /// Metadata
+    Metadata,
+    /// Model name and parameters
+    Model,
+    /// Usage and cost details
+    Usage,
+    /// Linked prompt name and version
+    Prompt,
+    /// Latency and time to first token
+    Metrics,
+}
+
+impl ObservationField {
+    /// Every observation field group
+    pub const ALL: [ObservationField; 9] = [
+        ObservationField::Core,
+        ObservationField::Basic,
+        ObservationField::Time,
+        ObservationField::Io,
+        ObservationField::Metadata,
+        ObservationField::Model,
+        ObservationField::Usage,
+        ObservationField::Prompt,
+        ObservationField::Metrics,
+    ];
+
+    /// Name of the field group as understood by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ObservationField::Core => "core",
+            ObservationField::Basic => "basic",
+            ObservationField::Time => "time",
+            ObservationField::Io => "io",
+            ObservationField::Metadata => "metadata",
+            ObservationField::Model => "model",
+            ObservationField::Usage => "usage",
+            ObservationField::Prompt => "prompt",
+            ObservationField::Metrics => "metrics",
+        }
+    }
+}
+
+impl fmt::Display for ObservationField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Direction of a sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    /// Smallest or oldest first
+    Asc,
+    /// Largest or newest first
+    Desc,
+}
+
+impl SortDirection {
+    /// Name of the direction as understood by the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        }
+    }
+}
+
+/// Sort order of trace lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderBy {
+    /// By trace ID
+    Id(SortDirection),
+    /// By trace timestamp
+    Timestamp(SortDirection),
+    /// By trace name
+    Name(SortDirection),
+    /// By user ID
+    UserId(SortDirection),
+    /// By release
+    Release(SortDirection),
+    /// By version
+    Version(SortDirection),
+    /// By whether the trace is public
+    Public(SortDirection),
+    /// By whether the trace is bookmarked
+    Bookmarked(SortDirection),
+    /// By session ID
+    SessionId(SortDirection),
+}
+
+impl OrderBy {
+    /// Field sorted on, as understood by the API
+    pub fn field(&self) -> &'static str {
+        match self {
+            OrderBy::Id(_) => "id",
+            OrderBy::Timestamp(_) => "timestamp",
+            OrderBy::Name(_) => "name",
+            OrderBy::UserId(_) => "userId",
+            OrderBy::Release(_) => "release",
+            OrderBy::Version(_) => "version",
+            OrderBy::Public(_) => "public",
+            OrderBy::Bookmarked(_) => "bookmarked",
+            OrderBy::SessionId(_) => "sessionId",
+        }
+    }
+
+    /// Direction of the sort
+    pub fn direction(&self) -> SortDirection {
+        match *self {
+            OrderBy::Id(direction)
+            | OrderBy::Timestamp(direction)
+            | OrderBy::Name(direction)
+            | OrderBy::UserId(direction)
+            | OrderBy::Release(direction)
+            | OrderBy::Version(direction)
+            | OrderBy::Public(direction)
+            | OrderBy::Bookmarked(direction)
+            | OrderBy::SessionId(direction) => direction,
+        }
+    }
+}
+
+impl fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.field(), self.direction().as_str())
+    }
+}
+
+/// Traces to list, summarize, or export
+///
+/// Every criterion that is set must match; an empty filter matches all traces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceFilter {
+    /// Only traces with this name
+    pub name: Option<String>,
+    /// Only traces of this user
+    pub user_id: Option<String>,
+    /// Only traces of this session
+    pub session_id: Option<String>,
+    /// Only traces of this version
+    pub version: Option<String>,
+    /// Only traces of this release
+    pub release: Option<String>,
+    /// Only traces having all of these tags
+    pub tags: Vec<String>,
+    /// Only traces from one of these environments
+    pub environments: Vec<String>,
+    /// Only traces at or after this time
+    pub from_timestamp: Option<DateTime<Utc>>,
+    /// Only traces before this time
+    pub to_timestamp: Option<DateTime<Utc>>,
+}
+
+impl TraceFilter {
+    /// Match all traces
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only traces with this name
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Only traces of this user
+    #[must_use]
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Only traces of this session
+    #[must_use]
+    pub fn session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Only traces of this version
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Only traces of this release
+    #[must_use]
+    pub fn release(mut self, release: impl Into<String>) -> Self {
+        self.release = Some(release.into());
+        self
+    }
+
+    /// Only traces having all of these tags
+    #[must_use]
+    pub fn tags(mut self, tags: impl IntoTags) -> Self {
+        self.tags = tags.into_tags();
+        self
+    }
+
+    /// Only traces from one of these environments, given as names or
+    /// [`Environment`](crate::Environment)s
+    #[must_use]
+    pub fn environment(
+        mut self,
+        environments: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.environments = environments.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Only traces at or after this time
+    #[must_use]
+    pub fn from_timestamp(mut self, from: DateTime<Utc>) -> Self {
+        self.from_timestamp = Some(from);
+        self
+    }
+
+    /// Only traces before this time
+    #[must_use]
+    pub fn to_timestamp(mut self, to: DateTime<Utc>) -> Self {
+        self.to_timestamp = Some(to);
+        self
+    }
+}
+
+/// Format a time bound the way the list endpoints expect
+pub(crate) fn timestamp_param(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Join field groups into the comma-separated form expected by the API
+///
+/// Duplicates are removed while keeping the first occurrence; an empty selection
+/// yields `None` so the server default applies.
+pub(crate) fn join_fields<T: fmt::Display + PartialEq>(fields: &[T]) -> Option<String> {
+    let mut unique: Vec<&T> = Vec::with_capacity(fields.len());
+    for field in fields {
+        if !unique.contains(&field) {
+            unique.push(field);
+        }
+    }
+
+    if unique.is_empty() {
+        return None;
+    }
+
+    Some(
+        unique
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_trace_fields() {
+        assert_eq!(
+            join_fields(&[TraceField::Core, TraceField::Metrics]),
+            Some("core,metrics".to_string())
+        );
+        assert_eq!(join_fields::<TraceField>(&[]), None);
+    }
+
+    #[test]
+    fn test_order_by_format() {
+        assert_eq!(
+            OrderBy::Timestamp(SortDirection::Desc).to_string(),
+            "timestamp.desc"
+        );
+        assert_eq!(
+            OrderBy::SessionId(SortDirection::Asc).to_string(),
+            "sessionId.asc"
+        );
+    }
+
+    #[test]
+    fn test_join_fields_deduplicates() {
+        assert_eq!(
+            join_fields(&[
+                ObservationField::Core,
+                ObservationField::Usage,
+                ObservationField::Core
+            ]),
+            Some("core,usage".to_string())
+        );
+    }
+}
@@ -0,0 +1,317 @@
+//! Persistent queues for the batcher
+//!
+//! By default a [`Batcher`](crate::Batcher) keeps queued events in memory only,
+//! so events not yet sent are lost if the process crashes. With a
+//! [`QueueBackend`] every accepted event is also written to the backend and
+//! acknowledged once it is sent, fails permanently, or is dropped. Events left
+//! unacknowledged are replayed into the queue when the next batcher starts
+//! with the same backend.
+//!
+//! [`FileQueue`] keeps this journal in an append-only file:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::queue::FileQueue;
+//! use langfuse_ergonomic::{Batcher, ClientBuilder};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! let batcher = Batcher::builder()
+//!     .client(client)
+//!     .queue_backend(FileQueue::open("/var/lib/my-agent/langfuse-queue.jsonl")?)
+//!     .build()
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The file holds one JSON record per line: an added event or a list of
+//! acknowledged event IDs. It is compacted when opened and truncated whenever
+//! every event in it has been acknowledged. Events are delivered at least
+//! once: an event sent just before a crash may be sent again after replay,
+//! which Langfuse deduplicates by event ID.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use langfuse_client_base::models::IngestionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::batcher::BatchEvent;
+use crate::error::{run_blocking, Error, Result};
+
+/// Durable store for the events queued by a batcher
+///
+/// The batcher awaits these calls on its send path, so implementations that
+/// block should move the work off the async executor, as [`FileQueue`] does.
+#[async_trait::async_trait]
+pub trait QueueBackend: Send + Sync {
+    /// Persist an event the batcher accepted
+    async fn append(&self, event: &BatchEvent) -> Result<()>;
+
+    /// Forget events that were sent, failed permanently, or were dropped
+    async fn acknowledge(&self, event_ids: &[String]) -> Result<()>;
+
+    /// Events persisted but not acknowledged, in the order they were appended
+    async fn replay(&self) -> Result<Vec<IngestionEvent>>;
+}
+
+/// One line of a [`FileQueue`] file
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Record {
+    Add { id: String, event: IngestionEvent },
+    Ack { ids: Vec<String> },
+}
+
+/// Append-only file journal of queued events
+///
+/// File operations run on the blocking thread pool. See the
+/// [module documentation](self).
+#[derive(Debug)]
+pub struct FileQueue {
+    path: PathBuf,
+    state: Arc<Mutex<FileQueueState>>,
+}
+
+#[derive(Debug)]
+struct FileQueueState {
+    file: File,
+    /// Number of unacknowledged events per event ID
+    pending: HashMap<String, usize>,
+}
+
+impl FileQueue {
+    /// Open or create the journal at `path`, compacting existing records
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let events = if path.exists() {
+            read_pending(&path)?
+        } else {
+            Vec::new()
+        };
+
+        // Rewrite only the pending events so acknowledged ones don't pile up
+        let temp = path.with_extension("compact");
+        {
+            let mut file = File::create(&temp).map_err(|e| io_error(&temp, e))?;
+            for (id, event) in &events {
+                write_record(
+                    &mut file,
+                    &path,
+                    &Record::Add {
+                        id: id.clone(),
+                        event: event.clone(),
+                    },
+                )?;
+            }
+            file.sync_all().map_err(|e| io_error(&temp, e))?;
+        }
+        std::fs::rename(&temp, &path).map_err(|e| io_error(&path, e))?;
+
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .map_err(|e| io_error(&path, e))?;
+        let mut pending = HashMap::new();
+        for (id, _) in events {
+            *pending.entry(id).or_insert(0) += 1;
+        }
+
+        Ok(Self {
+            path,
+            state: Arc::new(Mutex::new(FileQueueState { file, pending })),
+        })
+    }
+
+    /// Path of the journal file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Run `f` with the locked journal on the blocking thread pool
+    async fn with_state<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut FileQueueState, &Path) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let state = Arc::clone(&self.state);
+        let path = self.path.clone();
+        run_blocking(move || {
+            let mut state = state
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            f(&mut state, &path)
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl QueueBackend for FileQueue {
+    async fn append(&self, event: &BatchEvent) -> Result<()> {
+        let id = event.id.clone();
+        let record = Record::Add {
+            id: id.clone(),
+            event: event.event.clone(),
+        };
+        self.with_state(move |state, path| {
+            write_record(&mut state.file, path, &record)?;
+            *state.pending.entry(id).or_insert(0) += 1;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn acknowledge(&self, event_ids: &[String]) -> Result<()> {
+        let event_ids = event_ids.to_vec();
+        self.with_state(move |state, path| {
+            let mut acknowledged = Vec::new();
+            for id in event_ids {
+                if let Some(count) = state.pending.get_mut(&id) {
+                    *count -= 1;
+                    if *count == 0 {
+                        state.pending.remove(&id);
+                    }
+                    acknowledged.push(id);
+                }
+            }
+            if acknowledged.is_empty() {
+                return Ok(());
+            }
+
+            if state.pending.is_empty() {
+                // Nothing left to replay
+                state.file.set_len(0).map_err(|e| io_error(path, e))
+            } else {
+                write_record(&mut state.file, path, &Record::Ack { ids: acknowledged })
+            }
+        })
+        .await
+    }
+
+    async fn replay(&self) -> Result<Vec<IngestionEvent>> {
+        self.with_state(|_, path| {
+            Ok(read_pending(path)?
+                .into_iter()
+                .map(|(_, event)| event)
+                .collect())
+        })
+        .await
+    }
+}
+
+/// Added events of the journal at `path` that were not acknowledged
+fn read_pending(path: &Path) -> Result<Vec<(String, IngestionEvent)>> {
+    let file = File::open(path).map_err(|e| io_error(path, e))?;
+    let mut events: Vec<(String, IngestionEvent)> = Vec::new();
+
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| io_error(path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A crash can leave the last line half written; skip it
+        let Ok(record) = serde_json::from_str::<Record>(&line) else {
+            tracing::warn!(
+                "Skipping unreadable record in queue file {}",
+                path.display()
+            );
+            continue;
+        };
+        match record {
+            Record::Add { id, event } => events.push((id, event)),
+            Record::Ack { ids } => {
+                for id in ids {
+                    if let Some(i) = events.iter().position(|(pending, _)| *pending == id) {
+                        events.remove(i);
+                    }
+                }
+            }
+        }
+    }
+    Ok(events)
+}
+
+fn write_record(file: &mut File, path: &Path, record: &Record) -> Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line).map_err(|e| io_error(path, e))
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> Error {
+    Error::io(
+        format!("Failed to access queue file {}", path.display()),
+        error,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use langfuse_client_base::models::{IngestionEventOneOf, TraceBody};
+
+    fn event(id: &str) -> BatchEvent {
+        let event = IngestionEvent::IngestionEventOneOf(Box::new(IngestionEventOneOf::new(
+            id.to_string(),
+            "2024-01-01T00:00:00.000Z".to_string(),
+            TraceBody::default(),
+            langfuse_client_base::models::ingestion_event_one_of::Type::TraceCreate,
+        )));
+        BatchEvent::new(event, id.to_string()).unwrap()
+    }
+
+    fn ids(events: &[IngestionEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| {
+                serde_json::to_value(e).unwrap()["id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_replay_skips_acknowledged_events() {
+        let path =
+            std::env::temp_dir().join(format!("langfuse-queue-{}.jsonl", uuid::Uuid::new_v4()));
+        let queue = FileQueue::open(&path).unwrap();
+        for id in ["a", "b", "c"] {
+            queue.append(&event(id)).await.unwrap();
+        }
+        queue.acknowledge(&["b".to_string()]).await.unwrap();
+        assert_eq!(ids(&queue.replay().await.unwrap()), ["a", "c"]);
+
+        // Reopening compacts the file to the pending events
+        drop(queue);
+        let queue = FileQueue::open(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        assert_eq!(ids(&queue.replay().await.unwrap()), ["a", "c"]);
+
+        queue
+            .acknowledge(&["a".to_string(), "c".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_truncated_last_line_is_skipped() {
+        let path =
+            std::env::temp_dir().join(format!("langfuse-queue-{}.jsonl", uuid::Uuid::new_v4()));
+        let queue = FileQueue::open(&path).unwrap();
+        queue.append(&event("a")).await.unwrap();
+        drop(queue);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"op":"add","id":"b","ev"#).unwrap();
+
+        let queue = FileQueue::open(&path).unwrap();
+        assert_eq!(ids(&queue.replay().await.unwrap()), ["a"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
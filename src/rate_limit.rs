@@ -0,0 +1,154 @@
+//! Client-side rate limiting of Langfuse API requests
+//!
+//! High-volume scripts, such as evaluations over a large dataset, can send
+//! requests faster than Langfuse accepts them and run into `429 Too Many
+//! Requests`. A [`RateLimit`] set with
+//! [`ClientBuilder::rate_limit`](crate::ClientBuilder::rate_limit) paces
+//! requests with a token bucket instead: up to `burst` requests go out at
+//! once, after which requests wait for their turn at `requests_per_second`.
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{ClientBuilder, RateLimit};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .rate_limit(RateLimit::new(10.0).burst(20))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The bucket is shared by clones of the client, clients derived with
+//! [`LangfuseClient::with_overrides`](crate::LangfuseClient::with_overrides),
+//! and batchers built from them. Each HTTP attempt takes a token, including
+//! retries by middleware of a custom HTTP client. Uploads to media storage
+//! don't go to the Langfuse API and are not limited.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use http::Extensions;
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+
+use crate::auth::Unauthenticated;
+
+/// Rate limit for requests to the Langfuse API
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Sustained number of requests per second
+    pub requests_per_second: f64,
+    /// Number of requests that may be sent at once after a quiet period
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Allow `requests_per_second` requests per second, with a burst of one second's worth
+    pub fn new(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second.ceil().max(1.0) as u32,
+        }
+    }
+
+    /// Allow up to `burst` requests at once
+    #[must_use]
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+/// Token bucket shared by every request of a client
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Available tokens; negative when requests are waiting for tokens
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: f64::from(limit.burst),
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token, returning how long to wait before it is available
+    ///
+    /// Waiting requests hold their token, so they are served in order.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let elapsed = now
+            .saturating_duration_since(state.refilled_at)
+            .as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.limit.requests_per_second)
+            .min(f64::from(self.limit.burst));
+        state.refilled_at = now;
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.limit.requests_per_second)
+        }
+    }
+
+    async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Delays requests to the Langfuse API until the bucket has a token for them
+pub(crate) struct RateLimitMiddleware {
+    pub(crate) bucket: std::sync::Arc<TokenBucket>,
+}
+
+#[async_trait::async_trait]
+impl Middleware for RateLimitMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if extensions.get::<Unauthenticated>().is_none() {
+            self.bucket.acquire().await;
+        }
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_paces_after_burst() {
+        let bucket = TokenBucket::new(RateLimit::new(10.0).burst(2));
+        let start = Instant::now();
+
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+
+        // One second later the waiting requests are served and the bucket is full again
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert!(bucket.reserve(later) > Duration::ZERO);
+    }
+}
@@ -0,0 +1,109 @@
+//! Process-wide registry of batchers for flushing and shutting down at exit
+//!
+//! Every [`Batcher`](crate::Batcher) registers itself when it is built and leaves the
+//! registry once it is drained or shut down. Applications with several
+//! batchers, say one per tenant or component, can then drain all of them with
+//! a single call when the process exits, without keeping track of each:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{shutdown_all, Batcher, ClientBuilder};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! let ingest = Batcher::builder().client(client.clone()).build().await;
+//! let evals = Batcher::builder().client(client).build().await;
+//!
+//! // ... run the application ...
+//!
+//! for report in shutdown_all(Duration::from_secs(10)).await {
+//!     if report.timed_out {
+//!         eprintln!("{} Langfuse events were not sent", report.remaining);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Batchers are drained concurrently, so the whole call takes at most about
+//! `deadline`. The registry does not keep batchers alive: a batcher dropped
+//! without being shut down flushes its pending events in the background and
+//! then leaves the registry. [`shutdown_all`] waits for those still flushing.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use tokio::task::JoinSet;
+
+use crate::batcher::{DrainReport, WeakBatcher};
+use crate::error::{IngestionResponse, Result};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static BATCHERS: Mutex<BTreeMap<u64, WeakBatcher>> = Mutex::new(BTreeMap::new());
+
+fn batchers() -> MutexGuard<'static, BTreeMap<u64, WeakBatcher>> {
+    BATCHERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Reserve an ID for a new batcher
+pub(crate) fn next_id() -> u64 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Add `batcher` to the registry under its ID
+pub(crate) fn register(id: u64, batcher: WeakBatcher) {
+    batchers().insert(id, batcher);
+}
+
+/// Remove the batcher with the given ID from the registry
+pub(crate) fn unregister(id: u64) {
+    batchers().remove(&id);
+}
+
+/// Number of batchers currently registered
+pub fn registered_batchers() -> usize {
+    batchers().len()
+}
+
+/// Flush every registered batcher
+///
+/// Returns the result of each flush, in the order the batchers were built.
+/// The batchers keep running. Dropped batchers still flushing in the
+/// background are skipped.
+pub async fn flush_all() -> Vec<Result<IngestionResponse>> {
+    let registered: Vec<_> = batchers()
+        .values()
+        .filter_map(WeakBatcher::upgrade)
+        .collect();
+    let mut results = Vec::with_capacity(registered.len());
+    for batcher in &registered {
+        results.push(batcher.flush().await);
+    }
+    results
+}
+
+/// Drain every registered batcher within `deadline`
+///
+/// Each batcher stops accepting events and sends what is pending, as with
+/// [`Batcher::drain`](crate::Batcher::drain). Returns a report per batcher,
+/// in the order the batchers were built.
+pub async fn shutdown_all(deadline: Duration) -> Vec<DrainReport> {
+    let registered = std::mem::take(&mut *batchers());
+    let mut drains = JoinSet::new();
+    for (id, batcher) in registered {
+        drains.spawn(async move { (id, batcher.drain(deadline).await) });
+    }
+
+    let mut reports = BTreeMap::new();
+    while let Some(joined) = drains.join_next().await {
+        match joined {
+            Ok((id, report)) => {
+                reports.insert(id, report);
+            }
+            Err(e) => tracing::warn!("Failed to drain Langfuse batcher: {e}"),
+        }
+    }
+    reports.into_values().collect()
+}
@@ -0,0 +1,354 @@
+//! Rubric-based evaluations
+//!
+//! A [`RubricEvaluation`] grades a trace or observation against weighted
+//! [`RubricCriterion`]s. [`LangfuseClient::rubric_score`](crate::LangfuseClient::rubric_score)
+//! sends it as one numeric score: the weighted mean of the normalized
+//! per-criterion scores, between 0 and 1. The criteria, per-criterion scores,
+//! and rationales are stored in the score metadata under [`RUBRIC_METADATA_KEY`]
+//! in a fixed schema, so human and automated rubric evaluations can be
+//! compared side by side.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::rubric::{Evaluator, RubricCriterion, RubricEvaluation};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let evaluation = RubricEvaluation::new(
+//!     "answer-quality",
+//!     vec![
+//!         RubricCriterion::new("correctness", 2.0),
+//!         RubricCriterion::new("tone", 1.0).max_score(5.0),
+//!     ],
+//! )
+//! .evaluator(Evaluator::Human, Some("reviewer@example.com"))
+//! .score("correctness", 1.0, Some("Matches the reference answer"))
+//! .score("tone", 4.0, None);
+//!
+//! client
+//!     .rubric_score()
+//!     .trace_id("trace-1")
+//!     .evaluation(evaluation)
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+
+/// Metadata key holding the rubric details of a score
+pub const RUBRIC_METADATA_KEY: &str = "rubric";
+
+/// Version of the rubric metadata schema
+pub const RUBRIC_SCHEMA_VERSION: u32 = 1;
+
+/// A criterion of a rubric
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RubricCriterion {
+    /// Name of the criterion, unique within the rubric
+    pub name: String,
+    /// What the criterion measures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Relative weight in the aggregate score
+    pub weight: f64,
+    /// Highest possible score; scores range from 0 to this value
+    pub max_score: f64,
+}
+
+impl RubricCriterion {
+    /// Criterion scored from 0 to 1 with the given weight
+    pub fn new(name: impl Into<String>, weight: f64) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            weight,
+            max_score: 1.0,
+        }
+    }
+
+    /// Describe what the criterion measures
+    #[must_use]
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Score the criterion from 0 to `max_score`
+    #[must_use]
+    pub fn max_score(mut self, max_score: f64) -> Self {
+        self.max_score = max_score;
+        self
+    }
+}
+
+/// Score given for one criterion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CriterionScore {
+    /// Name of the criterion
+    pub criterion: String,
+    /// Score between 0 and the criterion's maximum
+    pub score: f64,
+    /// Why the score was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+}
+
+/// Who performed an evaluation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Evaluator {
+    /// A person, e.g. an annotator or reviewer
+    Human,
+    /// A model or program, e.g. an LLM judge (default)
+    #[default]
+    Automated,
+}
+
+/// Evaluation of a trace or observation against a rubric
+#[derive(Debug, Clone, PartialEq)]
+pub struct RubricEvaluation {
+    /// Name of the rubric, used as the score name
+    pub name: String,
+    /// Version of the rubric, if versioned
+    pub version: Option<String>,
+    /// Criteria of the rubric
+    pub criteria: Vec<RubricCriterion>,
+    /// Scores given per criterion
+    pub scores: Vec<CriterionScore>,
+    /// Who performed the evaluation
+    pub evaluator: Evaluator,
+    /// Name of the annotator or judge model
+    pub evaluator_name: Option<String>,
+    /// Overall rationale, sent as the score comment
+    pub rationale: Option<String>,
+}
+
+impl RubricEvaluation {
+    /// Start an evaluation against the rubric `name` with `criteria`
+    pub fn new(name: impl Into<String>, criteria: Vec<RubricCriterion>) -> Self {
+        Self {
+            name: name.into(),
+            version: None,
+            criteria,
+            scores: Vec::new(),
+            evaluator: Evaluator::default(),
+            evaluator_name: None,
+            rationale: None,
+        }
+    }
+
+    /// Set the rubric version
+    #[must_use]
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Set who performed the evaluation
+    #[must_use]
+    pub fn evaluator(mut self, evaluator: Evaluator, name: Option<&str>) -> Self {
+        self.evaluator = evaluator;
+        self.evaluator_name = name.map(str::to_string);
+        self
+    }
+
+    /// Score a criterion
+    #[must_use]
+    pub fn score(
+        mut self,
+        criterion: impl Into<String>,
+        score: f64,
+        rationale: Option<&str>,
+    ) -> Self {
+        self.scores.push(CriterionScore {
+            criterion: criterion.into(),
+            score,
+            rationale: rationale.map(str::to_string),
+        });
+        self
+    }
+
+    /// Set the overall rationale
+    #[must_use]
+    pub fn rationale(mut self, rationale: impl Into<String>) -> Self {
+        self.rationale = Some(rationale.into());
+        self
+    }
+
+    /// Check that every criterion is scored exactly once within its range
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| {
+            Err(Error::Validation(format!(
+                "Rubric `{}`: {message}",
+                self.name
+            )))
+        };
+
+        if self.criteria.is_empty() {
+            return invalid("has no criteria".to_string());
+        }
+        for (i, criterion) in self.criteria.iter().enumerate() {
+            if self.criteria[..i].iter().any(|c| c.name == criterion.name) {
+                return invalid(format!("criterion `{}` is defined twice", criterion.name));
+            }
+            if !(criterion.weight.is_finite() && criterion.weight > 0.0) {
+                return invalid(format!(
+                    "criterion `{}` needs a positive weight",
+                    criterion.name
+                ));
+            }
+            if !(criterion.max_score.is_finite() && criterion.max_score > 0.0) {
+                return invalid(format!(
+                    "criterion `{}` needs a positive maximum score",
+                    criterion.name
+                ));
+            }
+            let scores = self.scores.iter().filter(|s| s.criterion == criterion.name);
+            match scores.collect::<Vec<_>>().as_slice() {
+                [] => return invalid(format!("criterion `{}` is not scored", criterion.name)),
+                [score] if !(0.0..=criterion.max_score).contains(&score.score) => {
+                    return invalid(format!(
+                        "score {} for `{}` is outside 0..={}",
+                        score.score, criterion.name, criterion.max_score
+                    ))
+                }
+                [_] => {}
+                _ => {
+                    return invalid(format!(
+                        "criterion `{}` is scored more than once",
+                        criterion.name
+                    ))
+                }
+            }
+        }
+        if let Some(unknown) = self
+            .scores
+            .iter()
+            .find(|s| !self.criteria.iter().any(|c| c.name == s.criterion))
+        {
+            return invalid(format!("`{}` is not a criterion", unknown.criterion));
+        }
+        Ok(())
+    }
+
+    /// Weighted mean of the normalized criterion scores, between 0 and 1
+    pub fn aggregate(&self) -> Result<f64> {
+        self.validate()?;
+        let total_weight: f64 = self.criteria.iter().map(|c| c.weight).sum();
+        let weighted: f64 = self
+            .criteria
+            .iter()
+            .map(|criterion| criterion.weight * self.normalized(criterion))
+            .sum();
+        Ok(weighted / total_weight)
+    }
+
+    /// Score metadata in the rubric schema, stored under [`RUBRIC_METADATA_KEY`]
+    pub fn to_metadata(&self) -> Result<Value> {
+        let aggregate = self.aggregate()?;
+        let criteria: Vec<Value> = self
+            .criteria
+            .iter()
+            .map(|criterion| {
+                let score = self.score_for(criterion);
+                json!({
+                    "name": criterion.name,
+                    "description": criterion.description,
+                    "weight": criterion.weight,
+                    "max_score": criterion.max_score,
+                    "score": score.map(|s| s.score),
+                    "normalized": self.normalized(criterion),
+                    "rationale": score.and_then(|s| s.rationale.as_deref()),
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            RUBRIC_METADATA_KEY: {
+                "schema_version": RUBRIC_SCHEMA_VERSION,
+                "name": self.name,
+                "version": self.version,
+                "evaluator": self.evaluator,
+                "evaluator_name": self.evaluator_name,
+                "criteria": criteria,
+                "aggregate": aggregate,
+            }
+        }))
+    }
+
+    fn score_for(&self, criterion: &RubricCriterion) -> Option<&CriterionScore> {
+        self.scores.iter().find(|s| s.criterion == criterion.name)
+    }
+
+    fn normalized(&self, criterion: &RubricCriterion) -> f64 {
+        self.score_for(criterion)
+            .map_or(0.0, |s| s.score / criterion.max_score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluation() -> RubricEvaluation {
+        RubricEvaluation::new(
+            "answer-quality",
+            vec![
+                RubricCriterion::new("correctness", 3.0),
+                RubricCriterion::new("tone", 1.0).max_score(5.0),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_aggregate_is_weighted_mean_of_normalized_scores() {
+        let evaluation = evaluation()
+            .score("correctness", 1.0, None)
+            .score("tone", 2.0, None);
+        // (3 * 1.0 + 1 * 0.4) / 4
+        assert!((evaluation.aggregate().unwrap() - 0.85).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_validation() {
+        let missing = evaluation().score("correctness", 1.0, None);
+        assert_eq!(
+            missing.validate().unwrap_err().to_string(),
+            "Validation error: Rubric `answer-quality`: criterion `tone` is not scored"
+        );
+
+        let out_of_range = evaluation()
+            .score("correctness", 1.0, None)
+            .score("tone", 6.0, None);
+        assert!(out_of_range.validate().is_err());
+
+        let unknown = evaluation()
+            .score("correctness", 1.0, None)
+            .score("tone", 1.0, None)
+            .score("style", 1.0, None);
+        assert!(unknown.validate().is_err());
+    }
+
+    #[test]
+    fn test_metadata_schema() {
+        let metadata = evaluation()
+            .version("v2")
+            .evaluator(Evaluator::Human, Some("ann"))
+            .score("correctness", 0.5, Some("Partially right"))
+            .score("tone", 5.0, None)
+            .to_metadata()
+            .unwrap();
+        let rubric = &metadata[RUBRIC_METADATA_KEY];
+        assert_eq!(rubric["schema_version"], RUBRIC_SCHEMA_VERSION);
+        assert_eq!(rubric["evaluator"], "human");
+        assert_eq!(rubric["evaluator_name"], "ann");
+        assert_eq!(rubric["criteria"][0]["rationale"], "Partially right");
+        assert_eq!(rubric["criteria"][1]["normalized"], 1.0);
+        assert_eq!(rubric["aggregate"], 0.625);
+    }
+}
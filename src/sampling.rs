@@ -0,0 +1,186 @@
+//! Client-side sampling of traces
+//!
+//! Recording every request of a high-traffic service is often more than
+//! analysis needs. A [`Sampler`] configured on the
+//! [`ClientBuilder`](crate::ClientBuilder) drops a share of traces before they
+//! are sent, together with every observation and score of those traces, so a
+//! trace is either recorded completely or not at all:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::ClientBuilder;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // Keep 10% of traces
+//! let client = ClientBuilder::from_env()?.sample_rate(0.1).build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Sampler::ratio`] decides by a stable hash of the trace ID, so every
+//! event of a trace gets the same decision, in any process and any batcher,
+//! without coordination. [`Sampler::custom`] decides with a function of the
+//! trace itself, such as its name or tags, when the trace is created; its
+//! observations and scores then follow that decision. Events of traces the
+//! custom sampler has not seen are kept.
+//!
+//! Sampling applies to events sent through the ingestion API by the client
+//! and by batchers built from it. Events built with `build_event` are not
+//! sampled.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use langfuse_client_base::models::{IngestionEvent, TraceBody};
+
+/// Number of custom sampling decisions remembered for the traces' children
+const MAX_REMEMBERED_TRACES: usize = 10_000;
+
+type SampleFn = dyn Fn(&TraceBody) -> bool + Send + Sync;
+
+/// Decides which traces are sent
+///
+/// See the [module documentation](self).
+pub struct Sampler {
+    kind: SamplerKind,
+    decisions: Mutex<Decisions>,
+}
+
+enum SamplerKind {
+    Ratio(f64),
+    Custom(Arc<SampleFn>),
+}
+
+/// Recent custom decisions by trace ID, oldest first
+#[derive(Default)]
+struct Decisions {
+    by_trace: HashMap<String, bool>,
+    order: VecDeque<String>,
+}
+
+impl Sampler {
+    /// Keep this fraction of traces, between 0.0 and 1.0, chosen by trace ID
+    pub fn ratio(rate: f64) -> Self {
+        Self::with_kind(SamplerKind::Ratio(rate.clamp(0.0, 1.0)))
+    }
+
+    /// Keep the traces for which `sample` returns `true`
+    pub fn custom(sample: impl Fn(&TraceBody) -> bool + Send + Sync + 'static) -> Self {
+        Self::with_kind(SamplerKind::Custom(Arc::new(sample)))
+    }
+
+    fn with_kind(kind: SamplerKind) -> Self {
+        Self {
+            kind,
+            decisions: Mutex::new(Decisions::default()),
+        }
+    }
+
+    /// Whether the trace with the given ID is kept by a ratio sampler
+    ///
+    /// Always `true` for a custom sampler.
+    pub fn samples_trace_id(&self, trace_id: &str) -> bool {
+        match self.kind {
+            SamplerKind::Ratio(rate) => {
+                rate >= 1.0 || (fnv1a(trace_id) as f64 / u64::MAX as f64) < rate
+            }
+            SamplerKind::Custom(_) => true,
+        }
+    }
+
+    /// Whether `event` is sent
+    pub(crate) fn keep(&self, event: &IngestionEvent) -> bool {
+        let SamplerKind::Custom(sample) = &self.kind else {
+            return crate::batcher::Batcher::event_trace_id(event)
+                .is_none_or(|trace_id| self.samples_trace_id(trace_id));
+        };
+
+        if let IngestionEvent::IngestionEventOneOf(trace) = event {
+            let Some(Some(trace_id)) = &trace.body.id else {
+                return sample(&trace.body);
+            };
+            // Updates of a trace follow the decision made when it was created
+            if let Some(keep) = self.decision(trace_id) {
+                return keep;
+            }
+            let keep = sample(&trace.body);
+            self.remember(trace_id, keep);
+            return keep;
+        }
+        crate::batcher::Batcher::event_trace_id(event)
+            .and_then(|trace_id| self.decision(trace_id))
+            .unwrap_or(true)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Decisions> {
+        self.decisions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn remember(&self, trace_id: &str, keep: bool) {
+        let mut decisions = self.lock();
+        if decisions
+            .by_trace
+            .insert(trace_id.to_string(), keep)
+            .is_none()
+        {
+            decisions.order.push_back(trace_id.to_string());
+            if decisions.order.len() > MAX_REMEMBERED_TRACES {
+                if let Some(oldest) = decisions.order.pop_front() {
+                    decisions.by_trace.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    fn decision(&self, trace_id: &str) -> Option<bool> {
+        self.lock().by_trace.get(trace_id).copied()
+    }
+}
+
+impl fmt::Debug for Sampler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SamplerKind::Ratio(rate) => write!(f, "Sampler::ratio({rate})"),
+            SamplerKind::Custom(_) => f.write_str("Sampler::custom"),
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across processes and Rust versions
+///
+/// Finished with the MurmurHash3 mix so similar IDs spread evenly.
+fn fnv1a(text: &str) -> u64 {
+    let mut hash = text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio_is_deterministic() {
+        let sampler = Sampler::ratio(0.25);
+        let kept = (0..10_000)
+            .filter(|i| sampler.samples_trace_id(&format!("trace-{i}")))
+            .count();
+        assert!((2_000..3_000).contains(&kept), "kept {kept}");
+        for i in 0..100 {
+            let id = format!("trace-{i}");
+            assert_eq!(
+                sampler.samples_trace_id(&id),
+                Sampler::ratio(0.25).samples_trace_id(&id)
+            );
+        }
+        assert!(Sampler::ratio(1.0).samples_trace_id("any"));
+        assert!(!Sampler::ratio(0.0).samples_trace_id("any"));
+    }
+}
@@ -0,0 +1,268 @@
+//! Versioned metadata conventions
+//!
+//! Instrumentation conventions (metadata keys, their meaning, input/output
+//! shapes) tend to change over time. Configure a [`SchemaVersion`] with
+//! [`ClientBuilder::schema_version`](crate::ClientBuilder::schema_version) and
+//! every trace and observation created by the client is stamped with a
+//! `schema_version` metadata entry.
+//!
+//! Consumers of Langfuse exports can then bring older events up to date with a
+//! [`SchemaUpgrader`], a chain of upgrade hooks between consecutive versions:
+//!
+//! ```
+//! use langfuse_ergonomic::schema::{SchemaUpgrader, SchemaVersion};
+//! use serde_json::json;
+//!
+//! let upgrader = SchemaUpgrader::new(SchemaVersion::new(2, 0, 0))
+//!     .step(SchemaVersion::new(1, 0, 0), SchemaVersion::new(2, 0, 0), |metadata| {
+//!         if let Some(team) = metadata.remove("team") {
+//!             metadata.insert("owner".to_string(), team);
+//!         }
+//!     });
+//!
+//! let mut metadata = json!({"schema_version": "1.0.0", "team": "search"});
+//! upgrader.upgrade(&mut metadata).unwrap();
+//! assert_eq!(metadata, json!({"schema_version": "2.0.0", "owner": "search"}));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::interceptor::metadata_object;
+
+/// Metadata key holding the schema version of an event
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// A `MAJOR.MINOR.PATCH` version of the instrumentation conventions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaVersion {
+    /// Incremented for incompatible changes
+    pub major: u32,
+    /// Incremented for backwards compatible additions
+    pub minor: u32,
+    /// Incremented for fixes that do not change the conventions
+    pub patch: u32,
+}
+
+impl SchemaVersion {
+    /// Create a version from its components
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Read the version stamped on an event's metadata, if any
+    pub fn from_metadata(metadata: &Value) -> Result<Option<Self>> {
+        match metadata.get(SCHEMA_VERSION_KEY) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(version)) => version.parse().map(Some),
+            Some(other) => Err(Error::Validation(format!(
+                "{SCHEMA_VERSION_KEY} must be a string, got {other}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::Validation(format!("Invalid schema version `{s}`"));
+        let mut parts = s.trim().split('.').map(|part| part.parse::<u32>());
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => {
+                Ok(Self::new(major, minor, patch))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Add the schema version to event metadata unless it already carries one
+pub(crate) fn stamp(metadata: Option<Value>, version: SchemaVersion) -> Value {
+    let mut map = metadata_object(metadata);
+    map.entry(SCHEMA_VERSION_KEY)
+        .or_insert_with(|| Value::String(version.to_string()));
+    Value::Object(map)
+}
+
+type UpgradeFn = Arc<dyn Fn(&mut Map<String, Value>) + Send + Sync>;
+
+/// Upgrades event metadata from older schema versions to the current one
+#[derive(Clone)]
+pub struct SchemaUpgrader {
+    current: SchemaVersion,
+    steps: BTreeMap<SchemaVersion, (SchemaVersion, UpgradeFn)>,
+}
+
+impl fmt::Debug for SchemaUpgrader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaUpgrader")
+            .field("current", &self.current)
+            .field(
+                "steps",
+                &self
+                    .steps
+                    .iter()
+                    .map(|(from, (to, _))| (from, to))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SchemaUpgrader {
+    /// Create an upgrader targeting `current`
+    pub fn new(current: SchemaVersion) -> Self {
+        Self {
+            current,
+            steps: BTreeMap::new(),
+        }
+    }
+
+    /// The version metadata is upgraded to
+    pub fn current(&self) -> SchemaVersion {
+        self.current
+    }
+
+    /// Register a hook upgrading metadata from `from` to `to`
+    ///
+    /// The hook only needs to rewrite the metadata; the version key is updated
+    /// by the upgrader.
+    pub fn step<F>(mut self, from: SchemaVersion, to: SchemaVersion, upgrade: F) -> Self
+    where
+        F: Fn(&mut Map<String, Value>) + Send + Sync + 'static,
+    {
+        self.steps.insert(from, (to, Arc::new(upgrade)));
+        self
+    }
+
+    /// Upgrade metadata in place and return the version it started at
+    ///
+    /// Metadata without a schema version is left untouched and `None` is
+    /// returned. Fails with [`Error::Validation`] if the metadata is newer than
+    /// the current version or no chain of steps leads to it.
+    pub fn upgrade(&self, metadata: &mut Value) -> Result<Option<SchemaVersion>> {
+        let Some(original) = SchemaVersion::from_metadata(metadata)? else {
+            return Ok(None);
+        };
+        let Value::Object(map) = metadata else {
+            return Ok(None);
+        };
+        if original > self.current {
+            return Err(Error::Validation(format!(
+                "Schema version {original} is newer than the supported version {}",
+                self.current
+            )));
+        }
+
+        let mut version = original;
+        while version != self.current {
+            let Some((next, upgrade)) = self.steps.get(&version) else {
+                return Err(Error::Validation(format!(
+                    "No upgrade path from schema version {version} to {}",
+                    self.current
+                )));
+            };
+            if *next <= version {
+                return Err(Error::Validation(format!(
+                    "Upgrade step from schema version {version} does not move forward"
+                )));
+            }
+            upgrade(map);
+            version = *next;
+            map.insert(
+                SCHEMA_VERSION_KEY.to_string(),
+                Value::String(version.to_string()),
+            );
+        }
+
+        Ok(Some(original))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_display() {
+        let version: SchemaVersion = "1.12.3".parse().unwrap();
+        assert_eq!(version, SchemaVersion::new(1, 12, 3));
+        assert_eq!(version.to_string(), "1.12.3");
+        assert!("1.2".parse::<SchemaVersion>().is_err());
+        assert!("1.2.3.4".parse::<SchemaVersion>().is_err());
+        assert!(SchemaVersion::new(1, 10, 0) > SchemaVersion::new(1, 9, 9));
+    }
+
+    #[test]
+    fn test_stamp_keeps_explicit_version() {
+        let version = SchemaVersion::new(2, 0, 0);
+        assert_eq!(stamp(None, version), json!({"schema_version": "2.0.0"}));
+        assert_eq!(
+            stamp(Some(json!({"schema_version": "1.0.0"})), version),
+            json!({"schema_version": "1.0.0"})
+        );
+        assert_eq!(
+            stamp(Some(json!("note")), version),
+            json!({"metadata": "note", "schema_version": "2.0.0"})
+        );
+    }
+
+    #[test]
+    fn test_upgrade_chain() {
+        let upgrader = SchemaUpgrader::new(SchemaVersion::new(3, 0, 0))
+            .step(
+                SchemaVersion::new(1, 0, 0),
+                SchemaVersion::new(2, 0, 0),
+                |m| {
+                    m.insert("v2".to_string(), json!(true));
+                },
+            )
+            .step(
+                SchemaVersion::new(2, 0, 0),
+                SchemaVersion::new(3, 0, 0),
+                |m| {
+                    m.insert("v3".to_string(), json!(true));
+                },
+            );
+
+        let mut metadata = json!({"schema_version": "1.0.0"});
+        let original = upgrader.upgrade(&mut metadata).unwrap();
+        assert_eq!(original, Some(SchemaVersion::new(1, 0, 0)));
+        assert_eq!(
+            metadata,
+            json!({"schema_version": "3.0.0", "v2": true, "v3": true})
+        );
+
+        let mut unversioned = json!({"k": "v"});
+        assert_eq!(upgrader.upgrade(&mut unversioned).unwrap(), None);
+        assert_eq!(unversioned, json!({"k": "v"}));
+    }
+
+    #[test]
+    fn test_upgrade_errors() {
+        let upgrader = SchemaUpgrader::new(SchemaVersion::new(2, 0, 0));
+        assert!(upgrader
+            .upgrade(&mut json!({"schema_version": "1.0.0"}))
+            .is_err());
+        assert!(upgrader
+            .upgrade(&mut json!({"schema_version": "3.0.0"}))
+            .is_err());
+    }
+}
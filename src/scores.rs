@@ -4,5 +4,440 @@
 //! The actual client methods are implemented in the traces module to
 //! consolidate all client methods under a single #[bon] impl block.
 
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::environment::Environment;
+use crate::error::{Error, Result};
+
 // Re-export common types that might be useful
-pub use langfuse_client_base::models::{CreateScoreValue, ScoreBody, ScoreDataType};
+pub use langfuse_client_base::models::{
+    CreateScoreValue, LegacyCreateScoreRequest, LegacyCreateScoreResponse, ScoreBody, ScoreDataType,
+};
+
+/// Name of the score recorded by [`LangfuseClient::user_feedback`](crate::LangfuseClient::user_feedback)
+pub const USER_FEEDBACK_SCORE_NAME: &str = "user-feedback";
+
+/// Tag added to traces with positive user feedback, if tagging is requested
+pub const POSITIVE_FEEDBACK_TAG: &str = "feedback:positive";
+
+/// Tag added to traces with negative user feedback, if tagging is requested
+pub const NEGATIVE_FEEDBACK_TAG: &str = "feedback:negative";
+
+/// How scores created with [`LangfuseClient::score`](crate::LangfuseClient::score) reach Langfuse
+///
+/// Set for all scores with
+/// [`ClientBuilder::score_transport`](crate::ClientBuilder::score_transport)
+/// or for one score with `.transport(...)` on the score builder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScoreTransport {
+    /// As `score-create` events through the ingestion API (default)
+    #[default]
+    Ingestion,
+    /// Through the scores API (`POST /api/public/scores`), which returns once
+    /// the score is stored
+    ///
+    /// Lower latency for scores written outside a batch, such as user
+    /// feedback. The sampler, environment, and interceptors apply as with
+    /// ingestion. Requires a Langfuse version that serves the scores API.
+    Direct,
+}
+
+/// Value of a score
+///
+/// The variant determines the data type reported to Langfuse. Plain values
+/// convert into the matching variant, so `.value(0.9)`, `.value("good")`, and
+/// `.value(true)` all work on the score builder.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
+#[cfg_attr(feature = "json-schema", serde(untagged))]
+pub enum ScoreValue {
+    /// Numeric score (`NUMERIC`)
+    Number(f64),
+    /// Categorical score (`CATEGORICAL`)
+    Category(String),
+    /// Boolean score (`BOOLEAN`), sent as `1` or `0`
+    Boolean(bool),
+}
+
+impl ScoreValue {
+    /// Data type reported to Langfuse for this value
+    pub fn data_type(&self) -> ScoreDataType {
+        match self {
+            ScoreValue::Number(_) => ScoreDataType::Numeric,
+            ScoreValue::Category(_) => ScoreDataType::Categorical,
+            ScoreValue::Boolean(_) => ScoreDataType::Boolean,
+        }
+    }
+
+    /// Convert into the API value and data type
+    pub(crate) fn into_parts(self) -> (CreateScoreValue, ScoreDataType) {
+        let data_type = self.data_type();
+        let value = match self {
+            ScoreValue::Number(n) => CreateScoreValue::Number(n),
+            ScoreValue::Category(c) => CreateScoreValue::String(c),
+            ScoreValue::Boolean(b) => CreateScoreValue::Number(if b { 1.0 } else { 0.0 }),
+        };
+        (value, data_type)
+    }
+
+    /// Convert into the API value, reported as `data_type` if given
+    ///
+    /// Follows Langfuse's rules: `NUMERIC` takes a number, `BOOLEAN` a boolean
+    /// or the number `0` or `1`, and `CATEGORICAL`, `CORRECTION`, and `TEXT` a
+    /// string.
+    pub(crate) fn into_parts_as(
+        self,
+        data_type: Option<ScoreDataType>,
+    ) -> Result<(CreateScoreValue, ScoreDataType)> {
+        let Some(data_type) = data_type else {
+            return Ok(self.into_parts());
+        };
+        let value = match (data_type, self) {
+            (ScoreDataType::Numeric, value @ ScoreValue::Number(_))
+            | (ScoreDataType::Boolean, value @ ScoreValue::Boolean(_)) => value,
+            (ScoreDataType::Boolean, ScoreValue::Number(n)) if n == 0.0 || n == 1.0 => {
+                ScoreValue::Boolean(n == 1.0)
+            }
+            (
+                ScoreDataType::Categorical | ScoreDataType::Correction | ScoreDataType::Text,
+                ScoreValue::Category(c),
+            ) => return Ok((CreateScoreValue::String(c), data_type)),
+            (_, value) => {
+                return Err(Error::Validation(format!(
+                    "Score value {value:?} does not match data type {data_type}"
+                )));
+            }
+        };
+        Ok((value.into_parts().0, data_type))
+    }
+
+    /// Read the value of a fetched score from its JSON representation
+    pub(crate) fn from_score_json(score: &Value) -> Option<Self> {
+        match score.get("dataType")?.as_str()? {
+            "NUMERIC" => score.get("value")?.as_f64().map(ScoreValue::Number),
+            "BOOLEAN" => score
+                .get("value")?
+                .as_f64()
+                .map(|v| ScoreValue::Boolean(v != 0.0)),
+            _ => score
+                .get("stringValue")?
+                .as_str()
+                .map(|s| ScoreValue::Category(s.to_string())),
+        }
+    }
+}
+
+impl From<f64> for ScoreValue {
+    fn from(value: f64) -> Self {
+        ScoreValue::Number(value)
+    }
+}
+
+impl From<f32> for ScoreValue {
+    fn from(value: f32) -> Self {
+        ScoreValue::Number(f64::from(value))
+    }
+}
+
+impl From<i32> for ScoreValue {
+    fn from(value: i32) -> Self {
+        ScoreValue::Number(f64::from(value))
+    }
+}
+
+impl From<bool> for ScoreValue {
+    fn from(value: bool) -> Self {
+        ScoreValue::Boolean(value)
+    }
+}
+
+impl From<String> for ScoreValue {
+    fn from(value: String) -> Self {
+        ScoreValue::Category(value)
+    }
+}
+
+impl From<&str> for ScoreValue {
+    fn from(value: &str) -> Self {
+        ScoreValue::Category(value.to_string())
+    }
+}
+
+/// One score of a [`LangfuseClient::scores_batch`](crate::LangfuseClient::scores_batch) call
+///
+/// Without a trace, session, or dataset run ID, the score belongs to the
+/// trace of the [ambient context](crate::ambient).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreInput {
+    /// Score name
+    pub name: String,
+    /// Score value
+    pub value: ScoreValue,
+    /// Trace the score belongs to
+    pub trace_id: Option<String>,
+    /// Observation within the trace the score is about
+    pub observation_id: Option<String>,
+    /// Session the score belongs to
+    pub session_id: Option<String>,
+    /// Dataset run the score belongs to
+    pub dataset_run_id: Option<String>,
+    /// Comment explaining the score
+    pub comment: Option<String>,
+    /// Score config the score follows
+    pub config_id: Option<String>,
+    /// Data type to report instead of the one implied by the value
+    pub data_type: Option<ScoreDataType>,
+    /// JSON object with additional data
+    pub metadata: Option<Value>,
+    /// Overrides the client's [environment](crate::environment)
+    pub environment: Option<Environment>,
+    /// Score ID; defaults to a new UUID
+    pub id: Option<String>,
+}
+
+impl ScoreInput {
+    /// Score of a trace
+    pub fn new(
+        trace_id: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<ScoreValue>,
+    ) -> Self {
+        Self {
+            trace_id: Some(trace_id.into()),
+            ..Self::unattached(name, value)
+        }
+    }
+
+    /// Score of a session
+    pub fn session(
+        session_id: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<ScoreValue>,
+    ) -> Self {
+        Self {
+            session_id: Some(session_id.into()),
+            ..Self::unattached(name, value)
+        }
+    }
+
+    /// Score of a dataset run
+    pub fn run(
+        dataset_run_id: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<ScoreValue>,
+    ) -> Self {
+        Self {
+            dataset_run_id: Some(dataset_run_id.into()),
+            ..Self::unattached(name, value)
+        }
+    }
+
+    /// Score of the trace of the ambient context
+    pub fn unattached(name: impl Into<String>, value: impl Into<ScoreValue>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            trace_id: None,
+            observation_id: None,
+            session_id: None,
+            dataset_run_id: None,
+            comment: None,
+            config_id: None,
+            data_type: None,
+            metadata: None,
+            environment: None,
+            id: None,
+        }
+    }
+
+    /// Score an observation of the trace
+    #[must_use]
+    pub fn observation_id(mut self, observation_id: impl Into<String>) -> Self {
+        self.observation_id = Some(observation_id.into());
+        self
+    }
+
+    /// Set the comment
+    #[must_use]
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Set the score config
+    #[must_use]
+    pub fn config_id(mut self, config_id: impl Into<String>) -> Self {
+        self.config_id = Some(config_id.into());
+        self
+    }
+
+    /// Report the score with `data_type`
+    #[must_use]
+    pub fn data_type(mut self, data_type: ScoreDataType) -> Self {
+        self.data_type = Some(data_type);
+        self
+    }
+
+    /// Set the metadata, a JSON object
+    #[must_use]
+    pub fn metadata(mut self, metadata: Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Set the environment
+    #[must_use]
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Set the score ID, to update an existing score
+    #[must_use]
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+}
+
+/// Body of the `score-create` event equivalent to a scores API request
+pub(crate) fn request_body(request: LegacyCreateScoreRequest) -> ScoreBody {
+    ScoreBody {
+        id: request.id,
+        trace_id: request.trace_id,
+        session_id: request.session_id,
+        observation_id: request.observation_id,
+        dataset_run_id: request.dataset_run_id,
+        name: request.name,
+        environment: request.environment,
+        queue_id: request.queue_id,
+        value: request.value,
+        comment: request.comment,
+        metadata: request
+            .metadata
+            .map(|metadata| metadata.map(|map| Value::Object(map.into_iter().collect()))),
+        data_type: request.data_type,
+        config_id: request.config_id,
+    }
+}
+
+/// Scores API request equivalent to a `score-create` event body
+pub(crate) fn body_request(body: ScoreBody) -> Result<LegacyCreateScoreRequest> {
+    Ok(LegacyCreateScoreRequest {
+        id: body.id,
+        trace_id: body.trace_id,
+        session_id: body.session_id,
+        observation_id: body.observation_id,
+        dataset_run_id: body.dataset_run_id,
+        name: body.name,
+        value: body.value,
+        comment: body.comment,
+        metadata: body
+            .metadata
+            .map(|metadata| metadata.map(metadata_map).transpose())
+            .transpose()?,
+        environment: body.environment,
+        queue_id: body.queue_id,
+        data_type: body.data_type,
+        config_id: body.config_id,
+    })
+}
+
+/// Convert score metadata into the map the scores API expects
+pub(crate) fn metadata_map(metadata: Value) -> Result<HashMap<String, Value>> {
+    match metadata {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        other => Err(Error::Validation(format!(
+            "Score metadata must be a JSON object, got {other}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_value_conversions() {
+        assert_eq!(ScoreValue::from(0.5), ScoreValue::Number(0.5));
+        assert_eq!(ScoreValue::from(3), ScoreValue::Number(3.0));
+        assert_eq!(ScoreValue::from(true), ScoreValue::Boolean(true));
+        assert_eq!(
+            ScoreValue::from("good"),
+            ScoreValue::Category("good".to_string())
+        );
+    }
+
+    #[test]
+    fn test_score_value_from_score_json() {
+        use serde_json::json;
+
+        assert_eq!(
+            ScoreValue::from_score_json(&json!({"dataType": "NUMERIC", "value": 0.5})),
+            Some(ScoreValue::Number(0.5))
+        );
+        assert_eq!(
+            ScoreValue::from_score_json(&json!({"dataType": "BOOLEAN", "value": 1.0})),
+            Some(ScoreValue::Boolean(true))
+        );
+        assert_eq!(
+            ScoreValue::from_score_json(
+                &json!({"dataType": "CATEGORICAL", "value": 0.0, "stringValue": "good"})
+            ),
+            Some(ScoreValue::Category("good".to_string()))
+        );
+        assert_eq!(ScoreValue::from_score_json(&json!({})), None);
+    }
+
+    #[test]
+    fn test_score_value_parts() {
+        assert_eq!(
+            ScoreValue::Boolean(false).into_parts(),
+            (CreateScoreValue::Number(0.0), ScoreDataType::Boolean)
+        );
+        assert_eq!(
+            ScoreValue::Category("a".to_string()).into_parts(),
+            (
+                CreateScoreValue::String("a".to_string()),
+                ScoreDataType::Categorical
+            )
+        );
+    }
+
+    #[test]
+    fn test_score_value_parts_as_data_type() {
+        assert_eq!(
+            ScoreValue::Number(1.0)
+                .into_parts_as(Some(ScoreDataType::Boolean))
+                .unwrap(),
+            (CreateScoreValue::Number(1.0), ScoreDataType::Boolean)
+        );
+        assert_eq!(
+            ScoreValue::Category("fixed".to_string())
+                .into_parts_as(Some(ScoreDataType::Correction))
+                .unwrap(),
+            (
+                CreateScoreValue::String("fixed".to_string()),
+                ScoreDataType::Correction
+            )
+        );
+        assert_eq!(
+            ScoreValue::Number(0.5).into_parts_as(None).unwrap(),
+            (CreateScoreValue::Number(0.5), ScoreDataType::Numeric)
+        );
+        assert!(ScoreValue::Number(0.5)
+            .into_parts_as(Some(ScoreDataType::Boolean))
+            .is_err());
+        assert!(ScoreValue::Boolean(true)
+            .into_parts_as(Some(ScoreDataType::Numeric))
+            .is_err());
+        assert!(ScoreValue::Number(2.0)
+            .into_parts_as(Some(ScoreDataType::Categorical))
+            .is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! Redaction of sensitive data in trace payloads
+//!
+//! A [`Redactor`] masks the values of sensitive keys such as `password` or
+//! `api_key`, and text matching sensitive patterns such as email addresses,
+//! card numbers, and secret keys, anywhere in a JSON value.
+//!
+//! ```
+//! use langfuse_ergonomic::Redactor;
+//! use serde_json::json;
+//!
+//! let redactor = Redactor::default();
+//! let value = redactor.redact_json(json!({
+//!     "password": "hunter2",
+//!     "message": "Contact jane@example.com",
+//!     "max_tokens": 256,
+//! }));
+//! assert_eq!(
+//!     value,
+//!     json!({
+//!         "password": "[REDACTED]",
+//!         "message": "Contact [REDACTED]",
+//!         "max_tokens": 256,
+//!     })
+//! );
+//! ```
+//!
+//! Keys match case-insensitively, ignoring `-` and `_`, so `api_key`,
+//! `Api-Key`, and `apiKey` are all the same key.
+//!
+//! ## Redacting outgoing events
+//!
+//! With [`ClientBuilder::redact_payloads`](crate::ClientBuilder::redact_payloads),
+//! the input, output, and metadata of every trace, observation, and score
+//! the client or its batchers send pass through the redactor first, as do
+//! values uploaded as media. The default rules apply unless
+//! [`ClientBuilder::redactor`](crate::ClientBuilder::redactor) sets others:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{ClientBuilder, Redactor};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .redactor(Redactor::default().key("customer_id"))
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Redaction runs before any [interceptor](crate::interceptor). A redactor is
+//! itself a [`BatchInterceptor`], so it can also be registered as one.
+//!
+//! ## Custom masking
+//!
+//! Like the `mask` option of the Langfuse Python and JS SDKs,
+//! [`ClientBuilder::mask`](crate::ClientBuilder::mask) passes the input and
+//! output of every trace and observation through a function of your own, for
+//! PII scrubbers the built-in rules cannot express:
+//!
+//! ```no_run
+//! use langfuse_ergonomic::ClientBuilder;
+//! use serde_json::Value;
+//!
+//! # fn scrub(text: &str) -> String { text.to_string() }
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?
+//!     .mask(|value: &Value| match value {
+//!         Value::String(text) => Value::String(scrub(text)),
+//!         other => other.clone(),
+//!     })
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The mask runs after the redactor and before interceptors, and also on
+//! values uploaded as media. If it panics, the value is replaced with
+//! [`MASK_FAILED`] rather than sent unmasked.
+
+use std::fmt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use langfuse_client_base::models::IngestionEvent;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::interceptor::{payload_fields, BatchInterceptor};
+
+/// Text that replaces redacted values by default
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Text that replaces values when the mask function panics
+pub const MASK_FAILED: &str = "<fully masked due to failed mask function>";
+
+/// Keys whose values are always redacted by default
+const DEFAULT_KEYS: [&str; 15] = [
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "accesstoken",
+    "refreshtoken",
+    "apikey",
+    "secretkey",
+    "privatekey",
+    "clientsecret",
+    "authorization",
+    "cookie",
+    "setcookie",
+    "ssn",
+    "cardnumber",
+];
+
+/// Check a pattern match must pass to be redacted
+type Accept = fn(&str) -> bool;
+
+/// Patterns redacted inside strings by default
+const DEFAULT_PATTERNS: [(&str, Option<Accept>); 4] = [
+    // Email addresses
+    (r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}", None),
+    // Payment card numbers, optionally grouped by spaces or dashes. Only
+    // Luhn-valid ones, so timestamps, order IDs, and phone numbers are kept
+    (r"\b(?:\d[ -]?){12,18}\d\b", Some(is_luhn_valid)),
+    // Secret keys such as `sk-lf-...`
+    (r"\bsk-[A-Za-z0-9_-]{16,}", None),
+    // Bearer tokens
+    (r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*", None),
+];
+
+/// Whether the digits of `text` pass the Luhn checksum of card numbers
+fn is_luhn_valid(text: &str) -> bool {
+    let sum: u32 = text
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// A redaction pattern, and the check a match must pass to be redacted
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    accept: Option<Accept>,
+}
+
+/// Masks sensitive keys and patterns in JSON values and strings
+///
+/// [`Redactor::default`] comes with rules for common credentials and PII;
+/// [`Redactor::new`] starts without any.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    keys: Vec<String>,
+    patterns: Vec<Rule>,
+    replacement: String,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self {
+            keys: DEFAULT_KEYS.iter().map(|key| (*key).to_string()).collect(),
+            patterns: DEFAULT_PATTERNS
+                .iter()
+                .map(|&(pattern, accept)| Rule {
+                    regex: Regex::new(pattern).expect("default patterns are valid"),
+                    accept,
+                })
+                .collect(),
+            replacement: REDACTED.to_string(),
+        }
+    }
+}
+
+impl Redactor {
+    /// Create a redactor without any rules
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            patterns: Vec::new(),
+            replacement: REDACTED.to_string(),
+        }
+    }
+
+    /// Also redact the values of `key`
+    #[must_use]
+    pub fn key(mut self, key: impl AsRef<str>) -> Self {
+        self.keys.push(normalize_key(key.as_ref()));
+        self
+    }
+
+    /// Also redact text matching the regular expression `pattern`
+    pub fn pattern(mut self, pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            Error::Configuration(format!("Invalid redaction pattern `{pattern}`: {e}"))
+        })?;
+        self.patterns.push(Rule {
+            regex,
+            accept: None,
+        });
+        Ok(self)
+    }
+
+    /// Replace redacted values with `replacement` (defaults to [`REDACTED`])
+    #[must_use]
+    pub fn replacement(mut self, replacement: impl Into<String>) -> Self {
+        self.replacement = replacement.into();
+        self
+    }
+
+    /// Whether the values of `key` are redacted
+    pub fn is_sensitive_key(&self, key: &str) -> bool {
+        let key = normalize_key(key);
+        self.keys.contains(&key)
+    }
+
+    /// Redact every match of the patterns in `text`
+    pub fn redact_str(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for rule in &self.patterns {
+            if rule.regex.is_match(&text) {
+                text = rule
+                    .regex
+                    .replace_all(&text, |captures: &regex::Captures<'_>| {
+                        let matched = &captures[0];
+                        if rule.accept.is_none_or(|accept| accept(matched)) {
+                            self.replacement.clone()
+                        } else {
+                            matched.to_string()
+                        }
+                    })
+                    .into_owned();
+            }
+        }
+        text
+    }
+
+    /// Redact sensitive keys and patterns anywhere in `value`
+    ///
+    /// Values of sensitive keys are replaced whole, whatever their type.
+    pub fn redact_json(&self, mut value: Value) -> Value {
+        self.redact_in_place(&mut value);
+        value
+    }
+
+    fn redact_in_place(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.redact_str(text),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_in_place(item)),
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if self.is_sensitive_key(key) {
+                        *value = Value::String(self.replacement.clone());
+                    } else {
+                        self.redact_in_place(value);
+                    }
+                }
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) => {}
+        }
+    }
+}
+
+impl BatchInterceptor for Redactor {
+    /// Redact the input, output, and metadata of the event's body
+    fn on_event(&self, event: &mut IngestionEvent) {
+        // Scores have no input or output, but their metadata is redacted too
+        if let IngestionEvent::IngestionEventOneOf1(e) = event {
+            self.redact_field(&mut e.body.metadata);
+        }
+        for field in payload_fields(event).into_iter().flatten() {
+            self.redact_field(field);
+        }
+    }
+}
+
+impl Redactor {
+    fn redact_field(&self, field: &mut Option<Option<Value>>) {
+        if let Some(Some(value)) = field {
+            self.redact_in_place(value);
+        }
+    }
+}
+
+/// User-provided function masking input and output values
+#[derive(Clone)]
+pub(crate) struct Mask(Arc<dyn Fn(&Value) -> Value + Send + Sync>);
+
+impl Mask {
+    pub(crate) fn new(mask: impl Fn(&Value) -> Value + Send + Sync + 'static) -> Self {
+        Self(Arc::new(mask))
+    }
+
+    /// Masked `value`, or [`MASK_FAILED`] if the function panics
+    pub(crate) fn apply(&self, value: &Value) -> Value {
+        catch_unwind(AssertUnwindSafe(|| (self.0)(value))).unwrap_or_else(|_| {
+            tracing::warn!("Mask function panicked; replacing the value");
+            Value::String(MASK_FAILED.to_string())
+        })
+    }
+}
+
+impl fmt::Debug for Mask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Mask(<callback>)")
+    }
+}
+
+impl BatchInterceptor for Mask {
+    /// Mask the input and output of the event's body
+    fn on_event(&self, event: &mut IngestionEvent) {
+        let Some([input, output, _]) = crate::interceptor::payload_fields(event) else {
+            return;
+        };
+        for field in [input, output] {
+            if let Some(Some(value)) = field {
+                *value = self.apply(value);
+            }
+        }
+    }
+}
+
+/// Lowercase `key` and drop `-` and `_`
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_keys() {
+        let redactor = Redactor::default();
+        let value = redactor.redact_json(json!({
+            "headers": {"Authorization": "Basic abc", "Content-Type": "application/json"},
+            "apiKey": {"nested": true},
+            "max_tokens": 100,
+        }));
+        assert_eq!(
+            value,
+            json!({
+                "headers": {"Authorization": "[REDACTED]", "Content-Type": "application/json"},
+                "apiKey": "[REDACTED]",
+                "max_tokens": 100,
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_patterns() {
+        let redactor = Redactor::default();
+        assert_eq!(
+            redactor.redact_str("Card 4111 1111 1111 1111, mail a.b@example.org"),
+            "Card [REDACTED], mail [REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact_str("key sk-lf-1234567890abcdef1234"),
+            "key [REDACTED]"
+        );
+        assert_eq!(
+            redactor.redact_str("Authorization: Bearer eyJhbGciOi.J9"),
+            "Authorization: [REDACTED]"
+        );
+        assert_eq!(redactor.redact_str("order 12345"), "order 12345");
+        // Digit runs failing the Luhn check are not card numbers
+        assert_eq!(
+            redactor.redact_str("at 1700000000000, call +1 415 555 0123 456"),
+            "at 1700000000000, call +1 415 555 0123 456"
+        );
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let redactor = Redactor::new()
+            .key("customer-id")
+            .pattern(r"ACC-\d+")
+            .unwrap()
+            .replacement("***");
+        let value = redactor.redact_json(json!([
+            {"customer_id": "c-1", "password": "kept"},
+            "account ACC-42",
+        ]));
+        assert_eq!(
+            value,
+            json!([{"customer_id": "***", "password": "kept"}, "account ***"])
+        );
+        assert!(Redactor::new().pattern("(").is_err());
+    }
+
+    #[test]
+    fn test_redact_event_fields() {
+        use langfuse_client_base::models::{CreateSpanBody, IngestionEventOneOf2};
+
+        let body = CreateSpanBody {
+            name: Some(Some("login".to_string())),
+            input: Some(Some(json!({"user": "jane@example.com", "password": "x"}))),
+            output: Some(None),
+            metadata: Some(Some(json!({"token": "abc", "region": "eu"}))),
+            ..Default::default()
+        };
+        let mut event = IngestionEvent::IngestionEventOneOf2(Box::new(IngestionEventOneOf2::new(
+            "event-1".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            body,
+            Default::default(),
+        )));
+        Redactor::default().on_event(&mut event);
+
+        let IngestionEvent::IngestionEventOneOf2(span) = event else {
+            unreachable!()
+        };
+        assert_eq!(
+            span.body.input,
+            Some(Some(
+                json!({"user": "[REDACTED]", "password": "[REDACTED]"})
+            ))
+        );
+        assert_eq!(span.body.output, Some(None));
+        assert_eq!(
+            span.body.metadata,
+            Some(Some(json!({"token": "[REDACTED]", "region": "eu"})))
+        );
+        assert_eq!(span.body.name, Some(Some("login".to_string())));
+    }
+
+    #[test]
+    fn test_redact_score_metadata() {
+        use langfuse_client_base::models::{
+            ingestion_event_one_of_1::Type, CreateScoreValue, IngestionEventOneOf1, ScoreBody,
+        };
+
+        let mut body = ScoreBody::new("quality".to_string(), CreateScoreValue::Number(0.9));
+        body.metadata = Some(Some(json!({"api_key": "abc", "rater": "jane@example.com"})));
+        let mut event = IngestionEvent::IngestionEventOneOf1(Box::new(IngestionEventOneOf1::new(
+            "event-1".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            body,
+            Type::ScoreCreate,
+        )));
+        Redactor::default().on_event(&mut event);
+
+        let IngestionEvent::IngestionEventOneOf1(score) = event else {
+            unreachable!()
+        };
+        assert_eq!(
+            score.body.metadata,
+            Some(Some(
+                json!({"api_key": "[REDACTED]", "rater": "[REDACTED]"})
+            ))
+        );
+    }
+
+    #[test]
+    fn test_mask_replaces_values_when_it_panics() {
+        let mask = Mask::new(|value| match value {
+            Value::String(text) if text.contains("secret") => panic!("cannot mask"),
+            Value::String(text) => Value::String(text.to_uppercase()),
+            other => other.clone(),
+        });
+        assert_eq!(mask.apply(&json!("hello")), json!("HELLO"));
+        assert_eq!(mask.apply(&json!(42)), json!(42));
+        assert_eq!(mask.apply(&json!("a secret")), json!(MASK_FAILED));
+    }
+}
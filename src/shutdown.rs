@@ -0,0 +1,98 @@
+//! Graceful shutdown on SIGTERM and Ctrl-C
+//!
+//! [`install_shutdown_hook`] spawns a task that waits for SIGTERM (on Unix) or
+//! Ctrl-C, then drains the batcher with [`Batcher::drain`]: new events are
+//! rejected, pending events are flushed within a deadline, and the resulting
+//! [`DrainReport`] is logged as JSON at `info` level. If
+//! [`METRICS_FILE_ENV`](crate::metrics_file::METRICS_FILE_ENV) names a file, the
+//! final batcher metrics are also written there for post-run scraping.
+//!
+//! ```no_run
+//! use langfuse_ergonomic::{install_shutdown_hook, Batcher, ClientBuilder};
+//! use std::sync::Arc;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ClientBuilder::from_env()?.build()?;
+//! let batcher = Arc::new(Batcher::builder().client(client).build().await);
+//! let hook = install_shutdown_hook(batcher.clone());
+//!
+//! // ... serve requests, adding events to the batcher ...
+//!
+//! // Wait for the drain to finish before the process exits
+//! let report = hook.await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::batcher::{Batcher, DrainReport};
+use crate::metrics_file::MetricsFile;
+
+/// Default time allowed for flushing pending events after a shutdown signal
+pub const DEFAULT_DRAIN_DEADLINE: Duration = Duration::from_secs(10);
+
+/// Drain `batcher` when the process receives SIGTERM or Ctrl-C
+///
+/// Uses [`DEFAULT_DRAIN_DEADLINE`]. The returned handle resolves to the drain
+/// report once draining is complete.
+pub fn install_shutdown_hook(batcher: Arc<Batcher>) -> JoinHandle<DrainReport> {
+    install_shutdown_hook_with_deadline(batcher, DEFAULT_DRAIN_DEADLINE)
+}
+
+/// Drain `batcher` within `deadline` when the process receives SIGTERM or Ctrl-C
+pub fn install_shutdown_hook_with_deadline(
+    batcher: Arc<Batcher>,
+    deadline: Duration,
+) -> JoinHandle<DrainReport> {
+    tokio::spawn(async move {
+        let signal = wait_for_signal().await;
+        tracing::info!(
+            signal,
+            "Shutdown signal received, draining Langfuse batcher"
+        );
+
+        let report = batcher.drain(deadline).await;
+        match serde_json::to_string(&report) {
+            Ok(json) => tracing::info!(report = %json, "Langfuse batcher drained"),
+            Err(e) => tracing::warn!("Failed to serialize drain report: {e}"),
+        }
+        if let Some(file) = MetricsFile::from_env() {
+            if let Err(e) = file.write(&batcher.shutdown_metrics(report.clone())) {
+                tracing::warn!("{e}");
+            }
+        }
+        report
+    })
+}
+
+/// Wait for the first shutdown signal and return its name
+async fn wait_for_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => "SIGTERM",
+                    _ = tokio::signal::ctrl_c() => "SIGINT",
+                }
+            }
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM, falling back to Ctrl-C: {e}");
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "ctrl-c"
+    }
+}
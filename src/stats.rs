@@ -0,0 +1,264 @@
+//! Aggregate statistics over traces
+//!
+//! [`LangfuseClient::trace_stats`](crate::LangfuseClient::trace_stats) fetches
+//! the traces matching a [`TraceStatsFilter`] together with their observations
+//! and summarizes them into [`TraceStats`]: latency percentiles, token and cost
+//! totals, and the share of traces with errors.
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::TraceFilter;
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! let stats = client
+//!     .trace_stats(
+//!         TraceFilter::new()
+//!             .name("chat")
+//!             .from_timestamp(Utc::now() - Duration::days(7)),
+//!     )
+//!     .await?;
+//! println!(
+//!     "{} traces, p95 {:?}, ${:.2}, {:.1}% errors",
+//!     stats.count,
+//!     stats.p95_latency,
+//!     stats.total_cost,
+//!     stats.error_rate * 100.0
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The list endpoint does not return observations, so each matching trace is
+//! fetched individually, [`STATS_FETCH_CONCURRENCY`] at a time: summarizing
+//! N traces costs N requests plus one list request per 100 traces. Bound large
+//! time ranges with [`TraceStatsFilter::max_traces`].
+
+use std::time::Duration;
+
+use langfuse_client_base::models::{ObservationLevel, TraceWithFullDetails};
+
+use crate::latency::percentile;
+use crate::query::TraceFilter;
+use crate::traces::observed_duration;
+
+/// Default upper bound on the number of traces summarized
+pub const DEFAULT_MAX_TRACES: usize = 1000;
+
+/// Traces fetched at once while summarizing
+pub const STATS_FETCH_CONCURRENCY: usize = 8;
+
+/// Traces to include in [`TraceStats`]
+///
+/// A [`TraceFilter`] converts into a `TraceStatsFilter` with the default
+/// [`max_traces`](Self::max_traces).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStatsFilter {
+    /// Which traces to summarize
+    pub traces: TraceFilter,
+    /// Summarize at most this many traces (default [`DEFAULT_MAX_TRACES`])
+    pub max_traces: usize,
+}
+
+impl Default for TraceStatsFilter {
+    fn default() -> Self {
+        Self {
+            traces: TraceFilter::default(),
+            max_traces: DEFAULT_MAX_TRACES,
+        }
+    }
+}
+
+impl From<TraceFilter> for TraceStatsFilter {
+    fn from(traces: TraceFilter) -> Self {
+        Self {
+            traces,
+            ..Self::default()
+        }
+    }
+}
+
+impl TraceStatsFilter {
+    /// Match all traces, up to [`DEFAULT_MAX_TRACES`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only the traces matching `traces`
+    #[must_use]
+    pub fn traces(mut self, traces: TraceFilter) -> Self {
+        self.traces = traces;
+        self
+    }
+
+    /// Summarize at most this many traces
+    #[must_use]
+    pub fn max_traces(mut self, max_traces: usize) -> Self {
+        self.max_traces = max_traces;
+        self
+    }
+}
+
+/// Summary of a set of traces
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct TraceStats {
+    /// Number of traces summarized
+    pub count: usize,
+    /// Median trace latency
+    pub p50_latency: Option<Duration>,
+    /// 95th percentile trace latency
+    pub p95_latency: Option<Duration>,
+    /// Total tokens over all observations
+    pub total_tokens: u64,
+    /// Total cost in USD
+    pub total_cost: f64,
+    /// Number of traces with at least one `ERROR` observation
+    pub error_count: usize,
+    /// Share of traces with errors, between 0 and 1
+    pub error_rate: f64,
+    /// Whether more traces matched than [`TraceStatsFilter::max_traces`]
+    pub truncated: bool,
+}
+
+/// Summarize fetched traces
+///
+/// Latency comes from the observations (earliest start to latest end), falling
+/// back to the latency reported for the trace. Cost is the trace's total cost,
+/// or the sum of its observations' calculated costs.
+pub(crate) fn summarize(traces: &[TraceWithFullDetails], truncated: bool) -> TraceStats {
+    let mut latencies = Vec::with_capacity(traces.len());
+    let mut stats = TraceStats {
+        count: traces.len(),
+        truncated,
+        ..Default::default()
+    };
+
+    for trace in traces {
+        let latency = observed_duration(trace).or_else(|| {
+            trace
+                .latency
+                .flatten()
+                .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        });
+        latencies.extend(latency);
+
+        stats.total_tokens += trace
+            .observations
+            .iter()
+            .map(|observation| {
+                let tokens = observation
+                    .usage_details
+                    .get("total")
+                    .copied()
+                    .unwrap_or(observation.usage.total);
+                u64::try_from(tokens).unwrap_or(0)
+            })
+            .sum::<u64>();
+
+        stats.total_cost += trace.total_cost.flatten().unwrap_or_else(|| {
+            trace
+                .observations
+                .iter()
+                .filter_map(|observation| observation.calculated_total_cost.flatten())
+                .sum()
+        });
+
+        if trace
+            .observations
+            .iter()
+            .any(|observation| observation.level == ObservationLevel::Error)
+        {
+            stats.error_count += 1;
+        }
+    }
+
+    latencies.sort_unstable();
+    if !latencies.is_empty() {
+        stats.p50_latency = Some(percentile(&latencies, 0.5));
+        stats.p95_latency = Some(percentile(&latencies, 0.95));
+    }
+    if stats.count > 0 {
+        stats.error_rate = stats.error_count as f64 / stats.count as f64;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use langfuse_client_base::models::{ObservationsView, Usage};
+    use std::collections::HashMap;
+
+    fn observation(
+        start: &str,
+        end: &str,
+        tokens: i32,
+        level: ObservationLevel,
+    ) -> ObservationsView {
+        ObservationsView {
+            start_time: start.to_string(),
+            end_time: Some(Some(end.to_string())),
+            usage: Box::new(Usage {
+                total: tokens,
+                ..Default::default()
+            }),
+            usage_details: HashMap::from([("total".to_string(), tokens)]),
+            calculated_total_cost: Some(Some(0.5)),
+            level,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let ok = TraceWithFullDetails {
+            observations: vec![observation(
+                "2024-01-01T00:00:00Z",
+                "2024-01-01T00:00:01Z",
+                100,
+                ObservationLevel::Default,
+            )],
+            total_cost: Some(Some(2.0)),
+            ..Default::default()
+        };
+        let failed = TraceWithFullDetails {
+            observations: vec![
+                observation(
+                    "2024-01-01T00:00:00Z",
+                    "2024-01-01T00:00:02Z",
+                    50,
+                    ObservationLevel::Default,
+                ),
+                observation(
+                    "2024-01-01T00:00:02Z",
+                    "2024-01-01T00:00:03Z",
+                    0,
+                    ObservationLevel::Error,
+                ),
+            ],
+            ..Default::default()
+        };
+        let reported = TraceWithFullDetails {
+            latency: Some(Some(0.5)),
+            ..Default::default()
+        };
+
+        let stats = summarize(&[ok, failed, reported], false);
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.p50_latency, Some(Duration::from_secs(1)));
+        assert_eq!(stats.p95_latency, Some(Duration::from_secs(3)));
+        assert_eq!(stats.total_tokens, 150);
+        assert_eq!(stats.total_cost, 3.0);
+        assert_eq!(stats.error_count, 1);
+        assert!((stats.error_rate - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        let stats = summarize(&[], false);
+        assert_eq!(stats.count, 0);
+        assert_eq!(stats.p50_latency, None);
+        assert_eq!(stats.error_rate, 0.0);
+    }
+}
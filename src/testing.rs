@@ -0,0 +1,270 @@
+//! In-memory recording client for application tests
+//!
+//! A [`RecordingClient`] is a [disabled](crate::ClientBuilder::disabled)
+//! [`LangfuseClient`] that keeps every event it would have sent. Pass its
+//! client to the code under test, then assert on the recorded traces,
+//! observations, and scores, without an HTTP mock:
+//!
+//! ```
+//! use langfuse_ergonomic::testing::RecordingClient;
+//! use serde_json::json;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let langfuse = RecordingClient::new();
+//!
+//! // Code under test, given `langfuse.client().clone()`
+//! let trace = langfuse.trace().name("handle-request").call().await?;
+//! langfuse
+//!     .generation()
+//!     .trace_id(&trace.id)
+//!     .name("answer")
+//!     .output(json!("Hello!"))
+//!     .call()
+//!     .await?;
+//!
+//! assert_eq!(langfuse.recorded_traces()[0].name(), Some("handle-request"));
+//! let observations = langfuse.observations_of(&trace.id);
+//! assert_eq!(observations[0].kind, "GENERATION");
+//! assert_eq!(observations[0].output(), Some(&json!("Hello!")));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Events are recorded after sampling, the environment, redaction, and
+//! interceptors are applied, exactly as they would have been sent, including
+//! those queued in batchers built from the client. Updates of a trace or
+//! observation are merged into one [`Recorded`] entry, the way Langfuse
+//! merges them.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use langfuse_client_base::models::IngestionEvent;
+use serde_json::Value;
+
+use crate::client::{ClientBuilder, LangfuseClient};
+use crate::error::Result;
+
+/// Events kept by a recording client, shared by its clones and batchers
+#[derive(Debug, Default)]
+pub(crate) struct Recorder {
+    events: Mutex<Vec<IngestionEvent>>,
+}
+
+impl Recorder {
+    pub(crate) fn record(&self, events: &[IngestionEvent]) {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend_from_slice(events);
+    }
+
+    fn events(&self) -> Vec<IngestionEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn clear(&self) {
+        self.events
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// A trace, observation, or score as it would have been stored
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recorded {
+    /// ID of the trace, observation, or score
+    pub id: String,
+    /// `TRACE`, `SCORE`, or the observation type, e.g. `SPAN` or `GENERATION`
+    pub kind: String,
+    /// Body with the fields of all its events, as camelCase JSON
+    pub body: Value,
+}
+
+impl Recorded {
+    /// Value of a body field, by its camelCase API name
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        self.body.get(field).filter(|value| !value.is_null())
+    }
+
+    /// Name, if set
+    pub fn name(&self) -> Option<&str> {
+        self.get("name").and_then(Value::as_str)
+    }
+
+    /// Trace the observation or score belongs to, or the ID of a trace
+    pub fn trace_id(&self) -> Option<&str> {
+        if self.kind == "TRACE" {
+            return Some(&self.id);
+        }
+        self.get("traceId").and_then(Value::as_str)
+    }
+
+    /// Input, if set
+    pub fn input(&self) -> Option<&Value> {
+        self.get("input")
+    }
+
+    /// Output, if set
+    pub fn output(&self) -> Option<&Value> {
+        self.get("output")
+    }
+
+    /// Metadata, if set
+    pub fn metadata(&self) -> Option<&Value> {
+        self.get("metadata")
+    }
+}
+
+/// A disabled client recording the events it would have sent
+///
+/// Dereferences to [`LangfuseClient`]. See the [module documentation](self).
+#[derive(Clone)]
+pub struct RecordingClient {
+    client: LangfuseClient,
+    recorder: Arc<Recorder>,
+}
+
+impl Default for RecordingClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecordingClient {
+    /// Record with a client in its default configuration
+    pub fn new() -> Self {
+        Self::from_builder(ClientBuilder::new()).expect("the default configuration is valid")
+    }
+
+    /// Record with a client configured by `builder`
+    ///
+    /// Settings that change events, such as the environment, redaction, or
+    /// interceptors, apply to the recorded events. The client is always
+    /// disabled and needs no credentials.
+    pub fn from_builder(builder: ClientBuilder) -> Result<Self> {
+        let recorder = Arc::new(Recorder::default());
+        let client = builder.disabled(true).recorder(recorder.clone()).build()?;
+        Ok(Self { client, recorder })
+    }
+
+    /// The recording client, to pass to the code under test
+    pub fn client(&self) -> &LangfuseClient {
+        &self.client
+    }
+
+    /// All recorded ingestion events, in the order they were sent
+    pub fn recorded_events(&self) -> Vec<IngestionEvent> {
+        self.recorder.events()
+    }
+
+    /// Recorded traces, in the order they were created
+    pub fn recorded_traces(&self) -> Vec<Recorded> {
+        self.recorded(|kind| kind == "TRACE")
+    }
+
+    /// Recorded observations of all types, in the order they were created
+    pub fn recorded_observations(&self) -> Vec<Recorded> {
+        self.recorded(|kind| kind != "TRACE" && kind != "SCORE")
+    }
+
+    /// Recorded scores, in the order they were created
+    pub fn recorded_scores(&self) -> Vec<Recorded> {
+        self.recorded(|kind| kind == "SCORE")
+    }
+
+    /// Recorded observations of the trace `trace_id`
+    pub fn observations_of(&self, trace_id: &str) -> Vec<Recorded> {
+        self.recorded_observations()
+            .into_iter()
+            .filter(|observation| observation.trace_id() == Some(trace_id))
+            .collect()
+    }
+
+    /// Forget everything recorded so far
+    pub fn clear(&self) {
+        self.recorder.clear();
+    }
+
+    /// Merge the recorded events into entries whose kind matches `filter`
+    fn recorded(&self, filter: impl Fn(&str) -> bool) -> Vec<Recorded> {
+        let mut entries: Vec<Recorded> = Vec::new();
+        let mut positions: HashMap<(&str, String), usize> = HashMap::new();
+
+        for event in self.recorder.events() {
+            let Ok(Value::Object(mut event)) = serde_json::to_value(&event) else {
+                continue;
+            };
+            let Some(Value::Object(body)) = event.remove("body") else {
+                continue;
+            };
+            let event_type = event.get("type").and_then(Value::as_str).unwrap_or("");
+            let Some(kind) = kind(event_type, &body) else {
+                continue;
+            };
+            let Some(id) = body.get("id").and_then(Value::as_str).map(str::to_string) else {
+                continue;
+            };
+
+            // Observations of all types share one ID space
+            let namespace = match kind.as_str() {
+                "TRACE" => "trace",
+                "SCORE" => "score",
+                _ => "observation",
+            };
+            let key = (namespace, id.clone());
+            match positions.get(&key) {
+                Some(&position) => {
+                    let entry = &mut entries[position];
+                    if let Value::Object(merged) = &mut entry.body {
+                        merged.extend(body.into_iter().filter(|(_, value)| !value.is_null()));
+                    }
+                    if event_type.ends_with("-create") {
+                        entry.kind = kind;
+                    }
+                }
+                None => {
+                    positions.insert(key, entries.len());
+                    entries.push(Recorded {
+                        id,
+                        kind,
+                        body: Value::Object(body),
+                    });
+                }
+            }
+        }
+
+        entries.retain(|entry| filter(&entry.kind));
+        entries
+    }
+}
+
+impl Deref for RecordingClient {
+    type Target = LangfuseClient;
+
+    fn deref(&self) -> &LangfuseClient {
+        &self.client
+    }
+}
+
+/// Kind of the entity an event of `event_type` creates or updates
+fn kind(event_type: &str, body: &serde_json::Map<String, Value>) -> Option<String> {
+    let kind = match event_type {
+        "trace-create" => "TRACE",
+        "score-create" => "SCORE",
+        "span-create" | "span-update" => "SPAN",
+        "generation-create" | "generation-update" => "GENERATION",
+        "event-create" => "EVENT",
+        "observation-create" | "observation-update" => {
+            return Some(body.get("type")?.as_str()?.to_string());
+        }
+        _ => return None,
+    };
+    Some(kind.to_string())
+}
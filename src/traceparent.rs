@@ -0,0 +1,200 @@
+//! W3C Trace Context interop
+//!
+//! Services instrumented with OpenTelemetry or another distributed tracing
+//! system pass a [`traceparent`](https://www.w3.org/TR/trace-context/#traceparent-header)
+//! header along with each request. Using its trace ID as the Langfuse trace
+//! ID makes the Langfuse trace line up with the distributed trace, so the two
+//! can be found from each other:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::ClientBuilder;
+//! use langfuse_ergonomic::IdGenerator;
+//!
+//! # async fn example(header: &str) -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! // e.g. "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+//! let trace_id = IdGenerator::from_traceparent(header)?;
+//! client.trace().id(trace_id).name("handle-request").call().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! In the other direction, [`IdGenerator::w3c_trace_id`] creates trace IDs in
+//! the W3C format, and [`Traceparent::new`] builds the header to pass them on
+//! to downstream services.
+
+use std::fmt;
+use std::str::FromStr;
+
+use uuid::Uuid;
+
+use crate::error::{Error, Result};
+
+/// A parsed W3C `traceparent` header
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Traceparent {
+    /// Trace ID, 32 lowercase hex characters
+    pub trace_id: String,
+    /// ID of the calling span, 16 lowercase hex characters
+    pub parent_id: String,
+    /// Whether the caller sampled the trace
+    pub sampled: bool,
+}
+
+impl Traceparent {
+    /// Header for a call made from the span `parent_id` of the trace `trace_id`
+    ///
+    /// Both IDs must be in the W3C format; see [`IdGenerator::w3c_trace_id`](crate::IdGenerator::w3c_trace_id)
+    /// and [`IdGenerator::w3c_span_id`](crate::IdGenerator::w3c_span_id).
+    pub fn new(trace_id: impl Into<String>, parent_id: impl Into<String>) -> Result<Self> {
+        let traceparent = Self {
+            trace_id: trace_id.into(),
+            parent_id: parent_id.into(),
+            sampled: true,
+        };
+        hex_id_problem("trace ID", &traceparent.trace_id, 32)
+            .or_else(|| hex_id_problem("parent ID", &traceparent.parent_id, 16))
+            .map_or(Ok(traceparent), |problem| Err(Error::Validation(problem)))
+    }
+
+    /// Parse a `traceparent` header value
+    pub fn parse(header: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            Error::Validation(format!("Invalid traceparent header `{header}`: {reason}"))
+        };
+
+        let parts: Vec<&str> = header.trim().split('-').collect();
+        let [version, trace_id, parent_id, flags, rest @ ..] = parts.as_slice() else {
+            return Err(invalid("expected version-traceid-parentid-flags"));
+        };
+        if version.len() != 2 || !is_lower_hex(version) || *version == "ff" {
+            return Err(invalid("unsupported version"));
+        }
+        // Later versions may append fields; version 00 has exactly four
+        if *version == "00" && !rest.is_empty() {
+            return Err(invalid("version 00 has four fields"));
+        }
+        if let Some(problem) = hex_id_problem("trace ID", trace_id, 32)
+            .or_else(|| hex_id_problem("parent ID", parent_id, 16))
+        {
+            return Err(invalid(&problem));
+        }
+        if flags.len() != 2 || !is_lower_hex(flags) {
+            return Err(invalid("flags must be 2 hex characters"));
+        }
+        let flags = u8::from_str_radix(flags, 16).map_err(|_| invalid("invalid flags"))?;
+
+        Ok(Self {
+            trace_id: (*trace_id).to_string(),
+            parent_id: (*parent_id).to_string(),
+            sampled: flags & 1 == 1,
+        })
+    }
+
+    /// Mark the trace as sampled or not
+    #[must_use]
+    pub fn sampled(mut self, sampled: bool) -> Self {
+        self.sampled = sampled;
+        self
+    }
+}
+
+impl FromStr for Traceparent {
+    type Err = Error;
+
+    fn from_str(header: &str) -> Result<Self> {
+        Self::parse(header)
+    }
+}
+
+impl fmt::Display for Traceparent {
+    /// Formats the header value, as version `00`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "00-{}-{}-{:02x}",
+            self.trace_id,
+            self.parent_id,
+            u8::from(self.sampled)
+        )
+    }
+}
+
+/// Whether `id` is a W3C trace ID: 32 lowercase hex characters, not all zero
+pub(crate) fn is_w3c_trace_id(id: &str) -> bool {
+    hex_id_problem("trace ID", id, 32).is_none()
+}
+
+/// Random ID of `len` lowercase hex characters, at most 32
+pub(crate) fn random_hex_id(len: usize) -> String {
+    let mut id = Uuid::new_v4().simple().to_string();
+    id.truncate(len);
+    id
+}
+
+/// Why `id` is not a W3C ID of `len` hex characters, if it isn't
+fn hex_id_problem(kind: &str, id: &str, len: usize) -> Option<String> {
+    if id.len() != len || !is_lower_hex(id) {
+        Some(format!(
+            "{kind} must be {len} lowercase hex characters, got `{id}`"
+        ))
+    } else if id.bytes().all(|b| b == b'0') {
+        Some(format!("{kind} must not be all zeros"))
+    } else {
+        None
+    }
+}
+
+fn is_lower_hex(text: &str) -> bool {
+    text.bytes()
+        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_parse_and_format() {
+        let traceparent = Traceparent::parse(HEADER).unwrap();
+        assert_eq!(traceparent.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(traceparent.parent_id, "00f067aa0ba902b7");
+        assert!(traceparent.sampled);
+        assert_eq!(traceparent.to_string(), HEADER);
+
+        // Future versions may carry extra fields
+        let future =
+            Traceparent::parse("cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00-extra")
+                .unwrap();
+        assert!(!future.sampled);
+    }
+
+    #[test]
+    fn test_rejects_invalid_headers() {
+        for header in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-1",
+        ] {
+            assert!(Traceparent::parse(header).is_err(), "accepted {header:?}");
+        }
+    }
+
+    #[test]
+    fn test_random_ids_are_w3c_ids() {
+        let trace_id = random_hex_id(32);
+        assert!(is_w3c_trace_id(&trace_id));
+        let traceparent = Traceparent::new(trace_id, random_hex_id(16)).unwrap();
+        assert_eq!(
+            Traceparent::parse(&traceparent.to_string()).unwrap(),
+            traceparent
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Object-safe tracing interface for libraries and test doubles
+//!
+//! The builders of [`LangfuseClient`] are generic, so they cannot be called
+//! through a trait object. [`LangfuseTracer`] covers the core operations with
+//! the API's body types instead. A library can accept any tracer as
+//! `Arc<dyn LangfuseTracer>`, whether a client, a [`Batcher`], or a fake in
+//! its tests:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use langfuse_client_base::models::CreateSpanBody;
+//! use langfuse_ergonomic::{ClientBuilder, LangfuseTracer};
+//! use serde_json::json;
+//!
+//! struct Retriever {
+//!     tracer: Arc<dyn LangfuseTracer>,
+//! }
+//!
+//! impl Retriever {
+//!     async fn search(&self, trace_id: &str, query: &str) -> langfuse_ergonomic::Result<Vec<String>> {
+//!         let documents = vec!["doc-1".to_string()];
+//!         self.tracer
+//!             .create_span(CreateSpanBody {
+//!                 trace_id: Some(Some(trace_id.to_string())),
+//!                 name: Some(Some("search".to_string())),
+//!                 input: Some(Some(json!(query))),
+//!                 output: Some(Some(json!(documents))),
+//!                 ..Default::default()
+//!             })
+//!             .await?;
+//!         Ok(documents)
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let retriever = Retriever {
+//!     tracer: Arc::new(ClientBuilder::from_env()?.build()?),
+//! };
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Implementations only provide [`submit`](LangfuseTracer::submit) and
+//! [`flush`](LangfuseTracer::flush); the `create_*` methods wrap their body
+//! in an ingestion event, giving it an ID if it has none, and submit it.
+//! Bodies are sent like events passed to
+//! [`LangfuseClient::ingest`]: the sampler, environment, interceptors, and
+//! input and output size limits apply, but not the builders' validation and
+//! metadata size checks.
+
+use chrono::Utc;
+use langfuse_client_base::models::{
+    ingestion_event_one_of, ingestion_event_one_of_1, ingestion_event_one_of_2,
+    ingestion_event_one_of_4, ingestion_event_one_of_6, CreateEventBody, CreateGenerationBody,
+    CreateSpanBody, IngestionEvent, IngestionEventOneOf, IngestionEventOneOf1,
+    IngestionEventOneOf2, IngestionEventOneOf4, IngestionEventOneOf6, ScoreBody, TraceBody,
+};
+use uuid::Uuid;
+
+use crate::batcher::Batcher;
+use crate::client::LangfuseClient;
+use crate::error::Result;
+
+/// Core tracing operations, usable as `Arc<dyn LangfuseTracer>`
+///
+/// See the [module documentation](self).
+#[async_trait::async_trait]
+pub trait LangfuseTracer: Send + Sync {
+    /// Send or queue one ingestion event
+    async fn submit(&self, event: IngestionEvent) -> Result<()>;
+
+    /// Send everything queued so far
+    async fn flush(&self) -> Result<()>;
+
+    /// Create a trace and return its ID
+    async fn create_trace(&self, mut trace: TraceBody) -> Result<String> {
+        let id = ensure_id(&mut trace.id);
+        let event = IngestionEventOneOf::new(
+            Uuid::new_v4().to_string(),
+            timestamp(),
+            trace,
+            ingestion_event_one_of::Type::TraceCreate,
+        );
+        self.submit(IngestionEvent::IngestionEventOneOf(Box::new(event)))
+            .await?;
+        Ok(id)
+    }
+
+    /// Create a span and return its ID
+    async fn create_span(&self, mut span: CreateSpanBody) -> Result<String> {
+        let id = ensure_id(&mut span.id);
+        let event = IngestionEventOneOf2::new(
+            Uuid::new_v4().to_string(),
+            timestamp(),
+            span,
+            ingestion_event_one_of_2::Type::SpanCreate,
+        );
+        self.submit(IngestionEvent::IngestionEventOneOf2(Box::new(event)))
+            .await?;
+        Ok(id)
+    }
+
+    /// Create a generation and return its ID
+    async fn create_generation(&self, mut generation: CreateGenerationBody) -> Result<String> {
+        let id = ensure_id(&mut generation.id);
+        let event = IngestionEventOneOf4::new(
+            Uuid::new_v4().to_string(),
+            timestamp(),
+            generation,
+            ingestion_event_one_of_4::Type::GenerationCreate,
+        );
+        self.submit(IngestionEvent::IngestionEventOneOf4(Box::new(event)))
+            .await?;
+        Ok(id)
+    }
+
+    /// Create an event observation and return its ID
+    async fn create_event(&self, mut event: CreateEventBody) -> Result<String> {
+        let id = ensure_id(&mut event.id);
+        let event = IngestionEventOneOf6::new(
+            Uuid::new_v4().to_string(),
+            timestamp(),
+            event,
+            ingestion_event_one_of_6::Type::EventCreate,
+        );
+        self.submit(IngestionEvent::IngestionEventOneOf6(Box::new(event)))
+            .await?;
+        Ok(id)
+    }
+
+    /// Create a score and return its ID
+    async fn create_score(&self, mut score: ScoreBody) -> Result<String> {
+        let id = ensure_id(&mut score.id);
+        let event = IngestionEventOneOf1::new(
+            Uuid::new_v4().to_string(),
+            timestamp(),
+            score,
+            ingestion_event_one_of_1::Type::ScoreCreate,
+        );
+        self.submit(IngestionEvent::IngestionEventOneOf1(Box::new(event)))
+            .await?;
+        Ok(id)
+    }
+}
+
+/// Sends each event right away; flushing does nothing
+#[async_trait::async_trait]
+impl LangfuseTracer for LangfuseClient {
+    async fn submit(&self, event: IngestionEvent) -> Result<()> {
+        self.ingest_event(event).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Queues events; flushing sends the queue and fails if any event was rejected
+#[async_trait::async_trait]
+impl LangfuseTracer for Batcher {
+    async fn submit(&self, event: IngestionEvent) -> Result<()> {
+        self.add(event).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        match Batcher::flush(self).await?.to_error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "testing")]
+#[async_trait::async_trait]
+impl LangfuseTracer for crate::testing::RecordingClient {
+    async fn submit(&self, event: IngestionEvent) -> Result<()> {
+        self.client().submit(event).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The ID in `id`, generating one if it is not set
+fn ensure_id(id: &mut Option<Option<String>>) -> String {
+    id.get_or_insert(None)
+        .get_or_insert_with(|| Uuid::new_v4().to_string())
+        .clone()
+}
+
+fn timestamp() -> String {
+    Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// A fake tracer keeping submitted events
+    #[derive(Default)]
+    struct Fake(Mutex<Vec<IngestionEvent>>);
+
+    #[async_trait::async_trait]
+    impl LangfuseTracer for Fake {
+        async fn submit(&self, event: IngestionEvent) -> Result<()> {
+            self.0.lock().unwrap().push(event);
+            Ok(())
+        }
+
+        async fn flush(&self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_methods_wrap_bodies() {
+        let fake = Fake::default();
+        let tracer: &dyn LangfuseTracer = &fake;
+
+        let trace_id = tracer
+            .create_trace(TraceBody {
+                id: Some(Some("trace-1".to_string())),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(trace_id, "trace-1");
+        let span_id = tracer
+            .create_span(CreateSpanBody {
+                trace_id: Some(Some(trace_id.clone())),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(!span_id.is_empty());
+
+        let events = fake.0.lock().unwrap();
+        assert!(matches!(
+            &events[0],
+            IngestionEvent::IngestionEventOneOf(e) if e.r#type == ingestion_event_one_of::Type::TraceCreate
+        ));
+        let IngestionEvent::IngestionEventOneOf2(span) = &events[1] else {
+            panic!("expected a span-create event");
+        };
+        assert_eq!(span.body.id, Some(Some(span_id)));
+    }
+}
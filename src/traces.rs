@@ -1,14 +1,49 @@
 //! Trace-related functionality with builder patterns
+//!
+//! # Event IDs
+//!
+//! Every ingestion event has its own ID, distinct from the ID of the trace,
+//! observation, or score in its body. The builders that send events take an
+//! optional `event_id`; reuse it when retrying a call so Langfuse deduplicates
+//! the event instead of applying it twice.
 
 use bon::bon;
 use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
+use langfuse_client_base::models::{DatasetItem, DatasetStatus, IngestionEvent};
 use serde_json::Value;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use uuid::Uuid;
 
+use crate::batcher::BatcherHandle;
 use crate::client::LangfuseClient;
+use crate::context::TraceContext;
+use crate::datasets::{DatasetExport, DatasetImport, DatasetItemLink};
+use crate::environment::Environment;
 use crate::error::{Error, Result};
+use crate::experiments::{ExperimentItemResult, ExperimentOutput, ExperimentResult};
+use crate::export::{ExportFormat, TraceExport};
+use crate::media::{MediaField, MediaPayload, MediaReference};
+use crate::observations::{model_parameters_map, GenerationUsage, Stopwatch};
+use crate::payload::GuardedFields;
+use crate::policy::NameKind;
+use crate::query::{
+    join_fields, timestamp_param, ObservationField, OrderBy, TraceField, TraceFilter,
+};
+use crate::rubric::RubricEvaluation;
+use crate::scores::{
+    LegacyCreateScoreRequest, LegacyCreateScoreResponse, ScoreDataType, ScoreInput, ScoreTransport,
+    ScoreValue, NEGATIVE_FEEDBACK_TAG, POSITIVE_FEEDBACK_TAG, USER_FEEDBACK_SCORE_NAME,
+};
+use crate::stats::{TraceStats, TraceStatsFilter};
+use crate::transport::IngestionTransport;
+use crate::usage::Usage;
 
 /// Helper trait for ergonomic tag creation
 pub trait IntoTags {
@@ -28,6 +63,18 @@ pub fn parse_observation_level(level: &str) -> langfuse_client_base::models::Obs
     }
 }
 
+impl IntoTags for &str {
+    fn into_tags(self) -> Vec<String> {
+        vec![self.to_string()]
+    }
+}
+
+impl IntoTags for String {
+    fn into_tags(self) -> Vec<String> {
+        vec![self]
+    }
+}
+
 impl IntoTags for Vec<String> {
     fn into_tags(self) -> Vec<String> {
         self
@@ -53,35 +100,221 @@ impl<const N: usize> IntoTags for [String; N] {
 }
 
 /// Response from trace creation
+#[cfg_attr(
+    feature = "json-schema",
+    derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)
+)]
 pub struct TraceResponse {
     pub id: String,
     pub base_url: String,
+    /// Session the trace belongs to, if any
+    pub session_id: Option<String>,
+    /// Project of the trace, if URLs are [project-scoped](crate::ClientBuilder::project_scoped_urls)
+    pub project_id: Option<String>,
 }
 
 impl TraceResponse {
     /// Get the Langfuse URL for this trace
+    ///
+    /// The URL is project-scoped, `/project/{projectId}/traces/{id}`, if the
+    /// project ID is known, and `/trace/{id}` otherwise.
     pub fn url(&self) -> String {
-        // More robust URL construction that handles various base_url formats
-        let mut web_url = self.base_url.clone();
+        trace_url(&self.base_url, self.project_id.as_deref(), &self.id)
+    }
+
+    /// Get the Langfuse URL of an observation of this trace
+    ///
+    /// Opens the trace with the observation selected.
+    pub fn observation_url(&self, observation_id: impl AsRef<str>) -> String {
+        format!("{}?observation={}", self.url(), observation_id.as_ref())
+    }
 
-        // Remove trailing slashes
-        web_url = web_url.trim_end_matches('/').to_string();
+    /// Get the Langfuse URL of the session this trace belongs to
+    pub fn session_url(&self) -> Option<String> {
+        let session_id = self.session_id.as_ref()?;
+        Some(match &self.project_id {
+            Some(project_id) => format!(
+                "{}/project/{}/sessions/{}",
+                web_url(&self.base_url),
+                project_id,
+                session_id
+            ),
+            None => format!("{}/sessions/{}", web_url(&self.base_url), session_id),
+        })
+    }
 
-        // Replace /api/public or /api at the end with empty string
-        if web_url.ends_with("/api/public") {
-            web_url = web_url[..web_url.len() - 11].to_string();
-        } else if web_url.ends_with("/api") {
-            web_url = web_url[..web_url.len() - 4].to_string();
-        }
+    /// Context for attaching observations and scores to this trace
+    pub fn context(&self, client: &LangfuseClient) -> TraceContext {
+        client.trace_context(self.id.clone())
+    }
+
+    /// Set the output of this trace
+    ///
+    /// See [`LangfuseClient::update_trace`] for updating other fields.
+    pub async fn set_output(&self, client: &LangfuseClient, output: Value) -> Result<()> {
+        client
+            .update_trace()
+            .id(self.id.clone())
+            .output(output)
+            .call()
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Response from span, generation, and event creation
+///
+/// Converts into the observation ID, so it can be passed on wherever an ID
+/// is expected, e.g. as the `parent_observation_id` of a child observation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservationResponse {
+    /// ID of the observation
+    pub id: String,
+    /// ID of the trace the observation belongs to
+    pub trace_id: String,
+    /// Langfuse URL of the trace with the observation selected
+    pub url: String,
+}
+
+impl ObservationResponse {
+    fn new(base_url: &str, project_id: Option<&str>, trace_id: String, id: String) -> Self {
+        let url = format!(
+            "{}?observation={}",
+            trace_url(base_url, project_id, &trace_id),
+            id
+        );
+        Self { id, trace_id, url }
+    }
+}
+
+impl fmt::Display for ObservationResponse {
+    /// Formats the observation ID
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)
+    }
+}
 
-        format!("{}/trace/{}", web_url, self.id)
+impl AsRef<str> for ObservationResponse {
+    fn as_ref(&self) -> &str {
+        &self.id
     }
 }
 
+impl From<ObservationResponse> for String {
+    fn from(response: ObservationResponse) -> Self {
+        response.id
+    }
+}
+
+impl From<&ObservationResponse> for String {
+    fn from(response: &ObservationResponse) -> Self {
+        response.id.clone()
+    }
+}
+
+impl PartialEq<str> for ObservationResponse {
+    fn eq(&self, other: &str) -> bool {
+        self.id == other
+    }
+}
+
+impl PartialEq<String> for ObservationResponse {
+    fn eq(&self, other: &String) -> bool {
+        self.id == *other
+    }
+}
+
+impl PartialEq<&str> for ObservationResponse {
+    fn eq(&self, other: &&str) -> bool {
+        self.id == *other
+    }
+}
+
+/// UI URL of a trace, project-scoped if the project ID is known
+fn trace_url(base_url: &str, project_id: Option<&str>, trace_id: &str) -> String {
+    match project_id {
+        Some(project_id) => format!(
+            "{}/project/{}/traces/{}",
+            web_url(base_url),
+            project_id,
+            trace_id
+        ),
+        None => format!("{}/trace/{}", web_url(base_url), trace_id),
+    }
+}
+
+/// Web UI URL for an API base URL, e.g. without a trailing `/api/public`
+fn web_url(base_url: &str) -> &str {
+    let web_url = base_url.trim_end_matches('/');
+    web_url
+        .strip_suffix("/api/public")
+        .or_else(|| web_url.strip_suffix("/api"))
+        .unwrap_or(web_url)
+}
+
+/// Result of [`LangfuseClient::finish_trace`]
+#[derive(Debug, Clone)]
+pub struct FinishedTrace {
+    /// ID of the finished trace
+    pub trace_id: String,
+    /// Total duration of the trace
+    pub duration: Duration,
+    /// Latency objective the trace was measured against
+    pub sla: Duration,
+    /// Whether `duration` was within `sla`
+    pub sla_met: bool,
+    /// ID of the `latency_ms` score
+    pub latency_score_id: String,
+    /// ID of the `sla_met` score
+    pub sla_score_id: String,
+}
+
+/// Time from the earliest observation start to the latest observation end
+pub(crate) fn observed_duration(
+    trace: &langfuse_client_base::models::TraceWithFullDetails,
+) -> Option<Duration> {
+    let parse = |s: &str| {
+        DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    };
+
+    let mut start: Option<DateTime<Utc>> = None;
+    let mut end: Option<DateTime<Utc>> = None;
+    for observation in &trace.observations {
+        let Some(observed_start) = parse(&observation.start_time) else {
+            continue;
+        };
+        let observed_end = observation
+            .end_time
+            .clone()
+            .flatten()
+            .and_then(|t| parse(&t))
+            .unwrap_or(observed_start);
+        start = Some(start.map_or(observed_start, |s| s.min(observed_start)));
+        end = Some(end.map_or(observed_end, |e| e.max(observed_end)));
+    }
+
+    (end? - start?).to_std().ok()
+}
+
 /// Helper functions for generating deterministic IDs
 pub struct IdGenerator;
 
 impl IdGenerator {
+    /// Longest ID accepted by [`validate`](Self::validate), in bytes
+    pub const MAX_LENGTH: usize = 256;
+
+    /// Check that `id` is usable as a Langfuse trace or observation ID
+    ///
+    /// IDs must be 1 to [`MAX_LENGTH`](Self::MAX_LENGTH) printable ASCII
+    /// characters without whitespace, so they can be sent, stored, and used in
+    /// UI URLs unchanged. The trace, span, generation, and event builders
+    /// check their IDs this way before sending.
+    pub fn validate(id: &str) -> Result<()> {
+        validate_id("ID", id)
+    }
+
     /// Generate a deterministic UUID v5 from a seed string
     /// This ensures the same seed always produces the same ID
     pub fn from_seed(seed: &str) -> String {
@@ -105,28 +338,294 @@ impl IdGenerator {
         let hash = hasher.finish();
         format!("{:016x}", hash)
     }
+
+    /// Trace ID of a W3C `traceparent` header, to line a Langfuse trace up
+    /// with a distributed trace
+    ///
+    /// See [`crate::traceparent`].
+    pub fn from_traceparent(header: &str) -> Result<String> {
+        crate::traceparent::Traceparent::parse(header).map(|traceparent| traceparent.trace_id)
+    }
+
+    /// Generate a random trace ID in the W3C format, 32 lowercase hex characters
+    ///
+    /// Langfuse accepts it like any other ID, and it can be passed on to other
+    /// services in a `traceparent` header.
+    pub fn w3c_trace_id() -> String {
+        crate::traceparent::random_hex_id(32)
+    }
+
+    /// Generate a random span ID in the W3C format, 16 lowercase hex characters
+    pub fn w3c_span_id() -> String {
+        crate::traceparent::random_hex_id(16)
+    }
+
+    /// Whether `id` is a W3C trace ID: 32 lowercase hex characters, not all zero
+    pub fn is_w3c_trace_id(id: &str) -> bool {
+        crate::traceparent::is_w3c_trace_id(id)
+    }
+}
+
+/// Check an ID before it is sent; see [`IdGenerator::validate`]
+fn validate_id(kind: &str, id: &str) -> Result<()> {
+    let problem = if id.is_empty() {
+        "is empty".to_string()
+    } else if id.len() > IdGenerator::MAX_LENGTH {
+        format!(
+            "is {} bytes long, more than the maximum of {}",
+            id.len(),
+            IdGenerator::MAX_LENGTH
+        )
+    } else if let Some(c) = id.chars().find(|c| !c.is_ascii_graphic()) {
+        format!("contains the illegal character {c:?}")
+    } else {
+        return Ok(());
+    };
+    Err(Error::Validation(format!("{kind} `{id}` {problem}")))
+}
+
+/// Check the trace, observation, and parent observation IDs of an observation
+fn validate_observation_ids(
+    trace_id: &str,
+    observation_id: &str,
+    parent_observation_id: Option<&str>,
+) -> Result<()> {
+    validate_id("Trace ID", trace_id)?;
+    validate_id("Observation ID", observation_id)?;
+    parent_observation_id.map_or(Ok(()), |id| validate_id("Parent observation ID", id))
+}
+
+/// Receives the event built by a builder instead of it being sent right away
+#[derive(Clone)]
+enum EventCapture {
+    /// Kept for `build_event`
+    Store(Arc<Mutex<Option<IngestionEvent>>>),
+    /// Queued on a batcher
+    Batch(BatcherHandle),
+}
+
+impl Default for EventCapture {
+    fn default() -> Self {
+        Self::Store(Arc::default())
+    }
+}
+
+impl EventCapture {
+    fn into_event(self) -> Result<IngestionEvent> {
+        match self {
+            Self::Store(slot) => slot.lock().unwrap_or_else(|e| e.into_inner()).take(),
+            Self::Batch(_) => None,
+        }
+        .ok_or_else(|| Error::Validation("No ingestion event was built".to_string()))
+    }
+
+    /// Whether the event is only built for `build_event`
+    ///
+    /// A preview does no network IO, so its discarded response only uses a
+    /// project ID that is configured or already fetched.
+    fn is_preview(capture: &Option<Self>) -> bool {
+        matches!(capture, Some(Self::Store(_)))
+    }
+}
+
+/// Error for a score Langfuse rejected in a batch
+fn score_rejection(failure: crate::EventError, request_id: Option<String>) -> Error {
+    let status = failure
+        .code
+        .as_deref()
+        .and_then(|code| code.parse::<u16>().ok())
+        .unwrap_or_default();
+    let message = failure.message;
+    if status >= 500 {
+        Error::Server {
+            status,
+            message,
+            request_id,
+        }
+    } else {
+        Error::Client {
+            status,
+            message,
+            request_id,
+        }
+    }
+}
+
+/// Resolve the trace of a score, which defaults to the ambient trace unless
+/// the score belongs to a session or dataset run
+fn score_trace_id(
+    trace_id: Option<String>,
+    session_id: Option<&String>,
+    dataset_run_id: Option<&String>,
+) -> Result<Option<String>> {
+    match trace_id {
+        Some(id) => Ok(Some(id)),
+        None if session_id.is_none() && dataset_run_id.is_none() => {
+            crate::ambient::observation_ids(None, None).map(|(trace_id, _)| Some(trace_id))
+        }
+        None => Ok(None),
+    }
 }
 
 #[bon]
 impl LangfuseClient {
+    /// Apply the client's environment and interceptors to an outgoing event
+    fn prepare_event(&self, event: &mut IngestionEvent) {
+        if let Some(environment) = &self.environment {
+            environment.apply(event);
+        }
+        crate::interceptor::intercept(&self.interceptors, event);
+    }
+
+    /// Send custom ingestion events in one request
+    ///
+    /// An escape hatch for events the builders don't cover. The events go
+    /// through the client's sampler, environment, and interceptors like those
+    /// of the builders, but not its field size and naming checks. Events
+    /// Langfuse rejects are listed in the response's `failures`; use
+    /// [`IngestionResponse::to_error`](crate::IngestionResponse::to_error) to
+    /// treat them as an error. Sampled-out events are not sent and appear in
+    /// neither list.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use langfuse_client_base::models::{
+    ///     ingestion_event_one_of::Type, IngestionEvent, IngestionEventOneOf, TraceBody,
+    /// };
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let trace = IngestionEventOneOf::builder()
+    ///     .id("event-1".to_string())
+    ///     .timestamp("2025-01-01T00:00:00.000Z".to_string())
+    ///     .r#type(Type::TraceCreate)
+    ///     .body(Box::new(
+    ///         TraceBody::builder().id(Some("trace-1".to_string())).build(),
+    ///     ))
+    ///     .build();
+    /// let response = client
+    ///     .ingest(vec![IngestionEvent::IngestionEventOneOf(Box::new(trace))])
+    ///     .await?;
+    /// if let Some(error) = response.to_error() {
+    ///     eprintln!("{error}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ingest(&self, events: Vec<IngestionEvent>) -> Result<crate::IngestionResponse> {
+        self.ingest_events(events).await
+    }
+
     async fn ingest_events(
         &self,
-        events: Vec<langfuse_client_base::models::IngestionEvent>,
-    ) -> Result<langfuse_client_base::models::IngestionResponse> {
-        use langfuse_client_base::apis::ingestion_api;
-        use langfuse_client_base::models::IngestionBatchRequest;
+        mut events: Vec<IngestionEvent>,
+    ) -> Result<crate::IngestionResponse> {
+        if let Some(sampler) = &self.sampler {
+            events.retain(|event| sampler.keep(event));
+            if events.is_empty() {
+                return Ok(crate::IngestionResponse {
+                    success_ids: Vec::new(),
+                    failures: Vec::new(),
+                    success_count: 0,
+                    failure_count: 0,
+                    request_id: None,
+                });
+            }
+        }
+        events
+            .iter_mut()
+            .for_each(|event| self.prepare_event(event));
 
-        let batch_request = IngestionBatchRequest::builder().batch(events).build();
+        let batch = langfuse_client_base::models::IngestionBatchRequest::builder()
+            .batch(events)
+            .build();
+        IngestionTransport::new(self).send(&batch).await
+    }
 
-        ingestion_api::ingestion_batch()
-            .configuration(self.configuration())
-            .ingestion_batch_request(batch_request)
-            .call()
-            .await
-            .map_err(crate::error::map_api_error)
+    /// Send one event, returning [`Error::PartialFailure`] if Langfuse rejects it
+    pub(crate) async fn ingest_event(&self, event: IngestionEvent) -> Result<()> {
+        match self.ingest_events(vec![event]).await?.to_error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Send `event`, or hand it to `capture` if it is built by `build_event` or for a batcher
+    async fn ingest_or_capture(
+        &self,
+        mut event: IngestionEvent,
+        capture: Option<EventCapture>,
+    ) -> Result<()> {
+        match capture {
+            Some(EventCapture::Store(slot)) => {
+                self.prepare_event(&mut event);
+                *slot.lock().unwrap_or_else(|e| e.into_inner()) = Some(event);
+                Ok(())
+            }
+            Some(EventCapture::Batch(batcher)) => {
+                // The batcher runs the interceptors when it accepts the event
+                if let Some(environment) = &self.environment {
+                    environment.apply(&mut event);
+                }
+                batcher.add(event).await
+            }
+            None => self.ingest_event(event).await,
+        }
+    }
+
+    /// Send an event built by a builder, or hand it to `capture`
+    ///
+    /// Events the sampler drops are not sent. Large input and output values
+    /// of the others are uploaded as media right before sending, after
+    /// validation, so rejected and sampled-out events upload nothing.
+    ///
+    /// Returns `false` if the sampler dropped the event.
+    async fn send_or_capture(
+        &self,
+        mut event: IngestionEvent,
+        capture: Option<EventCapture>,
+        trace_id: &str,
+        observation_id: Option<&str>,
+    ) -> Result<bool> {
+        if capture.is_some() {
+            self.ingest_or_capture(event, capture).await?;
+            return Ok(true);
+        }
+        if let Some(sampler) = &self.sampler {
+            if !sampler.keep(&event) {
+                return Ok(false);
+            }
+        }
+        self.offload_event_fields(&mut event, trace_id, observation_id)
+            .await?;
+        self.ingest_event(event).await?;
+        Ok(true)
     }
 
     /// Create a new trace
+    ///
+    /// When a dataset run is given along with a dataset item, the dataset run
+    /// item linking the trace is created after the trace is ingested. No run
+    /// item is created if the client's sampler drops the trace.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let trace = client
+    ///     .trace()
+    ///     .name("checkout")
+    ///     .user_id("user-42")
+    ///     .input(json!({"cart_id": "cart-7"}))
+    ///     .tags(vec!["production".to_string()])
+    ///     .call()
+    ///     .await?;
+    /// println!("Created trace {}", trace.id);
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn trace(
         &self,
@@ -136,19 +635,72 @@ impl LangfuseClient {
         output: Option<Value>,
         metadata: Option<Value>,
         #[builder(default = Vec::new())] tags: Vec<String>,
-        #[builder(into)] user_id: Option<String>,
-        #[builder(into)] session_id: Option<String>,
+        /// Defaults to the user of the [ambient context](crate::ambient)
+        #[builder(into)]
+        user_id: Option<String>,
+        /// Defaults to the session of the [ambient context](crate::ambient)
+        #[builder(into)]
+        session_id: Option<String>,
         timestamp: Option<DateTime<Utc>>,
         #[builder(into)] release: Option<String>,
         #[builder(into)] version: Option<String>,
         public: Option<bool>,
+        /// Dataset item the trace was produced from, recorded in its metadata
+        #[builder(with = |dataset_name: impl Into<String>, item_id: impl Into<String>| {
+            DatasetItemLink::new(dataset_name, item_id)
+        })]
+        dataset_item: Option<DatasetItemLink>,
+        /// Dataset run to add the linked dataset item's run item to
+        #[builder(into)]
+        dataset_run: Option<String>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+        /// Set through `build_event` to receive the event instead of sending it
+        #[builder(setters(vis = "", name = capture_internal))]
+        capture: Option<EventCapture>,
     ) -> Result<TraceResponse> {
         use langfuse_client_base::models::{
             ingestion_event_one_of::Type as TraceEventType, IngestionEvent, IngestionEventOneOf,
             TraceBody,
         };
 
+        if dataset_run.is_some() && dataset_item.is_none() {
+            return Err(Error::Validation(
+                "dataset_run requires dataset_item to be set".to_string(),
+            ));
+        }
+        if dataset_run.is_some() && matches!(capture, Some(EventCapture::Batch(_))) {
+            return Err(Error::Validation(
+                "dataset_run is not supported on batched traces; create the trace with \
+                 the client to link it to a dataset run"
+                    .to_string(),
+            ));
+        }
+        let metadata = match &dataset_item {
+            Some(link) => Some(link.annotate(metadata, dataset_run.as_deref())),
+            None => metadata,
+        };
+
         let trace_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        validate_id("Trace ID", &trace_id)?;
+        let user_id = user_id.or_else(|| crate::ambient::ambient(|c| c.user_id.clone()));
+        let session_id = session_id.or_else(|| crate::ambient::ambient(|c| c.session_id.clone()));
+        let response_session_id = session_id.clone();
+
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, capture.is_none())?;
+        let name = self.guard_name(NameKind::Trace, name)?;
+        let mut tags = tags;
+        if let Some(requirements) = &self.trace_requirements {
+            requirements.enforce(metadata.as_ref(), &mut tags)?;
+        }
+
         let timestamp = timestamp
             .unwrap_or_else(Utc::now)
             .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
@@ -168,21 +720,231 @@ impl LangfuseClient {
             .maybe_metadata(metadata.map(Some))
             .maybe_tags(tags_option.map(Some))
             .maybe_public(public.map(Some))
+            .maybe_environment(environment.map(|e| Some(e.to_string())))
             .build();
 
         let event = IngestionEventOneOf::builder()
             .body(Box::new(trace_body))
-            .id(Uuid::new_v4().to_string())
+            .id(event_id.unwrap_or_else(|| Uuid::new_v4().to_string()))
             .timestamp(timestamp.clone())
             .r#type(TraceEventType::TraceCreate)
             .build();
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf(Box::new(event))])
-            .await
-            .map(|_| TraceResponse {
-                id: trace_id,
-                base_url: self.configuration().base_path.clone(),
-            })
+        let captured = capture.is_some();
+        let fetch_project_id = !EventCapture::is_preview(&capture);
+        let sent = self
+            .send_or_capture(
+                IngestionEvent::IngestionEventOneOf(Box::new(event)),
+                capture,
+                &trace_id,
+                None,
+            )
+            .await?;
+
+        // A sampled-out trace is never ingested, so there is nothing to link
+        if let (Some(link), Some(run_name), true) = (
+            dataset_item,
+            dataset_run,
+            sent && !captured && !self.disabled,
+        ) {
+            self.create_dataset_run_item()
+                .run_name(run_name)
+                .dataset_item_id(link.item_id)
+                .trace_id(trace_id.clone())
+                .call()
+                .await?;
+        }
+
+        Ok(TraceResponse {
+            id: trace_id,
+            base_url: self.configuration().base_path.clone(),
+            session_id: response_session_id,
+            project_id: self.url_project_id(fetch_project_id).await,
+        })
+    }
+
+    /// Update an existing trace
+    ///
+    /// Sends a `trace-create` event with the trace's ID, which Langfuse
+    /// applies as an upsert. Fields that are not set keep their current
+    /// values, and the trace keeps its original timestamp.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_trace()
+    ///     .id("trace-123")
+    ///     .output(json!({"answer": "42"}))
+    ///     .tags(["answered"])
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn update_trace(
+        &self,
+        #[builder(into)] id: String,
+        #[builder(into)] name: Option<String>,
+        input: Option<Value>,
+        output: Option<Value>,
+        metadata: Option<Value>,
+        #[builder(with = |tags: impl IntoTags| tags.into_tags())] tags: Option<Vec<String>>,
+        #[builder(into)] user_id: Option<String>,
+        #[builder(into)] session_id: Option<String>,
+        #[builder(into)] release: Option<String>,
+        #[builder(into)] version: Option<String>,
+        public: Option<bool>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+    ) -> Result<String> {
+        use langfuse_client_base::models::{
+            ingestion_event_one_of::Type as TraceEventType, IngestionEventOneOf, TraceBody,
+        };
+
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, true)?;
+        let name = self.guard_name(NameKind::Trace, name)?;
+
+        let trace_body = TraceBody::builder()
+            .id(Some(id.clone()))
+            .maybe_name(name.map(Some))
+            .maybe_user_id(user_id.map(Some))
+            .maybe_input(input.map(Some))
+            .maybe_output(output.map(Some))
+            .maybe_session_id(session_id.map(Some))
+            .maybe_release(release.map(Some))
+            .maybe_version(version.map(Some))
+            .maybe_metadata(metadata.map(Some))
+            .maybe_tags(tags.map(Some))
+            .maybe_public(public.map(Some))
+            .maybe_environment(environment.map(|e| Some(e.to_string())))
+            .build();
+
+        let event = IngestionEventOneOf::builder()
+            .body(Box::new(trace_body))
+            .id(event_id.unwrap_or_else(|| Uuid::new_v4().to_string()))
+            .timestamp(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+            .r#type(TraceEventType::TraceCreate)
+            .build();
+
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf(Box::new(event)),
+            None,
+            &id,
+            None,
+        )
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Context for attaching observations and scores to an existing trace
+    ///
+    /// See [`TraceContext`].
+    pub fn trace_context(&self, trace_id: impl Into<String>) -> TraceContext {
+        TraceContext::new(self.clone(), trace_id.into())
+    }
+
+    /// Finish a trace and record its latency against an SLA
+    ///
+    /// Unless `duration` is given, the trace is fetched to compute its total
+    /// duration from the recorded observations (earliest start to latest end).
+    /// Ingestion is asynchronous, so observations sent moments earlier may not
+    /// be visible yet; pass `duration` when it is known locally.
+    ///
+    /// The trace is then [updated](Self::update_trace) with the optional
+    /// `output` and with `duration_ms`, `sla_ms`, and `sla_met` metadata, and
+    /// two scores are attached: a numeric `latency_ms` and a boolean `sla_met`.
+    /// The update carries only these fields, so it never overwrites the
+    /// trace's stored timestamp, tags, or other metadata with a stale copy.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # use std::time::Duration;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// # let trace = client.trace().name("request").call().await?;
+    /// let finished = client
+    ///     .finish_trace(&trace.id, Duration::from_millis(500))
+    ///     .output(serde_json::json!({"status": "ok"}))
+    ///     .call()
+    ///     .await?;
+    /// println!("SLA met: {}", finished.sla_met);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn finish_trace(
+        &self,
+        #[builder(start_fn, into)] trace_id: String,
+        #[builder(start_fn)] sla: Duration,
+        output: Option<Value>,
+        metadata: Option<Value>,
+        /// Use this duration instead of computing it from the trace's observations
+        duration: Option<Duration>,
+    ) -> Result<FinishedTrace> {
+        let duration = match duration {
+            Some(duration) => duration,
+            None => {
+                let trace = self.get_trace(trace_id.clone()).await?;
+                observed_duration(&trace).ok_or_else(|| {
+                    Error::Validation(format!(
+                        "Cannot compute duration of trace {trace_id}: no observations with timestamps"
+                    ))
+                })?
+            }
+        };
+        let sla_met = duration <= sla;
+        let duration_ms = duration.as_secs_f64() * 1000.0;
+
+        let mut finish_metadata = crate::interceptor::metadata_object(metadata);
+        finish_metadata.insert("duration_ms".to_string(), Value::from(duration_ms));
+        finish_metadata.insert("sla_ms".to_string(), Value::from(sla.as_millis() as u64));
+        finish_metadata.insert("sla_met".to_string(), Value::from(sla_met));
+
+        self.update_trace()
+            .id(trace_id.clone())
+            .maybe_output(output)
+            .metadata(Value::Object(finish_metadata))
+            .call()
+            .await?;
+
+        let latency_score_id = self
+            .score()
+            .trace_id(trace_id.clone())
+            .name("latency_ms")
+            .value(duration_ms)
+            .call()
+            .await?;
+
+        let sla_score_id = self
+            .score()
+            .trace_id(trace_id.clone())
+            .name("sla_met")
+            .value(sla_met)
+            .comment(format!("SLA {}ms", sla.as_millis()))
+            .call()
+            .await?;
+
+        Ok(FinishedTrace {
+            trace_id,
+            duration,
+            sla,
+            sla_met,
+            latency_score_id,
+            sla_score_id,
+        })
     }
 
     /// Get a trace by ID
@@ -202,50 +964,167 @@ impl LangfuseClient {
             .map_err(crate::error::map_api_error)
     }
 
-    /// List traces with optional filters
+    /// Get a trace by ID, returning only the selected field groups
+    ///
+    /// Useful for scanning workloads that only need identifiers and timestamps:
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::{ClientBuilder, TraceField};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let trace = client
+    ///     .get_trace_with_fields("trace-123", &[TraceField::Core, TraceField::Metrics])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_trace_with_fields(
+        &self,
+        trace_id: impl Into<String>,
+        fields: &[TraceField],
+    ) -> Result<langfuse_client_base::models::TraceWithFullDetails> {
+        use langfuse_client_base::apis::trace_api;
+
+        let trace_id = trace_id.into();
+        let fields = join_fields(fields);
+
+        trace_api::trace_get()
+            .configuration(self.configuration())
+            .trace_id(trace_id.as_str())
+            .maybe_fields(fields.as_deref())
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+
+    /// List traces, optionally narrowed with a [`TraceFilter`]
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use langfuse_ergonomic::{OrderBy, SortDirection, TraceFilter};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let traces = client
+    ///     .list_traces()
+    ///     .filter(TraceFilter::new().name("checkout").user_id("user-42"))
+    ///     .order_by(OrderBy::Timestamp(SortDirection::Desc))
+    ///     .limit(50)
+    ///     .call()
+    ///     .await?;
+    /// println!("{} traces", traces.data.len());
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn list_traces(
         &self,
         page: Option<i32>,
         limit: Option<i32>,
-        #[builder(into)] user_id: Option<String>,
-        #[builder(into)] name: Option<String>,
-        #[builder(into)] session_id: Option<String>,
-        #[builder(into)] version: Option<String>,
-        #[builder(into)] release: Option<String>,
-        #[builder(into)] from_timestamp: Option<String>,
-        #[builder(into)] to_timestamp: Option<String>,
-        #[builder(into)] order_by: Option<String>,
-        #[builder(into)] tags: Option<String>,
+        #[builder(default)] filter: TraceFilter,
+        order_by: Option<OrderBy>,
+        #[builder(into)] fields: Option<Vec<TraceField>>,
     ) -> Result<langfuse_client_base::models::Traces> {
         use langfuse_client_base::apis::trace_api;
 
-        let user_id_ref = user_id.as_deref();
-        let name_ref = name.as_deref();
-        let session_id_ref = session_id.as_deref();
-        let version_ref = version.as_deref();
-        let release_ref = release.as_deref();
-        let order_by_ref = order_by.as_deref();
-        let tags_vec = tags.map(|t| vec![t]);
+        let order_by = order_by.map(|o| o.to_string());
+        let fields = fields.as_deref().and_then(join_fields);
+        let TraceFilter {
+            name,
+            user_id,
+            session_id,
+            version,
+            release,
+            tags,
+            environments,
+            from_timestamp,
+            to_timestamp,
+        } = filter;
 
         trace_api::trace_list()
             .configuration(self.configuration())
             .maybe_page(page)
             .maybe_limit(limit)
-            .maybe_user_id(user_id_ref)
-            .maybe_name(name_ref)
-            .maybe_session_id(session_id_ref)
-            .maybe_version(version_ref)
-            .maybe_release(release_ref)
-            .maybe_order_by(order_by_ref)
-            .maybe_from_timestamp(from_timestamp)
-            .maybe_to_timestamp(to_timestamp)
-            .maybe_tags(tags_vec)
+            .maybe_user_id(user_id.as_deref())
+            .maybe_name(name.as_deref())
+            .maybe_session_id(session_id.as_deref())
+            .maybe_version(version.as_deref())
+            .maybe_release(release.as_deref())
+            .maybe_order_by(order_by.as_deref())
+            .maybe_from_timestamp(from_timestamp.map(timestamp_param))
+            .maybe_to_timestamp(to_timestamp.map(timestamp_param))
+            .maybe_tags((!tags.is_empty()).then_some(tags))
+            .maybe_environment((!environments.is_empty()).then_some(environments))
+            .maybe_fields(fields.as_deref())
             .call()
             .await
             .map_err(|e| crate::error::Error::Api(format!("Failed to list traces: {}", e)))
     }
 
+    /// Export traces with their observations to JSONL or CSV
+    ///
+    /// See [`crate::export`].
+    pub fn export_traces(&self) -> TraceExport<'_> {
+        TraceExport::new(self)
+    }
+
+    /// Summarize latency, tokens, cost, and errors over the traces matching `filter`
+    ///
+    /// Token counts and errors come from the observations, which the list
+    /// endpoint does not return, so this costs one list request per 100
+    /// matching traces plus one request per trace, made
+    /// [`STATS_FETCH_CONCURRENCY`](crate::stats::STATS_FETCH_CONCURRENCY) at a
+    /// time. Bound it with [`TraceStatsFilter::max_traces`]. See
+    /// [`crate::stats`].
+    pub async fn trace_stats(&self, filter: impl Into<TraceStatsFilter>) -> Result<TraceStats> {
+        const PAGE_SIZE: i32 = 100;
+
+        let filter = filter.into();
+        let mut trace_ids = Vec::new();
+        let mut truncated = false;
+        let mut page = 1;
+        loop {
+            let traces = self
+                .list_traces()
+                .page(page)
+                .limit(PAGE_SIZE)
+                .filter(filter.traces.clone())
+                .fields(vec![TraceField::Core])
+                .call()
+                .await?;
+
+            let received = traces.data.len();
+            for trace in traces.data {
+                if trace_ids.len() == filter.max_traces {
+                    truncated = true;
+                    break;
+                }
+                trace_ids.push(trace.id);
+            }
+            if truncated || received < PAGE_SIZE as usize || page >= traces.meta.total_pages {
+                break;
+            }
+            page += 1;
+        }
+
+        let traces: Vec<_> = futures_util::stream::iter(trace_ids)
+            .map(|trace_id| {
+                self.get_trace_with_fields(
+                    trace_id,
+                    &[
+                        TraceField::Core,
+                        TraceField::Observations,
+                        TraceField::Metrics,
+                    ],
+                )
+            })
+            .buffered(crate::stats::STATS_FETCH_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(crate::stats::summarize(&traces, truncated))
+    }
+
     /// Delete a trace
     pub async fn delete_trace(&self, trace_id: impl Into<String>) -> Result<()> {
         use langfuse_client_base::apis::trace_api;
@@ -287,12 +1166,34 @@ impl LangfuseClient {
     // ===== OBSERVATIONS (SPANS, GENERATIONS, EVENTS) =====
 
     /// Create a span observation
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let span_id = client
+    ///     .span()
+    ///     .trace_id("trace-123")
+    ///     .name("retrieval")
+    ///     .input(json!({"query": "refund policy"}))
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn span(
         &self,
-        #[builder(into)] trace_id: String,
+        /// Defaults to the trace of the [ambient context](crate::ambient)
+        #[builder(into)]
+        trace_id: Option<String>,
         #[builder(into)] id: Option<String>,
-        #[builder(into)] parent_observation_id: Option<String>,
+        /// Defaults to the parent observation of the [ambient context](crate::ambient)
+        /// when the observation belongs to its trace
+        #[builder(into)]
+        parent_observation_id: Option<String>,
         #[builder(into)] name: Option<String>,
         input: Option<Value>,
         output: Option<Value>,
@@ -301,13 +1202,33 @@ impl LangfuseClient {
         #[builder(into)] status_message: Option<String>,
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
-    ) -> Result<String> {
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+        /// Set through `build_event` to receive the event instead of sending it
+        #[builder(setters(vis = "", name = capture_internal))]
+        capture: Option<EventCapture>,
+    ) -> Result<ObservationResponse> {
         use langfuse_client_base::models::{
             ingestion_event_one_of_2::Type as SpanEventType, CreateSpanBody, IngestionEvent,
             IngestionEventOneOf2,
         };
 
+        let (trace_id, parent_observation_id) =
+            crate::ambient::observation_ids(trace_id, parent_observation_id)?;
         let observation_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        validate_observation_ids(&trace_id, &observation_id, parent_observation_id.as_deref())?;
+
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, capture.is_none())?;
+        let name = self.guard_name(NameKind::Observation, name)?;
+        let status_message = self.guard_status_message(status_message)?;
+
         let timestamp = start_time
             .unwrap_or_else(Utc::now)
             .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
@@ -316,7 +1237,7 @@ impl LangfuseClient {
 
         let span_body = CreateSpanBody::builder()
             .id(Some(observation_id.clone()))
-            .trace_id(Some(trace_id))
+            .trace_id(Some(trace_id.clone()))
             .start_time(Some(timestamp.clone()))
             .maybe_end_time(end_time_str.map(Some))
             .maybe_name(name.map(Some))
@@ -326,28 +1247,65 @@ impl LangfuseClient {
             .maybe_level(level)
             .maybe_status_message(status_message.map(Some))
             .maybe_metadata(metadata.map(Some))
+            .maybe_environment(environment.map(|e| Some(e.to_string())))
             .build();
 
         let event = IngestionEventOneOf2::builder()
             .body(Box::new(span_body))
-            .id(Uuid::new_v4().to_string())
+            .id(event_id.unwrap_or_else(|| Uuid::new_v4().to_string()))
             .timestamp(timestamp.clone())
             .r#type(SpanEventType::SpanCreate)
             .build();
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf2(Box::new(event))])
-            .await
-            .map(|_| observation_id)
-            .map_err(|e| crate::error::Error::Api(format!("Failed to create span: {}", e)))
+        let fetch_project_id = !EventCapture::is_preview(&capture);
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf2(Box::new(event)),
+            capture,
+            &trace_id,
+            Some(&observation_id),
+        )
+        .await?;
+        Ok(ObservationResponse::new(
+            &self.configuration().base_path,
+            self.url_project_id(fetch_project_id).await.as_deref(),
+            trace_id,
+            observation_id,
+        ))
     }
 
     /// Create a generation observation
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let generation_id = client
+    ///     .generation()
+    ///     .trace_id("trace-123")
+    ///     .name("answer")
+    ///     .model("gpt-4o")
+    ///     .input(json!([{"role": "user", "content": "Hello"}]))
+    ///     .output(json!({"role": "assistant", "content": "Hi there!"}))
+    ///     .prompt_tokens(12)
+    ///     .completion_tokens(4)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn generation(
         &self,
-        #[builder(into)] trace_id: String,
+        /// Defaults to the trace of the [ambient context](crate::ambient)
+        #[builder(into)]
+        trace_id: Option<String>,
         #[builder(into)] id: Option<String>,
-        #[builder(into)] parent_observation_id: Option<String>,
+        /// Defaults to the parent observation of the [ambient context](crate::ambient)
+        /// when the observation belongs to its trace
+        #[builder(into)]
+        parent_observation_id: Option<String>,
         #[builder(into)] name: Option<String>,
         input: Option<Value>,
         output: Option<Value>,
@@ -357,27 +1315,68 @@ impl LangfuseClient {
         start_time: Option<DateTime<Utc>>,
         end_time: Option<DateTime<Utc>>,
         #[builder(into)] model: Option<String>,
-        _model_parameters: Option<Value>,
-        _prompt_tokens: Option<i32>,
-        _completion_tokens: Option<i32>,
-        _total_tokens: Option<i32>,
-    ) -> Result<String> {
+        /// Model parameters as a JSON object, e.g. `{"temperature": 0.7}`
+        model_parameters: Option<Value>,
+        prompt_tokens: Option<i32>,
+        completion_tokens: Option<i32>,
+        /// Defaults to the sum of prompt and completion tokens
+        total_tokens: Option<i32>,
+        /// Usage by type, e.g. `{"cache_read": 120}`; overrides token counts of the same name
+        usage_details: Option<HashMap<String, i32>>,
+        /// Cost by usage type in USD, e.g. `{"input": 0.003, "output": 0.006}`
+        cost_details: Option<HashMap<String, f64>>,
+        /// Token counts and costs, e.g. from a provider response; the fields above take precedence
+        usage: Option<Usage>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+        /// Set through `build_event` to receive the event instead of sending it
+        #[builder(setters(vis = "", name = capture_internal))]
+        capture: Option<EventCapture>,
+    ) -> Result<ObservationResponse> {
         use langfuse_client_base::models::{
             ingestion_event_one_of_4::Type as GenerationEventType, CreateGenerationBody,
             IngestionEvent, IngestionEventOneOf4,
         };
 
+        let (trace_id, parent_observation_id) =
+            crate::ambient::observation_ids(trace_id, parent_observation_id)?;
         let observation_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        validate_observation_ids(&trace_id, &observation_id, parent_observation_id.as_deref())?;
+
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, capture.is_none())?;
+        let name = self.guard_name(NameKind::Observation, name)?;
+        let status_message = self.guard_status_message(status_message)?;
+
         let timestamp = start_time
             .unwrap_or_else(Utc::now)
             .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
 
         let level = level.map(|l| parse_observation_level(&l));
         let end_time_str = end_time.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+        let model_parameters = model_parameters.map(model_parameters_map).transpose()?;
+        let GenerationUsage {
+            usage,
+            usage_details,
+            cost_details,
+        } = GenerationUsage::resolve(
+            usage,
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+            usage_details,
+            cost_details,
+        );
 
         let generation_body = CreateGenerationBody::builder()
             .id(Some(observation_id.clone()))
-            .trace_id(Some(trace_id))
+            .trace_id(Some(trace_id.clone()))
             .start_time(Some(timestamp.clone()))
             .maybe_name(name.map(Some))
             .maybe_end_time(end_time_str.map(Some))
@@ -388,28 +1387,168 @@ impl LangfuseClient {
             .maybe_level(level)
             .maybe_status_message(status_message.map(Some))
             .maybe_parent_observation_id(parent_observation_id.map(Some))
+            .maybe_model_parameters(model_parameters.map(Some))
+            .maybe_usage(usage)
+            .maybe_usage_details(usage_details)
+            .maybe_cost_details(cost_details.map(Some))
+            .maybe_environment(environment.map(|e| Some(e.to_string())))
             .build();
 
         let event = IngestionEventOneOf4::builder()
             .body(Box::new(generation_body))
-            .id(Uuid::new_v4().to_string())
+            .id(event_id.unwrap_or_else(|| Uuid::new_v4().to_string()))
             .timestamp(timestamp.clone())
             .r#type(GenerationEventType::GenerationCreate)
             .build();
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf4(Box::new(event))])
-            .await
-            .map(|_| observation_id)
-            .map_err(|e| crate::error::Error::Api(format!("Failed to create generation: {}", e)))
+        let fetch_project_id = !EventCapture::is_preview(&capture);
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf4(Box::new(event)),
+            capture,
+            &trace_id,
+            Some(&observation_id),
+        )
+        .await?;
+        Ok(ObservationResponse::new(
+            &self.configuration().base_path,
+            self.url_project_id(fetch_project_id).await.as_deref(),
+            trace_id,
+            observation_id,
+        ))
+    }
+
+    /// Record the attempts of a retried LLM call as linked sibling generations
+    ///
+    /// See [`GenerationRetries`](crate::observations::GenerationRetries).
+    pub fn generation_retries(
+        &self,
+        trace_id: impl Into<String>,
+    ) -> crate::observations::GenerationRetries<'_> {
+        crate::observations::GenerationRetries::new(self, trace_id.into())
+    }
+
+    /// Run an LLM call with a timeout and record it as a generation
+    ///
+    /// Returns the call's output, or the timeout error if it took longer than
+    /// the configured timeout, together with the result of recording the
+    /// generation. A timed-out call is dropped and recorded with level `ERROR`
+    /// and `"timeout": true` metadata. See
+    /// [LLM call timeouts](crate::observations#llm-call-timeouts).
+    pub async fn timed_generation<Fut, T>(
+        &self,
+        generation: crate::observations::TimedGeneration,
+        call: Fut,
+    ) -> (
+        std::result::Result<T, tokio::time::error::Elapsed>,
+        Result<String>,
+    )
+    where
+        Fut: std::future::Future<Output = T>,
+    {
+        generation.run(self, call).await
+    }
+
+    /// Start a generation now and time it until it is finished
+    ///
+    /// The generation is recorded with its start time right away; the
+    /// returned [`GenerationTimer`](crate::observations::GenerationTimer)
+    /// records the end time, output, and usage. See
+    /// [Generation timers](crate::observations#generation-timers).
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let timer = client
+    ///     .start_generation()
+    ///     .trace_id("trace-123")
+    ///     .name("summarize")
+    ///     .model("gpt-4")
+    ///     .call()
+    ///     .await?;
+    /// // ... call the model ...
+    /// timer.finish(json!("Summary"), None).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn start_generation(
+        &self,
+        /// Defaults to the trace of the [ambient context](crate::ambient)
+        #[builder(into)]
+        trace_id: Option<String>,
+        #[builder(into)] id: Option<String>,
+        /// Defaults to the parent observation of the [ambient context](crate::ambient)
+        /// when the generation belongs to its trace
+        #[builder(into)]
+        parent_observation_id: Option<String>,
+        #[builder(into)] name: Option<String>,
+        #[builder(into)] model: Option<String>,
+        /// JSON object of model parameters
+        model_parameters: Option<Value>,
+        input: Option<Value>,
+        metadata: Option<Value>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+    ) -> Result<crate::observations::GenerationTimer<'_>> {
+        let stopwatch = Stopwatch::start();
+        let (trace_id, parent_observation_id) =
+            crate::ambient::observation_ids(trace_id, parent_observation_id)?;
+        let id = self
+            .generation()
+            .trace_id(trace_id.clone())
+            .maybe_id(id)
+            .maybe_parent_observation_id(parent_observation_id)
+            .maybe_name(name)
+            .maybe_model(model)
+            .maybe_model_parameters(model_parameters)
+            .maybe_input(input)
+            .maybe_metadata(metadata)
+            .maybe_environment(environment.clone())
+            .start_time(stopwatch.started_at())
+            .call()
+            .await?;
+        Ok(crate::observations::GenerationTimer::new(
+            self,
+            id.into(),
+            trace_id,
+            stopwatch,
+            environment,
+        ))
     }
 
     /// Create an event observation
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .event()
+    ///     .trace_id("trace-123")
+    ///     .name("cache-miss")
+    ///     .level("WARNING")
+    ///     .metadata(json!({"key": "user:42"}))
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn event(
         &self,
-        #[builder(into)] trace_id: String,
+        /// Defaults to the trace of the [ambient context](crate::ambient)
+        #[builder(into)]
+        trace_id: Option<String>,
         #[builder(into)] id: Option<String>,
-        #[builder(into)] parent_observation_id: Option<String>,
+        /// Defaults to the parent observation of the [ambient context](crate::ambient)
+        /// when the observation belongs to its trace
+        #[builder(into)]
+        parent_observation_id: Option<String>,
         #[builder(into)] name: Option<String>,
         input: Option<Value>,
         output: Option<Value>,
@@ -417,13 +1556,33 @@ impl LangfuseClient {
         #[builder(into)] level: Option<String>,
         #[builder(into)] status_message: Option<String>,
         start_time: Option<DateTime<Utc>>,
-    ) -> Result<String> {
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+        /// Set through `build_event` to receive the event instead of sending it
+        #[builder(setters(vis = "", name = capture_internal))]
+        capture: Option<EventCapture>,
+    ) -> Result<ObservationResponse> {
         use langfuse_client_base::models::{
             ingestion_event_one_of_6::Type as EventEventType, CreateEventBody, IngestionEvent,
             IngestionEventOneOf6,
         };
 
+        let (trace_id, parent_observation_id) =
+            crate::ambient::observation_ids(trace_id, parent_observation_id)?;
         let observation_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        validate_observation_ids(&trace_id, &observation_id, parent_observation_id.as_deref())?;
+
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, capture.is_none())?;
+        let name = self.guard_name(NameKind::Observation, name)?;
+        let status_message = self.guard_status_message(status_message)?;
+
         let timestamp = start_time
             .unwrap_or_else(Utc::now)
             .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
@@ -432,7 +1591,7 @@ impl LangfuseClient {
 
         let event_body = CreateEventBody::builder()
             .id(Some(observation_id.clone()))
-            .trace_id(Some(trace_id))
+            .trace_id(Some(trace_id.clone()))
             .start_time(Some(timestamp.clone()))
             .maybe_name(name.map(Some))
             .maybe_input(input.map(Some))
@@ -441,19 +1600,30 @@ impl LangfuseClient {
             .maybe_status_message(status_message.map(Some))
             .maybe_parent_observation_id(parent_observation_id.map(Some))
             .maybe_metadata(metadata.map(Some))
+            .maybe_environment(environment.map(|e| Some(e.to_string())))
             .build();
 
         let event = IngestionEventOneOf6::builder()
             .body(Box::new(event_body))
-            .id(Uuid::new_v4().to_string())
+            .id(event_id.unwrap_or_else(|| Uuid::new_v4().to_string()))
             .timestamp(timestamp.clone())
             .r#type(EventEventType::EventCreate)
             .build();
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf6(Box::new(event))])
-            .await
-            .map(|_| observation_id)
-            .map_err(|e| crate::error::Error::Api(format!("Failed to create event: {}", e)))
+        let fetch_project_id = !EventCapture::is_preview(&capture);
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf6(Box::new(event)),
+            capture,
+            &trace_id,
+            Some(&observation_id),
+        )
+        .await?;
+        Ok(ObservationResponse::new(
+            &self.configuration().base_path,
+            self.url_project_id(fetch_project_id).await.as_deref(),
+            trace_id,
+            observation_id,
+        ))
     }
 
     // ===== OBSERVATION UPDATES AND RETRIEVAL =====
@@ -476,6 +1646,21 @@ impl LangfuseClient {
     }
 
     /// Get multiple observations
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let observations = client
+    ///     .get_observations()
+    ///     .trace_id("trace-123")
+    ///     .observation_type("GENERATION".to_string())
+    ///     .limit(20)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn get_observations(
         &self,
@@ -511,7 +1696,83 @@ impl LangfuseClient {
             .map_err(|e| crate::error::Error::Api(format!("Failed to get observations: {}", e)))
     }
 
+    /// List observations using the v2 endpoint with cursor pagination and field selection
+    ///
+    /// Pass the `cursor` from the previous response's metadata to fetch the next page.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::{ClientBuilder, ObservationField};
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let observations = client
+    ///     .list_observations()
+    ///     .trace_id("trace-123")
+    ///     .fields(vec![ObservationField::Core, ObservationField::Usage])
+    ///     .limit(100)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn list_observations(
+        &self,
+        #[builder(into)] fields: Option<Vec<ObservationField>>,
+        limit: Option<i32>,
+        #[builder(into)] cursor: Option<String>,
+        #[builder(into)] trace_id: Option<String>,
+        #[builder(into)] parent_observation_id: Option<String>,
+        #[builder(into)] name: Option<String>,
+        #[builder(into)] user_id: Option<String>,
+        #[builder(into)] observation_type: Option<String>,
+        from_start_time: Option<DateTime<Utc>>,
+        to_start_time: Option<DateTime<Utc>>,
+    ) -> Result<langfuse_client_base::models::ObservationsV2Response> {
+        use langfuse_client_base::apis::observations_api;
+
+        let fields = fields.as_deref().and_then(join_fields);
+        let from_start_time =
+            from_start_time.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+        let to_start_time =
+            to_start_time.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+
+        observations_api::observations_get_many()
+            .configuration(self.configuration())
+            .maybe_fields(fields.as_deref())
+            .maybe_limit(limit)
+            .maybe_cursor(cursor.as_deref())
+            .maybe_trace_id(trace_id.as_deref())
+            .maybe_parent_observation_id(parent_observation_id.as_deref())
+            .maybe_name(name.as_deref())
+            .maybe_user_id(user_id.as_deref())
+            .maybe_type(observation_type.as_deref())
+            .maybe_from_start_time(from_start_time)
+            .maybe_to_start_time(to_start_time)
+            .call()
+            .await
+            .map_err(|e| crate::error::Error::Api(format!("Failed to list observations: {}", e)))
+    }
+
     /// Update an existing span
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    /// use chrono::Utc;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_span()
+    ///     .id("span-456")
+    ///     .trace_id("trace-123")
+    ///     .output(json!({"documents": 3}))
+    ///     .end_time(Utc::now())
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn update_span(
         &self,
@@ -527,14 +1788,27 @@ impl LangfuseClient {
         status_message: Option<String>,
         version: Option<String>,
         #[builder(into)] parent_observation_id: Option<String>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
     ) -> Result<String> {
         use chrono::Utc as ChronoUtc;
         use langfuse_client_base::models::{IngestionEvent, IngestionEventOneOf3, UpdateSpanBody};
         use uuid::Uuid;
 
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, true)?;
+        let name = self.guard_name(NameKind::Observation, name)?;
+        let status_message = self.guard_status_message(status_message)?;
+
         let event_body = UpdateSpanBody {
             id: id.clone(),
-            trace_id: Some(Some(trace_id)),
+            trace_id: Some(Some(trace_id.clone())),
             name: Some(name),
             start_time: Some(start_time.map(|dt| dt.to_rfc3339())),
             end_time: Some(end_time.map(|dt| dt.to_rfc3339())),
@@ -545,25 +1819,49 @@ impl LangfuseClient {
             status_message: Some(status_message),
             version: Some(version),
             parent_observation_id: Some(parent_observation_id),
-            environment: None,
+            environment: environment.map(|e| Some(e.to_string())),
         };
 
         let event = IngestionEventOneOf3 {
             body: Box::new(event_body),
-            id: Uuid::new_v4().to_string(),
+            id: event_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
             timestamp: ChronoUtc::now().to_rfc3339(),
             metadata: None,
             r#type: langfuse_client_base::models::ingestion_event_one_of_3::Type::SpanUpdate,
         };
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf3(Box::new(event))])
-            .await
-            .map_err(|e| Error::Api(format!("Failed to update span: {}", e)))?;
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf3(Box::new(event)),
+            None,
+            &trace_id,
+            Some(&id),
+        )
+        .await?;
 
         Ok(id)
     }
 
     /// Update an existing generation
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    /// use chrono::Utc;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_generation()
+    ///     .id("generation-789")
+    ///     .trace_id("trace-123")
+    ///     .output(json!({"role": "assistant", "content": "Done"}))
+    ///     .completion_tokens(42)
+    ///     .end_time(Utc::now())
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn update_generation(
         &self,
@@ -581,6 +1879,23 @@ impl LangfuseClient {
         status_message: Option<String>,
         version: Option<String>,
         #[builder(into)] parent_observation_id: Option<String>,
+        /// Model parameters as a JSON object, e.g. `{"temperature": 0.7}`
+        model_parameters: Option<Value>,
+        prompt_tokens: Option<i32>,
+        completion_tokens: Option<i32>,
+        /// Defaults to the sum of prompt and completion tokens
+        total_tokens: Option<i32>,
+        /// Usage by type, e.g. `{"cache_read": 120}`; overrides token counts of the same name
+        usage_details: Option<HashMap<String, i32>>,
+        /// Cost by usage type in USD, e.g. `{"input": 0.003, "output": 0.006}`
+        cost_details: Option<HashMap<String, f64>>,
+        /// Token counts and costs, e.g. from a provider response; the fields above take precedence
+        usage: Option<Usage>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
     ) -> Result<String> {
         use chrono::Utc as ChronoUtc;
         use langfuse_client_base::models::{
@@ -588,121 +1903,849 @@ impl LangfuseClient {
         };
         use uuid::Uuid;
 
-        // Note: In v0.2, model_parameters and usage have different types
-        // We'll leave them out for now as they require special handling
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, true)?;
+        let name = self.guard_name(NameKind::Observation, name)?;
+        let status_message = self.guard_status_message(status_message)?;
+
+        let model_parameters = model_parameters.map(model_parameters_map).transpose()?;
+        let GenerationUsage {
+            usage,
+            usage_details,
+            cost_details,
+        } = GenerationUsage::resolve(
+            usage,
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+            usage_details,
+            cost_details,
+        );
+
         let event_body = UpdateGenerationBody {
             id: id.clone(),
-            trace_id: Some(Some(trace_id)),
+            trace_id: Some(Some(trace_id.clone())),
             name: Some(name),
             start_time: Some(start_time.map(|dt| dt.to_rfc3339())),
             end_time: Some(end_time.map(|dt| dt.to_rfc3339())),
             completion_start_time: Some(completion_start_time.map(|dt| dt.to_rfc3339())),
             model: Some(model),
-            model_parameters: None, // Requires HashMap<String, MapValue>
+            model_parameters: model_parameters.map(Some),
             input: Some(input),
             output: Some(output),
-            usage: None, // Requires Box<IngestionUsage>
+            usage,
             metadata: Some(metadata),
             level: level.map(|l| parse_observation_level(&l)),
             status_message: Some(status_message),
             version: Some(version),
             parent_observation_id: Some(parent_observation_id),
-            environment: None,
-            cost_details: None,
+            environment: environment.map(|e| Some(e.to_string())),
+            cost_details: cost_details.map(Some),
             prompt_name: None,
             prompt_version: None,
-            usage_details: None,
+            usage_details,
         };
 
         let event = IngestionEventOneOf5 {
             body: Box::new(event_body),
-            id: Uuid::new_v4().to_string(),
+            id: event_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
             timestamp: ChronoUtc::now().to_rfc3339(),
             metadata: None,
             r#type: langfuse_client_base::models::ingestion_event_one_of_5::Type::GenerationUpdate,
         };
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf5(Box::new(event))])
-            .await
-            .map_err(|e| Error::Api(format!("Failed to update generation: {}", e)))?;
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf5(Box::new(event)),
+            None,
+            &trace_id,
+            Some(&id),
+        )
+        .await?;
 
         Ok(id)
     }
 
-    // Note: UpdateEventBody exists in v0.2 but doesn't have a corresponding IngestionEvent variant
-    // This functionality will need to wait for a later version
+    /// Update an existing event observation
+    ///
+    /// The ingestion API has no `event-update` event, so this sends an
+    /// `observation-update` of type `EVENT`. Fields that are not set keep
+    /// their current values.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_event()
+    ///     .id("event-456")
+    ///     .trace_id("trace-123")
+    ///     .output(json!({"cached": true}))
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn update_event(
+        &self,
+        #[builder(into)] id: String,
+        #[builder(into)] trace_id: String,
+        #[builder(into)] name: Option<String>,
+        start_time: Option<DateTime<Utc>>,
+        metadata: Option<Value>,
+        input: Option<Value>,
+        output: Option<Value>,
+        level: Option<String>,
+        status_message: Option<String>,
+        version: Option<String>,
+        #[builder(into)] parent_observation_id: Option<String>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+    ) -> Result<String> {
+        use langfuse_client_base::models::{
+            ingestion_event_one_of_9::Type as ObservationUpdateType, IngestionEventOneOf9,
+            ObservationBody, ObservationType,
+        };
+
+        let GuardedFields {
+            input,
+            output,
+            metadata,
+        } = self.guard_fields(input, output, metadata, true)?;
+        let name = self.guard_name(NameKind::Observation, name)?;
+        let status_message = self.guard_status_message(status_message)?;
+
+        let event_body = ObservationBody {
+            id: Some(Some(id.clone())),
+            trace_id: Some(Some(trace_id.clone())),
+            name: name.map(Some),
+            start_time: start_time.map(|dt| Some(dt.to_rfc3339())),
+            metadata: metadata.map(Some),
+            input: input.map(Some),
+            output: output.map(Some),
+            level: level.map(|l| parse_observation_level(&l)),
+            status_message: status_message.map(Some),
+            version: version.map(Some),
+            parent_observation_id: parent_observation_id.map(Some),
+            environment: environment.map(|e| Some(e.to_string())),
+            ..ObservationBody::new(ObservationType::Event)
+        };
+
+        let event = IngestionEventOneOf9 {
+            body: Box::new(event_body),
+            id: event_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            timestamp: Utc::now().to_rfc3339(),
+            metadata: None,
+            r#type: ObservationUpdateType::ObservationUpdate,
+        };
+
+        self.send_or_capture(
+            IngestionEvent::IngestionEventOneOf9(Box::new(event)),
+            None,
+            &trace_id,
+            Some(&id),
+        )
+        .await?;
+
+        Ok(id)
+    }
 
     // ===== SCORING =====
 
     /// Create a score
+    ///
+    /// The value can be a number, a category, or a boolean; see [`ScoreValue`].
+    /// A score belongs to a trace, a session, or a dataset run; see also
+    /// [`session_score`](Self::session_score) and [`run_score`](Self::run_score).
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .score()
+    ///     .trace_id("trace-123")
+    ///     .name("relevance")
+    ///     .value(0.9)
+    ///     .comment("Answered the question directly")
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn score(
         &self,
-        #[builder(into)] trace_id: String,
+        /// Defaults to the trace of the [ambient context](crate::ambient),
+        /// unless a session or dataset run ID is given
+        #[builder(into)]
+        trace_id: Option<String>,
         #[builder(into)] name: String,
         #[builder(into)] observation_id: Option<String>,
-        value: Option<f64>,
-        #[builder(into)] string_value: Option<String>,
+        #[builder(into)] session_id: Option<String>,
+        #[builder(into)] dataset_run_id: Option<String>,
+        #[builder(into)] value: Option<ScoreValue>,
+        /// Set through the deprecated `string_value` shim
+        #[builder(setters(vis = "", name = string_value_internal))]
+        string_value: Option<String>,
         #[builder(into)] comment: Option<String>,
         #[builder(into)] queue_id: Option<String>,
+        /// Score config the score follows
+        #[builder(into)]
+        config_id: Option<String>,
+        /// Data type to report instead of the one implied by the value; a
+        /// `BOOLEAN` score accepts the numbers `0` and `1`
+        data_type: Option<ScoreDataType>,
         metadata: Option<Value>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+        /// See [event IDs](crate::traces#event-ids)
+        #[builder(into)]
+        event_id: Option<String>,
+        /// Overrides the client's [`ScoreTransport`]; `build_event` always builds an ingestion event
+        transport: Option<ScoreTransport>,
+        /// Set through `build_event` to receive the event instead of sending it
+        #[builder(setters(vis = "", name = capture_internal))]
+        capture: Option<EventCapture>,
     ) -> Result<String> {
-        // Validate that either value or string_value is set
-        if value.is_none() && string_value.is_none() {
-            return Err(crate::error::Error::Validation(
-                "Score must have either a numeric value or string value".to_string(),
-            ));
-        }
-
-        use langfuse_client_base::models::{
-            CreateScoreValue, IngestionEvent, IngestionEventOneOf1, ScoreBody, ScoreDataType,
+        let value = match (value, string_value) {
+            (Some(_), Some(_)) => {
+                return Err(crate::error::Error::Validation(
+                    "Score cannot have both a value and a string value".to_string(),
+                ));
+            }
+            (Some(value), None) => value,
+            (None, Some(category)) => ScoreValue::Category(category),
+            (None, None) => {
+                return Err(crate::error::Error::Validation(
+                    "Score must have either a numeric value or string value".to_string(),
+                ));
+            }
         };
 
+        use langfuse_client_base::models::ScoreBody;
+
+        let trace_id = score_trace_id(trace_id, session_id.as_ref(), dataset_run_id.as_ref())?;
         let score_id = Uuid::new_v4().to_string();
         let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
 
-        let score_value = if let Some(v) = value {
-            Box::new(CreateScoreValue::Number(v))
-        } else if let Some(s) = string_value {
-            Box::new(CreateScoreValue::String(s))
-        } else {
-            return Err(crate::error::Error::Validation(
-                "Score must have either a numeric value or string value".to_string(),
-            ));
-        };
+        let (score_value, data_type) = value.into_parts_as(data_type)?;
+        let name = self.check_name(NameKind::Score, name)?;
+
+        // A disabled client records scores as ingestion events
+        if transport.unwrap_or(self.score_transport) == ScoreTransport::Direct
+            && capture.is_none()
+            && !self.disabled
+        {
+            let request = LegacyCreateScoreRequest {
+                id: Some(Some(score_id)),
+                trace_id: trace_id.map(Some),
+                observation_id: observation_id.map(Some),
+                session_id: session_id.map(Some),
+                dataset_run_id: dataset_run_id.map(Some),
+                queue_id: queue_id.map(Some),
+                comment: comment.map(Some),
+                config_id: config_id.map(Some),
+                metadata: metadata
+                    .map(crate::scores::metadata_map)
+                    .transpose()?
+                    .map(Some),
+                data_type: Some(data_type),
+                environment: environment.map(|e| Some(e.to_string())),
+                ..LegacyCreateScoreRequest::new(name, score_value)
+            };
+            return self
+                .create_score_request(request)
+                .await
+                .map(|response| response.id);
+        }
 
         let score_body = ScoreBody {
             id: Some(Some(score_id.clone())),
-            trace_id: Some(Some(trace_id)),
+            trace_id: trace_id.map(Some),
             name,
             queue_id: queue_id.map(Some),
-            value: score_value,
+            value: Box::new(score_value),
             observation_id: observation_id.map(Some),
             comment: comment.map(Some),
-            data_type: if value.is_some() {
-                Some(ScoreDataType::Numeric)
-            } else {
-                Some(ScoreDataType::Categorical)
-            },
-            config_id: None,
-            session_id: None,
-            dataset_run_id: None,
-            environment: None,
+            data_type: Some(data_type),
+            config_id: config_id.map(Some),
+            session_id: session_id.map(Some),
+            dataset_run_id: dataset_run_id.map(Some),
+            environment: environment.map(|e| Some(e.to_string())),
+            metadata: metadata.map(Some),
+        };
+
+        self.ingest_score(score_body, timestamp, event_id, capture)
+            .await
+            .map(|_| score_id)
+    }
+
+    /// Create a score through the scores API instead of the ingestion pipeline
+    ///
+    /// Unlike [`score`](Self::score), which queues a `score-create` event, this
+    /// returns once Langfuse stored the score, and fails if it was rejected.
+    /// Use it for low-latency writes outside a batch, such as user feedback;
+    /// it requires a Langfuse version serving `POST /api/public/scores`. To
+    /// send every score this way, see
+    /// [`ClientBuilder::score_transport`](crate::ClientBuilder::score_transport).
+    ///
+    /// A score belongs to a trace, a session, or a dataset run. Without any of
+    /// them, the trace of the [ambient context](crate::ambient) is used.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let response = client
+    ///     .create_score_direct()
+    ///     .trace_id("trace-123")
+    ///     .name("thumbs-up")
+    ///     .value(true)
+    ///     .call()
+    ///     .await?;
+    /// println!("Stored score {}", response.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn create_score_direct(
+        &self,
+        #[builder(into)] name: String,
+        #[builder(into)] value: ScoreValue,
+        #[builder(into)] trace_id: Option<String>,
+        #[builder(into)] observation_id: Option<String>,
+        #[builder(into)] session_id: Option<String>,
+        #[builder(into)] dataset_run_id: Option<String>,
+        #[builder(into)] comment: Option<String>,
+        #[builder(into)] queue_id: Option<String>,
+        /// Score config the score follows
+        #[builder(into)]
+        config_id: Option<String>,
+        /// Data type to report instead of the one implied by the value; a
+        /// `BOOLEAN` score accepts the numbers `0` and `1`
+        data_type: Option<ScoreDataType>,
+        /// JSON object with additional data
+        metadata: Option<Value>,
+        /// Defaults to a new UUID; reuse it when retrying to update the same score
+        #[builder(into)]
+        id: Option<String>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+    ) -> Result<LegacyCreateScoreResponse> {
+        let trace_id = match trace_id {
+            Some(id) => Some(id),
+            None if session_id.is_none() && dataset_run_id.is_none() => Some(
+                crate::ambient::ambient(|context| context.trace_id.clone()).ok_or_else(|| {
+                    Error::Validation("Score needs a trace, session, or dataset run ID".to_string())
+                })?,
+            ),
+            None => None,
+        };
+        let (value, data_type) = value.into_parts_as(data_type)?;
+        let request = LegacyCreateScoreRequest {
+            id: Some(Some(id.unwrap_or_else(|| Uuid::new_v4().to_string()))),
+            trace_id: trace_id.map(Some),
+            observation_id: observation_id.map(Some),
+            session_id: session_id.map(Some),
+            dataset_run_id: dataset_run_id.map(Some),
+            comment: comment.map(Some),
+            queue_id: queue_id.map(Some),
+            config_id: config_id.map(Some),
+            metadata: metadata
+                .map(crate::scores::metadata_map)
+                .transpose()?
+                .map(Some),
+            data_type: Some(data_type),
+            environment: environment.map(|e| Some(e.to_string())),
+            ..LegacyCreateScoreRequest::new(self.check_name(NameKind::Score, name)?, value)
+        };
+        self.create_score_request(request).await
+    }
+
+    /// Send a prebuilt request to the scores API
+    ///
+    /// The request goes through the client's sampler, environment, and
+    /// interceptors, including redaction and masking, like a score sent as an
+    /// ingestion event. A sampled-out score is not sent, and its ID is
+    /// returned as if it were stored. See
+    /// [`create_score_direct`](Self::create_score_direct).
+    pub async fn create_score_request(
+        &self,
+        mut request: LegacyCreateScoreRequest,
+    ) -> Result<LegacyCreateScoreResponse> {
+        use langfuse_client_base::apis::legacy_score_v1_api;
+        use langfuse_client_base::models::{ingestion_event_one_of_1::Type, IngestionEventOneOf1};
+
+        let id = request
+            .id
+            .clone()
+            .flatten()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        if self.disabled {
+            return Ok(LegacyCreateScoreResponse::new(id));
+        }
+        request.id = Some(Some(id.clone()));
+
+        let mut event = IngestionEvent::IngestionEventOneOf1(Box::new(IngestionEventOneOf1::new(
+            Uuid::new_v4().to_string(),
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            crate::scores::request_body(request),
+            Type::ScoreCreate,
+        )));
+        if self
+            .sampler
+            .as_ref()
+            .is_some_and(|sampler| !sampler.keep(&event))
+        {
+            return Ok(LegacyCreateScoreResponse::new(id));
+        }
+        self.prepare_event(&mut event);
+        let IngestionEvent::IngestionEventOneOf1(event) = event else {
+            return Err(Error::Validation(
+                "An interceptor replaced the score event with another event type".to_string(),
+            ));
+        };
+        let request = crate::scores::body_request(*event.body)?;
+
+        legacy_score_v1_api::legacy_score_v1_create()
+            .configuration(self.configuration())
+            .legacy_create_score_request(request)
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+
+    /// Score a trace or observation with a rubric evaluation
+    ///
+    /// Sends one numeric score named after the rubric, with the aggregate as
+    /// its value, the overall rationale as its comment, and the rubric details
+    /// as metadata. See [`crate::rubric`].
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use langfuse_ergonomic::rubric::{RubricCriterion, RubricEvaluation};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let evaluation =
+    ///     RubricEvaluation::new("helpfulness", vec![RubricCriterion::new("resolved", 1.0)])
+    ///         .score("resolved", 1.0, None);
+    /// client
+    ///     .rubric_score()
+    ///     .trace_id("trace-123")
+    ///     .evaluation(evaluation)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn rubric_score(
+        &self,
+        #[builder(into)] trace_id: String,
+        #[builder(into)] observation_id: Option<String>,
+        evaluation: RubricEvaluation,
+    ) -> Result<String> {
+        let aggregate = evaluation.aggregate()?;
+        let metadata = evaluation.to_metadata()?;
+
+        self.score()
+            .trace_id(trace_id)
+            .maybe_observation_id(observation_id)
+            .name(evaluation.name)
+            .value(aggregate)
+            .maybe_comment(evaluation.rationale)
+            .metadata(metadata)
+            .call()
+            .await
+    }
+
+    /// Score a session, such as a whole conversation
+    ///
+    /// Shorthand for [`score`](Self::score) with only a session ID.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .session_score()
+    ///     .session_id("session-123")
+    ///     .name("goal-achieved")
+    ///     .value(true)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn session_score(
+        &self,
+        #[builder(into)] session_id: String,
+        #[builder(into)] name: String,
+        #[builder(into)] value: ScoreValue,
+        #[builder(into)] comment: Option<String>,
+        /// Score config the score follows
+        #[builder(into)]
+        config_id: Option<String>,
+        /// Data type to report instead of the one implied by the value
+        data_type: Option<ScoreDataType>,
+        metadata: Option<Value>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+    ) -> Result<String> {
+        self.score()
+            .session_id(session_id)
+            .name(name)
+            .value(value)
+            .maybe_comment(comment)
+            .maybe_config_id(config_id)
+            .maybe_data_type(data_type)
+            .maybe_metadata(metadata)
+            .maybe_environment(environment)
+            .call()
+            .await
+    }
+
+    /// Score a dataset run, such as an aggregate metric of an experiment
+    ///
+    /// Shorthand for [`score`](Self::score) with only a dataset run ID.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .run_score()
+    ///     .dataset_run_id("run-123")
+    ///     .name("mean-accuracy")
+    ///     .value(0.87)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn run_score(
+        &self,
+        #[builder(into)] dataset_run_id: String,
+        #[builder(into)] name: String,
+        #[builder(into)] value: ScoreValue,
+        #[builder(into)] comment: Option<String>,
+        /// Score config the score follows
+        #[builder(into)]
+        config_id: Option<String>,
+        /// Data type to report instead of the one implied by the value
+        data_type: Option<ScoreDataType>,
+        metadata: Option<Value>,
+        /// Overrides the client's [environment](crate::environment)
+        environment: Option<Environment>,
+    ) -> Result<String> {
+        self.score()
+            .dataset_run_id(dataset_run_id)
+            .name(name)
+            .value(value)
+            .maybe_comment(comment)
+            .maybe_config_id(config_id)
+            .maybe_data_type(data_type)
+            .maybe_metadata(metadata)
+            .maybe_environment(environment)
+            .call()
+            .await
+    }
+
+    /// Create many scores with one ingestion request
+    ///
+    /// Evaluation jobs producing thousands of scores send them together
+    /// instead of awaiting [`score`](Self::score) for each. The scores are
+    /// split over several requests only if they exceed the ingestion size
+    /// limit. The results are in the order of `scores`: the ID of each score,
+    /// or why it was invalid or rejected by Langfuse. When a request fails as
+    /// a whole, each of its scores gets that error, such as
+    /// [`Error::RateLimit`], so callers can tell which to retry. To queue the
+    /// scores on a batcher instead, see
+    /// [`Batcher::scores_batch`](crate::Batcher::scores_batch).
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use langfuse_ergonomic::ScoreInput;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let scores = vec![
+    ///     ScoreInput::new("trace-1", "accuracy", 1.0),
+    ///     ScoreInput::new("trace-2", "accuracy", 0.0).comment("Wrong answer"),
+    /// ];
+    /// for result in client.scores_batch(scores).await {
+    ///     if let Err(e) = result {
+    ///         eprintln!("Score not recorded: {e}");
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn scores_batch(&self, scores: Vec<ScoreInput>) -> Vec<Result<String>> {
+        use crate::batcher::MAX_BATCH_SIZE_BYTES;
+
+        let mut results = Vec::with_capacity(scores.len());
+        let mut chunks: Vec<Vec<(usize, String, IngestionEvent)>> = Vec::new();
+        let mut chunk_size = 0;
+        for score in scores {
+            let (score_id, event_id, event) = match self.score_input_event(score) {
+                Ok(built) => built,
+                Err(e) => {
+                    results.push(Err(e));
+                    continue;
+                }
+            };
+            let size = serde_json::to_vec(&event).map_or(0, |bytes| bytes.len());
+            let item = (results.len(), event_id, event);
+            match chunks.last_mut() {
+                Some(chunk) if chunk_size + size <= MAX_BATCH_SIZE_BYTES => {
+                    chunk_size += size;
+                    chunk.push(item);
+                }
+                _ => {
+                    chunk_size = size;
+                    chunks.push(vec![item]);
+                }
+            }
+            results.push(Ok(score_id));
+        }
+
+        for chunk in chunks {
+            let (positions, events): (HashMap<String, usize>, Vec<IngestionEvent>) = chunk
+                .into_iter()
+                .map(|(index, event_id, event)| ((event_id, index), event))
+                .unzip();
+            match self.ingest_events(events).await {
+                Ok(response) => {
+                    for failure in response.failures {
+                        if let Some(&index) = positions.get(&failure.event_id) {
+                            results[index] =
+                                Err(score_rejection(failure, response.request_id.clone()));
+                        }
+                    }
+                }
+                Err(e) => {
+                    // Every score of the chunk failed the same way; keep the
+                    // error's kind so callers can tell whether to retry
+                    let mut indices: Vec<usize> = positions.into_values().collect();
+                    indices.sort_unstable();
+                    if let Some((&first, rest)) = indices.split_first() {
+                        for &index in rest {
+                            results[index] = Err(e.duplicate());
+                        }
+                        results[first] = Err(e);
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    /// Queue scores on a batcher, returning the ID of each score or why it is invalid
+    pub(crate) async fn batched_scores(
+        &self,
+        batcher: BatcherHandle,
+        scores: Vec<ScoreInput>,
+    ) -> Vec<Result<String>> {
+        let mut results = Vec::with_capacity(scores.len());
+        for score in scores {
+            let result = match self.score_input_event(score) {
+                Ok((score_id, _, event)) => self
+                    .ingest_or_capture(event, Some(EventCapture::Batch(batcher.clone())))
+                    .await
+                    .map(|()| score_id),
+                Err(e) => Err(e),
+            };
+            results.push(result);
+        }
+        results
+    }
+
+    /// Build the `score-create` event of a batched score, with the score and event IDs
+    fn score_input_event(&self, input: ScoreInput) -> Result<(String, String, IngestionEvent)> {
+        use langfuse_client_base::models::{IngestionEventOneOf1, ScoreBody};
+
+        let ScoreInput {
+            name,
+            value,
+            trace_id,
+            observation_id,
+            session_id,
+            dataset_run_id,
+            comment,
+            config_id,
+            data_type,
+            metadata,
+            environment,
+            id,
+        } = input;
+        let trace_id = score_trace_id(trace_id, session_id.as_ref(), dataset_run_id.as_ref())?;
+        let (value, data_type) = value.into_parts_as(data_type)?;
+        let score_id = id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let body = ScoreBody {
+            id: Some(Some(score_id.clone())),
+            trace_id: trace_id.map(Some),
+            name: self.check_name(NameKind::Score, name)?,
+            queue_id: None,
+            value: Box::new(value),
+            observation_id: observation_id.map(Some),
+            comment: comment.map(Some),
+            data_type: Some(data_type),
+            config_id: config_id.map(Some),
+            session_id: session_id.map(Some),
+            dataset_run_id: dataset_run_id.map(Some),
+            environment: environment.map(|e| Some(e.to_string())),
             metadata: metadata.map(Some),
         };
+        let event_id = Uuid::new_v4().to_string();
+        let event = IngestionEventOneOf1 {
+            body: Box::new(body),
+            id: event_id.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            metadata: None,
+            r#type: langfuse_client_base::models::ingestion_event_one_of_1::Type::ScoreCreate,
+        };
+        Ok((
+            score_id,
+            event_id,
+            IngestionEvent::IngestionEventOneOf1(Box::new(event)),
+        ))
+    }
+
+    /// Send a score event; scores with an existing ID replace that score
+    async fn ingest_score(
+        &self,
+        body: langfuse_client_base::models::ScoreBody,
+        timestamp: String,
+        event_id: Option<String>,
+        capture: Option<EventCapture>,
+    ) -> Result<()> {
+        use langfuse_client_base::models::{IngestionEvent, IngestionEventOneOf1};
 
         let event = IngestionEventOneOf1 {
-            body: Box::new(score_body),
-            id: Uuid::new_v4().to_string(),
-            timestamp: timestamp.clone(),
+            body: Box::new(body),
+            id: event_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            timestamp,
             metadata: None,
             r#type: langfuse_client_base::models::ingestion_event_one_of_1::Type::ScoreCreate,
         };
 
-        self.ingest_events(vec![IngestionEvent::IngestionEventOneOf1(Box::new(event))])
+        self.ingest_or_capture(
+            IngestionEvent::IngestionEventOneOf1(Box::new(event)),
+            capture,
+        )
+        .await
+    }
+
+    /// Correct an existing score in place
+    ///
+    /// The score is fetched and re-sent under the same ID with the given
+    /// changes, so it is replaced rather than duplicated. Fields that are not
+    /// set keep their current values, including the score's timestamp. A new
+    /// value must have the same data type as the stored one.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_score("score-123")
+    ///     .value(0.4)
+    ///     .comment("Re-graded after rubric fix")
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn update_score(
+        &self,
+        #[builder(start_fn, into)] score_id: String,
+        #[builder(into)] value: Option<ScoreValue>,
+        #[builder(into)] comment: Option<String>,
+        metadata: Option<Value>,
+    ) -> Result<String> {
+        use langfuse_client_base::models::ScoreBody;
+
+        let existing = serde_json::to_value(self.get_score(score_id.clone()).await?)
+            .map_err(|e| Error::Api(format!("Failed to read score '{}': {}", score_id, e)))?;
+        let field = |key: &str| existing.get(key).and_then(Value::as_str).map(String::from);
+
+        let value = match value {
+            Some(value) => value,
+            None => ScoreValue::from_score_json(&existing).ok_or_else(|| {
+                Error::Validation(format!(
+                    "Score '{}' has a value that cannot be re-sent; set a new value",
+                    score_id
+                ))
+            })?,
+        };
+        let (score_value, data_type) = value.into_parts();
+        if let (Some(existing_type), Ok(Value::String(new_type))) =
+            (field("dataType"), serde_json::to_value(data_type))
+        {
+            if existing_type != new_type {
+                return Err(Error::Validation(format!(
+                    "Score '{}' has data type {}; it cannot be changed to {}",
+                    score_id, existing_type, new_type
+                )));
+            }
+        }
+        let name = field("name").ok_or_else(|| {
+            Error::Validation(format!("Score '{}' has no name to re-send", score_id))
+        })?;
+
+        let score_body = ScoreBody {
+            id: Some(Some(score_id.clone())),
+            trace_id: Some(field("traceId")),
+            session_id: Some(field("sessionId")),
+            observation_id: Some(field("observationId")),
+            dataset_run_id: Some(field("datasetRunId")),
+            queue_id: Some(field("queueId")),
+            config_id: Some(field("configId")),
+            environment: Some(field("environment")),
+            name,
+            value: Box::new(score_value),
+            comment: Some(comment.or_else(|| field("comment"))),
+            data_type: Some(data_type),
+            metadata: Some(
+                metadata.or_else(|| existing.get("metadata").filter(|m| !m.is_null()).cloned()),
+            ),
+        };
+        // The event timestamp becomes the score's timestamp, so keep the original
+        let timestamp = field("timestamp")
+            .unwrap_or_else(|| Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+
+        self.ingest_score(score_body, timestamp, None, None)
             .await
-            .map(|_| score_id)
-            .map_err(|e| crate::error::Error::Api(format!("Failed to create score: {}", e)))
+            .map(|_| score_id.clone())
+            .map_err(|e| Error::Api(format!("Failed to update score '{}': {}", score_id, e)))
+    }
+
+    /// Delete a score
+    pub async fn delete_score(&self, score_id: impl Into<String>) -> Result<()> {
+        use langfuse_client_base::apis::legacy_score_v1_api;
+
+        let score_id = score_id.into();
+
+        legacy_score_v1_api::legacy_score_v1_delete()
+            .configuration(self.configuration())
+            .score_id(score_id.as_str())
+            .call()
+            .await
+            .map_err(|e| Error::Api(format!("Failed to delete score '{}': {}", score_id, e)))
     }
 
     /// Create a binary score (0 or 1)
@@ -751,33 +2794,269 @@ impl LangfuseClient {
             "max_rating": max_rating
         });
 
-        self.score()
-            .trace_id(trace_id.into())
-            .name(name.into())
-            .value(normalized)
-            .metadata(final_metadata)
+        self.score()
+            .trace_id(trace_id.into())
+            .name(name.into())
+            .value(normalized)
+            .metadata(final_metadata)
+            .call()
+            .await
+    }
+
+    /// Create a categorical score
+    pub async fn categorical_score(
+        &self,
+        trace_id: impl Into<String>,
+        name: impl Into<String>,
+        category: impl Into<String>,
+    ) -> Result<String> {
+        self.score()
+            .trace_id(trace_id.into())
+            .name(name.into())
+            .value(ScoreValue::Category(category.into()))
+            .call()
+            .await
+    }
+
+    /// Record end-user feedback on a trace as a thumbs up or down
+    ///
+    /// Sends a boolean score named [`USER_FEEDBACK_SCORE_NAME`] with the
+    /// user's comment and, if given, their ID in the score metadata. With
+    /// `tag_trace`, the trace is also tagged [`POSITIVE_FEEDBACK_TAG`] or
+    /// [`NEGATIVE_FEEDBACK_TAG`] so it can be filtered on in the trace list.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .user_feedback()
+    ///     .trace_id("trace-123")
+    ///     .thumbs_up(false)
+    ///     .comment("The answer was out of date")
+    ///     .user_id("user-42")
+    ///     .tag_trace(true)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn user_feedback(
+        &self,
+        #[builder(into)] trace_id: String,
+        /// Observation the feedback is about, if not the whole trace
+        #[builder(into)]
+        observation_id: Option<String>,
+        /// Whether the user rated the response positively
+        thumbs_up: bool,
+        #[builder(into)] comment: Option<String>,
+        /// User who gave the feedback
+        #[builder(into)]
+        user_id: Option<String>,
+        /// Also tag the trace with the feedback
+        #[builder(default)]
+        tag_trace: bool,
+    ) -> Result<String> {
+        use langfuse_client_base::models::{
+            ingestion_event_one_of::Type as TraceEventType, IngestionEventOneOf, TraceBody,
+        };
+
+        let metadata = user_id.map(|user_id| serde_json::json!({ "user_id": user_id }));
+        let score_id = self
+            .score()
+            .trace_id(trace_id.clone())
+            .maybe_observation_id(observation_id)
+            .name(USER_FEEDBACK_SCORE_NAME)
+            .value(thumbs_up)
+            .maybe_comment(comment)
+            .maybe_metadata(metadata)
+            .call()
+            .await?;
+
+        if tag_trace {
+            let tag = if thumbs_up {
+                POSITIVE_FEEDBACK_TAG
+            } else {
+                NEGATIVE_FEEDBACK_TAG
+            };
+            // Upsert only the tags so the trace keeps its other fields
+            let trace_body = TraceBody::builder()
+                .id(Some(trace_id))
+                .tags(Some(vec![tag.to_string()]))
+                .build();
+            let event = IngestionEventOneOf::builder()
+                .body(Box::new(trace_body))
+                .id(Uuid::new_v4().to_string())
+                .timestamp(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+                .r#type(TraceEventType::TraceCreate)
+                .build();
+            self.ingest_event(IngestionEvent::IngestionEventOneOf(Box::new(event)))
+                .await?;
+        }
+
+        Ok(score_id)
+    }
+
+    /// List scores with optional filters
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let scores = client
+    ///     .list_scores()
+    ///     .name("relevance")
+    ///     .trace_id("trace-123")
+    ///     .limit(50)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn list_scores(
+        &self,
+        page: Option<i32>,
+        limit: Option<i32>,
+        #[builder(into)] name: Option<String>,
+        #[builder(into)] user_id: Option<String>,
+        #[builder(into)] trace_id: Option<String>,
+        #[builder(into)] observation_id: Option<String>,
+        #[builder(into)] session_id: Option<String>,
+        /// Only scores of traces with all of these tags
+        trace_tags: Option<Vec<String>>,
+        data_type: Option<langfuse_client_base::models::ScoreDataType>,
+        /// Only scores created at or after this time
+        from_timestamp: Option<DateTime<Utc>>,
+        /// Only scores created before this time
+        to_timestamp: Option<DateTime<Utc>>,
+    ) -> Result<langfuse_client_base::models::GetScoresResponse> {
+        use langfuse_client_base::apis::scores_api;
+
+        let from_timestamp =
+            from_timestamp.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+        let to_timestamp =
+            to_timestamp.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+
+        scores_api::scores_get_many()
+            .configuration(self.configuration())
+            .maybe_page(page)
+            .maybe_limit(limit)
+            .maybe_name(name.as_deref())
+            .maybe_user_id(user_id.as_deref())
+            .maybe_trace_id(trace_id.as_deref())
+            .maybe_observation_id(observation_id.as_deref())
+            .maybe_session_id(session_id.as_deref())
+            .maybe_trace_tags(trace_tags)
+            .maybe_data_type(data_type)
+            .maybe_from_timestamp(from_timestamp)
+            .maybe_to_timestamp(to_timestamp)
+            .call()
+            .await
+            .map_err(|e| crate::error::Error::Api(format!("Failed to list scores: {}", e)))
+    }
+
+    /// Get a score by ID
+    pub async fn get_score(
+        &self,
+        score_id: impl Into<String>,
+    ) -> Result<langfuse_client_base::models::Score> {
+        use langfuse_client_base::apis::scores_api;
+
+        let score_id = score_id.into();
+
+        scores_api::scores_get_by_id()
+            .configuration(self.configuration())
+            .score_id(score_id.as_str())
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+
+    // ===== SESSIONS =====
+
+    /// List sessions with optional filters
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use chrono::Utc;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let sessions = client
+    ///     .list_sessions()
+    ///     .from_timestamp(Utc::now() - chrono::Duration::days(1))
+    ///     .limit(20)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn list_sessions(
+        &self,
+        page: Option<i32>,
+        limit: Option<i32>,
+        /// Only sessions created at or after this time
+        from_timestamp: Option<DateTime<Utc>>,
+        /// Only sessions created before this time
+        to_timestamp: Option<DateTime<Utc>>,
+        environment: Option<Vec<String>>,
+    ) -> Result<langfuse_client_base::models::PaginatedSessions> {
+        use langfuse_client_base::apis::sessions_api;
+
+        let from_timestamp =
+            from_timestamp.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+        let to_timestamp =
+            to_timestamp.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+
+        sessions_api::sessions_list()
+            .configuration(self.configuration())
+            .maybe_page(page)
+            .maybe_limit(limit)
+            .maybe_from_timestamp(from_timestamp)
+            .maybe_to_timestamp(to_timestamp)
+            .maybe_environment(environment)
             .call()
             .await
+            .map_err(|e| crate::error::Error::Api(format!("Failed to list sessions: {}", e)))
     }
 
-    /// Create a categorical score
-    pub async fn categorical_score(
+    /// Get a session with its traces
+    pub async fn get_session(
         &self,
-        trace_id: impl Into<String>,
-        name: impl Into<String>,
-        category: impl Into<String>,
-    ) -> Result<String> {
-        self.score()
-            .trace_id(trace_id.into())
-            .name(name.into())
-            .string_value(category.into())
+        session_id: impl Into<String>,
+    ) -> Result<langfuse_client_base::models::SessionWithTraces> {
+        use langfuse_client_base::apis::sessions_api;
+
+        let session_id = session_id.into();
+
+        sessions_api::sessions_get()
+            .configuration(self.configuration())
+            .session_id(session_id.as_str())
             .call()
             .await
+            .map_err(crate::error::map_api_error)
     }
 
     // ===== DATASET MANAGEMENT =====
 
     /// Create a dataset
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let dataset = client
+    ///     .create_dataset()
+    ///     .name("support-questions")
+    ///     .description("Questions collected from support tickets")
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn create_dataset(
         &self,
@@ -824,6 +3103,15 @@ impl LangfuseClient {
     }
 
     /// List datasets with pagination
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let datasets = client.list_datasets().page(1).limit(20).call().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn list_datasets(
         &self,
@@ -902,6 +3190,23 @@ impl LangfuseClient {
     // ===== DATASET ITEM OPERATIONS =====
 
     /// Create a new dataset item
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let item = client
+    ///     .create_dataset_item()
+    ///     .dataset_name("support-questions")
+    ///     .input(json!({"question": "How do I reset my password?"}))
+    ///     .expected_output(json!({"answer": "Use the 'Forgot password' link"}))
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn create_dataset_item(
         &self,
@@ -954,6 +3259,20 @@ impl LangfuseClient {
     }
 
     /// List dataset items
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let items = client
+    ///     .list_dataset_items()
+    ///     .dataset_name("support-questions")
+    ///     .limit(100)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn list_dataset_items(
         &self,
@@ -999,12 +3318,443 @@ impl LangfuseClient {
         Ok(())
     }
 
-    // Note: dataset_run_items_api doesn't exist in v0.2
-    // We'll implement this when the API is available
+    /// Create or update dataset items from a JSONL or CSV reader
+    ///
+    /// See [Importing items](crate::datasets#importing-items).
+    pub fn import_dataset_items<R: std::io::BufRead + Send + 'static>(
+        &self,
+        dataset_name: impl Into<String>,
+        reader: R,
+        format: ExportFormat,
+    ) -> DatasetImport<'_, R> {
+        DatasetImport::new(self, dataset_name.into(), reader, format)
+    }
+
+    /// Export the items of a dataset to JSONL or CSV, or stream them
+    ///
+    /// See [Exporting items](crate::datasets#exporting-items).
+    pub fn export_dataset(&self, dataset_name: impl Into<String>) -> DatasetExport<'_> {
+        DatasetExport::new(self, dataset_name.into())
+    }
+
+    /// Create a dataset run item linking a dataset item to a trace or observation
+    ///
+    /// The run is created if it does not exist yet.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .create_dataset_run_item()
+    ///     .run_name("prompt-v2")
+    ///     .dataset_item_id("item-123")
+    ///     .trace_id("trace-123")
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn create_dataset_run_item(
+        &self,
+        #[builder(into)] run_name: String,
+        #[builder(into)] dataset_item_id: String,
+        #[builder(into)] trace_id: Option<String>,
+        #[builder(into)] observation_id: Option<String>,
+        #[builder(into)] run_description: Option<String>,
+        metadata: Option<Value>,
+    ) -> Result<langfuse_client_base::models::DatasetRunItem> {
+        use langfuse_client_base::apis::dataset_run_items_api;
+        use langfuse_client_base::models::CreateDatasetRunItemRequest;
+
+        let request = CreateDatasetRunItemRequest {
+            run_name,
+            run_description: Some(run_description),
+            metadata: Some(metadata),
+            dataset_item_id,
+            observation_id: Some(observation_id),
+            trace_id: Some(trace_id),
+            ..Default::default()
+        };
+
+        dataset_run_items_api::dataset_run_items_create()
+            .configuration(self.configuration())
+            .create_dataset_run_item_request(request)
+            .call()
+            .await
+            .map_err(|e| {
+                crate::error::Error::Api(format!("Failed to create dataset run item: {}", e))
+            })
+    }
+
+    /// Run `task` against every active item of a dataset as the run `run_name`
+    ///
+    /// Each item gets a trace named after the run, with the item's input, the
+    /// task's output, and the task's scores, linked to the item in the run.
+    /// See [`crate::experiments`].
+    pub async fn run_experiment<F, Fut, O, E>(
+        &self,
+        dataset_name: impl Into<String>,
+        run_name: impl Into<String>,
+        mut task: F,
+    ) -> Result<ExperimentResult>
+    where
+        F: FnMut(DatasetItem) -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<O, E>>,
+        O: Into<ExperimentOutput>,
+        E: std::fmt::Display,
+    {
+        const PAGE_SIZE: i32 = 50;
+
+        let dataset_name = dataset_name.into();
+        let run_name = run_name.into();
+
+        let mut items = Vec::new();
+        let mut page = 1;
+        loop {
+            let response = self
+                .list_dataset_items()
+                .dataset_name(dataset_name.clone())
+                .page(page)
+                .limit(PAGE_SIZE)
+                .call()
+                .await?;
+            let received = response.data.len();
+            items.extend(
+                response
+                    .data
+                    .into_iter()
+                    .filter(|item| item.status == DatasetStatus::Active),
+            );
+            if received < PAGE_SIZE as usize || page >= response.meta.total_pages {
+                break;
+            }
+            page += 1;
+        }
+
+        let mut results = Vec::with_capacity(items.len());
+        for item in items {
+            let item_id = item.id.clone();
+            let input = item.input.clone();
+            let timestamp = Utc::now();
+
+            let (output, scores, error) = match task(item).await {
+                Ok(output) => {
+                    let ExperimentOutput { output, scores } = output.into();
+                    (Some(output), scores, None)
+                }
+                Err(e) => (None, Vec::new(), Some(e.to_string())),
+            };
+
+            let trace = self
+                .trace()
+                .name(run_name.clone())
+                .timestamp(timestamp)
+                .maybe_input(input)
+                .maybe_output(output.clone())
+                .maybe_metadata(
+                    error
+                        .as_ref()
+                        .map(|error| serde_json::json!({ "error": error })),
+                )
+                .dataset_item(dataset_name.clone(), item_id.clone())
+                .dataset_run(run_name.clone())
+                .call()
+                .await?;
+
+            for score in &scores {
+                self.score()
+                    .trace_id(trace.id.clone())
+                    .name(score.name.clone())
+                    .value(score.value.clone())
+                    .maybe_comment(score.comment.clone())
+                    .call()
+                    .await?;
+            }
+
+            results.push(ExperimentItemResult {
+                item_id,
+                trace_id: trace.id,
+                output,
+                scores,
+                error,
+            });
+        }
+
+        Ok(ExperimentResult {
+            dataset_name,
+            run_name,
+            items: results,
+        })
+    }
+
+    // ===== MODELS =====
+
+    /// Register a model definition so Langfuse can compute generation costs
+    ///
+    /// Prices are in USD per unit (per token for [`ModelUsageUnit::Tokens`]).
+    /// Generations whose model matches `match_pattern` (a regular expression)
+    /// get their costs computed server-side.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .create_model()
+    ///     .model_name("acme-llm")
+    ///     .match_pattern("(?i)^acme-llm(-latest)?$")
+    ///     .input_price(0.000_002)
+    ///     .output_price(0.000_008)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ModelUsageUnit::Tokens`]: langfuse_client_base::models::ModelUsageUnit::Tokens
+    #[builder]
+    pub async fn create_model(
+        &self,
+        #[builder(into)] model_name: String,
+        #[builder(into)] match_pattern: String,
+        /// Apply the definition to generations from this date on
+        start_date: Option<DateTime<Utc>>,
+        unit: Option<langfuse_client_base::models::ModelUsageUnit>,
+        input_price: Option<f64>,
+        output_price: Option<f64>,
+        total_price: Option<f64>,
+        /// Usage-dependent prices; takes precedence over the flat prices above
+        pricing_tiers: Option<Vec<langfuse_client_base::models::PricingTierInput>>,
+        #[builder(into)] tokenizer_id: Option<String>,
+        tokenizer_config: Option<Value>,
+    ) -> Result<langfuse_client_base::models::Model> {
+        use langfuse_client_base::apis::models_api;
+        use langfuse_client_base::models::CreateModelRequest;
+
+        let request = CreateModelRequest {
+            model_name,
+            match_pattern,
+            start_date: start_date
+                .map(|d| Some(d.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))),
+            unit,
+            input_price: input_price.map(Some),
+            output_price: output_price.map(Some),
+            total_price: total_price.map(Some),
+            pricing_tiers: pricing_tiers.map(Some),
+            tokenizer_id: tokenizer_id.map(Some),
+            tokenizer_config: tokenizer_config.map(Some),
+        };
+
+        models_api::models_create()
+            .configuration(self.configuration())
+            .create_model_request(request)
+            .call()
+            .await
+            .map_err(|e| crate::error::Error::Api(format!("Failed to create model: {}", e)))
+    }
+
+    /// List model definitions, including those managed by Langfuse
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let models = client.list_models().limit(50).call().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn list_models(
+        &self,
+        page: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<langfuse_client_base::models::PaginatedModels> {
+        use langfuse_client_base::apis::models_api;
+
+        models_api::models_list()
+            .configuration(self.configuration())
+            .maybe_page(page)
+            .maybe_limit(limit)
+            .call()
+            .await
+            .map_err(|e| crate::error::Error::Api(format!("Failed to list models: {}", e)))
+    }
+
+    /// Get a model definition by ID
+    pub async fn get_model(
+        &self,
+        model_id: impl Into<String>,
+    ) -> Result<langfuse_client_base::models::Model> {
+        use langfuse_client_base::apis::models_api;
+
+        let model_id = model_id.into();
+
+        models_api::models_get()
+            .configuration(self.configuration())
+            .id(model_id.as_str())
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+
+    /// Delete a model definition
+    ///
+    /// Only models created in the project can be deleted, not those managed by Langfuse.
+    pub async fn delete_model(&self, model_id: impl Into<String>) -> Result<()> {
+        use langfuse_client_base::apis::models_api;
+
+        let model_id = model_id.into();
+
+        models_api::models_delete()
+            .configuration(self.configuration())
+            .id(model_id.as_str())
+            .call()
+            .await
+            .map_err(|e| {
+                crate::error::Error::Api(format!("Failed to delete model '{}': {}", model_id, e))
+            })
+    }
+
+    // ===== MEDIA =====
+
+    /// Upload media for a trace or observation
+    ///
+    /// Takes exactly one of `bytes`, `path`, or `data_uri`. The content is
+    /// hashed, a presigned upload URL is requested, and the content is uploaded
+    /// unless Langfuse already stores it. Place the returned reference's
+    /// [token](MediaReference::token) in the trace or observation payload.
+    /// See [`crate::media`].
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use langfuse_ergonomic::media::MediaField;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let audio = client
+    ///     .upload_media()
+    ///     .trace_id("trace-123")
+    ///     .field(MediaField::Input)
+    ///     .bytes(std::fs::read("question.mp3")?)
+    ///     .content_type("audio/mpeg")
+    ///     .call()
+    ///     .await?;
+    /// println!("Embed {}", audio.token());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn upload_media(
+        &self,
+        #[builder(into)] trace_id: String,
+        #[builder(into)] observation_id: Option<String>,
+        field: Option<MediaField>,
+        bytes: Option<Vec<u8>>,
+        #[builder(into)] path: Option<PathBuf>,
+        #[builder(into)] data_uri: Option<String>,
+        /// MIME type, e.g. `image/png`; detected from the data URI or file extension if unset
+        #[builder(into)]
+        content_type: Option<String>,
+    ) -> Result<MediaReference> {
+        use langfuse_client_base::apis::media_api;
+        use langfuse_client_base::models::{GetMediaUploadUrlRequest, PatchMediaBody};
+
+        let payload =
+            MediaPayload::load(bytes, path.as_deref(), data_uri.as_deref(), content_type).await?;
+        let content_length = payload.bytes.len();
+        let sha256 = payload.sha256();
+
+        let request = GetMediaUploadUrlRequest {
+            trace_id,
+            observation_id: observation_id.map(Some),
+            content_type: payload.content_type,
+            content_length: i32::try_from(content_length).map_err(|_| {
+                Error::Validation(format!("Media of {content_length} bytes is too large"))
+            })?,
+            sha256_hash: sha256.clone(),
+            field: field.unwrap_or_default().as_str().to_string(),
+        };
+        let response = media_api::media_get_upload_url()
+            .configuration(self.configuration())
+            .get_media_upload_url_request(request)
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)?;
+
+        let mut reference = MediaReference {
+            media_id: response.media_id,
+            content_type: payload.mime.clone(),
+            source: payload.source,
+            content_length,
+            uploaded: false,
+        };
+        let Some(upload_url) = response.upload_url.flatten() else {
+            // Langfuse already stores content with this hash
+            return Ok(reference);
+        };
+
+        let started = std::time::Instant::now();
+        let upload = self
+            .configuration
+            .client
+            .put(&upload_url)
+            .with_extension(crate::auth::Unauthenticated)
+            .header(reqwest::header::CONTENT_TYPE, &payload.mime)
+            .header("x-amz-checksum-sha256", &sha256)
+            .body(payload.bytes)
+            .send()
+            .await
+            .map_err(Error::Middleware)?;
+        let upload_time_ms = i32::try_from(started.elapsed().as_millis()).unwrap_or(i32::MAX);
+        let status = upload.status();
+        let upload_error = if status.is_success() {
+            None
+        } else {
+            Some(upload.text().await.unwrap_or_default())
+        };
+
+        media_api::media_patch()
+            .configuration(self.configuration())
+            .media_id(reference.media_id.as_str())
+            .patch_media_body(PatchMediaBody {
+                uploaded_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                upload_http_status: i32::from(status.as_u16()),
+                upload_http_error: upload_error.clone().map(Some),
+                upload_time_ms: Some(Some(upload_time_ms)),
+            })
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)?;
+
+        if let Some(message) = upload_error {
+            return Err(Error::Api(format!(
+                "Media upload failed with status {status}: {message}"
+            )));
+        }
+        reference.uploaded = true;
+        Ok(reference)
+    }
 
     // ===== PROMPT MANAGEMENT =====
 
     /// Create a new prompt or a new version of an existing prompt
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let prompt = client
+    ///     .create_prompt()
+    ///     .name("greeting")
+    ///     .prompt("Hello {{name}}, how can I help?")
+    ///     .labels(vec!["production".to_string()])
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn create_prompt(
         &self,
@@ -1037,6 +3787,25 @@ impl LangfuseClient {
     }
 
     /// Create a chat prompt with messages
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// use serde_json::json;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let prompt = client
+    ///     .create_chat_prompt()
+    ///     .name("support-agent")
+    ///     .messages(vec![
+    ///         json!({"role": "system", "content": "You are a support agent for {{product}}."}),
+    ///         json!({"role": "user", "content": "{{question}}"}),
+    ///     ])
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn create_chat_prompt(
         &self,
@@ -1083,6 +3852,21 @@ impl LangfuseClient {
     }
 
     /// Update labels for a specific prompt version
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_prompt_version()
+    ///     .name("greeting")
+    ///     .version(3)
+    ///     .labels(vec!["production".to_string()])
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn update_prompt_version(
         &self,
@@ -1129,6 +3913,20 @@ impl LangfuseClient {
     }
 
     /// List prompts with filters
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let prompts = client
+    ///     .list_prompts()
+    ///     .label("production")
+    ///     .limit(20)
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     #[builder]
     pub async fn list_prompts(
         &self,
@@ -1136,14 +3934,23 @@ impl LangfuseClient {
         #[builder(into)] tag: Option<String>,
         #[builder(into)] label: Option<String>,
         page: Option<i32>,
-        limit: Option<String>,
+        limit: Option<i32>,
+        /// Set through the deprecated `limit_str` shim
+        #[builder(setters(vis = "", name = limit_str_internal))]
+        limit_str: Option<String>,
     ) -> Result<langfuse_client_base::models::PromptMetaListResponse> {
         use langfuse_client_base::apis::prompts_api;
 
         let name_ref = name.as_deref();
         let tag_ref = tag.as_deref();
         let label_ref = label.as_deref();
-        let limit_num = limit.and_then(|value| value.parse::<i32>().ok());
+        let limit = match (limit, limit_str) {
+            (Some(limit), _) => Some(limit),
+            (None, Some(value)) => Some(value.trim().parse::<i32>().map_err(|_| {
+                crate::error::Error::Validation(format!("Invalid prompt list limit '{}'", value))
+            })?),
+            (None, None) => None,
+        };
 
         prompts_api::prompts_list()
             .configuration(self.configuration())
@@ -1151,9 +3958,385 @@ impl LangfuseClient {
             .maybe_tag(tag_ref)
             .maybe_label(label_ref)
             .maybe_page(page)
-            .maybe_limit(limit_num)
+            .maybe_limit(limit)
             .call()
             .await
             .map_err(|e| crate::error::Error::Api(format!("Failed to list prompts: {}", e)))
     }
+
+    /// Create a project
+    ///
+    /// Requires an organization-scoped API key.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let project = client
+    ///     .create_project()
+    ///     .name("support-bot")
+    ///     .retention_days(30)
+    ///     .call()
+    ///     .await?;
+    /// println!("Created project {}", project.id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn create_project(
+        &self,
+        #[builder(into)] name: String,
+        /// JSON object with additional data
+        metadata: Option<Value>,
+        /// Days to retain data, 0 or at least 3; defaults to 0, keeping data indefinitely
+        #[builder(default)]
+        retention_days: i32,
+    ) -> Result<langfuse_client_base::models::Project> {
+        use langfuse_client_base::apis::projects_api;
+        use langfuse_client_base::models::ProjectsCreateRequest;
+
+        let request = ProjectsCreateRequest {
+            name,
+            metadata: metadata
+                .map(crate::scores::metadata_map)
+                .transpose()?
+                .map(Some),
+            retention: retention_days,
+        };
+        projects_api::projects_create()
+            .configuration(self.configuration())
+            .projects_create_request(request)
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+
+    /// Rename a project and change its metadata or retention
+    ///
+    /// Requires an organization-scoped API key.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// client
+    ///     .update_project()
+    ///     .project_id("project-123")
+    ///     .name("support-bot-v2")
+    ///     .call()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn update_project(
+        &self,
+        #[builder(into)] project_id: String,
+        #[builder(into)] name: String,
+        /// JSON object with additional data
+        metadata: Option<Value>,
+        /// Days to retain data, 0 or at least 3
+        retention_days: Option<i32>,
+    ) -> Result<langfuse_client_base::models::Project> {
+        use langfuse_client_base::apis::projects_api;
+        use langfuse_client_base::models::ProjectsUpdateRequest;
+
+        let request = ProjectsUpdateRequest {
+            name,
+            metadata: metadata
+                .map(crate::scores::metadata_map)
+                .transpose()?
+                .map(Some),
+            retention: retention_days.map(Some),
+        };
+        projects_api::projects_update()
+            .configuration(self.configuration())
+            .project_id(&project_id)
+            .projects_update_request(request)
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+
+    /// Create an API key for a project
+    ///
+    /// Requires an organization-scoped API key. Without explicit keys,
+    /// Langfuse generates them; the secret key is only returned here.
+    ///
+    /// ```no_run
+    /// # use langfuse_ergonomic::ClientBuilder;
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = ClientBuilder::from_env()?.build()?;
+    /// let key = client
+    ///     .create_project_api_key()
+    ///     .project_id("project-123")
+    ///     .note("ci")
+    ///     .call()
+    ///     .await?;
+    /// println!("Public key {}", key.public_key);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[builder]
+    pub async fn create_project_api_key(
+        &self,
+        #[builder(into)] project_id: String,
+        #[builder(into)] note: Option<String>,
+        /// Public key to use, starting with `pk-lf-`
+        #[builder(into)]
+        public_key: Option<String>,
+        /// Secret key to use, starting with `sk-lf-`
+        #[builder(into)]
+        secret_key: Option<String>,
+    ) -> Result<langfuse_client_base::models::ApiKeyResponse> {
+        use langfuse_client_base::apis::projects_api;
+        use langfuse_client_base::models::ProjectsCreateApiKeyRequest;
+
+        let request = ProjectsCreateApiKeyRequest {
+            note: note.map(Some),
+            public_key: public_key.map(Some),
+            secret_key: secret_key.map(Some),
+        };
+        projects_api::projects_create_api_key()
+            .configuration(self.configuration())
+            .project_id(&project_id)
+            .projects_create_api_key_request(request)
+            .call()
+            .await
+            .map_err(crate::error::map_api_error)
+    }
+}
+
+impl<'a, S: langfuse_client_list_prompts_builder::State> LangfuseClientListPromptsBuilder<'a, S> {
+    /// Set the limit from a string, as the former `limit(String)` setter did
+    ///
+    /// Unlike before, a value that is not a number fails the call with
+    /// [`Error::Validation`] instead of being ignored.
+    #[deprecated(since = "0.7.0", note = "use `limit` with an `i32` instead")]
+    pub fn limit_str(
+        self,
+        limit: impl Into<String>,
+    ) -> LangfuseClientListPromptsBuilder<'a, langfuse_client_list_prompts_builder::SetLimitStr<S>>
+    where
+        S::LimitStr: langfuse_client_list_prompts_builder::IsUnset,
+    {
+        self.limit_str_internal(limit.into())
+    }
+}
+
+impl<'a, S: langfuse_client_score_builder::State> LangfuseClientScoreBuilder<'a, S> {
+    /// Set a categorical value, as the former `string_value` setter did
+    #[deprecated(
+        since = "0.7.0",
+        note = "use `value` with a string or `ScoreValue::Category` instead"
+    )]
+    pub fn string_value(
+        self,
+        value: impl Into<String>,
+    ) -> LangfuseClientScoreBuilder<'a, langfuse_client_score_builder::SetStringValue<S>>
+    where
+        S::StringValue: langfuse_client_score_builder::IsUnset,
+    {
+        self.string_value_internal(value.into())
+    }
+}
+
+/// Trace builder returned by [`Batcher::trace`](crate::Batcher::trace)
+pub type BatchedTraceBuilder<'a> =
+    LangfuseClientTraceBuilder<'a, langfuse_client_trace_builder::SetCapture>;
+
+/// Span builder returned by [`Batcher::span`](crate::Batcher::span)
+pub type BatchedSpanBuilder<'a> =
+    LangfuseClientSpanBuilder<'a, langfuse_client_span_builder::SetCapture>;
+
+/// Generation builder returned by [`Batcher::generation`](crate::Batcher::generation)
+pub type BatchedGenerationBuilder<'a> =
+    LangfuseClientGenerationBuilder<'a, langfuse_client_generation_builder::SetCapture>;
+
+/// Event builder returned by [`Batcher::event`](crate::Batcher::event)
+pub type BatchedEventBuilder<'a> =
+    LangfuseClientEventBuilder<'a, langfuse_client_event_builder::SetCapture>;
+
+/// Score builder returned by [`Batcher::score`](crate::Batcher::score)
+pub type BatchedScoreBuilder<'a> =
+    LangfuseClientScoreBuilder<'a, langfuse_client_score_builder::SetCapture>;
+
+/// Builders whose `call()` queues the event on a batcher, see [`Batcher::trace`](crate::Batcher::trace)
+impl LangfuseClient {
+    pub(crate) fn batched_trace(&self, batcher: BatcherHandle) -> BatchedTraceBuilder<'_> {
+        self.trace().capture_internal(EventCapture::Batch(batcher))
+    }
+
+    pub(crate) fn batched_span(&self, batcher: BatcherHandle) -> BatchedSpanBuilder<'_> {
+        self.span().capture_internal(EventCapture::Batch(batcher))
+    }
+
+    pub(crate) fn batched_generation(
+        &self,
+        batcher: BatcherHandle,
+    ) -> BatchedGenerationBuilder<'_> {
+        self.generation()
+            .capture_internal(EventCapture::Batch(batcher))
+    }
+
+    pub(crate) fn batched_event(&self, batcher: BatcherHandle) -> BatchedEventBuilder<'_> {
+        self.event().capture_internal(EventCapture::Batch(batcher))
+    }
+
+    pub(crate) fn batched_score(&self, batcher: BatcherHandle) -> BatchedScoreBuilder<'_> {
+        self.score().capture_internal(EventCapture::Batch(batcher))
+    }
+}
+
+impl<'a, S: langfuse_client_trace_builder::State> LangfuseClientTraceBuilder<'a, S>
+where
+    S::Capture: langfuse_client_trace_builder::IsUnset,
+    langfuse_client_trace_builder::SetCapture<S>: langfuse_client_trace_builder::IsComplete,
+{
+    /// Build the `trace-create` event for the trace without sending it
+    ///
+    /// The event is validated and guarded exactly as `call()` would send it,
+    /// so it can be logged, snapshotted, or passed to a [`Batcher`](crate::Batcher).
+    /// Large fields are not offloaded as media.
+    ///
+    /// No dataset run item is created for a linked dataset item. The span,
+    /// generation, event, and score builders have the same method.
+    pub async fn build_event(self) -> Result<IngestionEvent> {
+        let capture = EventCapture::default();
+        self.capture_internal(capture.clone()).call().await?;
+        capture.into_event()
+    }
+}
+
+impl<'a, S: langfuse_client_span_builder::State> LangfuseClientSpanBuilder<'a, S>
+where
+    S::Capture: langfuse_client_span_builder::IsUnset,
+    langfuse_client_span_builder::SetCapture<S>: langfuse_client_span_builder::IsComplete,
+{
+    /// Build the `span-create` event for the span without sending it
+    ///
+    /// Works like the trace builder's
+    /// [`build_event`](LangfuseClientTraceBuilder::build_event).
+    pub async fn build_event(self) -> Result<IngestionEvent> {
+        let capture = EventCapture::default();
+        self.capture_internal(capture.clone()).call().await?;
+        capture.into_event()
+    }
+}
+
+impl<'a, S: langfuse_client_generation_builder::State> LangfuseClientGenerationBuilder<'a, S>
+where
+    S::Capture: langfuse_client_generation_builder::IsUnset,
+    langfuse_client_generation_builder::SetCapture<S>:
+        langfuse_client_generation_builder::IsComplete,
+{
+    /// Build the `generation-create` event for the generation without sending it
+    ///
+    /// Works like the trace builder's
+    /// [`build_event`](LangfuseClientTraceBuilder::build_event).
+    pub async fn build_event(self) -> Result<IngestionEvent> {
+        let capture = EventCapture::default();
+        self.capture_internal(capture.clone()).call().await?;
+        capture.into_event()
+    }
+}
+
+impl<'a, S: langfuse_client_event_builder::State> LangfuseClientEventBuilder<'a, S>
+where
+    S::Capture: langfuse_client_event_builder::IsUnset,
+    langfuse_client_event_builder::SetCapture<S>: langfuse_client_event_builder::IsComplete,
+{
+    /// Build the `event-create` event for the event without sending it
+    ///
+    /// Works like the trace builder's
+    /// [`build_event`](LangfuseClientTraceBuilder::build_event).
+    pub async fn build_event(self) -> Result<IngestionEvent> {
+        let capture = EventCapture::default();
+        self.capture_internal(capture.clone()).call().await?;
+        capture.into_event()
+    }
+}
+
+impl<'a, S: langfuse_client_score_builder::State> LangfuseClientScoreBuilder<'a, S>
+where
+    S::Capture: langfuse_client_score_builder::IsUnset,
+    langfuse_client_score_builder::SetCapture<S>: langfuse_client_score_builder::IsComplete,
+{
+    /// Build the `score-create` event for the score without sending it
+    ///
+    /// The score is validated exactly as `call()` would send it; see the trace
+    /// builder's [`build_event`](LangfuseClientTraceBuilder::build_event).
+    pub async fn build_event(self) -> Result<IngestionEvent> {
+        let capture = EventCapture::default();
+        self.capture_internal(capture.clone()).call().await?;
+        capture.into_event()
+    }
+}
+
+impl<'a, S: langfuse_client_span_builder::State> LangfuseClientSpanBuilder<'a, S>
+where
+    S::Id: langfuse_client_span_builder::IsUnset,
+    S::StartTime: langfuse_client_span_builder::IsUnset,
+    S::EndTime: langfuse_client_span_builder::IsUnset,
+    langfuse_client_span_builder::SetEndTime<
+        langfuse_client_span_builder::SetStartTime<langfuse_client_span_builder::SetId<S>>,
+    >: langfuse_client_span_builder::IsComplete,
+{
+    /// Run `block` and record the span with its start and end time
+    ///
+    /// The block receives the span's ID, e.g. to parent nested observations.
+    /// Returns the block's output and the result of recording the span; the
+    /// output is returned even if recording fails. See
+    /// [Timing observations](crate::observations#timing-observations).
+    pub async fn timed<F, Fut, T>(self, block: F) -> (T, Result<ObservationResponse>)
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        let id = Uuid::new_v4().to_string();
+        let stopwatch = Stopwatch::start();
+        let output = block(id.clone()).await;
+        let recorded = self
+            .id(id)
+            .start_time(stopwatch.started_at())
+            .end_time(stopwatch.now())
+            .call()
+            .await;
+        (output, recorded)
+    }
+}
+
+impl<'a, S: langfuse_client_generation_builder::State> LangfuseClientGenerationBuilder<'a, S>
+where
+    S::Id: langfuse_client_generation_builder::IsUnset,
+    S::StartTime: langfuse_client_generation_builder::IsUnset,
+    S::EndTime: langfuse_client_generation_builder::IsUnset,
+    langfuse_client_generation_builder::SetEndTime<
+        langfuse_client_generation_builder::SetStartTime<
+            langfuse_client_generation_builder::SetId<S>,
+        >,
+    >: langfuse_client_generation_builder::IsComplete,
+{
+    /// Run `block` and record the generation with its start and end time
+    ///
+    /// The block receives the generation's ID, e.g. to parent nested observations.
+    /// Returns the block's output and the result of recording the generation; the
+    /// output is returned even if recording fails. See
+    /// [Timing observations](crate::observations#timing-observations).
+    pub async fn timed<F, Fut, T>(self, block: F) -> (T, Result<ObservationResponse>)
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: std::future::Future<Output = T>,
+    {
+        let id = Uuid::new_v4().to_string();
+        let stopwatch = Stopwatch::start();
+        let output = block(id.clone()).await;
+        let recorded = self
+            .id(id)
+            .start_time(stopwatch.started_at())
+            .end_time(stopwatch.now())
+            .call()
+            .await;
+        (output, recorded)
+    }
 }
@@ -0,0 +1,213 @@
+//! Ingestion transport shared by single calls and the batcher
+//!
+//! [`IngestionTransport::send`] posts one batch to `/api/public/ingestion`
+//! and maps the response the same way for every caller: a 207 body becomes an
+//! [`IngestionResponse`] with per-event failures, and error statuses become
+//! [`Error`] variants carrying the `x-request-id` header and, for 429, the
+//! `Retry-After` delay. Bodies are compressed as configured with the
+//! `compression` feature. A disabled client reports every event as delivered
+//! without sending anything.
+
+use std::time::Duration;
+
+use langfuse_client_base::models::IngestionBatchRequest;
+use serde::Deserialize;
+
+use crate::batcher::Batcher;
+use crate::client::LangfuseClient;
+use crate::error::{Error, EventError, IngestionResponse, Result};
+
+/// Body of a 207 Multi-Status ingestion response
+#[derive(Deserialize)]
+struct MultiStatusResponse {
+    successes: Vec<SuccessItem>,
+    errors: Vec<ErrorItem>,
+}
+
+#[derive(Deserialize)]
+struct SuccessItem {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorItem {
+    id: String,
+    status: Option<u16>,
+    error: Option<serde_json::Value>,
+    message: Option<String>,
+}
+
+/// Sends ingestion batches with a client's HTTP stack and configuration
+pub(crate) struct IngestionTransport<'a> {
+    client: &'a LangfuseClient,
+}
+
+impl<'a> IngestionTransport<'a> {
+    pub(crate) fn new(client: &'a LangfuseClient) -> Self {
+        Self { client }
+    }
+
+    /// Send one batch of prepared events
+    ///
+    /// Events Langfuse rejects in a 207 response are listed as failures of
+    /// the returned response rather than returned as an error.
+    pub(crate) async fn send(&self, batch: &IngestionBatchRequest) -> Result<IngestionResponse> {
+        let event_ids: Vec<String> = batch.batch.iter().map(Batcher::extract_event_id).collect();
+        if self.client.disabled {
+            #[cfg(feature = "testing")]
+            if let Some(recorder) = &self.client.recorder {
+                recorder.record(&batch.batch);
+            }
+            return Ok(IngestionResponse {
+                success_count: event_ids.len(),
+                success_ids: event_ids,
+                failures: vec![],
+                failure_count: 0,
+                request_id: None,
+            });
+        }
+        if let Some(capture) = &self.client.payload_capture {
+            capture.capture("/api/public/ingestion", batch).await;
+        }
+
+        let response = self.post(serde_json::to_vec(batch)?).await?;
+
+        let status = response.status().as_u16();
+        let request_id = response
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .map(ToString::to_string);
+
+        match status {
+            200..=202 => Ok(IngestionResponse {
+                success_count: event_ids.len(),
+                success_ids: event_ids,
+                failures: vec![],
+                failure_count: 0,
+                request_id,
+            }),
+            207 => {
+                let body = response
+                    .text()
+                    .await
+                    .map_err(|e| Error::Api(format!("Failed to read 207 response: {e}")))?;
+                let multi_status: MultiStatusResponse = serde_json::from_str(&body)
+                    .map_err(|e| Error::Api(format!("Failed to parse 207 response: {e}")))?;
+
+                let success_ids: Vec<String> =
+                    multi_status.successes.into_iter().map(|s| s.id).collect();
+                let failures: Vec<EventError> = multi_status
+                    .errors
+                    .into_iter()
+                    .map(|e| EventError::from_item(e.id, e.status, e.error.as_ref(), e.message))
+                    .collect();
+                Ok(IngestionResponse {
+                    success_count: success_ids.len(),
+                    failure_count: failures.len(),
+                    success_ids,
+                    failures,
+                    request_id,
+                })
+            }
+            401 | 403 => Err(Error::Auth {
+                message: response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Authentication failed".to_string()),
+                request_id,
+            }),
+            429 => Err(Error::RateLimit {
+                retry_after: response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_retry_after),
+                request_id,
+            }),
+            500..=599 => Err(Error::Server {
+                status,
+                message: response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Server error".to_string()),
+                request_id,
+            }),
+            _ => Err(Error::Client {
+                status,
+                message: response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| format!("Unexpected status: {status}")),
+                request_id,
+            }),
+        }
+    }
+
+    /// Post a serialized batch, compressed if the client is configured to
+    async fn post(&self, body: Vec<u8>) -> Result<reqwest::Response> {
+        #[cfg(feature = "compression")]
+        if let Some(state) = &self.client.request_compression {
+            if let Some((compressed, encoding)) = state.compress(&body) {
+                let response = self
+                    .post_encoded(compressed, Some(encoding.as_str()))
+                    .await?;
+                if response.status() != reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE {
+                    return Ok(response);
+                }
+                state.mark_unsupported();
+            }
+        }
+        self.post_encoded(body, None).await
+    }
+
+    async fn post_encoded(
+        &self,
+        body: Vec<u8>,
+        content_encoding: Option<&str>,
+    ) -> Result<reqwest::Response> {
+        use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE, USER_AGENT};
+
+        let mut request = self
+            .client
+            .configuration
+            .client
+            .post(format!(
+                "{}/api/public/ingestion",
+                self.client.configuration.base_path
+            ))
+            .header(CONTENT_TYPE, "application/json");
+        if let Some(user_agent) = &self.client.configuration.user_agent {
+            request = request.header(USER_AGENT, user_agent);
+        }
+        if let Some(encoding) = content_encoding {
+            request = request.header(CONTENT_ENCODING, encoding);
+        }
+        request.body(body).send().await.map_err(Error::Middleware)
+    }
+}
+
+/// Parse a `Retry-After` header given in seconds or as an HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let seconds = value.parse::<u64>().ok().or_else(|| {
+        let retry_at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        let delay = retry_at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+        u64::try_from(delay.num_seconds()).ok().filter(|&s| s > 0)
+    })?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("30"), Some(Duration::from_secs(30)));
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        let delay = parse_retry_after(&later).unwrap();
+        assert!(delay > Duration::from_secs(100) && delay <= Duration::from_secs(120));
+        assert_eq!(parse_retry_after("Mon, 01 Jan 2001 00:00:00 +0000"), None);
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
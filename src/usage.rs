@@ -0,0 +1,256 @@
+//! Token usage and cost of a generation
+//!
+//! [`Usage`] collects the token counts and costs reported by an LLM provider
+//! and can be passed to [`LangfuseClient::generation`](crate::LangfuseClient::generation)
+//! and [`LangfuseClient::update_generation`](crate::LangfuseClient::update_generation)
+//! instead of setting each count separately. Provider response shapes convert
+//! into it directly:
+//!
+//! ```no_run
+//! # use langfuse_ergonomic::{ClientBuilder, Usage};
+//! # async fn example(response: serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+//! # let client = ClientBuilder::from_env()?.build()?;
+//! // `response` is the body of an OpenAI chat completion
+//! let usage = Usage::from_openai(&response["usage"])?;
+//!
+//! client
+//!     .generation()
+//!     .trace_id("trace-1")
+//!     .model("gpt-4o")
+//!     .usage(usage.with_costs(0.0025, 0.01))
+//!     .call()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Cache tokens are sent as the `cache_read` and `cache_write` usage details,
+//! and costs as the `input` and `output` cost details.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// Token counts and costs of a generation
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    /// Input (prompt) tokens, including cached tokens where the provider counts them
+    pub prompt_tokens: Option<i32>,
+    /// Output (completion) tokens
+    pub completion_tokens: Option<i32>,
+    /// Total tokens; defaults to the sum of prompt and completion tokens
+    pub total_tokens: Option<i32>,
+    /// Input tokens read from the provider's prompt cache
+    pub cache_read_tokens: Option<i32>,
+    /// Input tokens written to the provider's prompt cache
+    pub cache_write_tokens: Option<i32>,
+    /// Cost of the input in USD
+    pub input_cost: Option<f64>,
+    /// Cost of the output in USD
+    pub output_cost: Option<f64>,
+}
+
+impl Usage {
+    /// Usage from prompt and completion token counts
+    pub fn new(prompt_tokens: i32, completion_tokens: i32) -> Self {
+        Self {
+            prompt_tokens: Some(prompt_tokens),
+            completion_tokens: Some(completion_tokens),
+            ..Default::default()
+        }
+    }
+
+    /// Set the input and output costs in USD
+    #[must_use]
+    pub fn with_costs(mut self, input_cost: f64, output_cost: f64) -> Self {
+        self.input_cost = Some(input_cost);
+        self.output_cost = Some(output_cost);
+        self
+    }
+
+    /// Parse an OpenAI `usage` object (Chat Completions or Responses API)
+    pub fn from_openai(usage: &Value) -> Result<Self> {
+        parse::<OpenAiUsage>(usage, "OpenAI").map(Self::from)
+    }
+
+    /// Parse an Anthropic `usage` object
+    pub fn from_anthropic(usage: &Value) -> Result<Self> {
+        parse::<AnthropicUsage>(usage, "Anthropic").map(Self::from)
+    }
+
+    /// Cache token counts as usage details
+    pub(crate) fn usage_details(&self) -> HashMap<String, i32> {
+        [
+            ("cache_read", self.cache_read_tokens),
+            ("cache_write", self.cache_write_tokens),
+        ]
+        .into_iter()
+        .filter_map(|(key, tokens)| tokens.map(|t| (key.to_string(), t)))
+        .collect()
+    }
+
+    /// Costs as cost details
+    pub(crate) fn cost_details(&self) -> HashMap<String, f64> {
+        [("input", self.input_cost), ("output", self.output_cost)]
+            .into_iter()
+            .filter_map(|(key, cost)| cost.map(|c| (key.to_string(), c)))
+            .collect()
+    }
+}
+
+fn parse<T: DeserializeOwned>(usage: &Value, provider: &str) -> Result<T> {
+    T::deserialize(usage)
+        .map_err(|e| Error::Validation(format!("Invalid {provider} usage object: {e}")))
+}
+
+/// Usage object of an OpenAI response
+///
+/// Accepts both the Chat Completions (`prompt_tokens`/`completion_tokens`) and
+/// the Responses API (`input_tokens`/`output_tokens`) field names.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OpenAiUsage {
+    /// Input tokens, including cached tokens
+    #[serde(default, alias = "input_tokens")]
+    pub prompt_tokens: i32,
+    /// Output tokens
+    #[serde(default, alias = "output_tokens")]
+    pub completion_tokens: i32,
+    /// Total tokens
+    #[serde(default)]
+    pub total_tokens: Option<i32>,
+    /// Breakdown of the input tokens
+    #[serde(default, alias = "input_tokens_details")]
+    pub prompt_tokens_details: Option<OpenAiPromptTokensDetails>,
+}
+
+/// Breakdown of the input tokens of an OpenAI response
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OpenAiPromptTokensDetails {
+    /// Input tokens served from the prompt cache
+    #[serde(default)]
+    pub cached_tokens: Option<i32>,
+}
+
+impl From<OpenAiUsage> for Usage {
+    fn from(usage: OpenAiUsage) -> Self {
+        Self {
+            prompt_tokens: Some(usage.prompt_tokens),
+            completion_tokens: Some(usage.completion_tokens),
+            total_tokens: usage.total_tokens,
+            cache_read_tokens: usage.prompt_tokens_details.and_then(|d| d.cached_tokens),
+            ..Default::default()
+        }
+    }
+}
+
+/// Usage object of an Anthropic response
+///
+/// Anthropic reports cache reads and writes separately from `input_tokens`,
+/// so the converted prompt token count includes them.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnthropicUsage {
+    /// Uncached input tokens
+    #[serde(default)]
+    pub input_tokens: i32,
+    /// Output tokens
+    #[serde(default)]
+    pub output_tokens: i32,
+    /// Input tokens written to the prompt cache
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<i32>,
+    /// Input tokens read from the prompt cache
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<i32>,
+}
+
+impl From<AnthropicUsage> for Usage {
+    fn from(usage: AnthropicUsage) -> Self {
+        let prompt_tokens = usage
+            .input_tokens
+            .saturating_add(usage.cache_creation_input_tokens.unwrap_or(0))
+            .saturating_add(usage.cache_read_input_tokens.unwrap_or(0));
+        Self {
+            prompt_tokens: Some(prompt_tokens),
+            completion_tokens: Some(usage.output_tokens),
+            cache_read_tokens: usage.cache_read_input_tokens,
+            cache_write_tokens: usage.cache_creation_input_tokens,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_openai_chat_completions() {
+        let usage = Usage::from_openai(&json!({
+            "prompt_tokens": 100,
+            "completion_tokens": 20,
+            "total_tokens": 120,
+            "prompt_tokens_details": {"cached_tokens": 64, "audio_tokens": 0}
+        }))
+        .unwrap();
+
+        assert_eq!(usage.prompt_tokens, Some(100));
+        assert_eq!(usage.completion_tokens, Some(20));
+        assert_eq!(usage.total_tokens, Some(120));
+        assert_eq!(
+            usage.usage_details(),
+            HashMap::from([("cache_read".to_string(), 64)])
+        );
+    }
+
+    #[test]
+    fn test_from_openai_responses_api() {
+        let usage = Usage::from_openai(&json!({
+            "input_tokens": 50,
+            "output_tokens": 10,
+            "total_tokens": 60,
+            "input_tokens_details": {"cached_tokens": 0}
+        }))
+        .unwrap();
+
+        assert_eq!(usage.prompt_tokens, Some(50));
+        assert_eq!(usage.completion_tokens, Some(10));
+        assert_eq!(usage.cache_read_tokens, Some(0));
+    }
+
+    #[test]
+    fn test_from_anthropic() {
+        let usage = Usage::from_anthropic(&json!({
+            "input_tokens": 10,
+            "output_tokens": 30,
+            "cache_creation_input_tokens": 200,
+            "cache_read_input_tokens": 500
+        }))
+        .unwrap();
+
+        assert_eq!(usage.prompt_tokens, Some(710));
+        assert_eq!(usage.completion_tokens, Some(30));
+        assert_eq!(usage.cache_write_tokens, Some(200));
+        assert_eq!(usage.cache_read_tokens, Some(500));
+    }
+
+    #[test]
+    fn test_invalid_usage_object() {
+        let err = Usage::from_anthropic(&json!({"input_tokens": "many"})).unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+    }
+
+    #[test]
+    fn test_cost_details() {
+        let usage = Usage::new(10, 5).with_costs(0.25, 0.5);
+        assert_eq!(
+            usage.cost_details(),
+            HashMap::from([("input".to_string(), 0.25), ("output".to_string(), 0.5)])
+        );
+        assert!(Usage::default().cost_details().is_empty());
+    }
+}
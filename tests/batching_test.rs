@@ -1,7 +1,9 @@
 //! Comprehensive tests for batching functionality
 
 use langfuse_client_base::models::{IngestionEvent, IngestionEventOneOf, TraceBody};
-use langfuse_ergonomic::{BackpressurePolicy, Batcher, ClientBuilder};
+use langfuse_ergonomic::{
+    BackpressurePolicy, Batcher, CircuitBreaker, CircuitEvent, ClientBuilder, DeliveryResult,
+};
 use mockito::Server;
 use std::time::Duration;
 
@@ -416,3 +418,793 @@ async fn test_auto_flush_on_size() {
 
     mock.assert_async().await;
 }
+
+#[tokio::test]
+async fn test_queue_capacity_metrics() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(200)
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid");
+
+    let batcher = Batcher::builder()
+        .client(client)
+        .max_queue_size(2)
+        .backpressure_policy(BackpressurePolicy::DropNew)
+        .build()
+        .await;
+
+    // Fill the queue without yielding to the background task
+    batcher.add(create_test_event("test-1")).await.unwrap();
+    batcher.add(create_test_event("test-2")).await.unwrap();
+    assert!(batcher.add(create_test_event("test-3")).await.is_err());
+
+    let metrics = batcher.metrics();
+    assert_eq!(metrics.queue_high_water, 2);
+
+    // Let the events sit in the buffer before flushing
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    batcher.flush().await.unwrap();
+
+    let metrics = batcher.metrics();
+    assert_eq!(metrics.queue_high_water, 2);
+    assert!(metrics.max_wait_ms >= 30);
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_pending_events_summary() {
+    let server = Server::new_async().await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid");
+
+    let batcher = Batcher::builder()
+        .client(client)
+        .max_events(100)
+        .flush_interval(Duration::from_secs(60))
+        .build()
+        .await;
+
+    let summary = batcher.pending_events_summary().await;
+    assert_eq!(summary.buffered, 0);
+    assert_eq!(summary.oldest_age, None);
+
+    batcher.add(create_test_event("test-1")).await.unwrap();
+    batcher.add(create_test_event("test-2")).await.unwrap();
+
+    // Let the background task move the events into the buffer
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let summary = batcher.pending_events_summary().await;
+    assert_eq!(summary.buffered, 2);
+    assert_eq!(summary.in_channel, 0);
+    assert_eq!(summary.by_type.get("trace-create"), Some(&2));
+    assert_eq!(summary.by_age.under_1s, 2);
+    assert_eq!(summary.retrying, 0);
+    assert!(summary.total_bytes > 0);
+    assert!(summary.oldest_age.is_some());
+}
+
+#[tokio::test]
+async fn test_drain_rejects_new_events_and_reports() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"successes": [{"id": "drain-1", "status": 201}, {"id": "drain-2", "status": 201}], "errors": []}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid");
+
+    let batcher = std::sync::Arc::new(Batcher::builder().client(client).build().await);
+    batcher.add(create_test_event("drain-1")).await.unwrap();
+    batcher.add(create_test_event("drain-2")).await.unwrap();
+
+    let report = batcher.drain(Duration::from_secs(5)).await;
+
+    mock.assert_async().await;
+    assert_eq!(report.flushed, 2);
+    assert_eq!(report.remaining, 0);
+    assert!(!report.timed_out);
+    assert!(batcher.add(create_test_event("late")).await.is_err());
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["flushed"], 2);
+}
+
+#[tokio::test]
+async fn test_flush_trace_sends_only_that_trace() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+            "batch": [{"body": {"id": "trace-a"}}]
+        })))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [{"id": "trace-a", "status": 201}], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid");
+
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(60))
+        .build()
+        .await;
+
+    batcher.add(create_test_event("trace-a")).await.unwrap();
+    batcher.add(create_test_event("trace-b")).await.unwrap();
+
+    let response = batcher.flush_trace("trace-a").await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(response.success_ids, vec!["trace-a".to_string()]);
+
+    let summary = batcher.pending_events_summary().await;
+    assert_eq!(summary.buffered, 1);
+    assert_eq!(batcher.metrics().queued, 1);
+}
+
+#[tokio::test]
+async fn test_adaptive_flush_interval_lengthens_when_idle() {
+    use langfuse_ergonomic::AdaptiveFlush;
+
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [{"id": "event-1", "status": 201}], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_millis(50))
+        .adaptive_flush(AdaptiveFlush::new(
+            Duration::from_millis(10),
+            Duration::from_millis(400),
+        ))
+        .build()
+        .await;
+    assert_eq!(batcher.metrics().flush_interval_ms, 50);
+
+    // Empty ticks double the interval
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert!(batcher.metrics().flush_interval_ms > 50);
+
+    // The first event after the idle stretch is sent within the configured interval
+    batcher.add(create_test_event("event-1")).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(batcher.metrics().flushed, 1);
+    mock.assert_async().await;
+
+    batcher.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_207_validation_details() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+            "successes": [],
+            "errors": [
+                {
+                    "id": "invalid-1",
+                    "status": 400,
+                    "error": [
+                        {
+                            "code": "invalid_type",
+                            "path": ["body", "name"],
+                            "message": "Expected string, received number"
+                        }
+                    ]
+                }
+            ]
+        }"#,
+        )
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid");
+
+    let batcher = Batcher::builder()
+        .client(client)
+        .max_retries(0)
+        .build()
+        .await;
+
+    batcher.add(create_test_event("invalid-1")).await.unwrap();
+    let response = batcher.flush().await.unwrap();
+
+    let failure = &response.failures[0];
+    assert_eq!(
+        failure.message,
+        "body.name: Expected string, received number"
+    );
+    assert_eq!(failure.validation_details.len(), 1);
+    assert_eq!(failure.validation_details[0].field(), "body.name");
+    assert_eq!(
+        failure.validation_details[0].code.as_deref(),
+        Some("invalid_type")
+    );
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_file_queue_replays_unsent_events() {
+    use langfuse_ergonomic::FileQueue;
+
+    let path = std::env::temp_dir().join(format!("langfuse-queue-{}.jsonl", uuid::Uuid::new_v4()));
+    let mut server = Server::new_async().await;
+
+    // A batcher that never gets to flush, like a process that crashed
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let crashed = Batcher::builder()
+        .client(client.clone())
+        .flush_interval(Duration::from_secs(3600))
+        .queue_backend(FileQueue::open(&path).unwrap())
+        .build()
+        .await;
+    crashed.add(create_test_event("event-1")).await.unwrap();
+    crashed.add(create_test_event("event-2")).await.unwrap();
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::Regex(r#""id":"event-1""#.to_string()),
+            mockito::Matcher::Regex(r#""id":"event-2""#.to_string()),
+        ]))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"successes": [{"id": "event-1", "status": 201}, {"id": "event-2", "status": 201}], "errors": []}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let restarted = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .queue_backend(FileQueue::open(&path).unwrap())
+        .build()
+        .await;
+    assert_eq!(restarted.metrics().queued, 2);
+
+    let response = restarted.flush().await.unwrap();
+    assert_eq!(response.success_count, 2);
+    mock.assert_async().await;
+    // Sent events are acknowledged, leaving nothing to replay
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_handles_add_from_many_tasks() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect_at_least(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+
+    let tasks: Vec<_> = (0..20)
+        .map(|i| {
+            let handle = batcher.handle();
+            tokio::spawn(async move { handle.add(create_test_event(&format!("event-{i}"))).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let handle = batcher.handle();
+    batcher.shutdown().await.unwrap();
+    mock.assert_async().await;
+
+    // The owner shut down, so handles stop accepting events
+    assert!(handle.is_closed());
+    assert!(handle.add(create_test_event("late")).await.is_err());
+    assert_eq!(handle.metrics().queued, 0);
+}
+
+#[tokio::test]
+async fn test_flush_includes_every_added_event() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(mockito::Matcher::Regex("event-9".to_string()))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+
+    for i in 0..10 {
+        batcher
+            .add(create_test_event(&format!("event-{i}")))
+            .await
+            .unwrap();
+    }
+    // No waiting: flush must pick up events still in the channel
+    batcher.flush().await.unwrap();
+
+    mock.assert_async().await;
+    assert_eq!(batcher.metrics().queued, 0);
+
+    batcher.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_pauses_sends() {
+    let mut server = Server::new_async().await;
+
+    let failing = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(503)
+        .with_body("Service unavailable")
+        .expect(2)
+        .create_async()
+        .await;
+    let recovered = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(200)
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .max_retries(5)
+        .initial_retry_delay(Duration::from_millis(10))
+        .retry_jitter(false)
+        .circuit_breaker(CircuitBreaker::new(2, Duration::from_millis(300)))
+        .on_circuit_change(move |event| events_clone.lock().unwrap().push(event.clone()))
+        .build()
+        .await;
+
+    batcher.add(create_test_event("test-1")).await.unwrap();
+
+    // Two failed requests open the circuit; no further retries are made
+    batcher.flush().await.unwrap();
+    let metrics = batcher.metrics();
+    assert!(metrics.circuit_open);
+    assert_eq!(metrics.circuit_opens, 1);
+    assert_eq!(metrics.queued, 1);
+    assert_eq!(metrics.failed, 0);
+
+    // While open, flushes leave the event queued without sending
+    let err = batcher.flush().await.unwrap_err();
+    assert!(matches!(err, langfuse_ergonomic::Error::CircuitOpen { .. }));
+    assert!(err.is_retryable());
+    failing.assert_async().await;
+
+    // After the cool-down a successful request closes the circuit
+    tokio::time::sleep(Duration::from_millis(350)).await;
+    let response = batcher.flush().await.unwrap();
+    assert_eq!(response.success_count, 1);
+    recovered.assert_async().await;
+    assert!(!batcher.metrics().circuit_open);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            CircuitEvent::Opened {
+                consecutive_failures: 2,
+                cooldown: Duration::from_millis(300),
+            },
+            CircuitEvent::Closed,
+        ]
+    );
+
+    batcher.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_add_with_ack_reports_delivery() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+            "successes": [{"id": "ok-1", "status": 201}],
+            "errors": [{"id": "bad-1", "status": 400, "message": "Validation failed"}]
+        }"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+
+    let ok = batcher
+        .add_with_ack(create_test_event("ok-1"))
+        .await
+        .unwrap();
+    // A second waiter for a pending ID is rejected, not queued
+    let duplicate = batcher.add_with_ack(create_test_event("ok-1")).await;
+    assert!(matches!(
+        duplicate,
+        Err(langfuse_ergonomic::Error::Validation(_))
+    ));
+    let bad = batcher
+        .handle()
+        .add_with_ack(create_test_event("bad-1"))
+        .await
+        .unwrap();
+    batcher.flush().await.unwrap();
+
+    assert!(matches!(ok.await.unwrap(), DeliveryResult::Delivered));
+    match bad.await.unwrap() {
+        DeliveryResult::Failed(error) => {
+            assert_eq!(error.event_id, "bad-1");
+            assert_eq!(error.message, "Validation failed");
+        }
+        other => panic!("expected a failure, got {other:?}"),
+    }
+    mock.assert_async().await;
+
+    batcher.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_add_with_ack_errors_when_event_is_left_queued() {
+    let mut server = Server::new_async().await;
+
+    let failing = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(503)
+        .with_body("Service unavailable")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .max_retries(0)
+        .circuit_breaker(CircuitBreaker::new(1, Duration::from_secs(3600)))
+        .build()
+        .await;
+
+    let ack = batcher
+        .add_with_ack(create_test_event("stuck-1"))
+        .await
+        .unwrap();
+    // The failed request opens the circuit, so the final flush can't send
+    batcher.flush().await.unwrap();
+    assert!(batcher.metrics().circuit_open);
+    let err = batcher.shutdown().await.unwrap_err();
+    assert!(matches!(err, langfuse_ergonomic::Error::CircuitOpen { .. }));
+
+    let result = tokio::time::timeout(Duration::from_secs(5), ack)
+        .await
+        .unwrap();
+    assert!(result.is_err());
+    failing.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_wait_for_pending_returns_once_events_are_buffered() {
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url("http://localhost:1")
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+
+    for id in ["event-1", "event-2", "event-3"] {
+        batcher.add(create_test_event(id)).await.unwrap();
+    }
+    // No flush is due, so this must not wait for one
+    tokio::time::timeout(Duration::from_secs(5), batcher.wait_for_pending())
+        .await
+        .unwrap();
+    assert_eq!(batcher.metrics().queued, 3);
+}
+
+#[tokio::test]
+async fn test_batched_trace_rejects_dataset_run() {
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url("http://localhost:1")
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+
+    let result = batcher
+        .trace()
+        .dataset_item("qa-golden", "item-42")
+        .dataset_run("nightly")
+        .call()
+        .await;
+    assert!(matches!(
+        result,
+        Err(langfuse_ergonomic::Error::Validation(_))
+    ));
+    assert_eq!(batcher.metrics().queued, 0);
+
+    batcher.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_typed_add_methods_queue_events() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(mockito::Matcher::AllOf(vec![
+            mockito::Matcher::Regex("trace-create".to_string()),
+            mockito::Matcher::Regex("span-create".to_string()),
+            mockito::Matcher::Regex("generation-create".to_string()),
+            mockito::Matcher::Regex("score-create".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+
+    let trace = batcher.trace().name("typed").call().await.unwrap();
+    let span_id = batcher
+        .span()
+        .trace_id(&trace.id)
+        .name("step")
+        .call()
+        .await
+        .unwrap();
+    batcher
+        .generation()
+        .trace_id(&trace.id)
+        .parent_observation_id(span_id)
+        .model("gpt-4o")
+        .call()
+        .await
+        .unwrap();
+    batcher
+        .score()
+        .trace_id(&trace.id)
+        .name("quality")
+        .value(0.9)
+        .call()
+        .await
+        .unwrap();
+
+    // Nothing is sent until the batch is flushed
+    let pending = batcher.pending_events_summary().await;
+    assert_eq!(pending.buffered + pending.in_channel, 4);
+    let response = batcher.flush().await.unwrap();
+    assert_eq!(response.success_count, 4);
+    mock.assert_async().await;
+
+    batcher.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dedup_skips_repeated_event_ids() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(200)
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .dedup_capacity(100)
+        .build()
+        .await;
+
+    for id in ["event-1", "event-2", "event-1", "event-2", "event-1"] {
+        batcher.add(create_test_event(id)).await.unwrap();
+    }
+
+    let response = batcher.flush().await.unwrap();
+    assert_eq!(response.success_count, 2);
+    let metrics = batcher.metrics();
+    assert_eq!(metrics.deduplicated, 3);
+    assert_eq!(metrics.flushed, 2);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_dedup_settles_ack_of_skipped_event() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [{"id": "event-1", "status": 201}], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+    let batcher = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(3600))
+        .dedup_capacity(100)
+        .build()
+        .await;
+
+    let first = batcher
+        .add_with_ack(create_test_event("event-1"))
+        .await
+        .unwrap();
+    batcher.flush().await.unwrap();
+    assert!(matches!(first.await.unwrap(), DeliveryResult::Delivered));
+
+    let second = batcher
+        .add_with_ack(create_test_event("event-1"))
+        .await
+        .unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), second)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(result, DeliveryResult::Dropped));
+    assert_eq!(batcher.metrics().deduplicated, 1);
+    mock.assert_async().await;
+
+    batcher.shutdown().await.unwrap();
+}
@@ -0,0 +1,99 @@
+//! Tests for request body compression (requires the `compression` feature)
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use langfuse_ergonomic::compression::RequestCompression;
+use langfuse_ergonomic::{ClientBuilder, LangfuseClient};
+use mockito::{Matcher, Server};
+use serde_json::json;
+
+fn create_client(server: &Server) -> LangfuseClient {
+    ClientBuilder::new()
+        .public_key("pk-lf-test")
+        .secret_key("sk-lf-test")
+        .base_url(server.url())
+        .request_compression(RequestCompression::gzip().min_bytes(512))
+        .build()
+        .unwrap()
+}
+
+fn gunzip(body: &[u8]) -> String {
+    let mut text = String::new();
+    GzDecoder::new(body).read_to_string(&mut text).unwrap();
+    text
+}
+
+#[tokio::test]
+async fn test_large_bodies_are_gzipped() {
+    let mut server = Server::new_async().await;
+    let compressed = server
+        .mock("POST", "/api/public/ingestion")
+        .match_header("content-encoding", "gzip")
+        .match_request(|request| {
+            request
+                .body()
+                .is_ok_and(|body| gunzip(body).contains(r#""name":"large""#))
+        })
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .create_async()
+        .await;
+    let plain = server
+        .mock("POST", "/api/public/ingestion")
+        .match_header("content-encoding", Matcher::Missing)
+        .match_body(Matcher::Regex(r#""name":"small""#.to_string()))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .create_async()
+        .await;
+    let client = create_client(&server);
+
+    client
+        .trace()
+        .name("large")
+        .input(json!({"document": "lorem ipsum ".repeat(200)}))
+        .call()
+        .await
+        .unwrap();
+    client.trace().name("small").call().await.unwrap();
+
+    compressed.assert_async().await;
+    plain.assert_async().await;
+    assert_eq!(
+        client.debug_config().request_compression.as_deref(),
+        Some("gzip above 512 bytes")
+    );
+}
+
+#[tokio::test]
+async fn test_unsupported_compression_falls_back_to_plain_bodies() {
+    let mut server = Server::new_async().await;
+    let rejected = server
+        .mock("POST", "/api/public/ingestion")
+        .match_header("content-encoding", "gzip")
+        .with_status(415)
+        .expect(1)
+        .create_async()
+        .await;
+    let plain = server
+        .mock("POST", "/api/public/ingestion")
+        .match_header("content-encoding", Matcher::Missing)
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(2)
+        .create_async()
+        .await;
+    let client = create_client(&server);
+    let input = json!({"document": "lorem ipsum ".repeat(200)});
+
+    // The rejected request is resent uncompressed, later ones are not compressed
+    client.trace().input(input.clone()).call().await.unwrap();
+    client.trace().input(input).call().await.unwrap();
+
+    rejected.assert_async().await;
+    plain.assert_async().await;
+}
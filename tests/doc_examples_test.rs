@@ -0,0 +1,66 @@
+//! Checks that every public builder method of `LangfuseClient` has a usage example
+//!
+//! The examples are `no_run` doctests, so `cargo test` compiles each one against
+//! the current builder signatures. This test makes sure no builder method is
+//! left without one: a new `#[builder]` method fails it until its doc comment
+//! gets an example that calls the method.
+
+const TRACES_SOURCE: &str = include_str!("../src/traces.rs");
+
+/// Name and doc comment of each `#[builder]` method in `source`
+fn builder_methods(source: &str) -> Vec<(String, Vec<&str>)> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut methods = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim() != "#[builder]" {
+            continue;
+        }
+        let Some(signature) = lines.get(i + 1) else {
+            continue;
+        };
+        let Some(name) = signature
+            .trim()
+            .strip_prefix("pub async fn ")
+            .or_else(|| signature.trim().strip_prefix("pub fn "))
+            .and_then(|rest| rest.split(['(', '<']).next())
+        else {
+            continue;
+        };
+
+        let doc: Vec<&str> = lines[..i]
+            .iter()
+            .rev()
+            .take_while(|line| line.trim_start().starts_with("///"))
+            .map(|line| line.trim_start().trim_start_matches("///").trim())
+            .collect();
+        methods.push((name.to_string(), doc.into_iter().rev().collect()));
+    }
+
+    methods
+}
+
+#[test]
+fn test_every_client_builder_has_an_example() {
+    let methods = builder_methods(TRACES_SOURCE);
+    assert!(
+        methods.len() >= 20,
+        "expected to find the client's builder methods, found {}",
+        methods.len()
+    );
+
+    let missing: Vec<&str> = methods
+        .iter()
+        .filter(|(name, doc)| {
+            let has_example = doc.contains(&"```no_run");
+            let calls_method = doc.iter().any(|line| line.contains(&format!(".{name}(")));
+            !(has_example && calls_method)
+        })
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    assert!(
+        missing.is_empty(),
+        "builder methods without a `no_run` example calling them: {missing:?}"
+    );
+}
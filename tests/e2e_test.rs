@@ -0,0 +1,250 @@
+//! End-to-end tests against an ephemeral self-hosted Langfuse
+//!
+//! The harness starts Postgres and Langfuse in Docker with
+//! [testcontainers](https://docs.rs/testcontainers), seeds a project with known
+//! API keys through Langfuse's headless initialization, and runs the client
+//! against it. Copy [`LangfuseContainer`] into your own test suite as a starting
+//! point for realistic integration tests.
+//!
+//! Requires Docker and the `e2e` feature:
+//!
+//! ```bash
+//! cargo test --features e2e --test e2e_test -- --ignored
+//! ```
+
+use std::time::Duration;
+
+use langfuse_ergonomic::{ClientBuilder, LangfuseClient, Prompt, TraceFilter};
+use serde_json::json;
+use testcontainers::core::{IntoContainerPort, WaitFor};
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+
+const LANGFUSE_IMAGE: &str = "langfuse/langfuse";
+const LANGFUSE_TAG: &str = "2";
+const POSTGRES_IMAGE: &str = "postgres";
+const POSTGRES_TAG: &str = "16-alpine";
+
+const PUBLIC_KEY: &str = "pk-lf-e2e-test";
+const SECRET_KEY: &str = "sk-lf-e2e-test";
+
+/// A running Langfuse instance with a seeded project
+struct LangfuseContainer {
+    // Kept alive for the duration of the test; containers are removed on drop
+    _postgres: ContainerAsync<GenericImage>,
+    _langfuse: ContainerAsync<GenericImage>,
+    base_url: String,
+}
+
+impl LangfuseContainer {
+    async fn start() -> Self {
+        let run_id = uuid::Uuid::new_v4().simple().to_string();
+        let network = format!("langfuse-e2e-{run_id}");
+        let db_host = format!("langfuse-db-{run_id}");
+
+        let postgres = GenericImage::new(POSTGRES_IMAGE, POSTGRES_TAG)
+            .with_exposed_port(5432.tcp())
+            .with_wait_for(WaitFor::message_on_stderr(
+                "database system is ready to accept connections",
+            ))
+            .with_network(&network)
+            .with_container_name(&db_host)
+            .with_env_var("POSTGRES_USER", "postgres")
+            .with_env_var("POSTGRES_PASSWORD", "postgres")
+            .with_env_var("POSTGRES_DB", "postgres")
+            .start()
+            .await
+            .expect("failed to start Postgres container");
+
+        let langfuse = GenericImage::new(LANGFUSE_IMAGE, LANGFUSE_TAG)
+            .with_exposed_port(3000.tcp())
+            .with_wait_for(WaitFor::message_on_stdout("Ready"))
+            .with_network(&network)
+            .with_startup_timeout(Duration::from_secs(180))
+            .with_env_var(
+                "DATABASE_URL",
+                format!("postgresql://postgres:postgres@{db_host}:5432/postgres"),
+            )
+            .with_env_var("NEXTAUTH_URL", "http://localhost:3000")
+            .with_env_var("NEXTAUTH_SECRET", "e2e-secret")
+            .with_env_var("SALT", "e2e-salt")
+            .with_env_var("TELEMETRY_ENABLED", "false")
+            .with_env_var("LANGFUSE_INIT_ORG_ID", "e2e-org")
+            .with_env_var("LANGFUSE_INIT_PROJECT_ID", "e2e-project")
+            .with_env_var("LANGFUSE_INIT_PROJECT_PUBLIC_KEY", PUBLIC_KEY)
+            .with_env_var("LANGFUSE_INIT_PROJECT_SECRET_KEY", SECRET_KEY)
+            .start()
+            .await
+            .expect("failed to start Langfuse container");
+
+        let host = langfuse.get_host().await.expect("Langfuse host");
+        let port = langfuse
+            .get_host_port_ipv4(3000)
+            .await
+            .expect("Langfuse port");
+
+        Self {
+            _postgres: postgres,
+            _langfuse: langfuse,
+            base_url: format!("http://{host}:{port}"),
+        }
+    }
+
+    fn client(&self) -> LangfuseClient {
+        ClientBuilder::new()
+            .public_key(PUBLIC_KEY)
+            .secret_key(SECRET_KEY)
+            .base_url(self.base_url.clone())
+            .build()
+            .expect("seeded credentials should be valid")
+    }
+}
+
+/// Poll until `check` succeeds, since ingestion is processed asynchronously
+async fn eventually<T, F, Fut>(mut check: F) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Option<T>>,
+{
+    for _ in 0..30 {
+        if let Some(value) = check().await {
+            return value;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    panic!("condition not met within 15 seconds");
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_full_api_surface() {
+    let langfuse = LangfuseContainer::start().await;
+    let client = langfuse.client();
+
+    assert!(client.validate().await.expect("health check"));
+
+    // Traces and observations
+    let trace = client
+        .trace()
+        .name("e2e-trace")
+        .input(json!({"query": "hello"}))
+        .tags(vec!["e2e".to_string()])
+        .call()
+        .await
+        .expect("create trace");
+
+    let span_id = client
+        .span()
+        .trace_id(trace.id.clone())
+        .name("e2e-span")
+        .output(json!({"ok": true}))
+        .call()
+        .await
+        .expect("create span");
+
+    client
+        .generation()
+        .trace_id(trace.id.clone())
+        .parent_observation_id(span_id.clone())
+        .name("e2e-generation")
+        .model("gpt-4")
+        .call()
+        .await
+        .expect("create generation");
+
+    client
+        .event()
+        .trace_id(trace.id.clone())
+        .name("e2e-event")
+        .call()
+        .await
+        .expect("create event");
+
+    // Scores
+    client
+        .score()
+        .trace_id(trace.id.clone())
+        .name("quality")
+        .value(0.9)
+        .call()
+        .await
+        .expect("create score");
+
+    let fetched = eventually(|| {
+        let client = client.clone();
+        let trace_id = trace.id.clone();
+        async move {
+            let trace = client.get_trace(trace_id).await.ok()?;
+            (trace.observations.len() >= 3 && !trace.scores.is_empty()).then_some(trace)
+        }
+    })
+    .await;
+    assert_eq!(fetched.name.flatten().as_deref(), Some("e2e-trace"));
+
+    let observations = client
+        .get_observations()
+        .trace_id(trace.id.clone())
+        .call()
+        .await
+        .expect("list observations");
+    assert_eq!(observations.data.len(), 3);
+
+    let traces = client
+        .list_traces()
+        .filter(TraceFilter::new().tags(["e2e"]))
+        .call()
+        .await
+        .expect("list traces");
+    assert!(traces.data.iter().any(|t| t.id == trace.id));
+
+    // Datasets
+    client
+        .create_dataset()
+        .name("e2e-dataset")
+        .description("End-to-end test dataset")
+        .call()
+        .await
+        .expect("create dataset");
+
+    client
+        .create_dataset_item()
+        .dataset_name("e2e-dataset")
+        .input(json!({"question": "2 + 2"}))
+        .expected_output(json!({"answer": "4"}))
+        .call()
+        .await
+        .expect("create dataset item");
+
+    let dataset = client
+        .get_dataset("e2e-dataset")
+        .await
+        .expect("get dataset");
+    assert_eq!(dataset.name, "e2e-dataset");
+
+    let items = client
+        .list_dataset_items()
+        .dataset_name("e2e-dataset")
+        .call()
+        .await
+        .expect("list dataset items");
+    assert_eq!(items.data.len(), 1);
+
+    // Prompts
+    client
+        .create_prompt()
+        .name("e2e-prompt")
+        .prompt("Hello {{name}}")
+        .labels(vec!["production".to_string()])
+        .call()
+        .await
+        .expect("create prompt");
+
+    let prompt = client
+        .get_prompt("e2e-prompt", None, None)
+        .await
+        .expect("get prompt");
+    match prompt {
+        Prompt::PromptOneOf1(text) => assert_eq!(text.prompt, "Hello {{name}}"),
+        other => panic!("expected a text prompt, got {other:?}"),
+    }
+}
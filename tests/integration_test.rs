@@ -60,7 +60,7 @@ async fn test_span_creation() {
 
     assert!(result.is_ok(), "Failed to create span: {:?}", result.err());
     let span_id = result.unwrap();
-    assert!(!span_id.is_empty(), "Span ID should not be empty");
+    assert!(!span_id.id.is_empty(), "Span ID should not be empty");
 }
 
 #[tokio::test]
@@ -97,7 +97,7 @@ async fn test_generation_creation() {
     );
     let generation_id = result.unwrap();
     assert!(
-        !generation_id.is_empty(),
+        !generation_id.id.is_empty(),
         "Generation ID should not be empty"
     );
 }
@@ -129,7 +129,7 @@ async fn test_event_creation() {
 
     assert!(result.is_ok(), "Failed to create event: {:?}", result.err());
     let event_id = result.unwrap();
-    assert!(!event_id.is_empty(), "Event ID should not be empty");
+    assert!(!event_id.id.is_empty(), "Event ID should not be empty");
 }
 
 #[tokio::test]
@@ -183,7 +183,7 @@ async fn test_categorical_score_creation() {
         .score()
         .trace_id(trace.id.clone())
         .name("sentiment")
-        .string_value("positive")
+        .value("positive")
         .comment("User sentiment analysis")
         .call()
         .await;
@@ -229,7 +229,10 @@ async fn test_nested_observations() {
         .await
         .expect("Failed to create child span");
 
-    assert!(!child_span.is_empty(), "Child span ID should not be empty");
+    assert!(
+        !child_span.id.is_empty(),
+        "Child span ID should not be empty"
+    );
 
     // Create a generation under the child span
     let generation = client
@@ -242,7 +245,10 @@ async fn test_nested_observations() {
         .await
         .expect("Failed to create nested generation");
 
-    assert!(!generation.is_empty(), "Generation ID should not be empty");
+    assert!(
+        !generation.id.is_empty(),
+        "Generation ID should not be empty"
+    );
 }
 
 #[tokio::test]
@@ -349,7 +355,7 @@ async fn test_prompt_management() {
     // We don't assert success here since the prompt might not exist
 
     // Test list_prompts
-    let list_result = client.list_prompts().limit("5".to_string()).call().await;
+    let list_result = client.list_prompts().limit(5).call().await;
     assert!(
         list_result.is_ok(),
         "Failed to list prompts: {:?}",
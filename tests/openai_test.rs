@@ -0,0 +1,117 @@
+//! Tests for OpenAI client instrumentation (requires the `openai` feature)
+
+use langfuse_ergonomic::openai::LangfuseOpenAi;
+use langfuse_ergonomic::{with_context, ClientBuilder, LangfuseClient, LangfuseContext};
+use mockito::{Matcher, Server};
+use serde_json::{json, Value};
+
+/// Stand-in for an OpenAI client answering every chat request
+struct FakeOpenAi {
+    reply: &'static str,
+}
+
+impl FakeOpenAi {
+    async fn create(&self, request: Value) -> Result<Value, String> {
+        if request["model"] == "missing-model" {
+            return Err("The model `missing-model` does not exist".to_string());
+        }
+        Ok(json!({
+            "model": "gpt-4o-2024-08-06",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": self.reply}}],
+            "usage": {"prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15},
+        }))
+    }
+}
+
+fn create_client(server: &Server) -> LangfuseClient {
+    ClientBuilder::new()
+        .public_key("pk-lf-test")
+        .secret_key("sk-lf-test")
+        .base_url(server.url())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_chat_call_is_recorded_in_ambient_trace() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""type":"generation-create""#.to_string()),
+            Matcher::Regex(r#""traceId":"trace-1""#.to_string()),
+            Matcher::Regex(r#""parentObservationId":"span-1""#.to_string()),
+            Matcher::Regex(r#""name":"chat-completion""#.to_string()),
+            Matcher::Regex(r#""model":"gpt-4o-2024-08-06""#.to_string()),
+            Matcher::Regex(r#""temperature":"#.to_string()),
+            Matcher::Regex(r#""content":"Hello!""#.to_string()),
+            Matcher::Regex(r#""content":"Hi there""#.to_string()),
+            Matcher::Regex(r#""input":12"#.to_string()),
+            Matcher::Regex(r#""endTime":"\d"#.to_string()),
+        ]))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let openai = LangfuseOpenAi::new(FakeOpenAi { reply: "Hi there" }, create_client(&server));
+    let context = LangfuseContext::new()
+        .trace_id("trace-1")
+        .parent_observation_id("span-1");
+    let response = with_context(context, async {
+        openai
+            .chat(
+                json!({
+                    "model": "gpt-4o",
+                    "temperature": 0.2,
+                    "messages": [{"role": "user", "content": "Hello!"}],
+                }),
+                |client, request| client.create(request),
+            )
+            .await
+    })
+    .await
+    .unwrap();
+    assert_eq!(response["choices"][0]["message"]["content"], "Hi there");
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_failed_call_is_recorded_as_error() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""type":"generation-create""#.to_string()),
+            Matcher::Regex(r#""name":"support-bot""#.to_string()),
+            Matcher::Regex(r#""model":"missing-model""#.to_string()),
+            Matcher::Regex(r#""level":"ERROR""#.to_string()),
+            Matcher::Regex(
+                r#""statusMessage":"The model `missing-model` does not exist""#.to_string(),
+            ),
+        ]))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let openai =
+        LangfuseOpenAi::new(FakeOpenAi { reply: "" }, create_client(&server)).name("support-bot");
+    let error = openai
+        .chat(
+            json!({"model": "missing-model", "messages": []}),
+            |client, request| client.create(request),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.contains("does not exist"));
+
+    mock.assert_async().await;
+}
@@ -0,0 +1,59 @@
+//! Tests for command tracing (requires the `process` feature)
+
+use langfuse_ergonomic::ClientBuilder;
+use mockito::{Matcher, Server};
+use tokio::process::Command;
+
+#[tokio::test]
+async fn test_failed_command_marks_span_as_error() {
+    let mut server = Server::new_async().await;
+
+    let start = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""type":"span-create""#.to_string()),
+            Matcher::Regex(r#""name":"sh""#.to_string()),
+            Matcher::Regex(r#""program":"sh""#.to_string()),
+        ]))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let end = server
+        .mock("POST", "/api/public/ingestion")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#""type":"span-update""#.to_string()),
+            Matcher::Regex(r#""level":"ERROR""#.to_string()),
+            Matcher::Regex(r#""exit_code":3"#.to_string()),
+            Matcher::Regex(r#""stderr":"boom""#.to_string()),
+        ]))
+        .with_status(207)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-lf-test")
+        .secret_key("sk-lf-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid");
+
+    let run = client
+        .trace_context("trace-1")
+        .command(Command::new("sh").args(["-c", "echo boom >&2; exit 3"]))
+        .call()
+        .await
+        .unwrap();
+
+    start.assert_async().await;
+    end.assert_async().await;
+    let output = run.output.unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stderr, b"boom\n");
+}
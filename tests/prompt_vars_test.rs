@@ -0,0 +1,62 @@
+//! Tests for `#[derive(PromptVars)]` (requires the `derive` feature)
+
+use langfuse_ergonomic::prompts::{check_prompt_vars, PromptPlaceholders};
+use langfuse_ergonomic::{Prompt, PromptVars};
+use serde_json::json;
+
+#[derive(PromptVars)]
+#[allow(dead_code)]
+struct SupportVars {
+    customer: String,
+    question: String,
+    #[prompt_var(rename = "history")]
+    chat_history: Vec<String>,
+    #[prompt_var(skip)]
+    request_id: u64,
+}
+
+#[derive(PromptVars)]
+#[allow(dead_code)]
+struct GreetingVars<'a> {
+    name: &'a str,
+}
+
+fn support_prompt() -> Prompt {
+    serde_json::from_value(json!({
+        "name": "support",
+        "version": 3,
+        "config": null,
+        "labels": ["production"],
+        "tags": [],
+        "type": "chat",
+        "prompt": [
+            {"role": "system", "content": "You are helping {{customer}}."},
+            {"type": "placeholder", "name": "history"},
+            {"role": "user", "content": "{{question}}"}
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_derived_names() {
+    assert_eq!(
+        <SupportVars as langfuse_ergonomic::prompts::PromptVars>::NAMES,
+        &["customer", "question", "history"]
+    );
+    assert_eq!(
+        <GreetingVars<'_> as langfuse_ergonomic::prompts::PromptVars>::NAMES,
+        &["name"]
+    );
+}
+
+#[test]
+fn test_derived_vars_match_prompt() {
+    let prompt = support_prompt();
+    assert!(check_prompt_vars::<SupportVars>(&prompt).is_ok());
+
+    let err = PromptPlaceholders::from_prompt(&prompt)
+        .check::<GreetingVars<'_>>()
+        .unwrap_err();
+    assert!(err.to_string().contains("unexpected `name`"));
+}
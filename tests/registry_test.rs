@@ -0,0 +1,70 @@
+//! Tests for the process-wide batcher registry
+//!
+//! Kept in its own test binary because `shutdown_all` drains every batcher in
+//! the process.
+
+use langfuse_ergonomic::{registry, shutdown_all, Batcher, ClientBuilder};
+use mockito::Server;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_shutdown_all_drains_every_batcher() {
+    let mut server = Server::new_async().await;
+
+    let mock = server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"successes": [], "errors": []}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = ClientBuilder::new()
+        .public_key("pk-test")
+        .secret_key("sk-test")
+        .base_url(server.url())
+        .build()
+        .unwrap();
+
+    let first = Batcher::builder()
+        .client(client.clone())
+        .flush_interval(Duration::from_secs(60))
+        .build()
+        .await;
+    let second = Batcher::builder()
+        .client(client)
+        .flush_interval(Duration::from_secs(60))
+        .build()
+        .await;
+    assert_eq!(registry::registered_batchers(), 2);
+
+    first
+        .trace()
+        .name("tenant-a")
+        .input(json!("a"))
+        .call()
+        .await
+        .unwrap();
+    second.trace().name("tenant-b").call().await.unwrap();
+    // The registry does not keep a dropped batcher running: its task flushes
+    // the pending event, ends, and leaves the registry
+    drop(second);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while registry::registered_batchers() > 1 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the dropped batcher's task should end");
+
+    let reports = shutdown_all(Duration::from_secs(5)).await;
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].flushed, 1);
+    assert!(!reports[0].timed_out);
+    assert_eq!(registry::registered_batchers(), 0);
+    assert!(first.trace().name("late").call().await.is_err());
+
+    mock.assert_async().await;
+}
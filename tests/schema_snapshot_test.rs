@@ -0,0 +1,303 @@
+//! Snapshot tests for the ingestion payloads produced by the builder APIs
+//!
+//! Each test captures the request body sent to `/api/public/ingestion`, replaces
+//! volatile values (generated UUIDs, timestamps) with stable placeholders, and
+//! compares the result with a golden file in `tests/golden/`.
+//!
+//! Timestamps are replaced by a placeholder naming their format, so a change from
+//! millisecond precision to another representation still shows up as a diff.
+//!
+//! To regenerate the golden files after an intentional payload change, run:
+//!
+//! ```bash
+//! UPDATE_GOLDEN=1 cargo test --test schema_snapshot_test
+//! ```
+
+use chrono::{DateTime, TimeZone, Utc};
+use langfuse_ergonomic::{ClientBuilder, LangfuseClient};
+use mockito::{Server, ServerGuard};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Start a mock ingestion endpoint that records every request body
+async fn recording_server() -> (ServerGuard, Arc<Mutex<Vec<Value>>>) {
+    let mut server = Server::new_async().await;
+    let captured = Arc::new(Mutex::new(Vec::new()));
+    let sink = captured.clone();
+
+    server
+        .mock("POST", "/api/public/ingestion")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_request(move |request| {
+            let body: Value =
+                serde_json::from_slice(request.body().expect("request body")).expect("json body");
+            sink.lock().unwrap().push(body);
+            br#"{"successes": [], "errors": []}"#.to_vec()
+        })
+        .create_async()
+        .await;
+
+    (server, captured)
+}
+
+fn create_mock_client(server: &ServerGuard) -> LangfuseClient {
+    ClientBuilder::new()
+        .public_key("pk-lf-test")
+        .secret_key("sk-lf-test")
+        .base_url(server.url())
+        .build()
+        .expect("mock credentials should be valid")
+}
+
+fn fixed_time(second: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, second).unwrap()
+}
+
+/// Replace generated UUIDs and timestamps with placeholders
+fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(map) => map.values_mut().for_each(normalize),
+        Value::Array(items) => items.iter_mut().for_each(normalize),
+        Value::String(s) => {
+            if let Some(placeholder) = placeholder_for(s) {
+                *s = placeholder.to_string();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn placeholder_for(s: &str) -> Option<&'static str> {
+    if uuid::Uuid::parse_str(s).is_ok() && s.len() == 36 {
+        return Some("<uuid>");
+    }
+    // Fixed test timestamps are kept verbatim so their exact format is visible
+    if s.starts_with("2024-01-01T12:00:") {
+        return None;
+    }
+    DateTime::parse_from_rfc3339(s).ok()?;
+    let millis_utc = s.len() == 24 && s.ends_with('Z') && s.as_bytes()[19] == b'.';
+    Some(if millis_utc {
+        "<timestamp:rfc3339-millis-utc>"
+    } else {
+        "<timestamp:rfc3339>"
+    })
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.json"))
+}
+
+/// Compare the captured payload against the golden file, or rewrite it
+fn assert_snapshot(name: &str, captured: &Arc<Mutex<Vec<Value>>>) {
+    let mut payload = captured
+        .lock()
+        .unwrap()
+        .pop()
+        .expect("an ingestion request should have been captured");
+    normalize(&mut payload);
+    let actual = serde_json::to_string_pretty(&payload).unwrap() + "\n";
+
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing golden file {}; run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+    assert_eq!(
+        expected,
+        actual,
+        "payload for `{name}` differs from {}; run with UPDATE_GOLDEN=1 if the change is intentional",
+        path.display()
+    );
+}
+
+#[tokio::test]
+async fn test_trace_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .trace()
+        .id("trace-1")
+        .name("snapshot-trace")
+        .input(json!({"query": "hello"}))
+        .output(json!({"answer": "world"}))
+        .metadata(json!({"key": "value"}))
+        .tags(vec!["a".to_string(), "b".to_string()])
+        .user_id("user-1")
+        .session_id("session-1")
+        .release("v1.0.0")
+        .version("1")
+        .public(false)
+        .timestamp(fixed_time(0))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("trace_create", &captured);
+}
+
+#[tokio::test]
+async fn test_span_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .span()
+        .trace_id("trace-1")
+        .id("span-1")
+        .parent_observation_id("parent-1")
+        .name("snapshot-span")
+        .input(json!({"step": 1}))
+        .output(json!({"ok": true}))
+        .metadata(json!({"key": "value"}))
+        .level("WARNING")
+        .status_message("slow")
+        .start_time(fixed_time(0))
+        .end_time(fixed_time(1))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("span_create", &captured);
+}
+
+#[tokio::test]
+async fn test_generation_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .generation()
+        .trace_id("trace-1")
+        .id("generation-1")
+        .name("snapshot-generation")
+        .model("gpt-4")
+        .input(json!([{"role": "user", "content": "hi"}]))
+        .output(json!({"role": "assistant", "content": "hello"}))
+        .start_time(fixed_time(0))
+        .end_time(fixed_time(2))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("generation_create", &captured);
+}
+
+#[tokio::test]
+async fn test_generation_usage_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .generation()
+        .trace_id("trace-1")
+        .id("generation-2")
+        .model("gpt-4")
+        .model_parameters(json!({"temperature": 0.5, "max_tokens": 256}))
+        .prompt_tokens(10)
+        .completion_tokens(5)
+        .usage_details(std::collections::HashMap::from([(
+            "cache_read".to_string(),
+            4,
+        )]))
+        .cost_details(std::collections::HashMap::from([
+            ("input".to_string(), 0.25),
+            ("output".to_string(), 0.5),
+        ]))
+        .start_time(fixed_time(0))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("generation_usage_create", &captured);
+}
+
+#[tokio::test]
+async fn test_event_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .event()
+        .trace_id("trace-1")
+        .id("event-1")
+        .name("snapshot-event")
+        .input(json!({"clicked": "button"}))
+        .level("DEBUG")
+        .start_time(fixed_time(0))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("event_create", &captured);
+}
+
+#[tokio::test]
+async fn test_score_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .score()
+        .trace_id("trace-1")
+        .observation_id("generation-1")
+        .name("quality")
+        .value(0.9)
+        .comment("good")
+        .metadata(json!({"grader": "human"}))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("score_create", &captured);
+}
+
+#[tokio::test]
+async fn test_span_update_payload_snapshot() {
+    let (server, captured) = recording_server().await;
+    let client = create_mock_client(&server);
+
+    client
+        .update_span()
+        .id("span-1")
+        .trace_id("trace-1")
+        .output(json!({"ok": true}))
+        .end_time(fixed_time(3))
+        .call()
+        .await
+        .unwrap();
+
+    assert_snapshot("span_update", &captured);
+}
+
+#[test]
+fn test_normalize_placeholders() {
+    let mut value = json!({
+        "id": "7f1c3f0e-7d53-4e8e-9c3a-1a2b3c4d5e6f",
+        "timestamp": "2025-02-03T04:05:06.789Z",
+        "other": "2025-02-03T04:05:06.789123+00:00",
+        "fixed": "2024-01-01T12:00:00.000Z",
+        "name": "plain"
+    });
+    normalize(&mut value);
+
+    assert_eq!(value["id"], "<uuid>");
+    assert_eq!(value["timestamp"], "<timestamp:rfc3339-millis-utc>");
+    assert_eq!(value["other"], "<timestamp:rfc3339>");
+    assert_eq!(value["fixed"], "2024-01-01T12:00:00.000Z");
+    assert_eq!(value["name"], "plain");
+}
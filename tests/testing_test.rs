@@ -0,0 +1,144 @@
+//! Tests for the recording client (requires the `testing` feature)
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use langfuse_client_base::models::CreateSpanBody;
+use langfuse_ergonomic::testing::RecordingClient;
+use langfuse_ergonomic::{
+    Batcher, ClientBuilder, Environment, IngestionEvent, LangfuseTracer, ScoreTransport,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_records_traces_observations_and_scores() {
+    let langfuse = RecordingClient::new();
+
+    let trace = langfuse
+        .trace()
+        .name("handle-request")
+        .input(json!({"question": "Hi?"}))
+        .call()
+        .await
+        .unwrap();
+    let span = langfuse
+        .span()
+        .trace_id(&trace.id)
+        .name("retrieve")
+        .call()
+        .await
+        .unwrap();
+    langfuse
+        .update_span()
+        .id(&span.id)
+        .trace_id(&trace.id)
+        .output(json!(["doc-1"]))
+        .call()
+        .await
+        .unwrap();
+    langfuse
+        .generation()
+        .trace_id(&trace.id)
+        .parent_observation_id(&span.id)
+        .name("answer")
+        .model("gpt-4o")
+        .call()
+        .await
+        .unwrap();
+    langfuse
+        .score()
+        .trace_id(&trace.id)
+        .name("quality")
+        .value(0.9)
+        .call()
+        .await
+        .unwrap();
+
+    let traces = langfuse.recorded_traces();
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].id, trace.id);
+    assert_eq!(traces[0].input(), Some(&json!({"question": "Hi?"})));
+
+    // The span update is merged into the span
+    let observations = langfuse.observations_of(&trace.id);
+    assert_eq!(observations.len(), 2);
+    assert_eq!(observations[0].kind, "SPAN");
+    assert_eq!(observations[0].name(), Some("retrieve"));
+    assert_eq!(observations[0].output(), Some(&json!(["doc-1"])));
+    assert_eq!(observations[1].kind, "GENERATION");
+    assert_eq!(observations[1].get("model"), Some(&json!("gpt-4o")));
+    assert_eq!(
+        observations[1].get("parentObservationId"),
+        Some(&json!(span.id))
+    );
+
+    let scores = langfuse.recorded_scores();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].trace_id(), Some(trace.id.as_str()));
+    assert_eq!(scores[0].get("value"), Some(&json!(0.9)));
+
+    assert_eq!(langfuse.recorded_events().len(), 5);
+    langfuse.clear();
+    assert!(langfuse.recorded_events().is_empty());
+}
+
+#[tokio::test]
+async fn test_records_configured_and_batched_events() {
+    let langfuse = RecordingClient::from_builder(
+        ClientBuilder::new()
+            .environment(Environment::new("test").unwrap())
+            .score_transport(ScoreTransport::Direct)
+            .interceptor(|event: &mut IngestionEvent| {
+                if let IngestionEvent::IngestionEventOneOf(trace) = event {
+                    trace.body.release = Some(Some("1.4.2".to_string()));
+                }
+            }),
+    )
+    .unwrap();
+
+    let batcher = Batcher::builder()
+        .client(langfuse.client().clone())
+        .flush_interval(Duration::from_secs(3600))
+        .build()
+        .await;
+    batcher.trace().name("batched").call().await.unwrap();
+    assert!(langfuse.recorded_traces().is_empty());
+    batcher.flush().await.unwrap();
+
+    // Direct scores are recorded as well
+    langfuse
+        .score()
+        .trace_id("trace-1")
+        .name("thumbs-up")
+        .value(true)
+        .call()
+        .await
+        .unwrap();
+
+    let traces = langfuse.recorded_traces();
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].name(), Some("batched"));
+    assert_eq!(traces[0].get("environment"), Some(&json!("test")));
+    assert_eq!(traces[0].get("release"), Some(&json!("1.4.2")));
+    assert_eq!(langfuse.recorded_scores().len(), 1);
+}
+
+#[tokio::test]
+async fn test_recording_client_as_tracer() {
+    let langfuse = RecordingClient::new();
+    let tracer: Arc<dyn LangfuseTracer> = Arc::new(langfuse.clone());
+
+    let span_id = tracer
+        .create_span(CreateSpanBody {
+            trace_id: Some(Some("trace-1".to_string())),
+            name: Some(Some("search".to_string())),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    let observations = langfuse.observations_of("trace-1");
+    assert_eq!(observations.len(), 1);
+    assert_eq!(observations[0].id, span_id);
+    assert_eq!(observations[0].name(), Some("search"));
+}
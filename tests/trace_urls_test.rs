@@ -38,6 +38,8 @@ fn test_trace_url_generation() {
         let response = langfuse_ergonomic::TraceResponse {
             id: trace_id.to_string(),
             base_url: base_url.to_string(),
+            session_id: None,
+            project_id: None,
         };
 
         assert_eq!(
@@ -286,6 +288,11 @@ async fn test_hierarchical_observations() {
         .unwrap();
 
     assert_eq!(span2_response, span2_id);
+    assert_eq!(span2_response.trace_id, trace_id);
+    assert_eq!(
+        span2_response.url,
+        trace_response.observation_url(&span2_id)
+    );
 
     // Verify all IDs are deterministic and reproducible
     assert_eq!(
@@ -303,3 +310,54 @@ async fn test_hierarchical_observations() {
 
     mock.assert_async().await;
 }
+
+#[test]
+fn test_w3c_trace_ids() {
+    let trace_id =
+        IdGenerator::from_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+            .unwrap();
+    assert_eq!(trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+    assert!(IdGenerator::from_traceparent("not-a-traceparent").is_err());
+
+    let generated = IdGenerator::w3c_trace_id();
+    assert!(IdGenerator::is_w3c_trace_id(&generated));
+    assert_ne!(generated, IdGenerator::w3c_trace_id());
+    assert_eq!(IdGenerator::w3c_span_id().len(), 16);
+    assert!(!IdGenerator::is_w3c_trace_id(&IdGenerator::from_seed(
+        "seed"
+    )));
+}
+
+#[test]
+fn test_observation_and_session_urls() {
+    let mut response = langfuse_ergonomic::TraceResponse {
+        id: "trace-1".to_string(),
+        base_url: "https://cloud.langfuse.com/api/public".to_string(),
+        session_id: None,
+        project_id: None,
+    };
+    assert_eq!(
+        response.observation_url("span-1"),
+        "https://cloud.langfuse.com/trace/trace-1?observation=span-1"
+    );
+    assert_eq!(response.session_url(), None);
+
+    response.session_id = Some("session-1".to_string());
+    assert_eq!(
+        response.session_url().as_deref(),
+        Some("https://cloud.langfuse.com/sessions/session-1")
+    );
+}
+
+#[test]
+fn test_id_validation() {
+    IdGenerator::validate("trace-123").unwrap();
+    IdGenerator::validate(&IdGenerator::from_seed("seed")).unwrap();
+    IdGenerator::validate(&"a".repeat(IdGenerator::MAX_LENGTH)).unwrap();
+
+    for id in ["", "has space", "tab\t", "ünicode", "line\nbreak"] {
+        assert!(IdGenerator::validate(id).is_err(), "accepted {id:?}");
+    }
+    let too_long = "a".repeat(IdGenerator::MAX_LENGTH + 1);
+    assert!(IdGenerator::validate(&too_long).is_err());
+}